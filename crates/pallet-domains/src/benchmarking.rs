@@ -6,13 +6,15 @@ use super::*;
 use crate::block_tree::{prune_receipt, BlockTreeNode};
 use crate::bundle_storage_fund::refund_storage_fee;
 use crate::domain_registry::DomainConfig;
+use crate::domain_sunset::{next_sunset_receipt, DomainSunsetStatus};
 use crate::staking::{
     do_convert_previous_epoch_deposits, do_reward_operators, do_slash_operators, OperatorConfig,
     OperatorStatus,
 };
 use crate::staking_epoch::{
     do_finalize_domain_current_epoch, do_finalize_domain_epoch_staking,
-    do_finalize_slashed_operators, operator_take_reward_tax_and_stake,
+    do_finalize_operator_config_updates, do_finalize_redelegations, do_finalize_slashed_operators,
+    operator_take_reward_tax_and_stake,
 };
 use crate::{DomainBlockNumberFor, Pallet as Domains};
 #[cfg(not(feature = "std"))]
@@ -32,6 +34,7 @@ use sp_domains::{
 };
 use sp_domains_fraud_proof::fraud_proof::FraudProof;
 use sp_runtime::traits::{CheckedAdd, One, Zero};
+use sp_runtime::Percent;
 use sp_std::collections::btree_set::BTreeSet;
 
 const SEED: u32 = 0;
@@ -676,6 +679,117 @@ mod benchmarks {
         );
     }
 
+    #[benchmark]
+    fn update_operator_config() {
+        let domain_id = register_domain::<T>();
+        let (operator_owner, operator_id) =
+            register_helper_operator::<T>(domain_id, T::MinNominatorStake::get());
+        let minimum_nominator_stake = T::MinNominatorStake::get() * 2u32.into();
+        let nomination_tax = Percent::from_percent(10);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(operator_owner.clone()),
+            operator_id,
+            minimum_nominator_stake,
+            nomination_tax,
+        );
+
+        let config_update = PendingOperatorConfigUpdates::<T>::get(domain_id, operator_id)
+            .expect("config update must exist");
+        assert_eq!(
+            config_update.minimum_nominator_stake,
+            Some(minimum_nominator_stake)
+        );
+        assert_eq!(config_update.nomination_tax, Some(nomination_tax));
+    }
+
+    /// Benchmark `rotate_operator_signing_key` extrinsic with the worst possible conditions:
+    /// - There is a pending rotation in the same epoch whose reserved key need to be released
+    #[benchmark]
+    fn rotate_operator_signing_key() {
+        let domain_id = register_domain::<T>();
+        let (operator_owner, operator_id) =
+            register_helper_operator::<T>(domain_id, T::MinNominatorStake::get());
+        let pending_signing_key = OperatorPublicKey::unchecked_from([2u8; 32]);
+        let new_signing_key = OperatorPublicKey::unchecked_from([3u8; 32]);
+        assert_ok!(Domains::<T>::rotate_operator_signing_key(
+            RawOrigin::Signed(operator_owner.clone()).into(),
+            operator_id,
+            pending_signing_key.clone(),
+        ));
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(operator_owner.clone()),
+            operator_id,
+            new_signing_key.clone(),
+        );
+
+        let config_update = PendingOperatorConfigUpdates::<T>::get(domain_id, operator_id)
+            .expect("config update must exist");
+        assert_eq!(config_update.signing_key, Some(new_signing_key.clone()));
+        assert_eq!(OperatorSigningKey::<T>::get(pending_signing_key), None);
+        assert_eq!(
+            OperatorSigningKey::<T>::get(new_signing_key),
+            Some(operator_id)
+        );
+    }
+
+    /// Benchmark `do_finalize_operator_config_updates` based on the number of operators that
+    /// requested a config update in the current epoch, with the worst possible conditions:
+    /// - Every operator rotates its signing key
+    /// - Every operator rotated its signing key in the previous epoch too, so the previous key
+    ///   need to be released
+    #[benchmark]
+    fn finalize_operator_config_updates(p: Linear<0, { T::MaxPendingStakingOperation::get() }>) {
+        let domain_id = register_domain::<T>();
+        let mut operators = Vec::new();
+        for i in 0..p {
+            operators.push(register_operator_with_seed::<T>(
+                domain_id,
+                i + 1,
+                T::MinNominatorStake::get(),
+            ));
+        }
+        do_finalize_domain_current_epoch::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        let rotated_signing_key = |operator_seed: u32, round: u8| {
+            let mut k = [0u8; 32];
+            (k[..4]).copy_from_slice(&operator_seed.to_be_bytes()[..]);
+            k[31] = round;
+            OperatorPublicKey::unchecked_from(k)
+        };
+        for round in 1..=2 {
+            for (i, (operator_owner, operator_id)) in operators.iter().enumerate() {
+                assert_ok!(Domains::<T>::rotate_operator_signing_key(
+                    RawOrigin::Signed(operator_owner.clone()).into(),
+                    *operator_id,
+                    rotated_signing_key(i as u32 + 1, round),
+                ));
+            }
+            if round == 1 {
+                do_finalize_domain_current_epoch::<T>(domain_id)
+                    .expect("finalize domain staking should success");
+            }
+        }
+
+        #[block]
+        {
+            assert_eq!(do_finalize_operator_config_updates::<T>(domain_id), p);
+        }
+
+        for (i, (_, operator_id)) in operators.into_iter().enumerate() {
+            let operator = Operators::<T>::get(operator_id).expect("operator must exist");
+            assert_eq!(operator.signing_key, rotated_signing_key(i as u32 + 1, 2));
+            assert_eq!(
+                OperatorSigningKey::<T>::get(rotated_signing_key(i as u32 + 1, 1)),
+                Some(operator_id)
+            );
+        }
+    }
+
     /// Benchmark `withdraw_stake` extrinsic with the worst possible conditions:
     /// - There is a pending withdrawal and a pending deposit from the previous epoch that
     ///   need to convert into balance/share
@@ -725,6 +839,120 @@ mod benchmarks {
         assert_eq!(operator.withdrawals_in_epoch, withdraw_amount.into());
     }
 
+    /// Benchmark `redelegate` extrinsic with the worst possible conditions:
+    /// - There is a pending withdrawal and a pending deposit from the previous epoch that
+    ///   need to convert into balance/share
+    /// - Only redelegate partial of the nominator's stake
+    #[benchmark]
+    fn redelegate() {
+        let nominator = account("nominator", 1, SEED);
+        let minimum_nominator_stake = T::MinNominatorStake::get();
+        let redelegate_amount = T::MinOperatorStake::get();
+        T::Currency::set_balance(
+            &nominator,
+            redelegate_amount * 4u32.into() + T::MinNominatorStake::get(),
+        );
+
+        let domain_id = register_domain::<T>();
+        let (_, from_operator_id) =
+            register_operator_with_seed::<T>(domain_id, 1u32, minimum_nominator_stake);
+        let (_, to_operator_id) =
+            register_operator_with_seed::<T>(domain_id, 2u32, minimum_nominator_stake);
+        assert_ok!(Domains::<T>::nominate_operator(
+            RawOrigin::Signed(nominator.clone()).into(),
+            from_operator_id,
+            redelegate_amount * 3u32.into(),
+        ));
+        do_finalize_domain_epoch_staking::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        // Add one more withdraw and deposit to the previous epoch
+        assert_ok!(Domains::<T>::withdraw_stake(
+            RawOrigin::Signed(nominator.clone()).into(),
+            from_operator_id,
+            redelegate_amount.into(),
+        ));
+        assert_ok!(Domains::<T>::nominate_operator(
+            RawOrigin::Signed(nominator.clone()).into(),
+            from_operator_id,
+            redelegate_amount,
+        ));
+        do_finalize_domain_epoch_staking::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(nominator.clone()),
+            from_operator_id,
+            to_operator_id,
+            redelegate_amount.into(),
+        );
+
+        let operator = Operators::<T>::get(from_operator_id).expect("operator must exist");
+        assert_eq!(operator.withdrawals_in_epoch, redelegate_amount.into());
+        assert_eq!(PendingRedelegations::<T>::get(domain_id).len(), 1);
+    }
+
+    /// Benchmark `do_finalize_redelegations` based on the number of redelegations happen in the
+    /// current epoch
+    ///
+    /// Every redelegation also withdraws from a distinct operator thus takes two pending staking
+    /// operations.
+    #[benchmark]
+    fn finalize_redelegations(p: Linear<0, { T::MaxPendingStakingOperation::get() / 2 }>) {
+        let minimum_nominator_stake = T::MinNominatorStake::get();
+        let staking_amount = T::MinOperatorStake::get();
+
+        let domain_id = register_domain::<T>();
+        let mut operator_ids = Vec::new();
+        for i in 0..=p {
+            let (_, operator_id) =
+                register_operator_with_seed::<T>(domain_id, i + 1, minimum_nominator_stake);
+            operator_ids.push(operator_id);
+        }
+
+        let nominators: Vec<T::AccountId> = (0..p).map(|i| account("nominator", i, SEED)).collect();
+        for (nominator, operator_id) in nominators.iter().zip(operator_ids.iter()) {
+            T::Currency::set_balance(nominator, staking_amount * 2u32.into());
+            assert_ok!(Domains::<T>::nominate_operator(
+                RawOrigin::Signed(nominator.clone()).into(),
+                *operator_id,
+                staking_amount,
+            ));
+        }
+        do_finalize_domain_current_epoch::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        // Redelegate half of the stake to the next operator
+        for (i, nominator) in nominators.iter().enumerate() {
+            assert_ok!(Domains::<T>::redelegate(
+                RawOrigin::Signed(nominator.clone()).into(),
+                operator_ids[i],
+                operator_ids[i + 1],
+                (staking_amount / 2u32.into()).into(),
+            ));
+        }
+        assert_eq!(PendingRedelegations::<T>::get(domain_id).len(), p as usize);
+        do_finalize_domain_epoch_staking::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        #[block]
+        {
+            assert_eq!(
+                do_finalize_redelegations::<T>(domain_id)
+                    .expect("finalize redelegations should success"),
+                p
+            );
+        }
+
+        assert!(PendingRedelegations::<T>::get(domain_id).is_empty());
+        assert_eq!(RedelegationLiabilities::<T>::get(domain_id).len(), p as usize);
+        for operator_id in operator_ids.into_iter().skip(1) {
+            let operator = Operators::<T>::get(operator_id).expect("operator must exist");
+            assert!(!operator.deposits_in_epoch.is_zero());
+        }
+    }
+
     /// Benchmark `unlock_funds` extrinsic with the worst possible conditions:
     /// - Unlock a full withdrawal which also remove the deposit storage for the nominator
     #[benchmark]
@@ -871,6 +1099,61 @@ mod benchmarks {
         assert_eq!(domain_obj.domain_config.operator_allow_list, new_allow_list);
     }
 
+    #[benchmark]
+    fn deactivate_domain() {
+        let domain_id = register_domain::<T>();
+        let domain_owner = DomainRegistry::<T>::get(domain_id)
+            .expect("domain object must exist")
+            .owner_account_id;
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(domain_owner), domain_id);
+
+        assert!(Domains::<T>::is_domain_deactivated(domain_id));
+    }
+
+    /// Benchmark the last step of `advance_domain_sunset` based on the number of operators of the
+    /// domain, which deregisters all the operators and prunes the domain state
+    #[benchmark]
+    fn sunset_domain(n: Linear<1, MAX_BUNLDE_PER_BLOCK>) {
+        let domain_id = register_domain::<T>();
+        for i in 0..n {
+            register_operator_with_seed::<T>(domain_id, i + 1, T::MinNominatorStake::get());
+        }
+        do_finalize_domain_current_epoch::<T>(domain_id)
+            .expect("finalize domain staking should success");
+
+        let domain_owner = DomainRegistry::<T>::get(domain_id)
+            .expect("domain object must exist")
+            .owner_account_id;
+        assert_ok!(Domains::<T>::deactivate_domain(
+            RawOrigin::Signed(domain_owner).into(),
+            domain_id,
+        ));
+        let challenge_period_end = match DomainSunsets::<T>::get(domain_id) {
+            Some(DomainSunsetStatus::Deactivated {
+                challenge_period_end,
+            }) => challenge_period_end,
+            _ => panic!("domain must be deactivated"),
+        };
+        System::<T>::set_block_number(challenge_period_end);
+
+        // Confirm the remaining receipts so the next step sunsets the domain
+        let caller = account("caller", 1, SEED);
+        while next_sunset_receipt::<T>(domain_id).is_some() {
+            assert_ok!(Domains::<T>::advance_domain_sunset(
+                RawOrigin::Signed(caller.clone()).into(),
+                domain_id,
+            ));
+        }
+
+        #[extrinsic_call]
+        advance_domain_sunset(RawOrigin::Signed(caller), domain_id);
+
+        assert!(Domains::<T>::is_domain_sunset(domain_id));
+        assert!(DomainRegistry::<T>::get(domain_id).is_none());
+    }
+
     fn register_runtime<T: Config>() -> RuntimeId {
         let genesis_storage = include_bytes!("../res/evm-domain-genesis-storage").to_vec();
         let runtime_id = NextRuntimeId::<T>::get();
@@ -311,83 +311,7 @@ pub(crate) fn process_execution_receipt<T: Config>(
             if let Some(to_prune) =
                 receipt_block_number.checked_sub(&T::BlockTreePruningDepth::get())
             {
-                let BlockTreeNode {
-                    execution_receipt,
-                    operator_ids,
-                } = match prune_receipt::<T>(domain_id, to_prune)? {
-                    Some(n) => n,
-                    // The receipt at `to_prune` may already been pruned if there is fraud proof being
-                    // processed previously and the `HeadReceiptNumber` is reverted.
-                    None => return Ok(None),
-                };
-
-                // Collect the paid bundle storage fees and the invalid bundle author
-                let mut paid_bundle_storage_fees = BTreeMap::new();
-                let mut invalid_bundle_authors = Vec::new();
-                let bundle_digests = ExecutionInbox::<T>::get((
-                    domain_id,
-                    to_prune,
-                    execution_receipt.consensus_block_number,
-                ));
-                for (index, bd) in bundle_digests.into_iter().enumerate() {
-                    if let Some(bundle_author) = InboxedBundleAuthor::<T>::take(bd.header_hash) {
-                        // It is okay to index `ER::bundles` here since `verify_execution_receipt` have checked
-                        // the `ER::bundles` have the same length of `ExecutionInbox`
-                        if execution_receipt.inboxed_bundles[index].is_invalid() {
-                            invalid_bundle_authors.push(bundle_author);
-                        } else {
-                            paid_bundle_storage_fees
-                                .entry(bundle_author)
-                                .and_modify(|s| *s += bd.size)
-                                .or_insert(bd.size);
-                        }
-                    }
-                }
-
-                // Remove the block's `ExecutionInbox` as the domain block is confirmed and no need to verify
-                // its receipt's `extrinsics_root` anymore.
-                let _ = ExecutionInbox::<T>::clear_prefix((domain_id, to_prune), u32::MAX, None);
-
-                ConsensusBlockHash::<T>::remove(
-                    domain_id,
-                    execution_receipt.consensus_block_number,
-                );
-
-                let block_fees = execution_receipt
-                    .block_fees
-                    .total_fees()
-                    .ok_or(Error::BalanceOverflow)?;
-
-                ensure!(
-                    execution_receipt
-                        .transfers
-                        .is_valid(ChainId::Domain(domain_id)),
-                    Error::InvalidDomainTransfers
-                );
-
-                update_domain_transfers::<T>(domain_id, &execution_receipt.transfers, block_fees)
-                    .map_err(|_| Error::DomainTransfersTracking)?;
-
-                LatestConfirmedDomainBlock::<T>::insert(
-                    domain_id,
-                    ConfirmedDomainBlock {
-                        block_number: to_prune,
-                        block_hash: execution_receipt.domain_block_hash,
-                        parent_block_receipt_hash: execution_receipt
-                            .parent_domain_block_receipt_hash,
-                        state_root: execution_receipt.final_state_root,
-                        extrinsics_root: execution_receipt.domain_block_extrinsic_root,
-                    },
-                );
-
-                return Ok(Some(ConfirmedDomainBlockInfo {
-                    domain_block_number: to_prune,
-                    operator_ids,
-                    rewards: execution_receipt.block_fees.domain_execution_fee,
-                    invalid_bundle_authors,
-                    total_storage_fee: execution_receipt.block_fees.consensus_storage_fee,
-                    paid_bundle_storage_fees,
-                }));
+                return confirm_domain_block::<T>(domain_id, to_prune);
             }
         }
         AcceptedReceiptType::CurrentHead => {
@@ -411,6 +335,87 @@ pub(crate) fn process_execution_receipt<T: Config>(
     Ok(None)
 }
 
+/// Confirms the domain block at `to_confirm` by pruning its receipt from the block tree.
+/// Returns the info of the confirmed domain block, if any
+pub(crate) fn confirm_domain_block<T: Config>(
+    domain_id: DomainId,
+    to_confirm: DomainBlockNumberFor<T>,
+) -> ProcessExecutionReceiptResult<T> {
+    let BlockTreeNode {
+        execution_receipt,
+        operator_ids,
+    } = match prune_receipt::<T>(domain_id, to_confirm)? {
+        Some(n) => n,
+        // The receipt at `to_confirm` may already been pruned if there is fraud proof being
+        // processed previously and the `HeadReceiptNumber` is reverted.
+        None => return Ok(None),
+    };
+
+    // Collect the paid bundle storage fees and the invalid bundle author
+    let mut paid_bundle_storage_fees = BTreeMap::new();
+    let mut invalid_bundle_authors = Vec::new();
+    let bundle_digests = ExecutionInbox::<T>::get((
+        domain_id,
+        to_confirm,
+        execution_receipt.consensus_block_number,
+    ));
+    for (index, bd) in bundle_digests.into_iter().enumerate() {
+        if let Some(bundle_author) = InboxedBundleAuthor::<T>::take(bd.header_hash) {
+            // It is okay to index `ER::bundles` here since `verify_execution_receipt` have checked
+            // the `ER::bundles` have the same length of `ExecutionInbox`
+            if execution_receipt.inboxed_bundles[index].is_invalid() {
+                invalid_bundle_authors.push(bundle_author);
+            } else {
+                paid_bundle_storage_fees
+                    .entry(bundle_author)
+                    .and_modify(|s| *s += bd.size)
+                    .or_insert(bd.size);
+            }
+        }
+    }
+
+    // Remove the block's `ExecutionInbox` as the domain block is confirmed and no need to verify
+    // its receipt's `extrinsics_root` anymore.
+    let _ = ExecutionInbox::<T>::clear_prefix((domain_id, to_confirm), u32::MAX, None);
+
+    ConsensusBlockHash::<T>::remove(domain_id, execution_receipt.consensus_block_number);
+
+    let block_fees = execution_receipt
+        .block_fees
+        .total_fees()
+        .ok_or(Error::BalanceOverflow)?;
+
+    ensure!(
+        execution_receipt
+            .transfers
+            .is_valid(ChainId::Domain(domain_id)),
+        Error::InvalidDomainTransfers
+    );
+
+    update_domain_transfers::<T>(domain_id, &execution_receipt.transfers, block_fees)
+        .map_err(|_| Error::DomainTransfersTracking)?;
+
+    LatestConfirmedDomainBlock::<T>::insert(
+        domain_id,
+        ConfirmedDomainBlock {
+            block_number: to_confirm,
+            block_hash: execution_receipt.domain_block_hash,
+            parent_block_receipt_hash: execution_receipt.parent_domain_block_receipt_hash,
+            state_root: execution_receipt.final_state_root,
+            extrinsics_root: execution_receipt.domain_block_extrinsic_root,
+        },
+    );
+
+    Ok(Some(ConfirmedDomainBlockInfo {
+        domain_block_number: to_confirm,
+        operator_ids,
+        rewards: execution_receipt.block_fees.domain_execution_fee,
+        invalid_bundle_authors,
+        total_storage_fee: execution_receipt.block_fees.consensus_storage_fee,
+        paid_bundle_storage_fees,
+    }))
+}
+
 type TransferTrackerError<T> =
     <<T as Config>::DomainsTransfersTracker as DomainsTransfersTracker<BalanceOf<T>>>::Error;
 
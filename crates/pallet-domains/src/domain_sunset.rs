@@ -0,0 +1,181 @@
+//! Domain sunset
+//!
+//! A deactivated domain doesn't accept bundles anymore, but its receipts can still be challenged
+//! by fraud proofs until the end of the challenge period. After that the remaining receipts are
+//! pruned (if they are bad) or confirmed one at a time, then once the transfers from the domain
+//! are settled, the stake of the domain is unlocked and the domain state is pruned. The transfers
+//! to the domain are not waited on, they are cancelled since the domain will never process them,
+//! and new transfers and messages to the deactivated domain are rejected by the messenger.
+
+use crate::pallet::{
+    BlockTree, BlockTreeNodes, ConsensusBlockHash, DomainRegistry, DomainStakingSummary,
+    DomainSunsets, DomainTxRangeState, ExecutionInbox, HeadDomainNumber, HeadReceiptExtended,
+    HeadReceiptNumber, InboxedBundleAuthor, LastEpochStakingDistribution,
+    LatestConfirmedDomainBlock, PendingOperatorConfigUpdates, PendingOperatorSwitches,
+    PendingRedelegations, PendingSlashes, PendingStakingOperationCount,
+    PreviousOperatorSigningKeys, RedelegationLiabilities,
+};
+use crate::{Config, DomainBlockNumberFor, HoldIdentifier, Pallet};
+use codec::{Decode, Encode};
+use frame_support::traits::fungible::MutateHold;
+use frame_support::traits::tokens::Precision;
+use frame_support::{ensure, PalletError};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::TypeInfo;
+use sp_core::Get;
+use sp_domains::DomainId;
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::{SaturatedConversion, Saturating};
+
+/// Domain sunset specific errors
+#[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
+pub enum Error {
+    DomainNotFound,
+    NotDomainOwner,
+    DomainAlreadyDeactivated,
+    DomainNotDeactivated,
+    DomainAlreadySunset,
+    ChallengePeriodNotComplete,
+    UnsettledTransfers,
+    TransfersTracker,
+    ReleaseInstantiationDeposit,
+    MintIntoTreasury,
+}
+
+/// Sunset status of a deactivated domain.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub enum DomainSunsetStatus<ConsensusBlockNumber> {
+    /// The domain doesn't accept bundles anymore, its receipts can be challenged until the
+    /// `challenge_period_end` consensus block, the sunset can be advanced after that.
+    Deactivated {
+        challenge_period_end: ConsensusBlockNumber,
+    },
+    /// The stake of the domain is unlocked and the domain state is pruned.
+    Sunset { sunset_at: ConsensusBlockNumber },
+}
+
+/// Receipt of a deactivated domain that is processed by the next step of the sunset.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SunsetReceipt<DomainNumber> {
+    /// Bad receipt that is pending to prune, its submitters are slashed.
+    Bad(DomainNumber),
+    /// Unconfirmed receipt of the head receipt's branch that is confirmed.
+    Unconfirmed(DomainNumber),
+}
+
+/// Deactivates the domain, `maybe_domain_owner` is `None` if the deactivation is requested by
+/// the governance. Returns the end of the challenge period of the domain.
+pub(crate) fn do_deactivate_domain<T: Config>(
+    maybe_domain_owner: Option<T::AccountId>,
+    domain_id: DomainId,
+    deactivated_at: BlockNumberFor<T>,
+) -> Result<BlockNumberFor<T>, Error> {
+    let domain_obj = DomainRegistry::<T>::get(domain_id).ok_or(Error::DomainNotFound)?;
+    if let Some(domain_owner) = maybe_domain_owner {
+        ensure!(
+            domain_obj.owner_account_id == domain_owner,
+            Error::NotDomainOwner
+        );
+    }
+
+    ensure!(
+        !DomainSunsets::<T>::contains_key(domain_id),
+        Error::DomainAlreadyDeactivated
+    );
+
+    // The domain doesn't produce blocks anymore, thus the challenge period is measured in
+    // consensus blocks.
+    let challenge_period_end = deactivated_at.saturating_add(
+        T::BlockTreePruningDepth::get()
+            .saturated_into::<u32>()
+            .into(),
+    );
+    DomainSunsets::<T>::insert(
+        domain_id,
+        DomainSunsetStatus::Deactivated {
+            challenge_period_end,
+        },
+    );
+
+    Ok(challenge_period_end)
+}
+
+/// Returns the receipt that should be processed by the next step of the sunset, `None` means
+/// all the receipts of the domain are processed.
+pub(crate) fn next_sunset_receipt<T: Config>(
+    domain_id: DomainId,
+) -> Option<SunsetReceipt<DomainBlockNumberFor<T>>> {
+    let head_receipt_number = HeadReceiptNumber::<T>::get(domain_id);
+
+    // Bad receipts are pruned first, they are the receipts above the head receipt that was
+    // reverted by fraud proof. Receipts can't go beyond the head domain block, and the reverted
+    // receipt itself is already pruned so there may be gaps.
+    let mut receipt_number = HeadDomainNumber::<T>::get(domain_id);
+    while receipt_number > head_receipt_number {
+        if BlockTree::<T>::contains_key(domain_id, receipt_number) {
+            return Some(SunsetReceipt::Bad(receipt_number));
+        }
+        receipt_number = receipt_number.saturating_sub(One::one());
+    }
+
+    // The rest of the receipts are confirmed in order, starting from the genesis receipt if it is
+    // not confirmed yet.
+    let oldest_unconfirmed_receipt_number = Pallet::<T>::latest_confirmed_domain_block(domain_id)
+        .map(|(block_number, _)| block_number.saturating_add(One::one()))
+        .unwrap_or_else(Zero::zero);
+    (oldest_unconfirmed_receipt_number <= head_receipt_number
+        && BlockTree::<T>::contains_key(domain_id, oldest_unconfirmed_receipt_number))
+    .then_some(SunsetReceipt::Unconfirmed(
+        oldest_unconfirmed_receipt_number,
+    ))
+}
+
+/// Releases the domain instantiation deposit back to the domain owner.
+pub(crate) fn release_instantiation_deposit<T: Config>(domain_id: DomainId) -> Result<(), Error> {
+    let domain_obj = DomainRegistry::<T>::get(domain_id).ok_or(Error::DomainNotFound)?;
+
+    T::Currency::release_all(
+        &T::HoldIdentifier::domain_instantiation_id(domain_id),
+        &domain_obj.owner_account_id,
+        Precision::Exact,
+    )
+    .map_err(|_| Error::ReleaseInstantiationDeposit)?;
+
+    Ok(())
+}
+
+/// Prunes the block tree, the registry entry and the staking state of the sunset domain.
+///
+/// NOTE: `LatestSubmittedER` is not pruned here since it is removed as the receipts of the domain
+/// are pruned, and the operators and nominators are removed as they are unlocked.
+pub(crate) fn prune_domain_state<T: Config>(domain_id: DomainId) {
+    DomainRegistry::<T>::remove(domain_id);
+    DomainTxRangeState::<T>::remove(domain_id);
+
+    // Block tree
+    for (_, receipt_hash) in BlockTree::<T>::drain_prefix(domain_id) {
+        BlockTreeNodes::<T>::remove(receipt_hash);
+    }
+    // Bundles included after the last receipt of the domain are never confirmed
+    for (_, bundle_digests) in ExecutionInbox::<T>::drain_prefix((domain_id,)) {
+        for bundle_digest in bundle_digests {
+            InboxedBundleAuthor::<T>::remove(bundle_digest.header_hash);
+        }
+    }
+    let _ = ConsensusBlockHash::<T>::clear_prefix(domain_id, u32::MAX, None);
+    HeadReceiptNumber::<T>::remove(domain_id);
+    HeadReceiptExtended::<T>::remove(domain_id);
+    HeadDomainNumber::<T>::remove(domain_id);
+    LatestConfirmedDomainBlock::<T>::remove(domain_id);
+
+    // Staking
+    DomainStakingSummary::<T>::remove(domain_id);
+    LastEpochStakingDistribution::<T>::remove(domain_id);
+    PendingOperatorSwitches::<T>::remove(domain_id);
+    let _ = PendingOperatorConfigUpdates::<T>::clear_prefix(domain_id, u32::MAX, None);
+    PendingRedelegations::<T>::remove(domain_id);
+    RedelegationLiabilities::<T>::remove(domain_id);
+    let _ = PreviousOperatorSigningKeys::<T>::clear_prefix(domain_id, u32::MAX, None);
+    PendingSlashes::<T>::remove(domain_id);
+    PendingStakingOperationCount::<T>::remove(domain_id);
+}
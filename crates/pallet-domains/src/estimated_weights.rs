@@ -0,0 +1,211 @@
+//! Estimated weights for pallet_domains
+//!
+//! NOTE: THESE WEIGHTS ARE HAND-WRITTEN ESTIMATES, NOT BENCHMARK RESULTS.
+//!
+//! They cover the calls and epoch transition steps whose benchmarks in `benchmarking.rs` were
+//! added after `weights.rs` was last generated. Each estimate is derived from its storage accesses
+//! and the benchmarked execution time of the closest function of `weights.rs`, as noted on it.
+//!
+//! Once `weights.rs` is regenerated with the benchmark CLI (see the command in its header) these
+//! functions are part of `WeightInfo`, then this file must be removed along with the
+//! `EstimatedWeightInfo` bound of `Config::WeightInfo`.
+
+use crate::weights::SubstrateWeight;
+use frame_support::weights::constants::ParityDbWeight;
+use frame_support::weights::Weight;
+
+/// Weight functions of pallet_domains that are not benchmarked yet.
+pub trait EstimatedWeightInfo {
+    fn update_operator_config() -> Weight;
+    fn rotate_operator_signing_key() -> Weight;
+    fn redelegate() -> Weight;
+    fn finalize_redelegations(p: u32) -> Weight;
+    fn finalize_operator_config_updates(p: u32) -> Weight;
+    fn deactivate_domain() -> Weight;
+    fn sunset_domain(n: u32) -> Weight;
+}
+
+impl<T: frame_system::Config> EstimatedWeightInfo for SubstrateWeight<T> {
+    fn update_operator_config() -> Weight {
+        estimates::update_operator_config::<T::DbWeight>()
+    }
+    fn rotate_operator_signing_key() -> Weight {
+        estimates::rotate_operator_signing_key::<T::DbWeight>()
+    }
+    fn redelegate() -> Weight {
+        estimates::redelegate::<T::DbWeight>()
+    }
+    fn finalize_redelegations(p: u32) -> Weight {
+        estimates::finalize_redelegations::<T::DbWeight>(p)
+    }
+    fn finalize_operator_config_updates(p: u32) -> Weight {
+        estimates::finalize_operator_config_updates::<T::DbWeight>(p)
+    }
+    fn deactivate_domain() -> Weight {
+        estimates::deactivate_domain::<T::DbWeight>()
+    }
+    fn sunset_domain(n: u32) -> Weight {
+        estimates::sunset_domain::<T::DbWeight>(n)
+    }
+}
+
+// For backwards compatibility and tests
+impl EstimatedWeightInfo for () {
+    fn update_operator_config() -> Weight {
+        estimates::update_operator_config::<ParityDbWeight>()
+    }
+    fn rotate_operator_signing_key() -> Weight {
+        estimates::rotate_operator_signing_key::<ParityDbWeight>()
+    }
+    fn redelegate() -> Weight {
+        estimates::redelegate::<ParityDbWeight>()
+    }
+    fn finalize_redelegations(p: u32) -> Weight {
+        estimates::finalize_redelegations::<ParityDbWeight>(p)
+    }
+    fn finalize_operator_config_updates(p: u32) -> Weight {
+        estimates::finalize_operator_config_updates::<ParityDbWeight>(p)
+    }
+    fn deactivate_domain() -> Weight {
+        estimates::deactivate_domain::<ParityDbWeight>()
+    }
+    fn sunset_domain(n: u32) -> Weight {
+        estimates::sunset_domain::<ParityDbWeight>(n)
+    }
+}
+
+mod estimates {
+    use frame_support::traits::Get;
+    use frame_support::weights::{RuntimeDbWeight, Weight};
+
+    /// Storage: `Domains::OperatorIdOwner` (r:1 w:0)
+    /// Storage: `Domains::Operators` (r:1 w:0)
+    /// Storage: `Domains::LatestSubmittedER` (r:1 w:0)
+    /// Storage: `Domains::PendingOperatorConfigUpdates` (r:1 w:1)
+    /// Storage: `Domains::PendingStakingOperationCount` (r:1 w:1)
+    ///
+    /// Estimated from `deregister_operator`, which does the same ownership and status checks.
+    pub(super) fn update_operator_config<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+        Weight::from_parts(34_000_000, 4414)
+            .saturating_add(DbWeight::get().reads(5_u64))
+            .saturating_add(DbWeight::get().writes(2_u64))
+    }
+
+    /// Storage: `Domains::OperatorSigningKey` (r:1 w:1)
+    /// Storage: `Domains::OperatorIdOwner` (r:1 w:0)
+    /// Storage: `Domains::Operators` (r:1 w:0)
+    /// Storage: `Domains::LatestSubmittedER` (r:1 w:0)
+    /// Storage: `Domains::PendingOperatorConfigUpdates` (r:1 w:1)
+    /// Storage: `Domains::PendingStakingOperationCount` (r:1 w:1)
+    ///
+    /// Estimated from `update_operator_config` plus the signing key check and reservation.
+    pub(super) fn rotate_operator_signing_key<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+        Weight::from_parts(38_000_000, 4414)
+            .saturating_add(DbWeight::get().reads(6_u64))
+            .saturating_add(DbWeight::get().writes(3_u64))
+    }
+
+    /// Storage: `Domains::Operators` (r:2 w:1)
+    /// Storage: `Domains::LatestSubmittedER` (r:2 w:0)
+    /// Storage: `Domains::Deposits` (r:2 w:1)
+    /// Storage: `Domains::NominatorCount` (r:1 w:0)
+    /// Storage: `Domains::PendingStakingOperationCount` (r:1 w:1)
+    /// Storage: `Domains::DomainStakingSummary` (r:1 w:0)
+    /// Storage: `Domains::OperatorEpochSharePrice` (r:1 w:0)
+    /// Storage: `Domains::Withdrawals` (r:1 w:1)
+    /// Storage: `Domains::OperatorIdOwner` (r:1 w:0)
+    /// Storage: `Domains::LatestConfirmedDomainBlock` (r:1 w:0)
+    /// Storage: `Domains::RedelegationLiabilities` (r:1 w:0)
+    /// Storage: `Domains::PendingRedelegations` (r:1 w:1)
+    /// Storage: `System::Account` (r:2 w:2)
+    /// Storage: `Balances::Holds` (r:1 w:1)
+    ///
+    /// Estimated from `withdraw_stake` plus the checks of the new operator.
+    pub(super) fn redelegate<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+        Weight::from_parts(126_000_000, 6215)
+            .saturating_add(DbWeight::get().reads(18_u64))
+            .saturating_add(DbWeight::get().writes(8_u64))
+    }
+
+    /// Storage: `Domains::RedelegationLiabilities` (r:1 w:1)
+    /// Storage: `Domains::PendingRedelegations` (r:1 w:1)
+    /// Storage: `Domains::DomainStakingSummary` (r:1 w:0)
+    /// Storage: `Domains::LatestConfirmedDomainBlock` (r:1 w:0)
+    /// Per redelegation:
+    /// Storage: `Domains::OperatorEpochSharePrice` (r:1 w:0)
+    /// Storage: `Domains::Operators` (r:1 w:1)
+    /// Storage: `Domains::Withdrawals` (r:1 w:1)
+    /// Storage: `Domains::Deposits` (r:2 w:2)
+    /// Storage: `Domains::NominatorCount` (r:1 w:1)
+    /// Storage: `System::Account` (r:2 w:2)
+    /// Storage: `Balances::Holds` (r:1 w:1)
+    ///
+    /// Execution time per redelegation estimated from `withdraw_stake` and `nominate_operator`.
+    pub(super) fn finalize_redelegations<DbWeight: Get<RuntimeDbWeight>>(p: u32) -> Weight {
+        Weight::from_parts(10_000_000, 4657)
+            .saturating_add(Weight::from_parts(237_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(4_u64))
+            .saturating_add(DbWeight::get().reads((9_u64).saturating_mul(p.into())))
+            .saturating_add(DbWeight::get().writes(2_u64))
+            .saturating_add(DbWeight::get().writes((8_u64).saturating_mul(p.into())))
+            .saturating_add(Weight::from_parts(0, 6215).saturating_mul(p.into()))
+    }
+
+    /// Storage: `Subspace::BlockSlots` (r:1 w:0)
+    /// Per operator:
+    /// Storage: `Domains::PreviousOperatorSigningKeys` (r:1 w:1)
+    /// Storage: `Domains::OperatorSigningKey` (r:0 w:1)
+    /// Storage: `Domains::PendingOperatorConfigUpdates` (r:1 w:1)
+    /// Storage: `Domains::Operators` (r:1 w:1)
+    ///
+    /// Execution time per operator estimated from `deregister_operator`.
+    pub(super) fn finalize_operator_config_updates<DbWeight: Get<RuntimeDbWeight>>(
+        p: u32,
+    ) -> Weight {
+        Weight::from_parts(10_000_000, 27092)
+            .saturating_add(Weight::from_parts(34_000_000, 0).saturating_mul(p.into()))
+            .saturating_add(DbWeight::get().reads(1_u64))
+            .saturating_add(DbWeight::get().reads((3_u64).saturating_mul(p.into())))
+            .saturating_add(DbWeight::get().writes((4_u64).saturating_mul(p.into())))
+            .saturating_add(Weight::from_parts(0, 4414).saturating_mul(p.into()))
+    }
+
+    /// Storage: `Domains::DomainRegistry` (r:1 w:0)
+    /// Storage: `Domains::DomainSunsets` (r:1 w:1)
+    ///
+    /// Estimated from `update_domain_operator_allow_list`, which does the same domain owner check.
+    pub(super) fn deactivate_domain<DbWeight: Get<RuntimeDbWeight>>() -> Weight {
+        Weight::from_parts(18_000_000, 3897)
+            .saturating_add(DbWeight::get().reads(2_u64))
+            .saturating_add(DbWeight::get().writes(1_u64))
+    }
+
+    /// Storage: `Domains::DomainSunsets` (r:1 w:1)
+    /// Storage: `Domains::HeadReceiptNumber` (r:1 w:1)
+    /// Storage: `Domains::HeadDomainNumber` (r:1 w:1)
+    /// Storage: `Domains::LatestConfirmedDomainBlock` (r:1 w:1)
+    /// Storage: `Domains::BlockTree` (r:1 w:0)
+    /// Storage: `Transporter::UnconfirmedTransfers` (r:2 w:0)
+    /// Storage: `Transporter::DomainBalances` (r:1 w:1)
+    /// Storage: `Domains::DomainStakingSummary` (r:1 w:1)
+    /// Storage: `Domains::DomainRegistry` (r:1 w:1)
+    /// Storage: `Balances::Holds` (r:1 w:1)
+    /// Storage: `System::Account` (r:1 w:1)
+    /// Per operator:
+    /// Storage: `Domains::Operators` (r:1 w:1)
+    /// Storage: `Domains::LatestSubmittedER` (r:1 w:0)
+    ///
+    /// Execution time estimated from `unlock_funds` for the instantiation deposit and the remaining
+    /// domain balance minted into the treasury, plus `deregister_operator` per operator. The
+    /// receipts of the domain are processed separately, weighted with `handle_bad_receipt` and
+    /// `confirm_domain_block`.
+    pub(super) fn sunset_domain<DbWeight: Get<RuntimeDbWeight>>(n: u32) -> Weight {
+        Weight::from_parts(93_000_000, 6215)
+            .saturating_add(Weight::from_parts(34_000_000, 0).saturating_mul(n.into()))
+            .saturating_add(DbWeight::get().reads(12_u64))
+            .saturating_add(DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+            .saturating_add(DbWeight::get().writes(9_u64))
+            .saturating_add(DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+            .saturating_add(Weight::from_parts(0, 4414).saturating_mul(n.into()))
+    }
+}
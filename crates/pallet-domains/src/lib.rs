@@ -27,6 +27,8 @@ mod tests;
 pub mod block_tree;
 mod bundle_storage_fund;
 pub mod domain_registry;
+mod domain_sunset;
+pub mod estimated_weights;
 pub mod runtime_registry;
 mod staking;
 mod staking_epoch;
@@ -34,15 +36,29 @@ pub mod weights;
 
 extern crate alloc;
 
-use crate::block_tree::verify_execution_receipt;
-use crate::bundle_storage_fund::storage_fund_account;
+use crate::block_tree::{
+    confirm_domain_block, prune_receipt, verify_execution_receipt, ConfirmedDomainBlockInfo,
+    Error as BlockTreeError,
+};
+use crate::bundle_storage_fund::{refund_storage_fee, storage_fund_account};
 use crate::domain_registry::Error as DomainRegistryError;
-use crate::staking::OperatorStatus;
-use crate::staking_epoch::EpochTransitionResult;
+use crate::domain_sunset::{
+    next_sunset_receipt, prune_domain_state, release_instantiation_deposit, DomainSunsetStatus,
+    Error as DomainSunsetError, SunsetReceipt,
+};
+use crate::staking::{
+    do_deregister_domain_operators, do_reward_operators, do_slash_operators, OperatorStatus,
+};
+use crate::staking_epoch::{
+    do_finalize_domain_current_epoch, mint_into_treasury, EpochTransitionResult,
+};
+use crate::estimated_weights::EstimatedWeightInfo;
 use crate::weights::WeightInfo;
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
 use alloc::collections::btree_map::BTreeMap;
+use alloc::format;
+use alloc::string::String;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use codec::{Decode, Encode};
@@ -56,13 +72,15 @@ use frame_system::offchain::SubmitTransaction;
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
 use scale_info::TypeInfo;
+use sp_consensus_slots::Slot;
 use sp_consensus_subspace::consensus::is_proof_of_time_valid;
 use sp_consensus_subspace::WrappedPotOutput;
 use sp_core::H256;
 use sp_domains::bundle_producer_election::BundleProducerElectionParams;
+use sp_domains::staking::{NominatorPosition, OperatorSummary};
 use sp_domains::{
-    DomainBlockLimit, DomainBundleLimit, DomainId, DomainInstanceData, ExecutionReceipt,
-    OpaqueBundle, OperatorId, OperatorPublicKey, RuntimeId,
+    DomainBlockLimit, DomainBundleLimit, DomainId, DomainInstanceData, DomainsTransfersTracker,
+    ExecutionReceipt, OpaqueBundle, OperatorId, OperatorPublicKey, RuntimeId,
     DOMAIN_EXTRINSICS_SHUFFLING_SEED_SUBJECT, EMPTY_EXTRINSIC_ROOT,
 };
 use sp_domains_fraud_proof::fraud_proof::{
@@ -74,10 +92,11 @@ use sp_domains_fraud_proof::verification::{
     verify_invalid_domain_extrinsics_root_fraud_proof, verify_invalid_state_transition_fraud_proof,
     verify_invalid_transfers_fraud_proof, verify_valid_bundle_fraud_proof,
 };
+use sp_domains_fraud_proof::FraudProofValidationError;
 use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Hash, Header, One, Zero};
 use sp_runtime::transaction_validity::TransactionPriority;
 use sp_runtime::{RuntimeAppPublic, SaturatedConversion, Saturating};
-pub use staking::OperatorConfig;
+pub use staking::{OperatorConfig, RedelegationFailure};
 use subspace_core_primitives::{BlockHash, PotOutput, SlotNumber, U256};
 use subspace_runtime_primitives::Balance;
 
@@ -130,6 +149,32 @@ pub(crate) enum FraudProofTag {
     BundleEquivocation(OperatorId),
 }
 
+/// Failure of the fraud proof validation, with the reason if the fraud proof failed the
+/// verification of its proof.
+#[derive(Debug)]
+pub(crate) struct FraudProofValidationFailure {
+    error: FraudProofError,
+    reason: Option<String>,
+}
+
+impl FraudProofValidationFailure {
+    fn verification_failed<E: core::fmt::Debug>(error: FraudProofError, err: E) -> Self {
+        Self {
+            error,
+            reason: Some(format!("{err:?}")),
+        }
+    }
+}
+
+impl From<FraudProofError> for FraudProofValidationFailure {
+    fn from(error: FraudProofError) -> Self {
+        Self {
+            error,
+            reason: None,
+        }
+    }
+}
+
 pub type DomainBlockNumberFor<T> = <<T as Config>::DomainHeader as Header>::Number;
 pub type DomainHashingFor<T> = <<T as Config>::DomainHeader as Header>::Hashing;
 pub type ReceiptHashFor<T> = <<T as Config>::DomainHeader as Header>::Hash;
@@ -160,25 +205,27 @@ mod pallet {
     };
     #[cfg(not(feature = "runtime-benchmarks"))]
     use crate::block_tree::{prune_receipt, AcceptedReceiptType};
-    #[cfg(not(feature = "runtime-benchmarks"))]
-    use crate::bundle_storage_fund::refund_storage_fee;
     use crate::bundle_storage_fund::{charge_bundle_storage_fee, Error as BundleStorageFundError};
     use crate::domain_registry::{
         do_instantiate_domain, do_update_domain_allow_list, DomainConfig, DomainObject,
         Error as DomainRegistryError,
     };
+    use crate::domain_sunset::{
+        do_deactivate_domain, DomainSunsetStatus, Error as DomainSunsetError,
+    };
     use crate::runtime_registry::{
         do_register_runtime, do_schedule_runtime_upgrade, do_upgrade_runtimes,
         register_runtime_at_genesis, Error as RuntimeRegistryError, RuntimeObject,
         ScheduledRuntimeUpgrade,
     };
-    #[cfg(not(feature = "runtime-benchmarks"))]
-    use crate::staking::do_reward_operators;
     use crate::staking::{
-        do_deregister_operator, do_nominate_operator, do_register_operator, do_slash_operators,
-        do_unlock_funds, do_unlock_operator, do_withdraw_stake, Deposit, DomainEpoch,
-        Error as StakingError, Operator, OperatorConfig, SharePrice, StakingSummary, Withdrawal,
+        do_deregister_operator, do_nominate_operator, do_redelegate, do_register_operator,
+        do_rotate_operator_signing_key, do_slash_operators, do_unlock_funds, do_unlock_operator,
+        do_update_operator_config, do_withdraw_stake, Deposit, DomainEpoch, Error as StakingError,
+        Operator, OperatorConfig, OperatorConfigUpdate, PendingRedelegation, RedelegationFailure,
+        RedelegationLiability, SharePrice, StakingSummary, Withdrawal,
     };
+    use crate::estimated_weights::EstimatedWeightInfo;
     use crate::staking_epoch::{do_finalize_domain_current_epoch, Error as StakingEpochError};
     use crate::weights::WeightInfo;
     #[cfg(not(feature = "runtime-benchmarks"))]
@@ -216,7 +263,7 @@ mod pallet {
         AtLeast32BitUnsigned, BlockNumberProvider, CheckEqual, CheckedAdd, Header as HeaderT,
         MaybeDisplay, One, SimpleBitOps, Zero,
     };
-    use sp_runtime::Saturating;
+    use sp_runtime::{Percent, Saturating};
     use sp_std::boxed::Box;
     use sp_std::collections::btree_set::BTreeSet;
     use sp_std::fmt::Debug;
@@ -323,7 +370,7 @@ mod pallet {
         type DomainInstantiationDeposit: Get<BalanceOf<Self>>;
 
         /// Weight information for extrinsics in this pallet.
-        type WeightInfo: WeightInfo;
+        type WeightInfo: WeightInfo + EstimatedWeightInfo;
 
         /// Initial domain tx range value.
         #[pallet::constant]
@@ -473,6 +520,56 @@ mod pallet {
     pub(super) type PendingOperatorSwitches<T: Config> =
         StorageMap<_, Identity, DomainId, BTreeSet<OperatorId>, OptionQuery>;
 
+    /// Temporary hold of the operator configuration updates requested in the current epoch.
+    /// Once epoch is complete, these updates are applied to the operators.
+    #[pallet::storage]
+    pub(super) type PendingOperatorConfigUpdates<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        DomainId,
+        Identity,
+        OperatorId,
+        OperatorConfigUpdate<BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Stake redelegations requested in the current epoch of the domain, the redelegated stake is
+    /// deposited to the new operators at the epoch transition.
+    #[pallet::storage]
+    pub(super) type PendingRedelegations<T: Config> = StorageMap<
+        _,
+        Identity,
+        DomainId,
+        Vec<PendingRedelegation<NominatorId<T>, DomainBlockNumberFor<T>, T::Share, BalanceOf<T>>>,
+        ValueQuery,
+    >;
+
+    /// Redelegated stake of the domain that is still slashable for the previous operators, they
+    /// are removed once their unlocking period is complete.
+    #[pallet::storage]
+    pub(super) type RedelegationLiabilities<T: Config> = StorageMap<
+        _,
+        Identity,
+        DomainId,
+        Vec<RedelegationLiability<NominatorId<T>, DomainBlockNumberFor<T>, BalanceOf<T>>>,
+        ValueQuery,
+    >;
+
+    /// Signing keys of the operators that were rotated at the last epoch transition of the domain,
+    /// along with the first slot the new signing key is used for.
+    /// These keys are still accepted during the current epoch, but only for the bundles of slots
+    /// before the rotation and for the bundle equivocation fraud proofs against such bundles.
+    #[pallet::storage]
+    pub(super) type PreviousOperatorSigningKeys<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        DomainId,
+        Identity,
+        OperatorId,
+        (OperatorPublicKey, Slot),
+        OptionQuery,
+    >;
+
     /// Share price for the operator pool at the end of Domain epoch.
     // TODO: currently unbounded storage.
     #[pallet::storage]
@@ -654,6 +751,12 @@ mod pallet {
     #[pallet::storage]
     pub(super) type AccumulatedTreasuryFunds<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+    /// Sunset status of the deactivated domains, the entry is kept after the sunset is complete
+    /// so the stake of the domain can be unlocked.
+    #[pallet::storage]
+    pub(super) type DomainSunsets<T: Config> =
+        StorageMap<_, Identity, DomainId, DomainSunsetStatus<BlockNumberFor<T>>, OptionQuery>;
+
     #[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
     pub enum BundleError {
         /// Can not find the operator for given operator id.
@@ -688,6 +791,8 @@ mod pallet {
         UnableToCalculateBundleLimit,
         /// Bundle weight exceeds the max bundle weight limit
         BundleTooHeavy,
+        /// The domain is deactivated and doesn't accept bundles anymore
+        DomainDeactivated,
     }
 
     #[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
@@ -723,6 +828,8 @@ mod pallet {
         BadBundleEquivocationFraudProof,
         /// The bad receipt already reported by a previous fraud proof
         BadReceiptAlreadyReported,
+        /// The challenge period of the deactivated domain is over
+        ChallengePeriodExpired,
     }
 
     impl<T> From<FraudProofError> for Error<T> {
@@ -776,6 +883,12 @@ mod pallet {
         }
     }
 
+    impl<T> From<DomainSunsetError> for Error<T> {
+        fn from(err: DomainSunsetError) -> Self {
+            Error::DomainSunset(err)
+        }
+    }
+
     #[pallet::error]
     pub enum Error<T> {
         /// Invalid fraud proof.
@@ -794,6 +907,8 @@ mod pallet {
         BundleStorageFund(BundleStorageFundError),
         /// Permissioned action is not allowed by the caller.
         PermissionedActionNotAllowed,
+        /// Domain sunset specific errors
+        DomainSunset(DomainSunsetError),
     }
 
     /// Reason for slashing an operator
@@ -893,6 +1008,33 @@ mod pallet {
             nominator_id: NominatorId<T>,
             amount: BalanceOf<T>,
         },
+        OperatorConfigUpdateScheduled {
+            operator_id: OperatorId,
+            domain_id: DomainId,
+        },
+        OperatorSigningKeyRotationScheduled {
+            operator_id: OperatorId,
+            domain_id: DomainId,
+        },
+        StakeRedelegated {
+            nominator_id: NominatorId<T>,
+            from_operator_id: OperatorId,
+            to_operator_id: OperatorId,
+        },
+        RedelegationFailed {
+            nominator_id: NominatorId<T>,
+            from_operator_id: OperatorId,
+            to_operator_id: OperatorId,
+            reason: RedelegationFailure,
+        },
+        DomainDeactivated {
+            domain_id: DomainId,
+            challenge_period_end: BlockNumberFor<T>,
+        },
+        DomainSunset {
+            domain_id: DomainId,
+            remaining_balance: BalanceOf<T>,
+        },
     }
 
     /// Per-domain state for tx range calculation.
@@ -995,47 +1137,9 @@ mod pallet {
                     // `submit_bundle` call, these operations will be benchmarked separately.
                     #[cfg(not(feature = "runtime-benchmarks"))]
                     if let Some(confirmed_block_info) = maybe_confirmed_domain_block_info {
-                        actual_weight =
-                            actual_weight.saturating_add(T::WeightInfo::confirm_domain_block(
-                                confirmed_block_info.operator_ids.len() as u32,
-                                confirmed_block_info.invalid_bundle_authors.len() as u32,
-                            ));
-
-                        refund_storage_fee::<T>(
-                            confirmed_block_info.total_storage_fee,
-                            confirmed_block_info.paid_bundle_storage_fees,
-                        )
-                        .map_err(Error::<T>::from)?;
-
-                        do_reward_operators::<T>(
-                            domain_id,
-                            confirmed_block_info.operator_ids.into_iter(),
-                            confirmed_block_info.rewards,
-                        )
-                        .map_err(Error::<T>::from)?;
-
-                        do_slash_operators::<T>(
-                            confirmed_block_info.invalid_bundle_authors.into_iter(),
-                            SlashedReason::InvalidBundle(confirmed_block_info.domain_block_number),
-                        )
-                        .map_err(Error::<T>::from)?;
-
-                        if confirmed_block_info.domain_block_number % T::StakeEpochDuration::get()
-                            == Zero::zero()
-                        {
-                            let epoch_transition_res =
-                                do_finalize_domain_current_epoch::<T>(domain_id)
-                                    .map_err(Error::<T>::from)?;
-
-                            Self::deposit_event(Event::DomainEpochCompleted {
-                                domain_id,
-                                completed_epoch_index: epoch_transition_res.completed_epoch_index,
-                            });
-
-                            actual_weight = actual_weight.saturating_add(
-                                Self::actual_epoch_transition_weight(epoch_transition_res),
-                            );
-                        }
+                        actual_weight = actual_weight.saturating_add(
+                            Self::process_confirmed_domain_block(domain_id, confirmed_block_info)?,
+                        );
                     }
                 }
             }
@@ -1414,6 +1518,129 @@ mod pallet {
             PermissionedActionAllowedBy::<T>::put(permissioned_action_allowed_by);
             Ok(())
         }
+
+        /// Update the nomination tax and minimum nominator stake of the operator.
+        /// The new configuration takes effect from the next epoch.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::update_operator_config())]
+        pub fn update_operator_config(
+            origin: OriginFor<T>,
+            operator_id: OperatorId,
+            minimum_nominator_stake: BalanceOf<T>,
+            nomination_tax: Percent,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let domain_id = do_update_operator_config::<T>(
+                who,
+                operator_id,
+                minimum_nominator_stake,
+                nomination_tax,
+            )
+            .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::OperatorConfigUpdateScheduled {
+                operator_id,
+                domain_id,
+            });
+
+            Ok(())
+        }
+
+        /// Rotate the signing key of the operator.
+        /// The new key takes effect from the next epoch, while the old key is still accepted during
+        /// that epoch for the bundles signed before the rotation.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::rotate_operator_signing_key())]
+        pub fn rotate_operator_signing_key(
+            origin: OriginFor<T>,
+            operator_id: OperatorId,
+            new_signing_key: OperatorPublicKey,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let domain_id = do_rotate_operator_signing_key::<T>(who, operator_id, new_signing_key)
+                .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::OperatorSigningKeyRotationScheduled {
+                operator_id,
+                domain_id,
+            });
+
+            Ok(())
+        }
+
+        /// Move the stake of the nominator from one operator to another operator of the same
+        /// domain without unlocking and nominating it again.
+        /// The shares are withdrawn in the current epoch and deposited to the new operator at the
+        /// epoch transition, if the deposit is not possible by then the shares are left as a
+        /// regular withdrawal of the previous operator and `RedelegationFailed` is emitted.
+        /// Until the unlocking period is complete the redelegated stake can't be withdrawn from the
+        /// new operator and is slashed if the previous operator is slashed.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::redelegate())]
+        pub fn redelegate(
+            origin: OriginFor<T>,
+            from_operator_id: OperatorId,
+            to_operator_id: OperatorId,
+            shares: T::Share,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            do_redelegate::<T>(who.clone(), from_operator_id, to_operator_id, shares)
+                .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::StakeRedelegated {
+                nominator_id: who,
+                from_operator_id,
+                to_operator_id,
+            });
+
+            Ok(())
+        }
+
+        /// Deactivate the domain to start its sunset, can be called by the domain owner or root.
+        /// The domain stops accepting bundles and new stake right away, while its receipts can still
+        /// be challenged by fraud proofs during the challenge period.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::deactivate_domain())]
+        pub fn deactivate_domain(origin: OriginFor<T>, domain_id: DomainId) -> DispatchResult {
+            let maybe_domain_owner = ensure_signed_or_root(origin)?;
+
+            let deactivated_at = frame_system::Pallet::<T>::current_block_number();
+            let challenge_period_end =
+                do_deactivate_domain::<T>(maybe_domain_owner, domain_id, deactivated_at)
+                    .map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::DomainDeactivated {
+                domain_id,
+                challenge_period_end,
+            });
+
+            Ok(())
+        }
+
+        /// Advance the sunset of the deactivated domain by one step once its challenge period is
+        /// over. Anyone can advance the sunset.
+        ///
+        /// Every step either prunes a bad receipt and slashes its submitters, or confirms the oldest
+        /// unconfirmed receipt of the domain. Once all the receipts are processed and the transfers
+        /// initiated from the domain are settled, the last step finalizes the current epoch,
+        /// deregisters all the operators so their stake can be unlocked with `unlock_operator`,
+        /// releases the instantiation deposit and prunes the domain state.
+        #[pallet::call_index(19)]
+        #[pallet::weight(Pallet::<T>::max_advance_domain_sunset_weight())]
+        pub fn advance_domain_sunset(
+            origin: OriginFor<T>,
+            domain_id: DomainId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+
+            let actual_weight = Self::do_advance_domain_sunset(domain_id)?;
+
+            // Ensure the returned weight not exceed the maximum weight in the `pallet::weight`
+            Ok(Some(actual_weight.min(Self::max_advance_domain_sunset_weight())).into())
+        }
     }
 
     #[pallet::genesis_config]
@@ -1712,10 +1939,43 @@ impl<T: Config> Pallet<T> {
             .map(|operator| (operator.signing_key, operator.current_total_stake))
     }
 
+    /// Returns the signing key the bundles of the operator at `slot` must be signed with: the key
+    /// rotated at the last epoch transition of the domain if `slot` is before the rotation and
+    /// the current signing key otherwise.
+    ///
+    /// Only one key is ever valid for a given slot, otherwise the operator would have two chances
+    /// to win the bundle election in the same slot.
+    pub(crate) fn bundle_signing_key(
+        domain_id: DomainId,
+        operator_id: OperatorId,
+        signing_key: OperatorPublicKey,
+        slot: Slot,
+    ) -> OperatorPublicKey {
+        match PreviousOperatorSigningKeys::<T>::get(domain_id, operator_id) {
+            Some((previous_signing_key, rotated_at)) if slot < rotated_at => previous_signing_key,
+            _ => signing_key,
+        }
+    }
+
+    /// Returns all the signing keys of the operator that are still known: the current signing key
+    /// and the key rotated at the last epoch transition of the domain, if any.
+    pub(crate) fn operator_signing_keys(
+        domain_id: DomainId,
+        operator_id: OperatorId,
+        signing_key: OperatorPublicKey,
+    ) -> Vec<OperatorPublicKey> {
+        sp_std::iter::once(signing_key)
+            .chain(
+                PreviousOperatorSigningKeys::<T>::get(domain_id, operator_id)
+                    .map(|(previous_signing_key, _rotated_at)| previous_signing_key),
+            )
+            .collect()
+    }
+
     fn check_bundle_duplication(opaque_bundle: &OpaqueBundleOf<T>) -> Result<(), BundleError> {
         // NOTE: it is important to use the hash that not incliude the signature, otherwise
-        // the malicious operator may update its `signing_key` (this may support in the future)
-        // and sign an existing bundle thus creating a duplicated bundle and pass the check.
+        // the malicious operator may rotate its `signing_key` and sign an existing bundle
+        // thus creating a duplicated bundle and pass the check.
         let bundle_header_hash = opaque_bundle.sealed_header.pre_hash();
         ensure!(
             !InboxedBundleAuthor::<T>::contains_key(bundle_header_hash),
@@ -1811,6 +2071,11 @@ impl<T: Config> Pallet<T> {
         let operator_id = opaque_bundle.operator_id();
         let sealed_header = &opaque_bundle.sealed_header;
 
+        ensure!(
+            !Self::is_domain_deactivated(domain_id),
+            BundleError::DomainDeactivated
+        );
+
         let operator = Operators::<T>::get(operator_id).ok_or(BundleError::InvalidOperatorId)?;
 
         ensure!(
@@ -1819,12 +2084,17 @@ impl<T: Config> Pallet<T> {
             BundleError::BadOperator
         );
 
-        if !operator
-            .signing_key
-            .verify(&sealed_header.pre_hash(), &sealed_header.signature)
-        {
-            return Err(BundleError::BadBundleSignature);
-        }
+        let bundle_header_hash = sealed_header.pre_hash();
+        let signing_key = Self::bundle_signing_key(
+            domain_id,
+            operator_id,
+            operator.signing_key.clone(),
+            sealed_header.slot_number().into(),
+        );
+        ensure!(
+            signing_key.verify(&bundle_header_hash, &sealed_header.signature),
+            BundleError::BadBundleSignature
+        );
 
         Self::check_bundle_duplication(opaque_bundle)?;
 
@@ -1861,7 +2131,7 @@ impl<T: Config> Pallet<T> {
         )?;
 
         sp_domains::bundle_producer_election::check_proof_of_election(
-            &operator.signing_key,
+            &signing_key,
             domain_config.bundle_slot_probability,
             proof_of_election,
             operator_stake.saturated_into(),
@@ -1877,6 +2147,30 @@ impl<T: Config> Pallet<T> {
     fn validate_fraud_proof(
         fraud_proof: &FraudProof<BlockNumberFor<T>, T::Hash, T::DomainHeader>,
     ) -> Result<(FraudProofTag, TransactionPriority), FraudProofError> {
+        Self::do_validate_fraud_proof(fraud_proof).map_err(|failure| failure.error)
+    }
+
+    /// Validate the fraud proof in the same way as the `submit_fraud_proof` extrinsic, return the
+    /// failed check and the reason if the fraud proof is invalid.
+    pub fn check_fraud_proof(
+        fraud_proof: &FraudProof<BlockNumberFor<T>, T::Hash, T::DomainHeader>,
+    ) -> Result<(), FraudProofValidationError> {
+        Self::do_validate_fraud_proof(fraud_proof)
+            .map(|_| ())
+            .map_err(|failure| FraudProofValidationError {
+                check: format!("{:?}", failure.error),
+                reason: failure.reason,
+            })
+    }
+
+    fn do_validate_fraud_proof(
+        fraud_proof: &FraudProof<BlockNumberFor<T>, T::Hash, T::DomainHeader>,
+    ) -> Result<(FraudProofTag, TransactionPriority), FraudProofValidationFailure> {
+        ensure!(
+            !Self::is_challenge_period_over(fraud_proof.domain_id()),
+            FraudProofError::ChallengePeriodExpired
+        );
+
         let tag_and_priority = if let Some(bad_receipt_hash) =
             fraud_proof.targeted_bad_receipt_hash()
         {
@@ -1912,7 +2206,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Block fees proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidBlockFeesFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidBlockFeesFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::InvalidTransfers(req) => {
@@ -1928,7 +2225,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Domain transfers proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidTransfersFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidTransfersFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::InvalidDomainBlockHash(InvalidDomainBlockHashProof {
@@ -1953,7 +2253,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Invalid Domain block hash proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidDomainBlockHashFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidDomainBlockHashFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::InvalidExtrinsicsRoot(proof) => {
@@ -1968,7 +2271,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Invalid Domain extrinsic root proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidExtrinsicRootFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidExtrinsicRootFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::InvalidStateTransition(proof) => {
@@ -1987,7 +2293,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Invalid State transition proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidStateTransitionFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidStateTransitionFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::InvalidBundles(invalid_bundles_fraud_proof) => {
@@ -2006,7 +2315,10 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Invalid Bundle proof verification failed: {err:?}"
                         );
-                        FraudProofError::InvalidBundleFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::InvalidBundleFraudProof,
+                            err,
+                        )
                     })?;
                 }
                 FraudProof::ValidBundle(proof) => verify_valid_bundle_fraud_proof::<
@@ -2020,9 +2332,12 @@ impl<T: Config> Pallet<T> {
                         target: "runtime::domains",
                         "Valid bundle proof verification failed: {err:?}"
                     );
-                    FraudProofError::BadValidBundleFraudProof
+                    FraudProofValidationFailure::verification_failed(
+                        FraudProofError::BadValidBundleFraudProof,
+                        err,
+                    )
                 })?,
-                _ => return Err(FraudProofError::UnexpectedFraudProof),
+                _ => return Err(FraudProofError::UnexpectedFraudProof.into()),
             }
 
             // The priority of fraud proof is determined by how many blocks left before the bad ER
@@ -2045,9 +2360,15 @@ impl<T: Config> Pallet<T> {
                 Operators::<T>::get(bad_operator_id).ok_or(FraudProofError::MissingOperator)?;
             match fraud_proof {
                 FraudProof::BundleEquivocation(proof) => {
-                    let operator_signing_key = operator.signing_key;
+                    // The equivocated bundles may be signed with the key that was rotated at the
+                    // last epoch transition, each bundle may be signed with any of the keys.
+                    let operator_signing_keys = Self::operator_signing_keys(
+                        fraud_proof.domain_id(),
+                        bad_operator_id,
+                        operator.signing_key,
+                    );
                     verify_bundle_equivocation_fraud_proof::<T::Block, T::DomainHeader, Balance>(
-                        &operator_signing_key,
+                        &operator_signing_keys,
                         &proof.first_header,
                         &proof.second_header,
                     )
@@ -2056,11 +2377,14 @@ impl<T: Config> Pallet<T> {
                             target: "runtime::domains",
                             "Bundle equivocation proof verification failed: {err:?}"
                         );
-                        FraudProofError::BadBundleEquivocationFraudProof
+                        FraudProofValidationFailure::verification_failed(
+                            FraudProofError::BadBundleEquivocationFraudProof,
+                            err,
+                        )
                     })?;
                 }
 
-                _ => return Err(FraudProofError::UnexpectedFraudProof),
+                _ => return Err(FraudProofError::UnexpectedFraudProof.into()),
             }
 
             // Bundle equivocation fraud proof doesn't target bad ER thus we give it the lowest priority
@@ -2075,7 +2399,7 @@ impl<T: Config> Pallet<T> {
 
             (tag, priority)
         } else {
-            return Err(FraudProofError::UnexpectedFraudProof);
+            return Err(FraudProofError::UnexpectedFraudProof.into());
         };
 
         Ok(tag_and_priority)
@@ -2273,16 +2597,24 @@ impl<T: Config> Pallet<T> {
                 // more weight than the max block weight
                 T::MaxNominators::get(),
             ))
+            .saturating_add(T::WeightInfo::finalize_operator_config_updates(
+                T::MaxPendingStakingOperation::get(),
+            ))
             .saturating_add(T::WeightInfo::finalize_domain_epoch_staking(
                 T::MaxPendingStakingOperation::get(),
             ))
+            .saturating_add(T::WeightInfo::finalize_redelegations(
+                T::MaxPendingStakingOperation::get(),
+            ))
     }
 
     fn actual_epoch_transition_weight(epoch_transition_res: EpochTransitionResult) -> Weight {
         let EpochTransitionResult {
             rewarded_operator_count,
             slashed_nominator_count,
+            updated_operator_count,
             finalized_operator_count,
+            redelegation_count,
             ..
         } = epoch_transition_res;
 
@@ -2290,15 +2622,209 @@ impl<T: Config> Pallet<T> {
             .saturating_add(T::WeightInfo::finalize_slashed_operators(
                 slashed_nominator_count,
             ))
+            .saturating_add(T::WeightInfo::finalize_operator_config_updates(
+                updated_operator_count,
+            ))
             .saturating_add(T::WeightInfo::finalize_domain_epoch_staking(
                 finalized_operator_count,
             ))
+            .saturating_add(T::WeightInfo::finalize_redelegations(redelegation_count))
+    }
+
+    pub fn max_advance_domain_sunset_weight() -> Weight {
+        // NOTE: a sunset step either handles a bad receipt, confirms a domain block or sunsets the
+        // domain, and the epoch transition can happen when confirming a domain block or sunsetting
+        // the domain, thus we use the `max` of them. The number of operators of the domain is not
+        // bounded, we use `MAX_BUNLDE_PER_BLOCK` here same as `operator_reward_tax_and_restake`.
+        T::WeightInfo::handle_bad_receipt(T::MaxNominators::get())
+            .max(T::WeightInfo::confirm_domain_block(
+                MAX_BUNLDE_PER_BLOCK,
+                MAX_BUNLDE_PER_BLOCK,
+            ))
+            .max(T::WeightInfo::sunset_domain(MAX_BUNLDE_PER_BLOCK))
+            .saturating_add(Self::max_staking_epoch_transition())
+    }
+
+    /// Distributes the rewards and storage fee refunds of the confirmed domain block, slashes the
+    /// authors of the invalid bundles and, if required, does epoch transition as well.
+    fn process_confirmed_domain_block(
+        domain_id: DomainId,
+        confirmed_block_info: ConfirmedDomainBlockInfo<DomainBlockNumberFor<T>, BalanceOf<T>>,
+    ) -> Result<Weight, Error<T>> {
+        let mut actual_weight = T::WeightInfo::confirm_domain_block(
+            confirmed_block_info.operator_ids.len() as u32,
+            confirmed_block_info.invalid_bundle_authors.len() as u32,
+        );
+
+        refund_storage_fee::<T>(
+            confirmed_block_info.total_storage_fee,
+            confirmed_block_info.paid_bundle_storage_fees,
+        )
+        .map_err(Error::<T>::from)?;
+
+        do_reward_operators::<T>(
+            domain_id,
+            confirmed_block_info.operator_ids.into_iter(),
+            confirmed_block_info.rewards,
+        )
+        .map_err(Error::<T>::from)?;
+
+        do_slash_operators::<T>(
+            confirmed_block_info.invalid_bundle_authors.into_iter(),
+            SlashedReason::InvalidBundle(confirmed_block_info.domain_block_number),
+        )
+        .map_err(Error::<T>::from)?;
+
+        if confirmed_block_info.domain_block_number % T::StakeEpochDuration::get() == Zero::zero() {
+            let epoch_transition_res =
+                do_finalize_domain_current_epoch::<T>(domain_id).map_err(Error::<T>::from)?;
+
+            Self::deposit_event(Event::DomainEpochCompleted {
+                domain_id,
+                completed_epoch_index: epoch_transition_res.completed_epoch_index,
+            });
+
+            actual_weight = actual_weight
+                .saturating_add(Self::actual_epoch_transition_weight(epoch_transition_res));
+        }
+
+        Ok(actual_weight)
+    }
+
+    fn do_advance_domain_sunset(domain_id: DomainId) -> Result<Weight, Error<T>> {
+        let challenge_period_end = match DomainSunsets::<T>::get(domain_id) {
+            Some(DomainSunsetStatus::Deactivated {
+                challenge_period_end,
+            }) => challenge_period_end,
+            Some(DomainSunsetStatus::Sunset { .. }) => {
+                return Err(DomainSunsetError::DomainAlreadySunset.into())
+            }
+            None => return Err(DomainSunsetError::DomainNotDeactivated.into()),
+        };
+        let current_block_number = frame_system::Pallet::<T>::current_block_number();
+        ensure!(
+            current_block_number >= challenge_period_end,
+            Error::<T>::from(DomainSunsetError::ChallengePeriodNotComplete)
+        );
+
+        match next_sunset_receipt::<T>(domain_id) {
+            Some(SunsetReceipt::Bad(receipt_number)) => {
+                let block_tree_node = prune_receipt::<T>(domain_id, receipt_number)
+                    .map_err(Error::<T>::from)?
+                    .ok_or::<Error<T>>(BlockTreeError::MissingDomainBlock.into())?;
+
+                let actual_weight = T::WeightInfo::handle_bad_receipt(
+                    (block_tree_node.operator_ids.len() as u32).min(MAX_BUNLDE_PER_BLOCK),
+                );
+
+                let bad_receipt_hash = block_tree_node
+                    .execution_receipt
+                    .hash::<DomainHashingFor<T>>();
+                do_slash_operators::<T>(
+                    block_tree_node.operator_ids.into_iter(),
+                    SlashedReason::BadExecutionReceipt(bad_receipt_hash),
+                )
+                .map_err(Error::<T>::from)?;
+
+                Ok(actual_weight)
+            }
+            Some(SunsetReceipt::Unconfirmed(receipt_number)) => {
+                match confirm_domain_block::<T>(domain_id, receipt_number)
+                    .map_err(Error::<T>::from)?
+                {
+                    Some(confirmed_block_info) => {
+                        Self::process_confirmed_domain_block(domain_id, confirmed_block_info)
+                    }
+                    None => Ok(T::DbWeight::get().reads(1)),
+                }
+            }
+            None => {
+                ensure!(
+                    T::DomainsTransfersTracker::is_domain_transfers_settled(domain_id),
+                    Error::<T>::from(DomainSunsetError::UnsettledTransfers)
+                );
+
+                // Finalize the last epoch so the pending deposits, withdrawals and slashes of the
+                // domain are processed before the operators are deregistered.
+                let epoch_transition_res =
+                    do_finalize_domain_current_epoch::<T>(domain_id).map_err(Error::<T>::from)?;
+                Self::deposit_event(Event::DomainEpochCompleted {
+                    domain_id,
+                    completed_epoch_index: epoch_transition_res.completed_epoch_index,
+                });
+                let epoch_transition_weight =
+                    Self::actual_epoch_transition_weight(epoch_transition_res);
+
+                let deregistered_operator_count =
+                    do_deregister_domain_operators::<T>(domain_id).map_err(Error::<T>::from)?;
+
+                let remaining_balance = T::DomainsTransfersTracker::close_domain_balance(domain_id)
+                    .map_err(|_| Error::<T>::from(DomainSunsetError::TransfersTracker))?;
+                // The funds left on the domain can't be moved out of the domain anymore, they go to
+                // the treasury instead of being burned.
+                mint_into_treasury::<T>(remaining_balance)
+                    .ok_or_else(|| Error::<T>::from(DomainSunsetError::MintIntoTreasury))?;
+
+                release_instantiation_deposit::<T>(domain_id).map_err(Error::<T>::from)?;
+
+                prune_domain_state::<T>(domain_id);
+
+                DomainSunsets::<T>::insert(
+                    domain_id,
+                    DomainSunsetStatus::Sunset {
+                        sunset_at: current_block_number,
+                    },
+                );
+
+                Self::deposit_event(Event::DomainSunset {
+                    domain_id,
+                    remaining_balance,
+                });
+
+                Ok(T::WeightInfo::sunset_domain(deregistered_operator_count)
+                    .saturating_add(epoch_transition_weight))
+            }
+        }
+    }
+
+    /// Returns true if the domain is deactivated, including the domain that is already sunset.
+    pub fn is_domain_deactivated(domain_id: DomainId) -> bool {
+        DomainSunsets::<T>::contains_key(domain_id)
+    }
+
+    /// Returns true if the sunset of the domain is complete.
+    pub fn is_domain_sunset(domain_id: DomainId) -> bool {
+        matches!(
+            DomainSunsets::<T>::get(domain_id),
+            Some(DomainSunsetStatus::Sunset { .. })
+        )
+    }
+
+    /// Returns true if the domain is deactivated and its receipts can't be challenged anymore.
+    fn is_challenge_period_over(domain_id: DomainId) -> bool {
+        match DomainSunsets::<T>::get(domain_id) {
+            Some(DomainSunsetStatus::Deactivated {
+                challenge_period_end,
+            }) => frame_system::Pallet::<T>::current_block_number() >= challenge_period_end,
+            Some(DomainSunsetStatus::Sunset { .. }) => true,
+            None => false,
+        }
     }
 
     pub fn storage_fund_account_balance(operator_id: OperatorId) -> BalanceOf<T> {
         let storage_fund_acc = storage_fund_account::<T>(operator_id);
         T::Currency::reducible_balance(&storage_fund_acc, Preservation::Preserve, Fortitude::Polite)
     }
+
+    pub fn nominator_positions(
+        nominator_id: NominatorId<T>,
+    ) -> Vec<NominatorPosition<BalanceOf<T>, DomainBlockNumberFor<T>>> {
+        crate::staking::nominator_positions::<T>(nominator_id)
+    }
+
+    pub fn operator_summaries(domain_id: DomainId) -> Vec<OperatorSummary<BalanceOf<T>>> {
+        crate::staking::operator_summaries::<T>(domain_id)
+    }
 }
 
 impl<T: Config> sp_domains::DomainOwner<T::AccountId> for Pallet<T> {
@@ -2311,6 +2837,12 @@ impl<T: Config> sp_domains::DomainOwner<T::AccountId> for Pallet<T> {
     }
 }
 
+impl<T: Config> sp_domains::DeactivatedDomains for Pallet<T> {
+    fn is_domain_deactivated(domain_id: DomainId) -> bool {
+        Pallet::<T>::is_domain_deactivated(domain_id)
+    }
+}
+
 impl<T> Pallet<T>
 where
     T: Config + frame_system::offchain::SendTransactionTypes<Call<T>>,
@@ -6,20 +6,26 @@ extern crate alloc;
 use crate::bundle_storage_fund::{self, deposit_reserve_for_storage_fund};
 use crate::pallet::{
     Deposits, DomainRegistry, DomainStakingSummary, NextOperatorId, NominatorCount,
-    OperatorIdOwner, OperatorSigningKey, Operators, PendingOperatorSwitches, PendingSlashes,
-    PendingStakingOperationCount, Withdrawals,
+    OperatorIdOwner, OperatorSigningKey, Operators, PendingOperatorConfigUpdates,
+    PendingOperatorSwitches, PendingRedelegations, PendingSlashes, PendingStakingOperationCount,
+    RedelegationLiabilities, Withdrawals,
 };
 use crate::staking_epoch::{mint_funds, mint_into_treasury};
 use crate::{
     BalanceOf, Config, DomainBlockNumberFor, Event, HoldIdentifier, NominatorId,
     OperatorEpochSharePrice, Pallet, ReceiptHashFor, SlashedReason,
 };
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use frame_support::traits::fungible::{Inspect, InspectHold, MutateHold};
 use frame_support::traits::tokens::{Fortitude, Precision, Preservation};
 use frame_support::{ensure, PalletError};
 use scale_info::TypeInfo;
 use sp_core::{sr25519, Get};
+use sp_domains::staking::{
+    NominatorPosition, OperatorSummary, PendingDepositPosition, WithdrawalPosition,
+};
 use sp_domains::{DomainId, EpochIndex, OperatorId, OperatorPublicKey};
 use sp_runtime::traits::{CheckedAdd, CheckedSub, Zero};
 use sp_runtime::{Perbill, Percent, Perquintill, Saturating};
@@ -142,6 +148,50 @@ pub(crate) struct WithdrawalInShares<DomainBlockNumber, Share, Balance> {
     pub(crate) storage_fee_refund: Balance,
 }
 
+/// A nominator's request to move its stake to another operator of the same domain.
+///
+/// The shares are withdrawn from the previous operator in the current epoch, at the epoch
+/// transition they are taken out of the `withdrawal_in_shares` of `domain_epoch` and deposited to
+/// the new operator.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub(crate) struct PendingRedelegation<NominatorId, DomainBlockNumber, Share, Balance> {
+    pub(crate) nominator_id: NominatorId,
+    pub(crate) from_operator_id: OperatorId,
+    pub(crate) to_operator_id: OperatorId,
+    /// Epoch of the withdrawal in shares of the previous operator holding the redelegated shares.
+    pub(crate) domain_epoch: DomainEpoch,
+    pub(crate) unlock_at_confirmed_domain_block_number: DomainBlockNumber,
+    pub(crate) shares: Share,
+    pub(crate) storage_fee_refund: Balance,
+}
+
+/// Stake redelegated to the new operator that is still slashable for the previous operator.
+///
+/// Until `unlock_at_confirmed_domain_block_number` is confirmed, slashing the previous operator
+/// also slashes `amount` from the nominator's stake of the new operator, and the nominator can't
+/// withdraw from the new operator.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq)]
+pub(crate) struct RedelegationLiability<NominatorId, DomainBlockNumber, Balance> {
+    pub(crate) nominator_id: NominatorId,
+    pub(crate) from_operator_id: OperatorId,
+    pub(crate) to_operator_id: OperatorId,
+    pub(crate) unlock_at_confirmed_domain_block_number: DomainBlockNumber,
+    pub(crate) amount: Balance,
+}
+
+/// Reason why a redelegation could not be finalized at the epoch transition.
+#[derive(TypeInfo, Debug, Encode, Decode, Copy, Clone, PartialEq, Eq)]
+pub enum RedelegationFailure {
+    /// The previous operator is slashed or deregistered, the redelegated stake stays with it.
+    PreviousOperatorNotAvailable,
+    /// The new operator is not registered anymore, the redelegated stake stays as a regular
+    /// withdrawal of the previous operator.
+    NewOperatorNotAvailable,
+    /// The redelegated stake doesn't satisfy the nominator limits of the new operator, it stays as
+    /// a regular withdrawal of the previous operator.
+    NominatorLimits,
+}
+
 #[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct OperatorDeregisteredInfo<DomainBlockNumber> {
     pub domain_epoch: DomainEpoch,
@@ -257,6 +307,15 @@ pub struct OperatorConfig<Balance> {
     pub nomination_tax: Percent,
 }
 
+/// Operator configuration changes requested during the current epoch, they are applied to the
+/// operator at the next epoch transition of its domain.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Default)]
+pub struct OperatorConfigUpdate<Balance> {
+    pub signing_key: Option<OperatorPublicKey>,
+    pub minimum_nominator_stake: Option<Balance>,
+    pub nomination_tax: Option<Percent>,
+}
+
 #[derive(TypeInfo, Encode, Decode, PalletError, Debug, PartialEq)]
 pub enum Error {
     MaximumOperatorId,
@@ -293,6 +352,39 @@ pub enum Error {
     OperatorNotDeregistered,
     BundleStorageFund(bundle_storage_fund::Error),
     UnconfirmedER,
+    SameOperatorRedelegation,
+    CrossDomainRedelegation,
+    DomainDeactivated,
+    MissingRedelegatedWithdrawal,
+    RedelegatedStakeLocked,
+}
+
+// Check that the domain is not deactivated, no new stake is accepted by a deactivated domain
+fn ensure_domain_active<T: Config>(domain_id: DomainId) -> Result<(), Error> {
+    ensure!(
+        !Pallet::<T>::is_domain_deactivated(domain_id),
+        Error::DomainDeactivated
+    );
+    Ok(())
+}
+
+// Check if the nominator has any stake redelegated to the operator that is still slashable for the
+// previous operator
+fn has_redelegation_liability<T: Config>(
+    domain_id: DomainId,
+    operator_id: OperatorId,
+    nominator_id: &NominatorId<T>,
+) -> bool {
+    let latest_confirmed_domain_block_number =
+        Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
+    RedelegationLiabilities::<T>::get(domain_id)
+        .iter()
+        .any(|liability| {
+            liability.to_operator_id == operator_id
+                && liability.nominator_id == *nominator_id
+                && liability.unlock_at_confirmed_domain_block_number
+                    > latest_confirmed_domain_block_number
+        })
 }
 
 // Increase `PendingStakingOperationCount` by one and check if the `MaxPendingStakingOperation`
@@ -316,6 +408,7 @@ pub(crate) fn do_register_operator<T: Config>(
     amount: BalanceOf<T>,
     config: OperatorConfig<BalanceOf<T>>,
 ) -> Result<(OperatorId, EpochIndex), Error> {
+    ensure_domain_active::<T>(domain_id)?;
     note_pending_staking_operation::<T>(domain_id)?;
 
     DomainStakingSummary::<T>::try_mutate(domain_id, |maybe_domain_stake_summary| {
@@ -561,6 +654,8 @@ pub(crate) fn do_nominate_operator<T: Config>(
             Error::OperatorNotRegistered
         );
 
+        ensure_domain_active::<T>(operator.current_domain_id)?;
+
         // If the this is the first staking request of this operator `note_pending_staking_operation` for it
         if operator.deposits_in_epoch.is_zero() && operator.withdrawals_in_epoch.is_zero() {
             note_pending_staking_operation::<T>(operator.current_domain_id)?;
@@ -689,11 +784,143 @@ pub(crate) fn do_deregister_operator<T: Config>(
     })
 }
 
+/// Deregisters all the registered operators of the deactivated domain as the last step of the
+/// domain sunset. Returns the number of deregistered operators.
+pub(crate) fn do_deregister_domain_operators<T: Config>(domain_id: DomainId) -> Result<u32, Error> {
+    DomainStakingSummary::<T>::try_mutate(domain_id, |maybe_domain_stake_summary| {
+        let stake_summary = maybe_domain_stake_summary
+            .as_mut()
+            .ok_or(Error::DomainNotInitialized)?;
+
+        let latest_confirmed_domain_block_number =
+            Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
+        let mut deregistered_operator_count = 0;
+        for operator_id in sp_std::mem::take(&mut stake_summary.next_operators) {
+            Operators::<T>::mutate(operator_id, |maybe_operator| {
+                if let Some(operator) = maybe_operator.as_mut()
+                    && *operator.status::<T>(operator_id) == OperatorStatus::Registered
+                {
+                    let operator_deregister_info = (
+                        domain_id,
+                        stake_summary.current_epoch_index,
+                        latest_confirmed_domain_block_number,
+                    )
+                        .into();
+                    operator.update_status(OperatorStatus::Deregistered(operator_deregister_info));
+
+                    deregistered_operator_count += 1;
+                    Pallet::<T>::deposit_event(Event::OperatorDeregistered { operator_id });
+                }
+            });
+        }
+
+        Ok(deregistered_operator_count)
+    })
+}
+
+/// Returns the current domain of the operator if `operator_owner` owns the operator and the
+/// operator is registered.
+fn ensure_registered_operator_owner<T: Config>(
+    operator_owner: &T::AccountId,
+    operator_id: OperatorId,
+) -> Result<DomainId, Error> {
+    ensure!(
+        OperatorIdOwner::<T>::get(operator_id).as_ref() == Some(operator_owner),
+        Error::NotOperatorOwner
+    );
+
+    let operator = Operators::<T>::get(operator_id).ok_or(Error::UnknownOperator)?;
+    ensure!(
+        *operator.status::<T>(operator_id) == OperatorStatus::Registered,
+        Error::OperatorNotRegistered
+    );
+
+    Ok(operator.current_domain_id)
+}
+
+/// Schedules an update of the operator's nomination tax and minimum nominator stake for the next
+/// epoch. Returns the domain of the operator.
+pub(crate) fn do_update_operator_config<T: Config>(
+    operator_owner: T::AccountId,
+    operator_id: OperatorId,
+    minimum_nominator_stake: BalanceOf<T>,
+    nomination_tax: Percent,
+) -> Result<DomainId, Error> {
+    ensure!(
+        minimum_nominator_stake >= T::MinNominatorStake::get(),
+        Error::MinimumNominatorStake
+    );
+
+    let domain_id = ensure_registered_operator_owner::<T>(&operator_owner, operator_id)?;
+
+    PendingOperatorConfigUpdates::<T>::try_mutate(domain_id, operator_id, |maybe_update| {
+        if maybe_update.is_none() {
+            note_pending_staking_operation::<T>(domain_id)?;
+        }
+
+        let update = maybe_update.get_or_insert_with(Default::default);
+        update.minimum_nominator_stake = Some(minimum_nominator_stake);
+        update.nomination_tax = Some(nomination_tax);
+
+        Ok(domain_id)
+    })
+}
+
+/// Schedules a rotation of the operator's signing key for the next epoch. Returns the domain of
+/// the operator.
+///
+/// The new key is reserved right away so that it can not be used by other operators in the
+/// meantime.
+pub(crate) fn do_rotate_operator_signing_key<T: Config>(
+    operator_owner: T::AccountId,
+    operator_id: OperatorId,
+    new_signing_key: OperatorPublicKey,
+) -> Result<DomainId, Error> {
+    ensure!(
+        new_signing_key != OperatorPublicKey::from(sr25519::Public::default()),
+        Error::InvalidOperatorSigningKey
+    );
+
+    ensure!(
+        !OperatorSigningKey::<T>::contains_key(new_signing_key.clone()),
+        Error::DuplicateOperatorSigningKey
+    );
+
+    let domain_id = ensure_registered_operator_owner::<T>(&operator_owner, operator_id)?;
+
+    PendingOperatorConfigUpdates::<T>::try_mutate(domain_id, operator_id, |maybe_update| {
+        if maybe_update.is_none() {
+            note_pending_staking_operation::<T>(domain_id)?;
+        }
+
+        let update = maybe_update.get_or_insert_with(Default::default);
+        // release the key reserved by the previous rotation request within the same epoch
+        if let Some(pending_signing_key) = update.signing_key.replace(new_signing_key.clone()) {
+            OperatorSigningKey::<T>::remove(pending_signing_key);
+        }
+        OperatorSigningKey::<T>::insert(new_signing_key, operator_id);
+
+        Ok(domain_id)
+    })
+}
+
 pub(crate) fn do_withdraw_stake<T: Config>(
     operator_id: OperatorId,
     nominator_id: NominatorId<T>,
     shares_withdrew: T::Share,
 ) -> Result<(), Error> {
+    withdraw_shares::<T>(operator_id, nominator_id, shares_withdrew).map(|_| ())
+}
+
+/// Withdraws the shares of the nominator from the operator.
+///
+/// Returns the withdrawal of this request alone, its shares may be all the shares of the nominator
+/// if the remaining stake would go below the minimum nominator stake.
+fn withdraw_shares<T: Config>(
+    operator_id: OperatorId,
+    nominator_id: NominatorId<T>,
+    shares_withdrew: T::Share,
+) -> Result<WithdrawalInShares<DomainBlockNumberFor<T>, T::Share, BalanceOf<T>>, Error> {
     Operators::<T>::try_mutate(operator_id, |maybe_operator| {
         let operator = maybe_operator.as_mut().ok_or(Error::UnknownOperator)?;
         ensure!(
@@ -703,6 +930,17 @@ pub(crate) fn do_withdraw_stake<T: Config>(
 
         ensure!(!shares_withdrew.is_zero(), Error::ZeroWithdrawShares);
 
+        // the redelegated stake can't leave the new operator while it is still slashable for the
+        // previous operator
+        ensure!(
+            !has_redelegation_liability::<T>(
+                operator.current_domain_id,
+                operator_id,
+                &nominator_id
+            ),
+            Error::RedelegatedStakeLocked
+        );
+
         // If the this is the first staking request of this operator `note_pending_staking_operation` for it
         if operator.deposits_in_epoch.is_zero() && operator.withdrawals_in_epoch.is_zero() {
             note_pending_staking_operation::<T>(operator.current_domain_id)?;
@@ -883,20 +1121,98 @@ pub(crate) fn do_withdraw_stake<T: Config>(
                 withdrawal.withdrawal_in_shares = Some(new_withdrawal_in_shares);
 
                 *maybe_withdrawal = Some(withdrawal);
-                Ok(())
+                Ok(WithdrawalInShares {
+                    domain_epoch: domain_current_epoch,
+                    unlock_at_confirmed_domain_block_number,
+                    shares: shares_withdrew,
+                    storage_fee_refund: withdraw_storage_fee,
+                })
             })
         })
     })
 }
 
+/// Redelegates the shares of the nominator from one operator to another operator of the same
+/// domain.
+///
+/// The shares are withdrawn from the previous operator in the current epoch and deposited to the
+/// new operator at the epoch transition, until the unlocking period is complete the redelegated
+/// stake is still slashed if the previous operator is slashed.
+pub(crate) fn do_redelegate<T: Config>(
+    nominator_id: NominatorId<T>,
+    from_operator_id: OperatorId,
+    to_operator_id: OperatorId,
+    shares: T::Share,
+) -> Result<(), Error> {
+    ensure!(
+        from_operator_id != to_operator_id,
+        Error::SameOperatorRedelegation
+    );
+
+    let from_operator = Operators::<T>::get(from_operator_id).ok_or(Error::UnknownOperator)?;
+    let to_operator = Operators::<T>::get(to_operator_id).ok_or(Error::UnknownOperator)?;
+
+    // NOTE: `status` also returns `PendingSlash` for the operator that is pending to slash, the
+    // stake can't be redelegated from or to such operator.
+    ensure!(
+        *from_operator.status::<T>(from_operator_id) == OperatorStatus::Registered
+            && *to_operator.status::<T>(to_operator_id) == OperatorStatus::Registered,
+        Error::OperatorNotRegistered
+    );
+
+    let domain_id = from_operator.current_domain_id;
+    ensure!(
+        from_operator.next_domain_id == domain_id
+            && to_operator.current_domain_id == domain_id
+            && to_operator.next_domain_id == domain_id,
+        Error::CrossDomainRedelegation
+    );
+
+    ensure_domain_active::<T>(domain_id)?;
+
+    if !Deposits::<T>::contains_key(to_operator_id, nominator_id.clone()) {
+        ensure!(
+            NominatorCount::<T>::get(to_operator_id) < T::MaxNominators::get(),
+            Error::MaximumNominators
+        );
+    }
+
+    // The redelegation is processed at the epoch transition thus count it as a pending operation
+    note_pending_staking_operation::<T>(domain_id)?;
+
+    let WithdrawalInShares {
+        domain_epoch,
+        unlock_at_confirmed_domain_block_number,
+        shares,
+        storage_fee_refund,
+    } = withdraw_shares::<T>(from_operator_id, nominator_id.clone(), shares)?;
+
+    PendingRedelegations::<T>::mutate(domain_id, |redelegations| {
+        redelegations.push(PendingRedelegation {
+            nominator_id,
+            from_operator_id,
+            to_operator_id,
+            domain_epoch,
+            unlock_at_confirmed_domain_block_number,
+            shares,
+            storage_fee_refund,
+        })
+    });
+
+    Ok(())
+}
+
 /// Unlocks any withdraws that are ready to be unlocked.
 pub(crate) fn do_unlock_funds<T: Config>(
     operator_id: OperatorId,
     nominator_id: NominatorId<T>,
 ) -> Result<BalanceOf<T>, Error> {
     let operator = Operators::<T>::get(operator_id).ok_or(Error::UnknownOperator)?;
+    // The operators of a sunset domain are deregistered and the domain won't progress anymore,
+    // thus the withdrawals from them can be unlocked right away.
+    let is_domain_sunset = Pallet::<T>::is_domain_sunset(operator.current_domain_id);
     ensure!(
-        *operator.status::<T>(operator_id) == OperatorStatus::Registered,
+        *operator.status::<T>(operator_id) == OperatorStatus::Registered || is_domain_sunset,
         Error::OperatorNotRegistered
     );
 
@@ -916,7 +1232,8 @@ pub(crate) fn do_unlock_funds<T: Config>(
         let latest_confirmed_block_number =
             Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
         ensure!(
-            unlock_at_confirmed_domain_block_number <= latest_confirmed_block_number,
+            unlock_at_confirmed_domain_block_number <= latest_confirmed_block_number
+                || is_domain_sunset,
             Error::UnlockPeriodNotComplete
         );
 
@@ -995,8 +1312,11 @@ pub(crate) fn do_unlock_operator<T: Config>(operator_id: OperatorId) -> Result<u
         let (domain_id, _) = domain_epoch.deconstruct();
         let latest_confirmed_block_number =
             Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
+        // The domain won't progress anymore once it is sunset, thus its operators can be unlocked
+        // right away.
         ensure!(
-            *unlock_at_confirmed_domain_block_number <= latest_confirmed_block_number,
+            *unlock_at_confirmed_domain_block_number <= latest_confirmed_block_number
+                || Pallet::<T>::is_domain_sunset(domain_id),
             Error::UnlockPeriodNotComplete
         );
 
@@ -1250,32 +1570,174 @@ pub(crate) fn do_slash_operators<T: Config>(
     Ok(())
 }
 
+/// Returns the positions of the nominator under all the operators it has a deposit or withdrawal
+/// with.
+pub(crate) fn nominator_positions<T: Config>(
+    nominator_id: NominatorId<T>,
+) -> Vec<NominatorPosition<BalanceOf<T>, DomainBlockNumberFor<T>>> {
+    Operators::<T>::iter()
+        .filter_map(|(operator_id, operator)| {
+            nominator_position::<T>(operator_id, operator, &nominator_id)
+        })
+        .collect()
+}
+
+fn nominator_position<T: Config>(
+    operator_id: OperatorId,
+    operator: Operator<BalanceOf<T>, T::Share, DomainBlockNumberFor<T>>,
+    nominator_id: &NominatorId<T>,
+) -> Option<NominatorPosition<BalanceOf<T>, DomainBlockNumberFor<T>>> {
+    let maybe_deposit = Deposits::<T>::get(operator_id, nominator_id);
+    let maybe_withdrawal = Withdrawals::<T>::get(operator_id, nominator_id);
+    if maybe_deposit.is_none() && maybe_withdrawal.is_none() {
+        return None;
+    }
+
+    let share_price =
+        SharePrice::new::<T>(operator.current_total_shares, operator.current_total_stake);
+
+    // deposits and withdrawals of the previous epochs are converted the same way as the next
+    // staking operation of the nominator would do it, but without writing them back
+    let mut deposit = maybe_deposit.unwrap_or_default();
+    do_convert_previous_epoch_deposits::<T>(operator_id, &mut deposit).ok()?;
+    let mut withdrawal = maybe_withdrawal.unwrap_or_default();
+    do_convert_previous_epoch_withdrawal::<T>(operator_id, &mut withdrawal).ok()?;
+
+    let shares = deposit.known.shares;
+    let accrued_rewards = DomainStakingSummary::<T>::get(operator.current_domain_id)
+        .and_then(|stake_summary| {
+            stake_summary
+                .current_epoch_rewards
+                .get(&operator_id)
+                .copied()
+        })
+        .filter(|_| !operator.current_total_shares.is_zero())
+        .map(|reward| {
+            let rewards = reward.saturating_sub(operator.nomination_tax.mul_floor(reward));
+            Perquintill::from_rational(shares, operator.current_total_shares).mul_floor(rewards)
+        })
+        .unwrap_or_default();
+
+    let pending_deposit = deposit.pending.map(|pending_deposit| {
+        let (_, effective_epoch_index) = pending_deposit.effective_domain_epoch.deconstruct();
+        PendingDepositPosition {
+            effective_epoch_index,
+            amount: pending_deposit.amount,
+            storage_fee_deposit: pending_deposit.storage_fee_deposit,
+        }
+    });
+
+    let withdrawals = withdrawal
+        .withdrawals
+        .into_iter()
+        .map(|withdrawal| WithdrawalPosition {
+            amount: withdrawal.amount_to_unlock,
+            storage_fee_refund: withdrawal.storage_fee_refund,
+            unlock_at_confirmed_domain_block_number: withdrawal
+                .unlock_at_confirmed_domain_block_number,
+            pending: false,
+        })
+        .chain(
+            withdrawal
+                .withdrawal_in_shares
+                .map(|withdrawal| WithdrawalPosition {
+                    amount: share_price.shares_to_stake::<T>(withdrawal.shares),
+                    storage_fee_refund: withdrawal.storage_fee_refund,
+                    unlock_at_confirmed_domain_block_number: withdrawal
+                        .unlock_at_confirmed_domain_block_number,
+                    pending: true,
+                }),
+        )
+        .collect();
+
+    Some(NominatorPosition {
+        operator_id,
+        domain_id: operator.current_domain_id,
+        shares: shares.into(),
+        current_staked_value: share_price.shares_to_stake::<T>(shares),
+        storage_fee_deposit: deposit.known.storage_fee_deposit,
+        accrued_rewards,
+        pending_deposit,
+        withdrawals,
+    })
+}
+
+/// Returns the summaries of the current and next epoch operators of the domain.
+pub(crate) fn operator_summaries<T: Config>(
+    domain_id: DomainId,
+) -> Vec<OperatorSummary<BalanceOf<T>>> {
+    let Some(stake_summary) = DomainStakingSummary::<T>::get(domain_id) else {
+        return Vec::new();
+    };
+
+    let operator_ids: BTreeSet<OperatorId> = stake_summary
+        .current_operators
+        .keys()
+        .chain(stake_summary.next_operators.iter())
+        .copied()
+        .collect();
+
+    operator_ids
+        .into_iter()
+        .filter_map(|operator_id| {
+            let operator = Operators::<T>::get(operator_id)?;
+            Some(OperatorSummary {
+                operator_id,
+                signing_key: operator.signing_key,
+                minimum_nominator_stake: operator.minimum_nominator_stake,
+                nomination_tax: operator.nomination_tax,
+                current_epoch_stake: stake_summary.current_operators.get(&operator_id).copied(),
+                current_total_stake: operator.current_total_stake,
+                current_total_shares: operator.current_total_shares.into(),
+                current_epoch_rewards: stake_summary
+                    .current_epoch_rewards
+                    .get(&operator_id)
+                    .copied()
+                    .unwrap_or_default(),
+                total_storage_fee_deposit: operator.total_storage_fee_deposit,
+                nominator_count: NominatorCount::<T>::get(operator_id),
+                is_next_operator: stake_summary.next_operators.contains(&operator_id),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use crate::domain_registry::{DomainConfig, DomainObject};
+    use crate::domain_sunset::DomainSunsetStatus;
     use crate::pallet::{
-        Config, Deposits, DomainRegistry, DomainStakingSummary, LatestConfirmedDomainBlock,
-        NextOperatorId, NominatorCount, OperatorIdOwner, Operators, PendingSlashes, Withdrawals,
+        Config, Deposits, DomainRegistry, DomainStakingSummary, DomainSunsets,
+        LatestConfirmedDomainBlock, NextOperatorId, NominatorCount, OperatorIdOwner,
+        OperatorSigningKey, Operators, PendingOperatorConfigUpdates, PendingRedelegations,
+        PendingSlashes, PreviousOperatorSigningKeys, RedelegationLiabilities, Withdrawals,
     };
     use crate::staking::{
-        do_convert_previous_epoch_withdrawal, do_nominate_operator, do_reward_operators,
-        do_slash_operators, do_unlock_funds, do_withdraw_stake, Error as StakingError, Operator,
-        OperatorConfig, OperatorStatus, StakingSummary,
+        do_convert_previous_epoch_withdrawal, do_deregister_domain_operators, do_nominate_operator,
+        do_reward_operators, do_slash_operators, do_unlock_funds, do_withdraw_stake,
+        nominator_positions, operator_summaries, Error as StakingError, Operator, OperatorConfig,
+        OperatorStatus, RedelegationFailure, StakingSummary,
     };
     use crate::staking_epoch::do_finalize_domain_current_epoch;
-    use crate::tests::{new_test_ext, ExistentialDeposit, RuntimeOrigin, Test};
-    use crate::{bundle_storage_fund, BalanceOf, Error, NominatorId, SlashedReason};
-    use frame_support::traits::fungible::Mutate;
+    use crate::tests::{
+        new_test_ext, ExistentialDeposit, RuntimeEvent, RuntimeOrigin,
+        StakeWithdrawalLockingPeriod, System, Test,
+    };
+    use crate::{
+        bundle_storage_fund, BalanceOf, Error, HoldIdentifier, NominatorId, SlashedReason,
+    };
+    use frame_support::traits::fungible::{InspectHold, Mutate};
     use frame_support::traits::Currency;
     use frame_support::weights::Weight;
     use frame_support::{assert_err, assert_ok};
+    use sp_consensus_slots::Slot;
     use sp_core::{sr25519, Pair, U256};
     use sp_domains::{
         ConfirmedDomainBlock, DomainId, OperatorAllowList, OperatorId, OperatorPair,
         OperatorPublicKey,
     };
     use sp_runtime::traits::Zero;
-    use sp_runtime::{PerThing, Perbill};
+    use sp_runtime::{PerThing, Perbill, Percent};
     use std::collections::{BTreeMap, BTreeSet};
     use std::vec;
     use subspace_runtime_primitives::SSC;
@@ -1859,77 +2321,794 @@ pub(crate) mod tests {
         });
     }
 
-    type WithdrawWithResult = Vec<(Share, Result<(), StakingError>)>;
+    #[test]
+    fn unlock_funds_of_sunset_domain() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 2;
+        let nominator_stake = 100 * SSC;
+        let nominator_free_balance = 150 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
 
-    /// Expected withdrawal amount.
-    /// Bool indicates to include exisitential deposit while asserting the final balance
-    /// since ED is not holded back from usable balance when there are no holds on the account.
-    type ExpectedWithdrawAmount = Option<(BalanceOf<Test>, bool)>;
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (nominator_free_balance, nominator_stake),
+                )]),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
 
-    /// The storage fund change in SSC, `true` means increase of the storage fund, `false` means decrease.
-    type StorageFundChange = (bool, u32);
+            assert_ok!(Domains::withdraw_stake(
+                RuntimeOrigin::signed(nominator_account),
+                operator_id,
+                10 * SSC,
+            ));
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            do_deregister_domain_operators::<Test>(domain_id).unwrap();
 
-    pub(crate) type Share = <Test as Config>::Share;
+            // The withdrawal can't be unlocked from a deregistered operator of an active domain
+            assert_err!(
+                do_unlock_funds::<Test>(operator_id, nominator_account),
+                StakingError::OperatorNotRegistered
+            );
 
-    struct WithdrawParams {
-        minimum_nominator_stake: BalanceOf<Test>,
-        nominators: Vec<(NominatorId<Test>, BalanceOf<Test>)>,
-        operator_reward: BalanceOf<Test>,
-        nominator_id: NominatorId<Test>,
-        withdraws: WithdrawWithResult,
-        maybe_deposit: Option<BalanceOf<Test>>,
-        expected_withdraw: ExpectedWithdrawAmount,
-        expected_nominator_count_reduced_by: u32,
-        storage_fund_change: StorageFundChange,
+            // The confirmed domain block is pruned once the domain is sunset, the withdrawal can
+            // still be unlocked
+            DomainSunsets::<Test>::insert(domain_id, DomainSunsetStatus::Sunset { sunset_at: 1 });
+            LatestConfirmedDomainBlock::<Test>::remove(domain_id);
+            let previous_usable_balance = Balances::usable_balance(nominator_account);
+            assert_ok!(do_unlock_funds::<Test>(operator_id, nominator_account));
+            assert!(Balances::usable_balance(nominator_account) > previous_usable_balance);
+            assert!(Withdrawals::<Test>::get(operator_id, nominator_account).is_none());
+        });
     }
 
-    fn withdraw_stake(params: WithdrawParams) {
-        let WithdrawParams {
-            minimum_nominator_stake,
-            nominators,
-            operator_reward,
-            nominator_id,
-            withdraws,
-            maybe_deposit,
-            expected_withdraw,
-            expected_nominator_count_reduced_by,
-            storage_fund_change,
-        } = params;
+    #[test]
+    fn update_operator_config() {
         let domain_id = DomainId::new(0);
-        let operator_account = 0;
+        let operator_account = 1;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
         let pair = OperatorPair::from_seed(&U256::from(0u32).into());
 
-        let mut total_balance = nominators.iter().map(|n| n.1).sum::<BalanceOf<Test>>()
-            + operator_reward
-            + maybe_deposit.unwrap_or(0);
-
-        let mut nominators = BTreeMap::from_iter(
-            nominators
-                .into_iter()
-                .map(|(id, bal)| (id, (bal + ExistentialDeposit::get(), bal)))
-                .collect::<Vec<(NominatorId<Test>, (BalanceOf<Test>, BalanceOf<Test>))>>(),
-        );
-
         let mut ext = new_test_ext();
         ext.execute_with(|| {
-            let (operator_free_balance, operator_stake) =
-                nominators.remove(&operator_account).unwrap();
             let (operator_id, _) = register_operator(
                 domain_id,
                 operator_account,
                 operator_free_balance,
                 operator_stake,
-                minimum_nominator_stake,
+                SSC,
                 pair.public(),
-                nominators,
+                BTreeMap::new(),
             );
-
             do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
 
-            if !operator_reward.is_zero() {
-                do_reward_operators::<Test>(
-                    domain_id,
-                    vec![operator_id].into_iter(),
+            // only the operator owner can update the config
+            let res = Domains::update_operator_config(
+                RuntimeOrigin::signed(2),
+                operator_id,
+                2 * SSC,
+                Percent::from_percent(10),
+            );
+            assert_err!(res, Error::<Test>::Staking(StakingError::NotOperatorOwner));
+
+            // minimum nominator stake can not go below the global minimum
+            let res = Domains::update_operator_config(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                SSC - 1,
+                Percent::from_percent(10),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::MinimumNominatorStake)
+            );
+
+            assert_ok!(Domains::update_operator_config(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                2 * SSC,
+                Percent::from_percent(10),
+            ));
+
+            // config is not changed until the epoch transition
+            let operator = Operators::<Test>::get(operator_id).unwrap();
+            assert_eq!(operator.minimum_nominator_stake, SSC);
+            assert_eq!(operator.nomination_tax, Percent::zero());
+
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let operator = Operators::<Test>::get(operator_id).unwrap();
+            assert_eq!(operator.minimum_nominator_stake, 2 * SSC);
+            assert_eq!(operator.nomination_tax, Percent::from_percent(10));
+            assert!(PendingOperatorConfigUpdates::<Test>::get(domain_id, operator_id).is_none());
+        });
+    }
+
+    #[test]
+    fn rotate_operator_signing_key() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+        let new_pair = OperatorPair::from_seed(&U256::from(2u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            // cannot rotate to the default key or a key that is already used
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                OperatorPublicKey::from(sr25519::Public::default()),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::InvalidOperatorSigningKey)
+            );
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                pair.public(),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::DuplicateOperatorSigningKey)
+            );
+
+            // the latest rotation request within the epoch wins and the previously requested key
+            // is released
+            assert_ok!(Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                other_pair.public(),
+            ));
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(other_pair.public()),
+                Some(operator_id)
+            );
+            assert_ok!(Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                new_pair.public(),
+            ));
+            assert_eq!(OperatorSigningKey::<Test>::get(other_pair.public()), None);
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(new_pair.public()),
+                Some(operator_id)
+            );
+            assert_eq!(
+                Operators::<Test>::get(operator_id).unwrap().signing_key,
+                pair.public()
+            );
+
+            // new key is used from the next epoch while the old key is still accepted
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert_eq!(
+                Operators::<Test>::get(operator_id).unwrap().signing_key,
+                new_pair.public()
+            );
+            assert_eq!(
+                PreviousOperatorSigningKeys::<Test>::get(domain_id, operator_id),
+                Some((pair.public(), Slot::from(0)))
+            );
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(pair.public()),
+                Some(operator_id)
+            );
+            assert_eq!(
+                Domains::operator_signing_keys(domain_id, operator_id, new_pair.public()),
+                vec![new_pair.public(), pair.public()]
+            );
+
+            // old key is only valid for the bundles of slots before the rotation
+            PreviousOperatorSigningKeys::<Test>::insert(
+                domain_id,
+                operator_id,
+                (pair.public(), Slot::from(10)),
+            );
+            assert_eq!(
+                Domains::bundle_signing_key(domain_id, operator_id, new_pair.public(), 9.into()),
+                pair.public()
+            );
+            assert_eq!(
+                Domains::bundle_signing_key(domain_id, operator_id, new_pair.public(), 10.into()),
+                new_pair.public()
+            );
+
+            // old key is released after one more epoch
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert_eq!(
+                PreviousOperatorSigningKeys::<Test>::get(domain_id, operator_id),
+                None
+            );
+            assert_eq!(OperatorSigningKey::<Test>::get(pair.public()), None);
+            assert_eq!(
+                OperatorSigningKey::<Test>::get(new_pair.public()),
+                Some(operator_id)
+            );
+        });
+    }
+
+    #[test]
+    fn rotate_signing_key_of_deregistered_operator() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let new_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            assert_ok!(Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                new_pair.public(),
+            ));
+            assert_ok!(Domains::deregister_operator(
+                RuntimeOrigin::signed(operator_account),
+                operator_id
+            ));
+
+            // rotation is dropped and the reserved key is released
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert_eq!(
+                Operators::<Test>::get(operator_id).unwrap().signing_key,
+                pair.public()
+            );
+            assert_eq!(OperatorSigningKey::<Test>::get(new_pair.public()), None);
+
+            // no more updates after deregistration
+            let res = Domains::rotate_operator_signing_key(
+                RuntimeOrigin::signed(operator_account),
+                operator_id,
+                new_pair.public(),
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::OperatorNotRegistered)
+            );
+        });
+    }
+
+    #[test]
+    fn redelegate() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let other_operator_account = 2;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 3;
+        let nominator_stake = 100 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (from_operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            let (to_operator_id, _) = register_operator(
+                domain_id,
+                other_operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                other_pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let res = Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                from_operator_id,
+                10 * SSC,
+            );
+            assert_err!(
+                res,
+                Error::<Test>::Staking(StakingError::SameOperatorRedelegation)
+            );
+
+            let shares = Deposits::<Test>::get(from_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .shares;
+            let storage_fee_deposit = Deposits::<Test>::get(from_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .storage_fee_deposit;
+            assert_ok!(Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                to_operator_id,
+                shares,
+            ));
+            assert_eq!(
+                Operators::<Test>::get(from_operator_id)
+                    .unwrap()
+                    .withdrawals_in_epoch,
+                shares
+            );
+            assert_eq!(PendingRedelegations::<Test>::get(domain_id).len(), 1);
+
+            // redelegated stake is deposited to the new operator at the epoch transition
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert!(PendingRedelegations::<Test>::get(domain_id).is_empty());
+            assert!(Withdrawals::<Test>::get(from_operator_id, nominator_account).is_none());
+            assert!(Deposits::<Test>::get(from_operator_id, nominator_account).is_none());
+            assert_eq!(NominatorCount::<Test>::get(from_operator_id), 0);
+            assert_eq!(NominatorCount::<Test>::get(to_operator_id), 1);
+
+            let pending_deposit = Deposits::<Test>::get(to_operator_id, nominator_account)
+                .unwrap()
+                .pending
+                .unwrap();
+            assert_eq!(
+                pending_deposit.total().unwrap(),
+                nominator_stake - STORAGE_FEE_RESERVE.mul_floor(nominator_stake)
+                    + storage_fee_deposit
+            );
+            assert_eq!(
+                Operators::<Test>::get(to_operator_id)
+                    .unwrap()
+                    .deposits_in_epoch,
+                pending_deposit.amount
+            );
+
+            // and stays slashable for the previous operator until the unlocking period is complete
+            let liabilities = RedelegationLiabilities::<Test>::get(domain_id);
+            assert_eq!(liabilities.len(), 1);
+            assert_eq!(liabilities[0].from_operator_id, from_operator_id);
+            assert_eq!(liabilities[0].to_operator_id, to_operator_id);
+            assert_eq!(liabilities[0].amount, pending_deposit.amount);
+            assert_eq!(
+                liabilities[0].unlock_at_confirmed_domain_block_number,
+                StakeWithdrawalLockingPeriod::get()
+            );
+
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            let operator = Operators::<Test>::get(to_operator_id).unwrap();
+            assert_eq!(
+                operator.current_total_stake,
+                operator_stake - STORAGE_FEE_RESERVE.mul_floor(operator_stake)
+                    + pending_deposit.amount
+            );
+
+            // the redelegated stake can't be withdrawn from the new operator in the meantime
+            let shares = Deposits::<Test>::get(to_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .shares;
+            assert_err!(
+                Domains::withdraw_stake(
+                    RuntimeOrigin::signed(nominator_account),
+                    to_operator_id,
+                    shares,
+                ),
+                Error::<Test>::Staking(StakingError::RedelegatedStakeLocked)
+            );
+
+            LatestConfirmedDomainBlock::<Test>::insert(
+                domain_id,
+                ConfirmedDomainBlock {
+                    block_number: StakeWithdrawalLockingPeriod::get(),
+                    block_hash: Default::default(),
+                    parent_block_receipt_hash: Default::default(),
+                    state_root: Default::default(),
+                    extrinsics_root: Default::default(),
+                },
+            );
+            assert_ok!(Domains::withdraw_stake(
+                RuntimeOrigin::signed(nominator_account),
+                to_operator_id,
+                shares,
+            ));
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert!(RedelegationLiabilities::<Test>::get(domain_id).is_empty());
+        });
+    }
+
+    #[test]
+    fn redelegate_and_slash_previous_operator() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let other_operator_account = 2;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 3;
+        let nominator_stake = 100 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (from_operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            let (to_operator_id, _) = register_operator(
+                domain_id,
+                other_operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                other_pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let shares = Deposits::<Test>::get(from_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .shares;
+            assert_ok!(Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                to_operator_id,
+                shares,
+            ));
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let to_operator = Operators::<Test>::get(to_operator_id).unwrap();
+            let redelegated_amount = RedelegationLiabilities::<Test>::get(domain_id)[0].amount;
+            let treasury_balance = Balances::total_balance(&crate::tests::TreasuryAccount::get());
+
+            // slashing the previous operator also slashes the redelegated stake
+            do_slash_operators::<Test>(vec![from_operator_id], SlashedReason::InvalidBundle(1))
+                .unwrap();
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            assert!(RedelegationLiabilities::<Test>::get(domain_id).is_empty());
+            assert!(Operators::<Test>::get(from_operator_id).is_none());
+            let deposit = Deposits::<Test>::get(to_operator_id, nominator_account).unwrap();
+            assert!(deposit.known.shares.is_zero());
+            assert_eq!(
+                Operators::<Test>::get(to_operator_id)
+                    .unwrap()
+                    .current_total_stake,
+                to_operator.current_total_stake - redelegated_amount
+            );
+            assert_eq!(
+                Balances::balance_on_hold(
+                    &<Test as Config>::HoldIdentifier::staking_staked(to_operator_id),
+                    &nominator_account
+                ),
+                0
+            );
+            assert!(
+                Balances::total_balance(&crate::tests::TreasuryAccount::get())
+                    >= treasury_balance + redelegated_amount
+            );
+        });
+    }
+
+    #[test]
+    fn redelegate_to_deregistered_operator() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let other_operator_account = 2;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 3;
+        let nominator_stake = 100 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (from_operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            let (to_operator_id, _) = register_operator(
+                domain_id,
+                other_operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                other_pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let shares = Deposits::<Test>::get(from_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .shares;
+            assert_ok!(Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                to_operator_id,
+                shares,
+            ));
+            assert_ok!(Domains::deregister_operator(
+                RuntimeOrigin::signed(other_operator_account),
+                to_operator_id
+            ));
+
+            // redelegation falls back to a regular withdrawal
+            System::set_block_number(1);
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert!(PendingRedelegations::<Test>::get(domain_id).is_empty());
+            assert!(RedelegationLiabilities::<Test>::get(domain_id).is_empty());
+            assert!(Deposits::<Test>::get(to_operator_id, nominator_account).is_none());
+            let withdrawal = Withdrawals::<Test>::get(from_operator_id, nominator_account).unwrap();
+            assert_eq!(withdrawal.withdrawal_in_shares.unwrap().shares, shares);
+            System::assert_has_event(RuntimeEvent::Domains(crate::Event::RedelegationFailed {
+                nominator_id: nominator_account,
+                from_operator_id,
+                to_operator_id,
+                reason: RedelegationFailure::NewOperatorNotAvailable,
+            }));
+        });
+    }
+
+    #[test]
+    fn redelegate_with_withdrawal_in_same_epoch() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let other_operator_account = 2;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 3;
+        let nominator_stake = 100 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (from_operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            let (to_operator_id, _) = register_operator(
+                domain_id,
+                other_operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                other_pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            // the redelegated shares and the withdrawn shares are in the same withdrawal in shares
+            assert_ok!(Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                to_operator_id,
+                40 * SSC,
+            ));
+            assert_ok!(Domains::withdraw_stake(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                10 * SSC,
+            ));
+            let withdrawal = Withdrawals::<Test>::get(from_operator_id, nominator_account).unwrap();
+            assert_eq!(withdrawal.withdrawal_in_shares.unwrap().shares, 50 * SSC);
+
+            // only the redelegated shares are taken out of it
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            let withdrawal = Withdrawals::<Test>::get(from_operator_id, nominator_account).unwrap();
+            assert_eq!(withdrawal.withdrawal_in_shares.unwrap().shares, 10 * SSC);
+            assert_eq!(
+                Deposits::<Test>::get(to_operator_id, nominator_account)
+                    .unwrap()
+                    .pending
+                    .unwrap()
+                    .amount,
+                RedelegationLiabilities::<Test>::get(domain_id)[0].amount
+            );
+        });
+    }
+
+    #[test]
+    fn redelegate_from_slashed_operator() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let other_operator_account = 2;
+        let operator_stake = 200 * SSC;
+        let operator_free_balance = 250 * SSC;
+        let nominator_account = 3;
+        let nominator_stake = 100 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+        let other_pair = OperatorPair::from_seed(&U256::from(1u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (from_operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            let (to_operator_id, _) = register_operator(
+                domain_id,
+                other_operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                other_pair.public(),
+                BTreeMap::new(),
+            );
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            let shares = Deposits::<Test>::get(from_operator_id, nominator_account)
+                .unwrap()
+                .known
+                .shares;
+            assert_ok!(Domains::redelegate(
+                RuntimeOrigin::signed(nominator_account),
+                from_operator_id,
+                to_operator_id,
+                shares,
+            ));
+            do_slash_operators::<Test>(vec![from_operator_id], SlashedReason::InvalidBundle(1))
+                .unwrap();
+
+            // the redelegated stake is slashed along with the previous operator
+            System::set_block_number(1);
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            assert!(PendingRedelegations::<Test>::get(domain_id).is_empty());
+            assert!(RedelegationLiabilities::<Test>::get(domain_id).is_empty());
+            assert!(Withdrawals::<Test>::get(from_operator_id, nominator_account).is_none());
+            assert!(Deposits::<Test>::get(to_operator_id, nominator_account).is_none());
+            System::assert_has_event(RuntimeEvent::Domains(crate::Event::RedelegationFailed {
+                nominator_id: nominator_account,
+                from_operator_id,
+                to_operator_id,
+                reason: RedelegationFailure::PreviousOperatorNotAvailable,
+            }));
+        });
+    }
+
+    type WithdrawWithResult = Vec<(Share, Result<(), StakingError>)>;
+
+    /// Expected withdrawal amount.
+    /// Bool indicates to include exisitential deposit while asserting the final balance
+    /// since ED is not holded back from usable balance when there are no holds on the account.
+    type ExpectedWithdrawAmount = Option<(BalanceOf<Test>, bool)>;
+
+    /// The storage fund change in SSC, `true` means increase of the storage fund, `false` means decrease.
+    type StorageFundChange = (bool, u32);
+
+    pub(crate) type Share = <Test as Config>::Share;
+
+    struct WithdrawParams {
+        minimum_nominator_stake: BalanceOf<Test>,
+        nominators: Vec<(NominatorId<Test>, BalanceOf<Test>)>,
+        operator_reward: BalanceOf<Test>,
+        nominator_id: NominatorId<Test>,
+        withdraws: WithdrawWithResult,
+        maybe_deposit: Option<BalanceOf<Test>>,
+        expected_withdraw: ExpectedWithdrawAmount,
+        expected_nominator_count_reduced_by: u32,
+        storage_fund_change: StorageFundChange,
+    }
+
+    fn withdraw_stake(params: WithdrawParams) {
+        let WithdrawParams {
+            minimum_nominator_stake,
+            nominators,
+            operator_reward,
+            nominator_id,
+            withdraws,
+            maybe_deposit,
+            expected_withdraw,
+            expected_nominator_count_reduced_by,
+            storage_fund_change,
+        } = params;
+        let domain_id = DomainId::new(0);
+        let operator_account = 0;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+
+        let mut total_balance = nominators.iter().map(|n| n.1).sum::<BalanceOf<Test>>()
+            + operator_reward
+            + maybe_deposit.unwrap_or(0);
+
+        let mut nominators = BTreeMap::from_iter(
+            nominators
+                .into_iter()
+                .map(|(id, bal)| (id, (bal + ExistentialDeposit::get(), bal)))
+                .collect::<Vec<(NominatorId<Test>, (BalanceOf<Test>, BalanceOf<Test>))>>(),
+        );
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_free_balance, operator_stake) =
+                nominators.remove(&operator_account).unwrap();
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                minimum_nominator_stake,
+                pair.public(),
+                nominators,
+            );
+
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+
+            if !operator_reward.is_zero() {
+                do_reward_operators::<Test>(
+                    domain_id,
+                    vec![operator_id].into_iter(),
                     operator_reward,
                 )
                 .unwrap();
@@ -2887,4 +4066,91 @@ pub(crate) mod tests {
             assert_eq!(bundle_storage_fund::total_balance::<Test>(operator_id), 0);
         });
     }
+
+    #[test]
+    fn staking_positions() {
+        let domain_id = DomainId::new(0);
+        let operator_account = 1;
+        let operator_free_balance = 250 * SSC;
+        let operator_stake = 200 * SSC;
+        let nominator_account = 2;
+        let nominator_stake = 200 * SSC;
+        let pair = OperatorPair::from_seed(&U256::from(0u32).into());
+
+        let mut ext = new_test_ext();
+        ext.execute_with(|| {
+            let (operator_id, _) = register_operator(
+                domain_id,
+                operator_account,
+                operator_free_balance,
+                operator_stake,
+                SSC,
+                pair.public(),
+                BTreeMap::from_iter(vec![(
+                    nominator_account,
+                    (operator_free_balance, nominator_stake),
+                )]),
+            );
+            assert!(nominator_positions::<Test>(3).is_empty());
+
+            // deposit is pending until the epoch is complete
+            let positions = nominator_positions::<Test>(nominator_account);
+            assert_eq!(positions.len(), 1);
+            let pending_deposit = positions[0].pending_deposit.clone().unwrap();
+            assert_eq!(pending_deposit.effective_epoch_index, 0);
+            assert_eq!(pending_deposit.amount, 160 * SSC);
+            assert_eq!(pending_deposit.storage_fee_deposit, 40 * SSC);
+            assert_eq!(positions[0].shares, 0);
+
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            let positions = nominator_positions::<Test>(nominator_account);
+            assert_eq!(positions[0].operator_id, operator_id);
+            assert_eq!(positions[0].domain_id, domain_id);
+            assert_eq!(positions[0].shares, 160 * SSC);
+            assert_eq!(positions[0].current_staked_value, 160 * SSC);
+            assert_eq!(positions[0].storage_fee_deposit, 40 * SSC);
+            assert_eq!(positions[0].accrued_rewards, 0);
+            assert!(positions[0].pending_deposit.is_none());
+            assert!(positions[0].withdrawals.is_empty());
+
+            // nominator owns half of the shares
+            do_reward_operators::<Test>(domain_id, vec![operator_id].into_iter(), 20 * SSC)
+                .unwrap();
+            let positions = nominator_positions::<Test>(nominator_account);
+            assert_eq!(positions[0].accrued_rewards, 10 * SSC);
+
+            let summaries = operator_summaries::<Test>(domain_id);
+            assert_eq!(summaries.len(), 1);
+            assert_eq!(summaries[0].operator_id, operator_id);
+            assert_eq!(summaries[0].signing_key, pair.public());
+            assert_eq!(summaries[0].current_epoch_stake, Some(320 * SSC));
+            assert_eq!(summaries[0].current_total_stake, 320 * SSC);
+            assert_eq!(summaries[0].current_total_shares, 320 * SSC);
+            assert_eq!(summaries[0].current_epoch_rewards, 20 * SSC);
+            assert_eq!(summaries[0].total_storage_fee_deposit, 80 * SSC);
+            assert_eq!(summaries[0].nominator_count, 1);
+            assert!(summaries[0].is_next_operator);
+
+            // withdrawal in the current epoch is valued at the latest share price
+            assert_ok!(Domains::withdraw_stake(
+                RuntimeOrigin::signed(nominator_account),
+                operator_id,
+                40 * SSC,
+            ));
+            let positions = nominator_positions::<Test>(nominator_account);
+            assert_eq!(positions[0].shares, 120 * SSC);
+            assert_eq!(positions[0].withdrawals.len(), 1);
+            assert_eq!(positions[0].withdrawals[0].amount, 40 * SSC);
+            assert!(positions[0].withdrawals[0].pending);
+
+            // once the epoch is complete, withdrawal includes the rewards of the epoch
+            do_finalize_domain_current_epoch::<Test>(domain_id).unwrap();
+            let positions = nominator_positions::<Test>(nominator_account);
+            assert_eq!(positions[0].accrued_rewards, 0);
+            assert_eq!(positions[0].withdrawals.len(), 1);
+            assert!(positions[0].withdrawals[0].amount > 40 * SSC);
+            assert!(!positions[0].withdrawals[0].pending);
+            assert!(positions[0].current_staked_value > 120 * SSC);
+        });
+    }
 }
@@ -1,18 +1,29 @@
 //! Staking epoch transition for domain
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 use crate::bundle_storage_fund::deposit_reserve_for_storage_fund;
 use crate::pallet::{
     AccumulatedTreasuryFunds, Deposits, DomainStakingSummary, LastEpochStakingDistribution,
-    OperatorIdOwner, Operators, PendingOperatorSwitches, PendingSlashes,
-    PendingStakingOperationCount, Withdrawals,
+    NominatorCount, OperatorIdOwner, OperatorSigningKey, Operators, PendingOperatorConfigUpdates,
+    PendingOperatorSwitches, PendingRedelegations, PendingSlashes, PendingStakingOperationCount,
+    PreviousOperatorSigningKeys, RedelegationLiabilities, Withdrawals,
 };
 use crate::staking::{
-    do_convert_previous_epoch_deposits, do_convert_previous_epoch_withdrawal, DomainEpoch,
-    Error as TransitionError, OperatorStatus, SharePrice, WithdrawalInShares,
+    do_calculate_previous_epoch_deposit_shares_and_add_new_deposit,
+    do_convert_previous_epoch_deposits, do_convert_previous_epoch_withdrawal, hold_deposit,
+    DomainEpoch, Error as TransitionError, OperatorConfigUpdate, OperatorStatus,
+    PendingRedelegation, RedelegationFailure, RedelegationLiability, SharePrice,
+    WithdrawalInShares,
 };
 use crate::{
-    bundle_storage_fund, BalanceOf, Config, ElectionVerificationParams, Event, HoldIdentifier,
-    OperatorEpochSharePrice, Pallet,
+    bundle_storage_fund, BalanceOf, BlockSlot, Config, DomainBlockNumberFor,
+    ElectionVerificationParams, Event, HoldIdentifier, NominatorId, OperatorEpochSharePrice,
+    Pallet,
 };
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use frame_support::traits::fungible::{Inspect, InspectHold, Mutate, MutateHold};
 use frame_support::traits::tokens::{
@@ -33,12 +44,15 @@ pub enum Error {
     FinalizeDomainEpochStaking(TransitionError),
     OperatorRewardStaking(TransitionError),
     SlashOperator(TransitionError),
+    FinalizeRedelegations(TransitionError),
 }
 
 pub(crate) struct EpochTransitionResult {
     pub rewarded_operator_count: u32,
     pub slashed_nominator_count: u32,
+    pub updated_operator_count: u32,
     pub finalized_operator_count: u32,
+    pub redelegation_count: u32,
     pub completed_epoch_index: EpochIndex,
 }
 
@@ -57,6 +71,9 @@ pub(crate) fn do_finalize_domain_current_epoch<T: Config>(
     let slashed_nominator_count =
         do_finalize_slashed_operators::<T>(domain_id).map_err(Error::SlashOperator)?;
 
+    // apply any operator config updates before the operators are switched to other domains
+    let updated_operator_count = do_finalize_operator_config_updates::<T>(domain_id);
+
     // finalize any operator switches
     do_finalize_switch_operator_domain::<T>(domain_id)?;
 
@@ -64,10 +81,17 @@ pub(crate) fn do_finalize_domain_current_epoch<T: Config>(
     let (completed_epoch_index, finalized_operator_count) =
         do_finalize_domain_epoch_staking::<T>(domain_id)?;
 
+    // deposit the stake redelegated in the completed epoch to the new operators, this must happen
+    // after the slashed operators and the epoch staking are finalized
+    let redelegation_count =
+        do_finalize_redelegations::<T>(domain_id).map_err(Error::FinalizeRedelegations)?;
+
     Ok(EpochTransitionResult {
         rewarded_operator_count,
         slashed_nominator_count,
+        updated_operator_count,
         finalized_operator_count,
+        redelegation_count,
         completed_epoch_index,
     })
 }
@@ -159,6 +183,75 @@ pub(crate) fn operator_take_reward_tax_and_stake<T: Config>(
     Ok(rewarded_operator_count)
 }
 
+/// Applies the operator config updates requested during the epoch and returns the number of
+/// updated operators.
+///
+/// Signing keys rotated at the previous epoch transition are released, while the keys rotated now
+/// are kept for one more epoch so the bundles signed before the rotation are still accepted.
+pub(crate) fn do_finalize_operator_config_updates<T: Config>(domain_id: DomainId) -> u32 {
+    for (_operator_id, (previous_signing_key, _rotated_at)) in
+        PreviousOperatorSigningKeys::<T>::drain_prefix(domain_id)
+    {
+        OperatorSigningKey::<T>::remove(previous_signing_key);
+    }
+
+    let mut updated_operator_count = 0;
+    for (operator_id, config_update) in PendingOperatorConfigUpdates::<T>::drain_prefix(domain_id) {
+        apply_operator_config_update::<T>(domain_id, operator_id, config_update);
+        updated_operator_count += 1;
+    }
+
+    updated_operator_count
+}
+
+fn apply_operator_config_update<T: Config>(
+    domain_id: DomainId,
+    operator_id: OperatorId,
+    config_update: OperatorConfigUpdate<BalanceOf<T>>,
+) {
+    let OperatorConfigUpdate {
+        signing_key,
+        minimum_nominator_stake,
+        nomination_tax,
+    } = config_update;
+
+    Operators::<T>::mutate(operator_id, |maybe_operator| {
+        let operator = match maybe_operator.as_mut() {
+            Some(operator) if *operator.status::<T>(operator_id) == OperatorStatus::Registered => {
+                operator
+            }
+            // operator is not registered anymore, release the signing key reserved for it
+            _ => {
+                if let Some(signing_key) = signing_key {
+                    OperatorSigningKey::<T>::remove(signing_key);
+                }
+                return;
+            }
+        };
+
+        if let Some(minimum_nominator_stake) = minimum_nominator_stake {
+            operator.minimum_nominator_stake = minimum_nominator_stake;
+        }
+
+        if let Some(nomination_tax) = nomination_tax {
+            operator.nomination_tax = nomination_tax;
+        }
+
+        if let Some(signing_key) = signing_key {
+            let previous_signing_key = sp_std::mem::replace(&mut operator.signing_key, signing_key);
+            // Bundles of slots before the one that can first be produced on top of the current
+            // block are produced without knowing about the rotation
+            let rotated_at = T::BlockSlot::future_slot(frame_system::Pallet::<T>::block_number())
+                .unwrap_or_default();
+            PreviousOperatorSigningKeys::<T>::insert(
+                domain_id,
+                operator_id,
+                (previous_signing_key, rotated_at),
+            );
+        }
+    })
+}
+
 /// Add all the switched operators to new domain as next operators.
 /// Once the new domain's epoch is complete, operators are included in the next epoch.
 fn do_finalize_switch_operator_domain<T: Config>(domain_id: DomainId) -> Result<(), Error> {
@@ -356,6 +449,338 @@ pub(crate) fn do_finalize_operator_epoch_staking<T: Config>(
     Ok((total_stake, true))
 }
 
+type PendingRedelegationOf<T> = PendingRedelegation<
+    NominatorId<T>,
+    DomainBlockNumberFor<T>,
+    <T as Config>::Share,
+    BalanceOf<T>,
+>;
+
+/// Deposits the stake redelegated in the completed epoch to the new operators as pending deposits
+/// of the new epoch and returns the number of processed redelegations.
+///
+/// The liabilities of the redelegated stake whose unlocking period is complete are removed.
+pub(crate) fn do_finalize_redelegations<T: Config>(
+    domain_id: DomainId,
+) -> Result<u32, TransitionError> {
+    let latest_confirmed_domain_block_number =
+        Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
+    let mut liabilities = RedelegationLiabilities::<T>::take(domain_id);
+    liabilities.retain(|liability| {
+        liability.unlock_at_confirmed_domain_block_number > latest_confirmed_domain_block_number
+    });
+    if !liabilities.is_empty() {
+        RedelegationLiabilities::<T>::insert(domain_id, liabilities);
+    }
+
+    let redelegations = PendingRedelegations::<T>::take(domain_id);
+    if redelegations.is_empty() {
+        return Ok(0);
+    }
+
+    let current_epoch_index = DomainStakingSummary::<T>::get(domain_id)
+        .ok_or(TransitionError::DomainNotInitialized)?
+        .current_epoch_index;
+
+    let redelegation_count = redelegations.len() as u32;
+    for redelegation in redelegations {
+        let (nominator_id, from_operator_id, to_operator_id) = (
+            redelegation.nominator_id.clone(),
+            redelegation.from_operator_id,
+            redelegation.to_operator_id,
+        );
+        if let Some(reason) =
+            finalize_redelegation::<T>(domain_id, current_epoch_index, redelegation)?
+        {
+            Pallet::<T>::deposit_event(Event::RedelegationFailed {
+                nominator_id,
+                from_operator_id,
+                to_operator_id,
+                reason,
+            });
+        }
+    }
+
+    Ok(redelegation_count)
+}
+
+/// Moves the redelegated stake from the withdrawal of the previous operator to a pending deposit
+/// of the new operator and records it as a liability of the previous operator until the unlocking
+/// period is complete.
+///
+/// Returns the reason if the redelegation is not possible, in which case the withdrawal of the
+/// previous operator is left untouched.
+fn finalize_redelegation<T: Config>(
+    domain_id: DomainId,
+    current_epoch_index: EpochIndex,
+    redelegation: PendingRedelegationOf<T>,
+) -> Result<Option<RedelegationFailure>, TransitionError> {
+    let PendingRedelegation {
+        nominator_id,
+        from_operator_id,
+        to_operator_id,
+        domain_epoch,
+        unlock_at_confirmed_domain_block_number,
+        shares,
+        storage_fee_refund,
+    } = redelegation;
+
+    // the share price is missing if the previous operator is slashed or deregistered, in which
+    // case the redelegated stake is slashed or unlocked along with the rest of its stake
+    let Some(share_price) = OperatorEpochSharePrice::<T>::get(from_operator_id, domain_epoch)
+    else {
+        return Ok(Some(RedelegationFailure::PreviousOperatorNotAvailable));
+    };
+
+    let mut to_operator = match Operators::<T>::get(to_operator_id) {
+        Some(operator)
+            if *operator.status::<T>(to_operator_id) == OperatorStatus::Registered
+                && operator.current_domain_id == domain_id =>
+        {
+            operator
+        }
+        _ => return Ok(Some(RedelegationFailure::NewOperatorNotAvailable)),
+    };
+
+    let amount = share_price.shares_to_stake::<T>(shares);
+    let total_deposit = amount
+        .checked_add(&storage_fee_refund)
+        .ok_or(TransitionError::BalanceOverflow)?;
+
+    let new_nominator = Deposits::<T>::get(to_operator_id, nominator_id.clone())
+        .map(|deposit| deposit.known.shares.is_zero() && deposit.pending.is_none())
+        .unwrap_or(true);
+    if new_nominator
+        && (total_deposit < to_operator.minimum_nominator_stake
+            || NominatorCount::<T>::get(to_operator_id) >= T::MaxNominators::get())
+    {
+        return Ok(Some(RedelegationFailure::NominatorLimits));
+    }
+
+    // remove the redelegated shares from the withdrawal in shares of the redelegation epoch, the
+    // withdrawal is not converted to balance yet since the epoch transition is still in progress
+    let mut withdrawal = Withdrawals::<T>::get(from_operator_id, nominator_id.clone())
+        .ok_or(TransitionError::MissingRedelegatedWithdrawal)?;
+    let withdrawal_in_shares = withdrawal
+        .withdrawal_in_shares
+        .as_mut()
+        .filter(|withdrawal_in_shares| withdrawal_in_shares.domain_epoch == domain_epoch)
+        .ok_or(TransitionError::MissingRedelegatedWithdrawal)?;
+    withdrawal_in_shares.shares = withdrawal_in_shares
+        .shares
+        .checked_sub(&shares)
+        .ok_or(TransitionError::MissingRedelegatedWithdrawal)?;
+    withdrawal_in_shares.storage_fee_refund = withdrawal_in_shares
+        .storage_fee_refund
+        .checked_sub(&storage_fee_refund)
+        .ok_or(TransitionError::MissingRedelegatedWithdrawal)?;
+    if withdrawal_in_shares.shares.is_zero() && withdrawal_in_shares.storage_fee_refund.is_zero() {
+        withdrawal.withdrawal_in_shares = None;
+    }
+    if withdrawal.withdrawals.is_empty() && withdrawal.withdrawal_in_shares.is_none() {
+        Withdrawals::<T>::remove(from_operator_id, nominator_id.clone());
+        // if there is no deposit or pending deposits, then clean up the deposit state as well
+        Deposits::<T>::mutate_exists(from_operator_id, nominator_id.clone(), |maybe_deposit| {
+            if let Some(deposit) = maybe_deposit
+                && deposit.known.shares.is_zero()
+                && deposit.pending.is_none()
+            {
+                *maybe_deposit = None
+            }
+        });
+    } else {
+        Withdrawals::<T>::insert(from_operator_id, nominator_id.clone(), withdrawal);
+    }
+
+    // If the this is the first staking request of the new operator in the new epoch note it, the
+    // redelegations are noted as pending operations of the completed epoch so the limit can't be
+    // exceeded here.
+    if to_operator.deposits_in_epoch.is_zero() && to_operator.withdrawals_in_epoch.is_zero() {
+        PendingStakingOperationCount::<T>::mutate(domain_id, |count| {
+            *count = count.saturating_add(1);
+        });
+    }
+
+    // release the redelegated stake from the previous operator, any gains are minted
+    let staked_hold_id = T::HoldIdentifier::staking_staked(from_operator_id);
+    let locked_amount = T::Currency::balance_on_hold(&staked_hold_id, &nominator_id);
+    let amount_to_release = amount.min(locked_amount);
+    T::Currency::release(
+        &staked_hold_id,
+        &nominator_id,
+        amount_to_release,
+        Precision::Exact,
+    )
+    .map_err(|_| TransitionError::RemoveLock)?;
+    mint_funds::<T>(&nominator_id, amount.saturating_sub(amount_to_release))?;
+
+    // release the storage fee refund from the previous operator's storage fund
+    T::Currency::release(
+        &T::HoldIdentifier::storage_fund_withdrawal(from_operator_id),
+        &nominator_id,
+        storage_fee_refund,
+        Precision::Exact,
+    )
+    .map_err(|_| TransitionError::RemoveLock)?;
+
+    // deposit all of it to the new operator, same as a new nomination
+    let new_deposit =
+        deposit_reserve_for_storage_fund::<T>(to_operator_id, &nominator_id, total_deposit)
+            .map_err(TransitionError::BundleStorageFund)?;
+
+    hold_deposit::<T>(&nominator_id, to_operator_id, new_deposit.staking)?;
+
+    to_operator.deposits_in_epoch = to_operator
+        .deposits_in_epoch
+        .checked_add(&new_deposit.staking)
+        .ok_or(TransitionError::BalanceOverflow)?;
+    to_operator.total_storage_fee_deposit = to_operator
+        .total_storage_fee_deposit
+        .checked_add(&new_deposit.storage_fee_deposit)
+        .ok_or(TransitionError::BalanceOverflow)?;
+    Operators::<T>::insert(to_operator_id, to_operator);
+
+    do_calculate_previous_epoch_deposit_shares_and_add_new_deposit::<T>(
+        to_operator_id,
+        nominator_id.clone(),
+        (domain_id, current_epoch_index).into(),
+        new_deposit,
+    )?;
+
+    if new_nominator {
+        NominatorCount::<T>::mutate(to_operator_id, |count| {
+            *count += 1;
+        });
+    }
+
+    // the redelegated stake is still slashable for the previous operator until the unlocking
+    // period is complete
+    RedelegationLiabilities::<T>::mutate(domain_id, |liabilities| {
+        liabilities.push(RedelegationLiability {
+            nominator_id,
+            from_operator_id,
+            to_operator_id,
+            unlock_at_confirmed_domain_block_number,
+            amount: new_deposit.staking,
+        })
+    });
+
+    Ok(None)
+}
+
+/// Slashes the stake redelegated from the slashed operator, whose unlocking period is not
+/// complete yet, from the new operators and returns the number of slashed nominators.
+///
+/// The liabilities of the stake redelegated to the slashed operator are dropped, since all of its
+/// stake is slashed already.
+fn slash_redelegation_liabilities<T: Config>(
+    domain_id: DomainId,
+    operator_id: OperatorId,
+) -> Result<u32, TransitionError> {
+    let (slashed_liabilities, remaining_liabilities): (Vec<_>, Vec<_>) =
+        RedelegationLiabilities::<T>::take(domain_id)
+            .into_iter()
+            .filter(|liability| liability.to_operator_id != operator_id)
+            .partition(|liability| liability.from_operator_id == operator_id);
+    if !remaining_liabilities.is_empty() {
+        RedelegationLiabilities::<T>::insert(domain_id, remaining_liabilities);
+    }
+
+    let latest_confirmed_domain_block_number =
+        Pallet::<T>::latest_confirmed_domain_block_number(domain_id);
+    let mut slashed_nominator_count = 0;
+    for liability in slashed_liabilities {
+        if liability.unlock_at_confirmed_domain_block_number <= latest_confirmed_domain_block_number
+        {
+            continue;
+        }
+
+        slash_redelegated_stake::<T>(
+            liability.to_operator_id,
+            liability.nominator_id,
+            liability.amount,
+        )?;
+        slashed_nominator_count += 1;
+    }
+
+    Ok(slashed_nominator_count)
+}
+
+/// Slashes `amount` from the stake of the nominator with the operator into the treasury.
+///
+/// The pending deposit is slashed first, the rest is slashed from the known shares at the current
+/// share price of the operator.
+fn slash_redelegated_stake<T: Config>(
+    operator_id: OperatorId,
+    nominator_id: NominatorId<T>,
+    amount: BalanceOf<T>,
+) -> Result<(), TransitionError> {
+    // the operator may be slashed too, along with all of its stake
+    let Some(mut operator) = Operators::<T>::get(operator_id) else {
+        return Ok(());
+    };
+    let Some(mut deposit) = Deposits::<T>::get(operator_id, nominator_id.clone()) else {
+        return Ok(());
+    };
+    do_convert_previous_epoch_deposits::<T>(operator_id, &mut deposit)?;
+
+    let mut amount_to_slash = BalanceOf::<T>::zero();
+    if let Some(pending_deposit) = deposit.pending.as_mut() {
+        amount_to_slash = amount.min(pending_deposit.amount);
+        pending_deposit.amount = pending_deposit.amount.saturating_sub(amount_to_slash);
+        operator.deposits_in_epoch = operator
+            .deposits_in_epoch
+            .checked_sub(&amount_to_slash)
+            .ok_or(TransitionError::BalanceUnderflow)?;
+    }
+
+    let share_price = SharePrice::new::<T>(
+        operator.current_total_shares,
+        operator
+            .current_total_stake
+            .checked_add(&operator.current_epoch_rewards)
+            .ok_or(TransitionError::BalanceOverflow)?,
+    );
+    let shares_to_slash = share_price
+        .stake_to_shares::<T>(amount.saturating_sub(amount_to_slash))
+        .min(deposit.known.shares);
+    let stake_to_slash = share_price.shares_to_stake::<T>(shares_to_slash);
+    deposit.known.shares = deposit
+        .known
+        .shares
+        .checked_sub(&shares_to_slash)
+        .ok_or(TransitionError::ShareUnderflow)?;
+    operator.current_total_shares = operator
+        .current_total_shares
+        .checked_sub(&shares_to_slash)
+        .ok_or(TransitionError::ShareUnderflow)?;
+    operator.current_total_stake = operator
+        .current_total_stake
+        .checked_sub(&stake_to_slash)
+        .ok_or(TransitionError::BalanceUnderflow)?;
+    amount_to_slash = amount_to_slash
+        .checked_add(&stake_to_slash)
+        .ok_or(TransitionError::BalanceOverflow)?;
+
+    let staked_hold_id = T::HoldIdentifier::staking_staked(operator_id);
+    let locked_amount = T::Currency::balance_on_hold(&staked_hold_id, &nominator_id);
+    T::Currency::transfer_on_hold(
+        &staked_hold_id,
+        &nominator_id,
+        &T::TreasuryAccount::get(),
+        amount_to_slash.min(locked_amount),
+        Precision::Exact,
+        Restriction::Free,
+        Fortitude::Force,
+    )
+    .map_err(|_| TransitionError::RemoveLock)?;
+
+    Deposits::<T>::insert(operator_id, nominator_id, deposit);
+    Operators::<T>::insert(operator_id, operator);
+
+    Ok(())
+}
+
 pub(crate) fn mint_funds<T: Config>(
     account_id: &T::AccountId,
     amount_to_mint: BalanceOf<T>,
@@ -538,6 +963,9 @@ pub(crate) fn do_finalize_slashed_operators<T: Config>(
 
             Ok(())
         })?;
+
+        // slash the stake redelegated from the operator that is still in its unlocking period
+        slashed_nominator_count += slash_redelegation_liabilities::<T>(domain_id, operator_id)?;
     }
 
     Ok(slashed_nominator_count)
@@ -1,11 +1,12 @@
 use crate::block_tree::BlockTreeNode;
 use crate::domain_registry::{DomainConfig, DomainObject};
+use crate::domain_sunset::Error as DomainSunsetError;
 use crate::staking::Operator;
 use crate::{
     self as pallet_domains, BalanceOf, BlockSlot, BlockTree, BlockTreeNodes, BundleError, Config,
-    ConsensusBlockHash, DomainBlockNumberFor, DomainHashingFor, DomainRegistry, ExecutionInbox,
-    ExecutionReceiptOf, FraudProofError, FungibleHoldId, HeadReceiptNumber, NextDomainId,
-    Operators, ReceiptHashFor,
+    ConsensusBlockHash, DomainBlockNumberFor, DomainHashingFor, DomainRegistry, Error,
+    ExecutionInbox, ExecutionReceiptOf, FraudProofError, FungibleHoldId, HeadReceiptNumber,
+    NextDomainId, Operators, ReceiptHashFor,
 };
 use codec::{Decode, Encode, MaxEncodedLen};
 use domain_runtime_primitives::opaque::Header as DomainHeader;
@@ -206,6 +207,9 @@ impl BlockSlot<Test> for DummyBlockSlot {
     }
 }
 
+/// Remaining balance of the sunset domain returned by `MockDomainsTransfersTracker`
+const SUNSET_DOMAIN_BALANCE: Balance = 10 * SSC;
+
 pub struct MockDomainsTransfersTracker;
 
 impl sp_domains::DomainsTransfersTracker<Balance> for MockDomainsTransfersTracker {
@@ -253,6 +257,14 @@ impl sp_domains::DomainsTransfersTracker<Balance> for MockDomainsTransfersTracke
     fn reduce_domain_balance(_domain_id: DomainId, _amount: Balance) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    fn is_domain_transfers_settled(_domain_id: DomainId) -> bool {
+        true
+    }
+
+    fn close_domain_balance(_domain_id: DomainId) -> Result<Balance, Self::Error> {
+        Ok(SUNSET_DOMAIN_BALANCE)
+    }
 }
 
 impl pallet_domains::Config for Test {
@@ -918,9 +930,17 @@ fn test_invalid_block_fees_fraud_proof() {
                 domain_block.execution_receipt.block_fees.burned_balance + 1,
             ),
         );
+
+        // The storage proof doesn't match the state root of the receipt, the reason of the
+        // verification failure should be returned
+        let validation_error = Domains::check_fraud_proof(&fraud_proof).unwrap_err();
+        assert_eq!(validation_error.check, "InvalidBlockFeesFraudProof");
+        assert!(validation_error.reason.is_some());
+
         domain_block.execution_receipt.final_state_root = root;
         BlockTreeNodes::<Test>::insert(bad_receipt_hash, domain_block);
         assert_ok!(Domains::validate_fraud_proof(&fraud_proof),);
+        assert_ok!(Domains::check_fraud_proof(&fraud_proof));
     });
 }
 
@@ -1360,3 +1380,108 @@ fn test_basic_fraud_proof_processing() {
         });
     }
 }
+
+#[test]
+fn test_domain_sunset() {
+    let creator = 0u128;
+    let operator_id = 1u64;
+    let head_domain_number = 5;
+    let mut ext = new_test_ext_with_extensions();
+    ext.execute_with(|| {
+        let domain_id = register_genesis_domain(creator, vec![operator_id]);
+        let head_receipt =
+            extend_block_tree_from_zero(domain_id, operator_id, head_domain_number + 2);
+        assert_eq!(
+            HeadReceiptNumber::<Test>::get(domain_id),
+            head_domain_number
+        );
+
+        // Only the domain owner or root can deactivate the domain
+        assert_err!(
+            Domains::deactivate_domain(RawOrigin::Signed(creator + 1).into(), domain_id),
+            Error::<Test>::DomainSunset(DomainSunsetError::NotDomainOwner)
+        );
+        assert_ok!(Domains::deactivate_domain(
+            RawOrigin::Signed(creator).into(),
+            domain_id
+        ));
+        assert!(Domains::is_domain_deactivated(domain_id));
+        assert_err!(
+            Domains::deactivate_domain(RawOrigin::Root.into(), domain_id),
+            Error::<Test>::DomainSunset(DomainSunsetError::DomainAlreadyDeactivated)
+        );
+
+        // Bundles of the deactivated domain are rejected
+        let bundle =
+            create_dummy_bundle_with_receipts(domain_id, operator_id, H256::random(), head_receipt);
+        assert_err!(
+            Domains::validate_bundle(&bundle, false),
+            BundleError::DomainDeactivated
+        );
+
+        // The sunset can't be advanced during the challenge period
+        assert_err!(
+            Domains::advance_domain_sunset(RawOrigin::Signed(creator).into(), domain_id),
+            Error::<Test>::DomainSunset(DomainSunsetError::ChallengePeriodNotComplete)
+        );
+
+        // Fraud proofs are rejected after the challenge period
+        let challenge_period_end = frame_system::Pallet::<Test>::current_block_number()
+            + BlockTreePruningDepth::get() as u64;
+        run_to_block::<Test>(challenge_period_end, H256::random());
+        let head_receipt_hash = BlockTree::<Test>::get(domain_id, head_domain_number).unwrap();
+        let fraud_proof = FraudProof::dummy_fraud_proof(domain_id, head_receipt_hash);
+        assert_err!(
+            Domains::validate_fraud_proof(&fraud_proof),
+            FraudProofError::ChallengePeriodExpired
+        );
+
+        // Every step confirms one receipt, the last step sunsets the domain
+        for receipt_number in 0..=head_domain_number {
+            assert_ok!(Domains::advance_domain_sunset(
+                RawOrigin::Signed(creator).into(),
+                domain_id
+            ));
+            assert_eq!(
+                Domains::latest_confirmed_domain_block_number(domain_id),
+                receipt_number
+            );
+        }
+        let treasury_balance = Balances::free_balance(TreasuryAccount::get());
+        let total_issuance = Balances::total_issuance();
+        assert_ok!(Domains::advance_domain_sunset(
+            RawOrigin::Signed(creator).into(),
+            domain_id
+        ));
+        assert!(Domains::is_domain_sunset(domain_id));
+        System::assert_has_event(RuntimeEvent::Domains(crate::Event::DomainSunset {
+            domain_id,
+            remaining_balance: SUNSET_DOMAIN_BALANCE,
+        }));
+
+        // The remaining balance of the domain is minted into the treasury
+        assert_eq!(
+            Balances::free_balance(TreasuryAccount::get()),
+            treasury_balance + SUNSET_DOMAIN_BALANCE
+        );
+        assert_eq!(
+            Balances::total_issuance(),
+            total_issuance + SUNSET_DOMAIN_BALANCE
+        );
+        assert!(DomainRegistry::<Test>::get(domain_id).is_none());
+        assert!(BlockTree::<Test>::iter_prefix(domain_id).next().is_none());
+        assert_eq!(HeadReceiptNumber::<Test>::get(domain_id), 0);
+
+        // The instantiation deposit is released
+        assert_eq!(
+            <Test as Config>::Currency::free_balance(creator),
+            <Test as Config>::DomainInstantiationDeposit::get()
+                + <Test as pallet_balances::Config>::ExistentialDeposit::get()
+        );
+
+        assert_err!(
+            Domains::advance_domain_sunset(RawOrigin::Signed(creator).into(), domain_id),
+            Error::<Test>::DomainSunset(DomainSunsetError::DomainAlreadySunset)
+        );
+    });
+}
@@ -122,7 +122,7 @@ impl<T: Config, O: Offence<FarmerPublicKey>> ReportOffence<FarmerPublicKey, O> f
             None => return Err(OffenceError::DuplicateReport),
         };
 
-        T::OnOffenceHandler::on_offence(&concurrent_offenders);
+        T::OnOffenceHandler::on_offence(O::ID, &concurrent_offenders);
 
         // Deposit the event.
         Self::deposit_event(Event::Offence {
@@ -38,7 +38,7 @@ thread_local! {
 }
 
 impl<Offender> offence::OnOffenceHandler<Offender> for OnOffenceHandler {
-    fn on_offence(_offenders: &[OffenceDetails<Offender>]) {
+    fn on_offence(_kind: Kind, _offenders: &[OffenceDetails<Offender>]) {
         ON_OFFENCE_PERBILL.with(|f| {
             *f.borrow_mut() = vec![Perbill::from_percent(25)];
         });
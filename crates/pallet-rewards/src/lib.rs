@@ -26,6 +26,7 @@ mod benchmarking;
 mod mock;
 #[cfg(all(feature = "std", test))]
 mod tests;
+pub mod vesting;
 pub mod weights;
 
 use frame_support::pallet_prelude::*;
@@ -35,8 +36,9 @@ use log::warn;
 pub use pallet::*;
 use serde::{Deserialize, Serialize};
 use sp_core::U256;
+use sp_rewards::VestedRewards;
 use sp_runtime::traits::{CheckedSub, Zero};
-use sp_runtime::Saturating;
+use sp_runtime::{DispatchResult, Saturating};
 use subspace_runtime_primitives::{BlockNumber, FindBlockRewardAddress, FindVotingRewardAddresses};
 
 type BalanceOf<T> =
@@ -51,6 +53,25 @@ impl<AccountId, Balance> OnReward<AccountId, Balance> for () {
     fn on_reward(_account: AccountId, _reward: Balance) {}
 }
 
+/// Vesting of the share of rewards, such that it is released gradually over time.
+pub trait RewardVesting<AccountId, Balance> {
+    /// Vests `amount` of the reward that was already deposited to `account`.
+    fn vest(account: &AccountId, amount: Balance) -> DispatchResult;
+
+    /// Returns locked and claimable vested rewards of `account`.
+    fn vested_rewards(account: &AccountId) -> VestedRewards<Balance>;
+}
+
+impl<AccountId, Balance: Default> RewardVesting<AccountId, Balance> for () {
+    fn vest(_account: &AccountId, _amount: Balance) -> DispatchResult {
+        Ok(())
+    }
+
+    fn vested_rewards(_account: &AccountId) -> VestedRewards<Balance> {
+        VestedRewards::default()
+    }
+}
+
 #[derive(
     Debug,
     Default,
@@ -73,10 +94,11 @@ pub struct RewardPoint<BlockNumber, Balance> {
 #[frame_support::pallet]
 mod pallet {
     use crate::weights::WeightInfo;
-    use crate::{BalanceOf, OnReward, RewardPoint};
+    use crate::{BalanceOf, OnReward, RewardPoint, RewardVesting};
     use frame_support::pallet_prelude::*;
     use frame_support::traits::Currency;
     use frame_system::pallet_prelude::*;
+    use sp_runtime::Perbill;
     use subspace_runtime_primitives::{FindBlockRewardAddress, FindVotingRewardAddresses};
 
     /// Pallet rewards for issuing rewards to block producers.
@@ -118,6 +140,14 @@ mod pallet {
         type WeightInfo: WeightInfo;
 
         type OnReward: OnReward<Self::AccountId, BalanceOf<Self>>;
+
+        /// Share of every reward that is vested instead of being paid out immediately, zero
+        /// disables vesting of rewards
+        #[pallet::constant]
+        type VestedRewardShare: Get<Perbill>;
+
+        /// Vesting of the vested share of rewards
+        type RewardVesting: RewardVesting<Self::AccountId, BalanceOf<Self>>;
     }
 
     #[pallet::genesis_config]
@@ -208,6 +238,11 @@ mod pallet {
             voter: T::AccountId,
             reward: BalanceOf<T>,
         },
+        /// Share of the issued reward was vested
+        RewardVested {
+            account: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     #[pallet::hooks]
@@ -320,7 +355,11 @@ impl<T: Config> Pallet<T> {
                     let _imbalance = T::Currency::deposit_creating(&voter, reward);
                     T::OnReward::on_reward(voter.clone(), reward);
 
-                    Self::deposit_event(Event::VoteReward { voter, reward });
+                    Self::deposit_event(Event::VoteReward {
+                        voter: voter.clone(),
+                        reward,
+                    });
+                    Self::vest_reward(&voter, reward);
                 }
             }
         }
@@ -331,9 +370,10 @@ impl<T: Config> Pallet<T> {
                 T::OnReward::on_reward(block_author.clone(), block_reward);
 
                 Self::deposit_event(Event::BlockReward {
-                    block_author,
+                    block_author: block_author.clone(),
                     reward: block_reward,
                 });
+                Self::vest_reward(&block_author, block_reward);
             }
         }
 
@@ -342,6 +382,33 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Returns locked and claimable vested rewards of the reward address.
+    pub fn vested_rewards(account: T::AccountId) -> VestedRewards<BalanceOf<T>> {
+        T::RewardVesting::vested_rewards(&account)
+    }
+
+    /// Vests the configured share of `reward` already deposited to `account`.
+    ///
+    /// Failure to vest is not fatal, in which case the whole reward stays available immediately.
+    fn vest_reward(account: &T::AccountId, reward: BalanceOf<T>) {
+        let vested_amount = T::VestedRewardShare::get() * reward;
+        if vested_amount.is_zero() {
+            return;
+        }
+
+        match T::RewardVesting::vest(account, vested_amount) {
+            Ok(()) => {
+                Self::deposit_event(Event::RewardVested {
+                    account: account.clone(),
+                    amount: vested_amount,
+                });
+            }
+            Err(error) => {
+                warn!("Failed to vest {vested_amount:?} of the reward for {account:?}: {error:?}");
+            }
+        }
+    }
+
     /// Returns new updated average blockspace usage based on given parameters
     fn update_avg_blockspace_usage(
         used_blockspace: u32,
@@ -2,7 +2,9 @@
 
 use frame_support::derive_impl;
 use frame_support::traits::{ConstU128, ConstU32};
+use frame_system::EnsureRoot;
 use sp_runtime::traits::parameter_types;
+use sp_runtime::{BuildStorage, Perbill};
 use subspace_runtime_primitives::{
     FindBlockRewardAddress, FindVotingRewardAddresses, RewardsEnabled,
 };
@@ -14,6 +16,7 @@ frame_support::construct_runtime!(
     pub struct Test {
         System: frame_system,
         Balances: pallet_balances,
+        Vesting: orml_vesting,
         Rewards: crate,
     }
 );
@@ -33,8 +36,21 @@ impl pallet_balances::Config for Test {
     type DustRemoval = ();
 }
 
+impl orml_vesting::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type MinVestedTransfer = ConstU128<0>;
+    type VestedTransferOrigin = EnsureRoot<u64>;
+    type WeightInfo = ();
+    type MaxVestingSchedules = ConstU32<4>;
+    type BlockNumberProvider = System;
+}
+
 parameter_types! {
     pub const ProposerTaxOnVotes: (u32, u32) = (1, 10);
+    pub const VestedRewardShare: Perbill = Perbill::from_percent(50);
+    pub const RewardVestingPeriod: u64 = 10;
+    pub const RewardVestingPeriodCount: u32 = 2;
 }
 
 pub struct MockRewardsEnabled;
@@ -73,4 +89,19 @@ impl crate::Config for Test {
     type FindVotingRewardAddresses = MockFindVotingRewardAddresses;
     type WeightInfo = ();
     type OnReward = ();
+    type VestedRewardShare = VestedRewardShare;
+    type RewardVesting =
+        crate::vesting::OrmlRewardVesting<Test, RewardVestingPeriod, RewardVestingPeriodCount>;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    let mut t: sp_io::TestExternalities = t.into();
+
+    t.execute_with(|| System::set_block_number(1));
+
+    t
 }
@@ -1,5 +1,8 @@
-use crate::mock::Test;
-use crate::RewardPoint;
+use crate::mock::{new_test_ext, Balances, RuntimeOrigin, System, Test, Vesting};
+use crate::{Event, RewardPoint};
+use frame_support::assert_ok;
+use frame_support::traits::Currency;
+use sp_rewards::VestedRewards;
 
 type Pallet = crate::Pallet<Test>;
 
@@ -115,3 +118,69 @@ fn correct_block_vote_reward() {
         last_point.subsidy
     );
 }
+
+#[test]
+fn vested_share_of_reward() {
+    new_test_ext().execute_with(|| {
+        let account = 1;
+        // Reward must cover existential deposit
+        let _imbalance = Balances::deposit_creating(&account, 100);
+        Pallet::vest_reward(&account, 100);
+
+        assert_eq!(
+            Pallet::vested_rewards(account),
+            VestedRewards {
+                locked: 50,
+                claimable: 0,
+            }
+        );
+        System::assert_last_event(
+            Event::<Test>::RewardVested {
+                account,
+                amount: 50,
+            }
+            .into(),
+        );
+
+        // Vesting starts with the next period, half of vested share is released every period
+        System::set_block_number(25);
+        assert_eq!(
+            Pallet::vested_rewards(account),
+            VestedRewards {
+                locked: 25,
+                claimable: 25,
+            }
+        );
+        assert_ok!(Vesting::claim(RuntimeOrigin::signed(account)));
+        assert_eq!(
+            Pallet::vested_rewards(account),
+            VestedRewards {
+                locked: 25,
+                claimable: 0,
+            }
+        );
+
+        System::set_block_number(30);
+        assert_eq!(
+            Pallet::vested_rewards(account),
+            VestedRewards {
+                locked: 0,
+                claimable: 25,
+            }
+        );
+    });
+}
+
+#[test]
+fn vested_rewards_in_same_period_are_merged() {
+    new_test_ext().execute_with(|| {
+        let account = 1;
+        let _imbalance = Balances::deposit_creating(&account, 100);
+        Pallet::vest_reward(&account, 40);
+        System::set_block_number(9);
+        Pallet::vest_reward(&account, 60);
+
+        assert_eq!(Vesting::vesting_schedules(account).len(), 1);
+        assert_eq!(Pallet::vested_rewards(account).locked, 50);
+    });
+}
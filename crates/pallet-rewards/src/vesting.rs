@@ -0,0 +1,73 @@
+//! Vesting of rewards using `orml-vesting`.
+
+use crate::RewardVesting;
+use core::marker::PhantomData;
+use frame_support::traits::Get;
+use frame_system::pallet_prelude::BlockNumberFor;
+use orml_vesting::{VestingSchedule, VESTING_LOCK_ID};
+use sp_rewards::VestedRewards;
+use sp_runtime::traits::{BlockNumberProvider, CheckedAdd, CheckedMul, One, Saturating, Zero};
+use sp_runtime::{ArithmeticError, DispatchResult};
+
+/// Vests rewards with `orml-vesting`, releasing them in `VestingPeriodCount` equal parts, one
+/// every `VestingPeriod` blocks.
+///
+/// Vesting starts at the beginning of the period following the reward, such that all rewards of
+/// the account within the same period are merged into a single vesting schedule. This means
+/// `MaxVestingSchedules` of `orml-vesting` must fit `VestingPeriodCount + 1` schedules in addition
+/// to the schedules created by other means. Remainder of the division of the reward into
+/// `VestingPeriodCount` parts is not vested.
+pub struct OrmlRewardVesting<T, VestingPeriod, VestingPeriodCount>(
+    PhantomData<(T, VestingPeriod, VestingPeriodCount)>,
+);
+
+impl<T, VestingPeriod, VestingPeriodCount> RewardVesting<T::AccountId, T::Balance>
+    for OrmlRewardVesting<T, VestingPeriod, VestingPeriodCount>
+where
+    T: orml_vesting::Config<Currency = pallet_balances::Pallet<T>> + pallet_balances::Config,
+    VestingPeriod: Get<BlockNumberFor<T>>,
+    VestingPeriodCount: Get<u32>,
+{
+    fn vest(account: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let period = VestingPeriod::get();
+        let period_count = VestingPeriodCount::get();
+        if period.is_zero() || period_count.is_zero() {
+            return Ok(());
+        }
+
+        let per_period = amount / T::Balance::from(period_count);
+        if per_period.is_zero() {
+            return Ok(());
+        }
+
+        let now = <T as orml_vesting::Config>::BlockNumberProvider::current_block_number();
+        let start = (now / period)
+            .checked_add(&One::one())
+            .and_then(|next_period| next_period.checked_mul(&period))
+            .ok_or(ArithmeticError::Overflow)?;
+
+        orml_vesting::Pallet::<T>::add_vesting_schedule(
+            account,
+            VestingSchedule {
+                start,
+                period,
+                period_count,
+                per_period,
+            },
+        )
+    }
+
+    fn vested_rewards(account: &T::AccountId) -> VestedRewards<T::Balance> {
+        let locked = orml_vesting::Pallet::<T>::vesting_locked_balance(account);
+        let lock_amount = pallet_balances::Pallet::<T>::locks(account)
+            .iter()
+            .find(|lock| lock.id == VESTING_LOCK_ID)
+            .map(|lock| lock.amount)
+            .unwrap_or_default();
+
+        VestedRewards {
+            locked,
+            claimable: lock_amount.saturating_sub(locked),
+        }
+    }
+}
@@ -4,7 +4,7 @@ use frame_benchmarking::v2::*;
 
 #[benchmarks]
 mod benchmarks {
-    use crate::{Call, Config, Pallet};
+    use crate::{Call, CallIndices, CallPermission, CallPermissions, Config, Pallet};
     use frame_system::RawOrigin;
 
     #[benchmark]
@@ -38,4 +38,20 @@ mod benchmarks {
 
         assert!(Pallet::<T>::enable_non_root_calls());
     }
+
+    #[benchmark]
+    fn set_call_permission() {
+        let call_indices = CallIndices {
+            pallet_index: 0,
+            call_index: Some(0),
+        };
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, call_indices, Some(CallPermission::Allow));
+
+        assert_eq!(
+            CallPermissions::<T>::get(call_indices),
+            Some(CallPermission::Allow)
+        );
+    }
 }
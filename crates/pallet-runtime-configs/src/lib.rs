@@ -19,14 +19,73 @@
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 pub mod weights;
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use frame_support::traits::{Contains, Nothing};
 pub use pallet::*;
+use scale_info::TypeInfo;
+
+/// Calls a [`CallPermission`] applies to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct CallIndices {
+    /// Index of the pallet in the runtime.
+    pub pallet_index: u8,
+    /// Index of the call in the pallet, `None` means all calls of the pallet.
+    pub call_index: Option<u8>,
+}
+
+/// Permission of the calls from non-root accounts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum CallPermission {
+    /// Calls are allowed even if balance transfers are disabled, this doesn't override any other
+    /// runtime configs.
+    Allow,
+    /// Calls are denied for all origins except root (see [`CallPermissionsFilter`]), this
+    /// includes unsigned calls. Calls of the pallets that provide inherents can't be denied.
+    Deny,
+}
+
+/// Provides the indices of a runtime call without encoding it, implemented for the `RuntimeCall`
+/// of the runtime with [`impl_get_call_indices`].
+pub trait GetCallIndices {
+    /// Returns the index of the pallet in the runtime and the index of the call in the pallet.
+    fn call_indices(&self) -> (u8, u8);
+}
+
+/// Implements [`GetCallIndices`] for the `RuntimeCall` of the runtime, the pallet index is
+/// provided by `PalletInfoAccess` and the call index by `GetCallIndex` of the pallet calls.
+///
+/// All pallets with calls must be listed by their name in the runtime.
+#[macro_export]
+macro_rules! impl_get_call_indices {
+    ($runtime_call:ident, $($pallet:ident),+ $(,)?) => {
+        impl $crate::GetCallIndices for $runtime_call {
+            fn call_indices(&self) -> (u8, u8) {
+                match self {
+                    $(
+                        $runtime_call::$pallet(call) => (
+                            <$pallet as frame_support::traits::PalletInfoAccess>::index() as u8,
+                            frame_support::traits::GetCallIndex::get_call_index(call),
+                        ),
+                    )+
+                }
+            }
+        }
+    };
+}
 
 #[frame_support::pallet]
 mod pallet {
     use crate::weights::WeightInfo;
+    use crate::{CallIndices, CallPermission};
     use frame_support::pallet_prelude::*;
+    use frame_support::traits::Contains;
     use frame_system::pallet_prelude::*;
     use sp_runtime::traits::Zero;
 
@@ -58,12 +117,42 @@ mod pallet {
     #[pallet::storage]
     pub type ConfirmationDepthK<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+    /// Permissions of the calls from non-root accounts, calls without a permission are subject to
+    /// the other runtime configs.
+    #[pallet::storage]
+    pub type CallPermissions<T> =
+        StorageMap<_, Twox64Concat, CallIndices, CallPermission, OptionQuery>;
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
+        /// `pallet-runtime-configs` events
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Indices of the pallets that provide inherents, calls of these pallets can't be denied
+        /// since the base call filter also applies to inherents.
+        type PalletsWithInherents: Contains<u8>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
+    /// `pallet-runtime-configs` events
+    #[pallet::event]
+    #[pallet::generate_deposit(pub (super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Permission of the calls was changed, `None` means it was removed.
+        CallPermissionChanged {
+            call_indices: CallIndices,
+            permission: Option<CallPermission>,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Calls of a pallet that provides inherents can't be denied.
+        PalletWithInherents,
+    }
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         /// Whether to enable domains
@@ -169,5 +258,71 @@ mod pallet {
 
             Ok(())
         }
+
+        /// Set or remove (if `None`) the permission of the calls from non-root users.
+        #[pallet::call_index(4)]
+        #[pallet::weight(< T as Config >::WeightInfo::set_call_permission())]
+        pub fn set_call_permission(
+            origin: OriginFor<T>,
+            call_indices: CallIndices,
+            permission: Option<CallPermission>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(
+                permission != Some(CallPermission::Deny)
+                    || !T::PalletsWithInherents::contains(&call_indices.pallet_index),
+                Error::<T>::PalletWithInherents
+            );
+
+            CallPermissions::<T>::set(call_indices, permission);
+
+            Self::deposit_event(Event::CallPermissionChanged {
+                call_indices,
+                permission,
+            });
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the permission of the call, permission of the specific call takes precedence over
+    /// the permission of all calls of the pallet.
+    ///
+    /// `None` means there is no permission for the call, in which case it is subject to the other
+    /// runtime configs.
+    pub fn call_permission<Call: GetCallIndices>(call: &Call) -> Option<CallPermission> {
+        let (pallet_index, call_index) = call.call_indices();
+
+        CallPermissions::<T>::get(CallIndices {
+            pallet_index,
+            call_index: Some(call_index),
+        })
+        .or_else(|| {
+            CallPermissions::<T>::get(CallIndices {
+                pallet_index,
+                call_index: None,
+            })
+        })
+    }
+}
+
+/// Call filter that denies calls with [`CallPermission::Deny`] permission.
+///
+/// Meant to be used as `frame_system::Config::BaseCallFilter`, such that calls nested in other
+/// calls (like `pallet_utility::Call::batch`) are denied too. Calls dispatched with root origin
+/// bypass the base call filter, while calls matching `Exempt` are never denied.
+pub struct CallPermissionsFilter<T, Exempt = Nothing>(PhantomData<(T, Exempt)>);
+
+impl<T, Exempt, Call> Contains<Call> for CallPermissionsFilter<T, Exempt>
+where
+    T: Config,
+    Exempt: Contains<Call>,
+    Call: GetCallIndices,
+{
+    fn contains(call: &Call) -> bool {
+        Exempt::contains(call) || Pallet::<T>::call_permission(call) != Some(CallPermission::Deny)
     }
 }
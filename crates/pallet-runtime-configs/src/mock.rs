@@ -0,0 +1,56 @@
+use crate::CallPermissionsFilter;
+use frame_support::derive_impl;
+use frame_support::traits::{Contains, PalletInfoAccess};
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        RuntimeConfigs: crate,
+        Utility: pallet_utility,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type BaseCallFilter = CallPermissionsFilter<Test>;
+    type Block = Block;
+}
+
+crate::impl_get_call_indices!(RuntimeCall, System, RuntimeConfigs, Utility);
+
+/// Utility pallet stands for a pallet that provides inherents in tests.
+pub struct PalletsWithInherents;
+
+impl Contains<u8> for PalletsWithInherents {
+    fn contains(pallet_index: &u8) -> bool {
+        usize::from(*pallet_index) == Utility::index()
+    }
+}
+
+impl crate::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletsWithInherents = PalletsWithInherents;
+    type WeightInfo = ();
+}
+
+impl pallet_utility::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    let mut t: sp_io::TestExternalities = t.into();
+
+    t.execute_with(|| System::set_block_number(1));
+
+    t
+}
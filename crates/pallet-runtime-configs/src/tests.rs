@@ -0,0 +1,166 @@
+use crate::mock::{
+    new_test_ext, RuntimeCall, RuntimeConfigs, RuntimeOrigin, System, Test, Utility,
+};
+use crate::{CallIndices, CallPermission, CallPermissions, Error, GetCallIndices};
+use frame_support::traits::PalletInfoAccess;
+use frame_support::{assert_err, assert_noop, assert_ok};
+use sp_runtime::traits::Dispatchable;
+
+const ACCOUNT_ID: u64 = 100;
+const SYSTEM_PALLET_INDEX: u8 = 0;
+const REMARK_CALL_INDEX: u8 = 0;
+
+fn remark_call() -> RuntimeCall {
+    RuntimeCall::System(frame_system::Call::remark {
+        remark: vec![1, 2, 3],
+    })
+}
+
+fn set_call_permission(call_index: Option<u8>, permission: Option<CallPermission>) {
+    assert_ok!(RuntimeConfigs::set_call_permission(
+        RuntimeOrigin::root(),
+        CallIndices {
+            pallet_index: SYSTEM_PALLET_INDEX,
+            call_index,
+        },
+        permission,
+    ));
+}
+
+#[test]
+fn denied_call_is_filtered() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(remark_call().dispatch(RuntimeOrigin::signed(ACCOUNT_ID)));
+
+        set_call_permission(Some(REMARK_CALL_INDEX), Some(CallPermission::Deny));
+        assert_eq!(
+            RuntimeConfigs::call_permission(&remark_call()),
+            Some(CallPermission::Deny)
+        );
+        assert_err!(
+            remark_call()
+                .dispatch(RuntimeOrigin::signed(ACCOUNT_ID))
+                .map_err(|error| error.error),
+            frame_system::Error::<Test>::CallFiltered
+        );
+        // Root bypasses call permissions
+        assert_ok!(remark_call().dispatch(RuntimeOrigin::root()));
+
+        // Removing the permission allows the call again
+        set_call_permission(Some(REMARK_CALL_INDEX), None);
+        assert_ok!(remark_call().dispatch(RuntimeOrigin::signed(ACCOUNT_ID)));
+
+        // Permission of all calls of the pallet
+        set_call_permission(None, Some(CallPermission::Deny));
+        assert_err!(
+            remark_call()
+                .dispatch(RuntimeOrigin::signed(ACCOUNT_ID))
+                .map_err(|error| error.error),
+            frame_system::Error::<Test>::CallFiltered
+        );
+    });
+}
+
+#[test]
+fn call_indices_are_resolved() {
+    assert_eq!(
+        remark_call().call_indices(),
+        (SYSTEM_PALLET_INDEX, REMARK_CALL_INDEX)
+    );
+    assert_eq!(
+        RuntimeCall::Utility(pallet_utility::Call::batch_all {
+            calls: vec![remark_call()],
+        })
+        .call_indices(),
+        (Utility::index() as u8, 2)
+    );
+}
+
+#[test]
+fn calls_of_pallet_with_inherents_cannot_be_denied() {
+    new_test_ext().execute_with(|| {
+        for call_index in [None, Some(0)] {
+            let call_indices = CallIndices {
+                pallet_index: Utility::index() as u8,
+                call_index,
+            };
+
+            assert_noop!(
+                RuntimeConfigs::set_call_permission(
+                    RuntimeOrigin::root(),
+                    call_indices,
+                    Some(CallPermission::Deny),
+                ),
+                Error::<Test>::PalletWithInherents
+            );
+
+            assert_ok!(RuntimeConfigs::set_call_permission(
+                RuntimeOrigin::root(),
+                call_indices,
+                Some(CallPermission::Allow),
+            ));
+            assert_eq!(
+                CallPermissions::<Test>::get(call_indices),
+                Some(CallPermission::Allow)
+            );
+        }
+    });
+}
+
+#[test]
+fn allowed_call_takes_precedence_over_pallet_permission() {
+    new_test_ext().execute_with(|| {
+        set_call_permission(None, Some(CallPermission::Deny));
+        set_call_permission(Some(REMARK_CALL_INDEX), Some(CallPermission::Allow));
+
+        assert_eq!(
+            RuntimeConfigs::call_permission(&remark_call()),
+            Some(CallPermission::Allow)
+        );
+        assert_ok!(remark_call().dispatch(RuntimeOrigin::signed(ACCOUNT_ID)));
+
+        // Other calls of the pallet are still denied
+        assert_err!(
+            RuntimeCall::System(frame_system::Call::remark_with_event {
+                remark: vec![1, 2, 3],
+            })
+            .dispatch(RuntimeOrigin::signed(ACCOUNT_ID))
+            .map_err(|error| error.error),
+            frame_system::Error::<Test>::CallFiltered
+        );
+    });
+}
+
+#[test]
+fn denied_call_nested_in_batch_is_filtered() {
+    new_test_ext().execute_with(|| {
+        set_call_permission(Some(REMARK_CALL_INDEX), Some(CallPermission::Deny));
+
+        assert_err!(
+            RuntimeCall::Utility(pallet_utility::Call::batch_all {
+                calls: vec![remark_call()],
+            })
+            .dispatch(RuntimeOrigin::signed(ACCOUNT_ID))
+            .map_err(|error| error.error),
+            frame_system::Error::<Test>::CallFiltered
+        );
+
+        assert_ok!(RuntimeCall::Utility(pallet_utility::Call::batch {
+            calls: vec![remark_call()],
+        })
+        .dispatch(RuntimeOrigin::signed(ACCOUNT_ID)));
+        System::assert_last_event(
+            pallet_utility::Event::BatchInterrupted {
+                index: 0,
+                error: frame_system::Error::<Test>::CallFiltered.into(),
+            }
+            .into(),
+        );
+
+        // Root bypasses call permissions of nested calls too
+        assert_ok!(RuntimeCall::Utility(pallet_utility::Call::batch_all {
+            calls: vec![remark_call()],
+        })
+        .dispatch(RuntimeOrigin::root()));
+    });
+}
@@ -33,6 +33,7 @@ pub trait WeightInfo {
 	fn set_enable_dynamic_cost_of_storage() -> Weight;
 	fn set_enable_balance_transfers() -> Weight;
 	fn set_enable_non_root_calls() -> Weight;
+	fn set_call_permission() -> Weight;
 }
 
 /// Weights for pallet_runtime_configs using the Substrate node and recommended hardware.
@@ -78,6 +79,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(5_890_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `RuntimeConfigs::CallPermissions` (r:0 w:1)
+	/// Proof: `RuntimeConfigs::CallPermissions` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	fn set_call_permission() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 7_012_000 picoseconds.
+		Weight::from_parts(7_245_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -122,4 +133,14 @@ impl WeightInfo for () {
 		Weight::from_parts(5_890_000, 0)
 			.saturating_add(ParityDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `RuntimeConfigs::CallPermissions` (r:0 w:1)
+	/// Proof: `RuntimeConfigs::CallPermissions` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	fn set_call_permission() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 7_012_000 picoseconds.
+		Weight::from_parts(7_245_000, 0)
+			.saturating_add(ParityDbWeight::get().writes(1_u64))
+	}
 }
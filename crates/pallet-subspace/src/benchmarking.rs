@@ -8,9 +8,9 @@ use frame_benchmarking::v2::*;
 #[benchmarks]
 mod benchmarks {
     use crate::{
-        AllowAuthoringByAnyone, Call, Config, CurrentSlot, EnableRewards, EnableRewardsAt,
-        NextSolutionRangeOverride, Pallet, SegmentCommitment, ShouldAdjustSolutionRange,
-        SolutionRanges,
+        AllowAuthoringByAnyone, BlockList, BlockListExpiry, Call, Config, CurrentSlot,
+        EnableRewards, EnableRewardsAt, NextSolutionRangeOverride, Pallet, SegmentCommitment,
+        ShouldAdjustSolutionRange, SolutionRanges,
     };
     #[cfg(not(feature = "std"))]
     use alloc::vec::Vec;
@@ -147,6 +147,19 @@ mod benchmarks {
         assert!(Pallet::<T>::root_plot_public_key().is_none());
     }
 
+    #[benchmark]
+    fn remove_from_block_list() {
+        let farmer_public_key = FarmerPublicKey::unchecked_from([1u8; 32]);
+        BlockList::<T>::insert(&farmer_public_key, ());
+        BlockListExpiry::<T>::insert(&farmer_public_key, BlockNumberFor::<T>::from(100u32));
+
+        #[extrinsic_call]
+        _(RawOrigin::Root, farmer_public_key.clone());
+
+        assert!(!BlockList::<T>::contains_key(&farmer_public_key));
+        assert!(!BlockListExpiry::<T>::contains_key(&farmer_public_key));
+    }
+
     // Create a dummy segment header
     fn create_segment_header(segment_index: SegmentIndex) -> SegmentHeader {
         SegmentHeader::V0 {
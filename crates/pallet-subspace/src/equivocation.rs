@@ -38,14 +38,16 @@ use frame_system::pallet_prelude::*;
 use sp_consensus_slots::Slot;
 use sp_consensus_subspace::offence::{Kind, Offence, OffenceError, ReportOffence};
 use sp_consensus_subspace::{EquivocationProof, FarmerPublicKey};
+use sp_runtime::traits::Convert;
 use sp_runtime::transaction_validity::{
     InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
     TransactionValidityError, ValidTransaction,
 };
 use sp_runtime::DispatchResult;
+use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 
-use crate::{Call, Config, Pallet};
+use crate::{BlockListDuration, Call, Config, Pallet};
 
 /// A trait with utility methods for handling equivocation reports in Subspace. The trait provides
 /// methods for reporting an offence triggered by a valid equivocation report, checking the current
@@ -241,6 +243,24 @@ pub struct SubspaceEquivocationOffence<PublicKey> {
     pub offender: PublicKey,
 }
 
+/// Block list duration of the offences, farmers reported for equivocation are blocked for
+/// `EquivocationDuration` while all other offences block farmers permanently.
+pub struct OffenceBlockListDuration<EquivocationDuration>(PhantomData<EquivocationDuration>);
+
+impl<BlockNumber, EquivocationDuration> Convert<Kind, BlockListDuration<BlockNumber>>
+    for OffenceBlockListDuration<EquivocationDuration>
+where
+    EquivocationDuration: Get<BlockListDuration<BlockNumber>>,
+{
+    fn convert(kind: Kind) -> BlockListDuration<BlockNumber> {
+        if kind == <SubspaceEquivocationOffence<FarmerPublicKey> as Offence<FarmerPublicKey>>::ID {
+            EquivocationDuration::get()
+        } else {
+            BlockListDuration::Permanent
+        }
+    }
+}
+
 impl<PublicKey: Clone> Offence<PublicKey> for SubspaceEquivocationOffence<PublicKey> {
     const ID: Kind = *b"sub:equivocation";
     type TimeSlot = Slot;
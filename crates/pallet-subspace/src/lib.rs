@@ -49,13 +49,13 @@ use schnorrkel::SignatureError;
 use sp_consensus_slots::Slot;
 use sp_consensus_subspace::consensus::{is_proof_of_time_valid, verify_solution};
 use sp_consensus_subspace::digests::CompatibleDigestItem;
-use sp_consensus_subspace::offence::{OffenceDetails, OffenceError, OnOffenceHandler};
+use sp_consensus_subspace::offence::{Kind, OffenceDetails, OffenceError, OnOffenceHandler};
 use sp_consensus_subspace::{
     EquivocationProof, FarmerPublicKey, FarmerSignature, PotParameters, PotParametersChange,
     SignedVote, Vote, WrappedPotOutput,
 };
 use sp_runtime::generic::DigestItem;
-use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Hash, One, Zero};
+use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Convert, Hash, One, Saturating, Zero};
 use sp_runtime::transaction_validity::{
     InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
     TransactionValidityError, ValidTransaction,
@@ -111,7 +111,9 @@ pub mod pallet {
     use sp_consensus_slots::Slot;
     use sp_consensus_subspace::digests::CompatibleDigestItem;
     use sp_consensus_subspace::inherents::{InherentError, InherentType, INHERENT_IDENTIFIER};
+    use sp_consensus_subspace::offence::Kind;
     use sp_consensus_subspace::{EquivocationProof, FarmerPublicKey, FarmerSignature, SignedVote};
+    use sp_runtime::traits::Convert;
     use sp_runtime::DigestItem;
     use sp_std::collections::btree_map::BTreeMap;
     use sp_std::num::NonZeroU32;
@@ -241,6 +243,9 @@ pub mod pallet {
         /// use this pallet's `ValidateUnsigned` in the runtime definition.
         type HandleEquivocation: HandleEquivocation<Self>;
 
+        /// How long an offender stays in the block list, depending on the kind of the offence.
+        type OffenceBlockListDuration: Convert<Kind, BlockListDuration<BlockNumberFor<Self>>>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
 
@@ -269,6 +274,17 @@ pub mod pallet {
         pub(super) entropy: Blake3Hash,
     }
 
+    /// How long an offender stays in the block list
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+    pub enum BlockListDuration<BlockNumber> {
+        /// For specified number of blocks
+        Blocks(BlockNumber),
+        /// For specified number of eras
+        Eras(u32),
+        /// Until removed with an explicit extrinsic
+        Permanent,
+    }
+
     /// When to enable block/vote rewards
     #[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -359,6 +375,15 @@ pub mod pallet {
             height: BlockNumberFor<T>,
             parent_hash: T::Hash,
         },
+        /// Farmer was added to the block list.
+        FarmerBlockListed {
+            public_key: FarmerPublicKey,
+            /// Block number at which the farmer is removed from the block list, `None` if blocked
+            /// permanently.
+            expires_at: Option<BlockNumberFor<T>>,
+        },
+        /// Farmer was removed from the block list.
+        FarmerRemovedFromBlockList { public_key: FarmerPublicKey },
     }
 
     #[pallet::error]
@@ -369,6 +394,8 @@ pub mod pallet {
         SolutionRangeAdjustmentAlreadyEnabled,
         /// Rewards already active.
         RewardsAlreadyEnabled,
+        /// Farmer is not in the block list.
+        FarmerNotInBlockList,
     }
 
     // TODO: Remove genesis slot
@@ -422,6 +449,12 @@ pub mod pallet {
     #[pallet::storage]
     pub(super) type BlockList<T> = StorageMap<_, Twox64Concat, FarmerPublicKey, ()>;
 
+    /// Block number at which the farmer is removed from the block list, farmers in the block list
+    /// without an entry here are blocked permanently.
+    #[pallet::storage]
+    pub(super) type BlockListExpiry<T> =
+        StorageMap<_, Twox64Concat, FarmerPublicKey, BlockNumberFor<T>>;
+
     /// Mapping from segment index to corresponding segment commitment of contained records.
     #[pallet::storage]
     #[pallet::getter(fn segment_commitment)]
@@ -523,6 +556,11 @@ pub mod pallet {
         fn on_finalize(block_number: BlockNumberFor<T>) {
             Self::do_finalize(block_number)
         }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_block_number: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
     }
 
     #[pallet::call]
@@ -622,6 +660,18 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Remove farmer from the block list before its block list entry expires.
+        #[pallet::call_index(6)]
+        #[pallet::weight(< T as Config >::WeightInfo::remove_from_block_list())]
+        pub fn remove_from_block_list(
+            origin: OriginFor<T>,
+            farmer_public_key: FarmerPublicKey,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Self::do_remove_from_block_list(farmer_public_key)
+        }
     }
 
     #[pallet::inherent]
@@ -1099,7 +1149,7 @@ impl<T: Config> Pallet<T> {
             ..
         } = signed_vote.vote;
 
-        if BlockList::<T>::contains_key(&solution.public_key) {
+        if Self::is_in_block_list(&solution.public_key) {
             Err(DispatchError::Other("Equivocated"))
         } else {
             Self::deposit_event(Event::FarmerVote {
@@ -1143,6 +1193,55 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    fn do_remove_from_block_list(farmer_public_key: FarmerPublicKey) -> DispatchResult {
+        if BlockList::<T>::take(&farmer_public_key).is_none() {
+            return Err(Error::<T>::FarmerNotInBlockList.into());
+        }
+        BlockListExpiry::<T>::remove(&farmer_public_key);
+
+        Self::deposit_event(Event::FarmerRemovedFromBlockList {
+            public_key: farmer_public_key,
+        });
+
+        Ok(())
+    }
+
+    /// Adds farmer to the block list for `duration`, an existing block list entry of the farmer is
+    /// only ever extended.
+    fn add_to_block_list(
+        farmer_public_key: FarmerPublicKey,
+        duration: BlockListDuration<BlockNumberFor<T>>,
+    ) {
+        let current_block_number = frame_system::Pallet::<T>::current_block_number();
+        let expires_at = match duration {
+            BlockListDuration::Blocks(blocks) => Some(current_block_number.saturating_add(blocks)),
+            BlockListDuration::Eras(eras) => Some(current_block_number.saturating_add(
+                T::EraDuration::get().saturating_mul(BlockNumberFor::<T>::from(eras)),
+            )),
+            BlockListDuration::Permanent => None,
+        };
+
+        let expires_at = if Self::is_in_block_list(&farmer_public_key) {
+            match (BlockListExpiry::<T>::get(&farmer_public_key), expires_at) {
+                (Some(existing), Some(expires_at)) => Some(existing.max(expires_at)),
+                _ => None,
+            }
+        } else {
+            expires_at
+        };
+
+        BlockList::<T>::insert(&farmer_public_key, ());
+        match expires_at {
+            Some(expires_at) => BlockListExpiry::<T>::insert(&farmer_public_key, expires_at),
+            None => BlockListExpiry::<T>::remove(&farmer_public_key),
+        }
+
+        Self::deposit_event(Event::FarmerBlockListed {
+            public_key: farmer_public_key,
+            expires_at,
+        });
+    }
+
     /// Submits an extrinsic to report an equivocation. This method will create an unsigned
     /// extrinsic with a call to `report_equivocation` and will push the transaction to the pool.
     /// Only useful in an offchain context.
@@ -1156,7 +1255,7 @@ impl<T: Config> Pallet<T> {
     pub fn submit_test_equivocation_report(
         equivocation_proof: EquivocationProof<HeaderFor<T>>,
     ) -> Option<()> {
-        BlockList::<T>::insert(equivocation_proof.offender, ());
+        Self::add_to_block_list(equivocation_proof.offender, BlockListDuration::Permanent);
         Some(())
     }
 
@@ -1203,9 +1302,37 @@ impl<T: Config> Pallet<T> {
         }
     }
 
-    /// Check if `farmer_public_key` is in block list (due to equivocation)
+    /// Check if `farmer_public_key` is in block list (due to equivocation) at current block
     pub fn is_in_block_list(farmer_public_key: &FarmerPublicKey) -> bool {
+        Self::is_in_block_list_at(
+            farmer_public_key,
+            frame_system::Pallet::<T>::current_block_number(),
+        )
+    }
+
+    /// Check if `farmer_public_key` is in block list (due to equivocation) at `block_number`,
+    /// taking expiration of the block list entry into account
+    pub fn is_in_block_list_at(
+        farmer_public_key: &FarmerPublicKey,
+        block_number: BlockNumberFor<T>,
+    ) -> bool {
         BlockList::<T>::contains_key(farmer_public_key)
+            && BlockListExpiry::<T>::get(farmer_public_key)
+                .map_or(true, |expires_at| block_number < expires_at)
+    }
+
+    /// Block list entries written before they could expire have no expiry and stay blocked
+    /// permanently, but every expiry must belong to an entry of the block list.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub(crate) fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+        for farmer_public_key in BlockListExpiry::<T>::iter_keys() {
+            frame_support::ensure!(
+                BlockList::<T>::contains_key(&farmer_public_key),
+                "Block list expiry without block list entry"
+            );
+        }
+
+        Ok(())
     }
 
     /// Size of the archived history of the blockchain in bytes
@@ -1407,7 +1534,7 @@ fn check_vote<T: Config>(
     let height = *height;
     let slot = *slot;
 
-    if BlockList::<T>::contains_key(&solution.public_key) {
+    if Pallet::<T>::is_in_block_list(&solution.public_key) {
         return Err(CheckVoteError::BlockListed);
     }
 
@@ -1766,7 +1893,7 @@ impl<T: Config> subspace_runtime_primitives::FindBlockRewardAddress<T::AccountId
             |(public_key, _sector_index, _piece_offset, _chunk, _slot, reward_address)| {
                 // Equivocation might have happened in this block, if so - no reward for block
                 // author
-                if !BlockList::<T>::contains_key(public_key) {
+                if !Pallet::<T>::is_in_block_list(&public_key) {
                     // Rewards might be disabled, in which case no block reward either
                     if let Some(height) = EnableRewards::<T>::get() {
                         if frame_system::Pallet::<T>::current_block_number() >= height {
@@ -1828,9 +1955,10 @@ impl<T: Config> frame_support::traits::Randomness<T::Hash, BlockNumberFor<T>> fo
 }
 
 impl<T: Config> OnOffenceHandler<FarmerPublicKey> for Pallet<T> {
-    fn on_offence(offenders: &[OffenceDetails<FarmerPublicKey>]) {
+    fn on_offence(kind: Kind, offenders: &[OffenceDetails<FarmerPublicKey>]) {
+        let duration = T::OffenceBlockListDuration::convert(kind);
         for offender in offenders {
-            BlockList::<T>::insert(offender.offender.clone(), ());
+            Self::add_to_block_list(offender.offender.clone(), duration);
         }
     }
 }
@@ -16,10 +16,10 @@
 
 //! Test utilities
 
-use crate::equivocation::EquivocationHandler;
+use crate::equivocation::{EquivocationHandler, OffenceBlockListDuration};
 use crate::{
-    self as pallet_subspace, AllowAuthoringBy, Config, CurrentSlot, EnableRewardsAt,
-    FarmerPublicKey, NormalEraChange,
+    self as pallet_subspace, AllowAuthoringBy, BlockListDuration, Config, CurrentSlot,
+    EnableRewardsAt, FarmerPublicKey, NormalEraChange,
 };
 use frame_support::traits::{ConstU128, ConstU16, OnInitialize};
 use frame_support::{derive_impl, parameter_types};
@@ -149,6 +149,7 @@ parameter_types! {
     pub const ReportLongevity: u64 = 34;
     pub const ShouldAdjustSolutionRange: bool = false;
     pub const BlockSlotCount: u32 = 6;
+    pub const EquivocationBlockListDuration: BlockListDuration<u64> = BlockListDuration::Eras(2);
 }
 
 impl Config for Test {
@@ -171,6 +172,7 @@ impl Config for Test {
     type BlockSlotCount = BlockSlotCount;
 
     type HandleEquivocation = EquivocationHandler<OffencesSubspace, ReportLongevity>;
+    type OffenceBlockListDuration = OffenceBlockListDuration<EquivocationBlockListDuration>;
 
     type WeightInfo = ();
 }
@@ -19,11 +19,11 @@
 use crate::mock::{
     allow_all_pot_extension, create_archived_segment, create_segment_header, create_signed_vote,
     generate_equivocation_proof, go_to_block, new_test_ext, progress_to_block, BlockAuthoringDelay,
-    ReportLongevity, RuntimeEvent, RuntimeOrigin, Subspace, System, Test, INITIAL_SOLUTION_RANGE,
-    SLOT_PROBABILITY,
+    EraDuration, ReportLongevity, RuntimeEvent, RuntimeOrigin, Subspace, System, Test,
+    INITIAL_SOLUTION_RANGE, SLOT_PROBABILITY,
 };
 use crate::{
-    pallet, AllowAuthoringByAnyone, BlockList, Call, CheckVoteError, Config,
+    pallet, AllowAuthoringByAnyone, BlockList, BlockListExpiry, Call, CheckVoteError, Config,
     CurrentBlockAuthorInfo, CurrentBlockVoters, CurrentSlot, EnableRewardsAt,
     ParentBlockAuthorInfo, ParentBlockVoters, SegmentCommitment, SubspaceEquivocationOffence,
 };
@@ -34,6 +34,7 @@ use frame_system::{EventRecord, Phase};
 use rand::prelude::*;
 use schnorrkel::Keypair;
 use sp_consensus_slots::Slot;
+use sp_consensus_subspace::offence::{Offence, OffenceDetails, OnOffenceHandler};
 use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature, PotExtension, SolutionRanges};
 use sp_core::crypto::UncheckedFrom;
 use sp_runtime::traits::{BlockNumberProvider, Header};
@@ -300,6 +301,136 @@ fn report_equivocation_old_session_works() {
     })
 }
 
+#[test]
+fn report_equivocation_block_list_expires() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        progress_to_block(&keypair, 1, 1);
+
+        let farmer_public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+        let equivocation_proof = generate_equivocation_proof(&keypair, CurrentSlot::<Test>::get());
+
+        Subspace::report_equivocation(RuntimeOrigin::none(), Box::new(equivocation_proof)).unwrap();
+
+        // Equivocation blocks farmer for 2 eras of 4 blocks each
+        let expires_at = 1 + 2 * EraDuration::get() as u64;
+        assert_eq!(
+            BlockListExpiry::<Test>::get(&farmer_public_key),
+            Some(expires_at)
+        );
+        assert!(Subspace::is_in_block_list_at(
+            &farmer_public_key,
+            expires_at - 1
+        ));
+        assert!(!Subspace::is_in_block_list_at(
+            &farmer_public_key,
+            expires_at
+        ));
+
+        progress_to_block(&keypair, expires_at - 1, 1);
+        assert!(Subspace::is_in_block_list(&farmer_public_key));
+
+        progress_to_block(&keypair, expires_at, 1);
+        assert!(!Subspace::is_in_block_list(&farmer_public_key));
+    })
+}
+
+#[test]
+fn block_list_entry_is_only_extended() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        progress_to_block(&keypair, 1, 1);
+
+        let farmer_public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+        let offenders = [OffenceDetails {
+            offender: farmer_public_key.clone(),
+        }];
+        let equivocation_kind =
+            <SubspaceEquivocationOffence<FarmerPublicKey> as Offence<FarmerPublicKey>>::ID;
+
+        Subspace::on_offence(*b"sub:other-offenc", &offenders);
+        assert!(Subspace::is_in_block_list(&farmer_public_key));
+        assert!(!BlockListExpiry::<Test>::contains_key(&farmer_public_key));
+
+        // Permanent block list entry is not shortened by a later offence
+        Subspace::on_offence(equivocation_kind, &offenders);
+        assert!(Subspace::is_in_block_list(&farmer_public_key));
+        assert!(!BlockListExpiry::<Test>::contains_key(&farmer_public_key));
+
+        Subspace::remove_from_block_list(RuntimeOrigin::root(), farmer_public_key.clone()).unwrap();
+        Subspace::on_offence(equivocation_kind, &offenders);
+        let expires_at = BlockListExpiry::<Test>::get(&farmer_public_key).unwrap();
+
+        // Later offence extends the block list entry
+        progress_to_block(&keypair, 3, 1);
+        Subspace::on_offence(equivocation_kind, &offenders);
+        assert_eq!(
+            BlockListExpiry::<Test>::get(&farmer_public_key),
+            Some(expires_at + 2)
+        );
+    })
+}
+
+#[test]
+fn block_list_entries_without_expiry_stay_blocked() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        progress_to_block(&keypair, 1, 1);
+
+        // Block list entry as written before block list entries could expire
+        let farmer_public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+        BlockList::<Test>::insert(&farmer_public_key, ());
+        assert_ok!(Subspace::do_try_state());
+
+        assert!(Subspace::is_in_block_list_at(&farmer_public_key, u64::MAX));
+        progress_to_block(&keypair, 10, 1);
+        assert!(Subspace::is_in_block_list(&farmer_public_key));
+
+        Subspace::remove_from_block_list(RuntimeOrigin::root(), farmer_public_key.clone()).unwrap();
+        assert_ok!(Subspace::do_try_state());
+
+        BlockListExpiry::<Test>::insert(&farmer_public_key, 20);
+        assert!(Subspace::do_try_state().is_err());
+    })
+}
+
+#[test]
+fn remove_from_block_list_works() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        progress_to_block(&keypair, 1, 1);
+
+        let farmer_public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+        let equivocation_proof = generate_equivocation_proof(&keypair, CurrentSlot::<Test>::get());
+        Subspace::submit_test_equivocation_report(equivocation_proof).unwrap();
+        assert!(Subspace::is_in_block_list(&farmer_public_key));
+
+        assert_err!(
+            Subspace::remove_from_block_list(RuntimeOrigin::signed(1), farmer_public_key.clone()),
+            DispatchError::BadOrigin
+        );
+
+        Subspace::remove_from_block_list(RuntimeOrigin::root(), farmer_public_key.clone()).unwrap();
+        assert!(!Subspace::is_in_block_list(&farmer_public_key));
+        assert!(!BlockList::<Test>::contains_key(&farmer_public_key));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            RuntimeEvent::Subspace(crate::Event::FarmerRemovedFromBlockList {
+                public_key: farmer_public_key.clone()
+            })
+        );
+
+        assert_err!(
+            Subspace::remove_from_block_list(RuntimeOrigin::root(), farmer_public_key),
+            pallet::Error::<Test>::FarmerNotInBlockList
+        );
+    })
+}
+
 #[test]
 fn report_equivocation_invalid_equivocation_proof() {
     new_test_ext(allow_all_pot_extension()).execute_with(|| {
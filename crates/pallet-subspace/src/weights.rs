@@ -37,6 +37,7 @@ pub trait WeightInfo {
 	fn vote() -> Weight;
 	fn enable_rewards() -> Weight;
 	fn enable_authoring_by_anyone() -> Weight;
+	fn remove_from_block_list() -> Weight;
 }
 
 /// Weights for pallet_subspace using the Substrate node and recommended hardware.
@@ -133,6 +134,19 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	/// Storage: Subspace BlockList (r:1 w:1)
+	/// Proof Skipped: Subspace BlockList (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Subspace BlockListExpiry (r:0 w:1)
+	/// Proof Skipped: Subspace BlockListExpiry (max_values: None, max_size: None, mode: Measured)
+	fn remove_from_block_list() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `116`
+		//  Estimated: `3581`
+		// Minimum execution time: 9_000_000 picoseconds.
+		Weight::from_parts(10_000_000, 3581)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -228,4 +242,17 @@ impl WeightInfo for () {
 			.saturating_add(ParityDbWeight::get().reads(2_u64))
 			.saturating_add(ParityDbWeight::get().writes(2_u64))
 	}
+	/// Storage: Subspace BlockList (r:1 w:1)
+	/// Proof Skipped: Subspace BlockList (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Subspace BlockListExpiry (r:0 w:1)
+	/// Proof Skipped: Subspace BlockListExpiry (max_values: None, max_size: None, mode: Measured)
+	fn remove_from_block_list() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `116`
+		//  Estimated: `3581`
+		// Minimum execution time: 9_000_000 picoseconds.
+		Weight::from_parts(10_000_000, 3581)
+			.saturating_add(ParityDbWeight::get().reads(1_u64))
+			.saturating_add(ParityDbWeight::get().writes(2_u64))
+	}
 }
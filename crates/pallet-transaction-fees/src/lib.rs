@@ -20,15 +20,28 @@
 #![warn(rust_2018_idioms, missing_debug_implementations)]
 
 mod default_weights;
-
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use codec::{Codec, Decode, Encode};
-use frame_support::sp_runtime::traits::Zero;
+use frame_support::sp_runtime::traits::{Saturating, Zero};
 use frame_support::sp_runtime::SaturatedConversion;
 use frame_support::traits::{tokens, Currency, Get};
 use frame_support::weights::Weight;
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
 use scale_info::TypeInfo;
+use sp_transaction_fees::{FeeEstimate, MAX_STORAGE_FEE_PROJECTION_SEGMENTS};
+use subspace_core_primitives::ArchivedHistorySegment;
 use subspace_runtime_primitives::FindBlockRewardAddress;
 
 type BalanceOf<T> =
@@ -226,7 +239,7 @@ where
                     tips: collected_fees.tips,
                 });
             } else {
-                // If farmer equivocated, fees are burned
+                // If farmer equivocated or rewards are not enabled yet, fees are burned
                 let amount = collected_fees.storage + collected_fees.compute + collected_fees.tips;
                 if !amount.is_zero() {
                     Self::deposit_event(Event::<T>::BurnedBlockFees {
@@ -255,10 +268,16 @@ where
     }
 
     pub fn calculate_transaction_byte_fee() -> BalanceOf<T> {
+        Self::calculate_transaction_byte_fee_for(T::BlockchainHistorySize::get())
+    }
+
+    /// Return the `transaction_byte_fee` value for the blockchain history of `history_size`,
+    /// with the current credit supply and space pledged to the network
+    fn calculate_transaction_byte_fee_for(history_size: u128) -> BalanceOf<T> {
         let credit_supply = T::CreditSupply::get();
 
         match (T::TotalSpacePledged::get() / u128::from(T::MinReplicationFactor::get()))
-            .checked_sub(T::BlockchainHistorySize::get())
+            .checked_sub(history_size)
         {
             Some(free_space) if free_space > 0 => {
                 credit_supply / BalanceOf::<T>::saturated_from(free_space)
@@ -267,6 +286,73 @@ where
         }
     }
 
+    /// Return the estimated fees of an extrinsic that pays `inclusion_fee` and `tip` for a call
+    /// of `call_size` bytes, with its storage fee projected for the next `segments` archived
+    /// segments (up to [`MAX_STORAGE_FEE_PROJECTION_SEGMENTS`])
+    pub fn estimate_fees(
+        inclusion_fee: BalanceOf<T>,
+        tip: BalanceOf<T>,
+        call_size: u32,
+        segments: u32,
+    ) -> FeeEstimate<BalanceOf<T>> {
+        let call_size = BalanceOf::<T>::from(u64::from(call_size));
+        let storage_fee = if inclusion_fee.is_zero() {
+            // Extrinsic doesn't pay fees at all (i.e. unsigned extrinsic)
+            Zero::zero()
+        } else {
+            Self::transaction_byte_fee()
+                .saturating_mul(call_size)
+                .min(inclusion_fee)
+        };
+        let compute_fee = inclusion_fee - storage_fee;
+
+        let storage_fee_projection = if inclusion_fee.is_zero() {
+            Vec::new()
+        } else if !T::DynamicCostOfStorage::get() {
+            let segments = segments.min(MAX_STORAGE_FEE_PROJECTION_SEGMENTS) as usize;
+            vec![storage_fee; segments]
+        } else {
+            let history_size = T::BlockchainHistorySize::get();
+            (1..=segments.min(MAX_STORAGE_FEE_PROJECTION_SEGMENTS))
+                .map(|segment| {
+                    let history_size = history_size
+                        .saturating_add(u128::from(segment) * ArchivedHistorySegment::SIZE as u128);
+                    Self::calculate_transaction_byte_fee_for(history_size).saturating_mul(call_size)
+                })
+                .collect()
+        };
+
+        let total = inclusion_fee.saturating_add(tip);
+        let (block_author_reward, burned) = if Self::is_block_author_rewarded() {
+            (total, Zero::zero())
+        } else {
+            (Zero::zero(), total)
+        };
+
+        FeeEstimate {
+            compute_fee,
+            storage_fee,
+            tip,
+            block_author_reward,
+            burned,
+            storage_fee_projection,
+        }
+    }
+
+    /// Whether fees go to the block author rather than being burned, using the same condition as
+    /// block finalization: fees are burned when there is no block reward address (block author
+    /// equivocated or rewards are not enabled yet).
+    ///
+    /// Outside of block execution block reward address of the latest block is used as an estimate
+    /// for the next block.
+    fn is_block_author_rewarded() -> bool {
+        if IsDuringBlockExecution::<T>::get() {
+            BlockAuthor::<T>::exists()
+        } else {
+            T::FindBlockRewardAddress::find_block_reward_address().is_some()
+        }
+    }
+
     pub fn note_transaction_fees(
         storage_fee: BalanceOf<T>,
         compute_fee: BalanceOf<T>,
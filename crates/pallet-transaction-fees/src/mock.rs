@@ -0,0 +1,70 @@
+#![allow(missing_debug_implementations)]
+
+use frame_support::derive_impl;
+use frame_support::traits::{ConstBool, ConstU128, ConstU16};
+use sp_runtime::traits::parameter_types;
+use sp_runtime::BuildStorage;
+use subspace_runtime_primitives::FindBlockRewardAddress;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+type Balance = u128;
+
+frame_support::construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        TransactionFees: crate,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig)]
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type RuntimeHoldReason = ();
+    type DustRemoval = ();
+}
+
+parameter_types! {
+    /// Block reward address, `None` means block author equivocated or rewards are not enabled
+    pub static BlockRewardAddress: Option<u64> = None;
+}
+
+pub struct MockFindBlockRewardAddress;
+
+impl FindBlockRewardAddress<u64> for MockFindBlockRewardAddress {
+    fn find_block_reward_address() -> Option<u64> {
+        BlockRewardAddress::get()
+    }
+}
+
+impl crate::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type MinReplicationFactor = ConstU16<1>;
+    type CreditSupply = ConstU128<1_000_000>;
+    type TotalSpacePledged = ConstU128<{ u128::MAX }>;
+    type BlockchainHistorySize = ConstU128<0>;
+    type Currency = Balances;
+    type FindBlockRewardAddress = MockFindBlockRewardAddress;
+    type DynamicCostOfStorage = ConstBool<false>;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    let mut t: sp_io::TestExternalities = t.into();
+
+    t.execute_with(|| System::set_block_number(1));
+
+    t
+}
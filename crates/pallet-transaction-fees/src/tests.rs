@@ -0,0 +1,88 @@
+use crate::mock::{new_test_ext, Balances, BlockRewardAddress, System, Test, TransactionFees};
+use crate::Event;
+use frame_support::traits::{Currency, Hooks};
+
+type Pallet = crate::Pallet<Test>;
+
+const BLOCK_AUTHOR: u64 = 1;
+const INCLUSION_FEE: u128 = 100;
+const TIP: u128 = 10;
+const CALL_SIZE: u32 = 30;
+
+/// Execute a block that includes a transaction with fees matching the estimate
+fn execute_block_with_transaction() {
+    TransactionFees::on_initialize(System::block_number());
+
+    let estimate = Pallet::estimate_fees(INCLUSION_FEE, TIP, CALL_SIZE, 0);
+    Pallet::note_transaction_fees(estimate.storage_fee, estimate.compute_fee, estimate.tip);
+
+    TransactionFees::on_finalize(System::block_number());
+}
+
+#[test]
+fn estimate_fees_rewards_block_author() {
+    new_test_ext().execute_with(|| {
+        BlockRewardAddress::set(Some(BLOCK_AUTHOR));
+
+        let estimate = Pallet::estimate_fees(INCLUSION_FEE, TIP, CALL_SIZE, 0);
+        assert_eq!(estimate.storage_fee, u128::from(CALL_SIZE));
+        assert_eq!(estimate.compute_fee, INCLUSION_FEE - u128::from(CALL_SIZE));
+        assert_eq!(estimate.tip, TIP);
+        assert_eq!(estimate.block_author_reward, INCLUSION_FEE + TIP);
+        assert_eq!(estimate.burned, 0);
+
+        execute_block_with_transaction();
+
+        assert_eq!(
+            Balances::total_balance(&BLOCK_AUTHOR),
+            estimate.block_author_reward
+        );
+        System::assert_last_event(
+            Event::<Test>::BlockFees {
+                who: BLOCK_AUTHOR,
+                storage: estimate.storage_fee,
+                compute: estimate.compute_fee,
+                tips: estimate.tip,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn estimate_fees_burned_without_block_reward_address() {
+    new_test_ext().execute_with(|| {
+        // Block author equivocated or rewards are not enabled yet
+        BlockRewardAddress::set(None);
+
+        let estimate = Pallet::estimate_fees(INCLUSION_FEE, TIP, CALL_SIZE, 0);
+        assert_eq!(estimate.block_author_reward, 0);
+        assert_eq!(estimate.burned, INCLUSION_FEE + TIP);
+
+        execute_block_with_transaction();
+
+        assert_eq!(Balances::total_issuance(), 0);
+        System::assert_last_event(
+            Event::<Test>::BurnedBlockFees {
+                storage: estimate.storage_fee,
+                compute: estimate.compute_fee,
+                tips: estimate.tip,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn estimate_fees_during_block_execution() {
+    new_test_ext().execute_with(|| {
+        BlockRewardAddress::set(Some(BLOCK_AUTHOR));
+        TransactionFees::on_initialize(System::block_number());
+
+        // Block author of the block being executed is used even if reward address changes
+        BlockRewardAddress::set(None);
+        let estimate = Pallet::estimate_fees(INCLUSION_FEE, TIP, CALL_SIZE, 0);
+        assert_eq!(estimate.block_author_reward, INCLUSION_FEE + TIP);
+        assert_eq!(estimate.burned, 0);
+    });
+}
@@ -71,9 +71,10 @@ use sp_runtime::traits::{Block as BlockT, CheckedSub, Header, NumberFor, One, Ze
 use sp_runtime::Justifications;
 use std::error::Error;
 use std::future::Future;
-use std::slice;
+use std::ops::RangeInclusive;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
+use std::{mem, slice};
 use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::objects::BlockObjectMapping;
@@ -83,6 +84,9 @@ use tracing::{debug, info, warn};
 /// Number of WASM instances is 8, this is a bit lower to avoid warnings exceeding number of
 /// instances
 const BLOCKS_TO_ARCHIVE_CONCURRENCY: usize = 6;
+/// Number of segments worth of blocks that are archived concurrently during archiver
+/// initialization, limits memory usage during initial archiving and re-archiving from genesis
+const SEGMENTS_TO_ARCHIVE_CONCURRENCY: usize = 4;
 
 /// How deep (in segments) should block be in order to be finalized.
 ///
@@ -436,6 +440,115 @@ where
     Ok(Some(new_archived_segment))
 }
 
+/// Re-create archived segments in specified range of segment indices by re-archiving blocks from
+/// the local database.
+///
+/// `on_archived_segment` is called for every re-created archived segment in order. Segment headers
+/// of re-created segments are checked against those stored in `segment_headers_store`, which also
+/// needs to contain the segment header preceding the first requested segment.
+pub fn recreate_archived_segments<Block, Client, AS, OnArchivedSegment>(
+    client: &Client,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    kzg: Kzg,
+    segment_indices: RangeInclusive<SegmentIndex>,
+    mut on_archived_segment: OnArchivedSegment,
+) -> Result<(), Box<dyn Error>>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+    Client::Api: ObjectsApi<Block>,
+    AS: AuxStore,
+    OnArchivedSegment: FnMut(NewArchivedSegment) -> Result<(), Box<dyn Error>>,
+{
+    let first_segment_index = *segment_indices.start();
+    let last_segment_index = *segment_indices.end();
+
+    let block_with_object_mappings = |block_number: BlockNumber| {
+        let Some(block_hash) = client.hash(block_number.into())? else {
+            return Err(format!(
+                "Block {block_number} is not available, can't re-create archived segments"
+            )
+            .into());
+        };
+        let Some(signed_block) = client.block(block_hash)? else {
+            return Err(format!(
+                "Block {block_number} was pruned, can't re-create archived segments"
+            )
+            .into());
+        };
+
+        let block_object_mappings = client
+            .runtime_api()
+            .validated_object_call_hashes(block_hash)
+            .and_then(|calls| {
+                client.runtime_api().extract_block_object_mapping(
+                    *signed_block.block.header().parent_hash(),
+                    signed_block.block.clone(),
+                    calls,
+                )
+            })
+            .unwrap_or_default();
+
+        Ok::<_, Box<dyn Error>>((signed_block, block_object_mappings))
+    };
+
+    let (mut archiver, mut block_number) = if first_segment_index == SegmentIndex::ZERO {
+        (Archiver::new(kzg)?, 0)
+    } else {
+        let previous_segment_index = first_segment_index - SegmentIndex::ONE;
+        let previous_segment_header = segment_headers_store
+            .get_segment_header(previous_segment_index)
+            .ok_or_else(|| format!("Segment header {previous_segment_index} not found"))?;
+        let last_archived_block_number = previous_segment_header.last_archived_block().number;
+        let (last_archived_block, block_object_mappings) =
+            block_with_object_mappings(last_archived_block_number)?;
+
+        let archiver = Archiver::with_initial_state(
+            kzg,
+            previous_segment_header,
+            &encode_block(last_archived_block),
+            block_object_mappings,
+        )?;
+
+        (archiver, last_archived_block_number + 1)
+    };
+
+    loop {
+        let (signed_block, block_object_mappings) = block_with_object_mappings(block_number)?;
+
+        for archived_segment in
+            archiver.add_block(encode_block(signed_block), block_object_mappings, false)
+        {
+            let segment_index = archived_segment.segment_header.segment_index();
+
+            if segment_index < first_segment_index {
+                continue;
+            }
+            if segment_index > last_segment_index {
+                return Ok(());
+            }
+
+            if let Some(segment_header) = segment_headers_store.get_segment_header(segment_index)
+                && segment_header != archived_segment.segment_header
+            {
+                return Err(format!(
+                    "Re-created segment header {segment_index} doesn't match the one in segment \
+                    headers store"
+                )
+                .into());
+            }
+
+            on_archived_segment(archived_segment)?;
+
+            if segment_index == last_segment_index {
+                return Ok(());
+            }
+        }
+
+        block_number += 1;
+    }
+}
+
 struct InitializedArchiver<Block>
 where
     Block: BlockT,
@@ -663,6 +776,27 @@ where
                         (block.block.hash(), *block.block.header().number())
                     });
 
+            let mut archive_blocks =
+                |encoded_blocks: Vec<(Vec<u8>, BlockObjectMapping)>| -> sp_blockchain::Result<()> {
+                    let archived_segments = archiver.add_blocks(encoded_blocks);
+                    let new_segment_headers: Vec<SegmentHeader> = archived_segments
+                        .iter()
+                        .map(|archived_segment| archived_segment.segment_header)
+                        .collect();
+
+                    older_archived_segments.extend(archived_segments);
+
+                    if !new_segment_headers.is_empty() {
+                        segment_headers_store.add_segment_headers(&new_segment_headers)?;
+                    }
+
+                    Ok(())
+                };
+
+            // Blocks are archived in batches, such that multiple segments can be produced
+            // concurrently without keeping too much data in memory at once
+            let mut encoded_blocks = Vec::new();
+            let mut encoded_blocks_size = 0;
             for (signed_block, block_object_mappings) in blocks_to_archive {
                 let block_number_to_archive = *signed_block.block.header().number();
 
@@ -674,19 +808,18 @@ where
                     encoded_block.len() as f32 / 1024.0
                 );
 
-                let archived_segments =
-                    archiver.add_block(encoded_block, block_object_mappings, false);
-                let new_segment_headers: Vec<SegmentHeader> = archived_segments
-                    .iter()
-                    .map(|archived_segment| archived_segment.segment_header)
-                    .collect();
-
-                older_archived_segments.extend(archived_segments);
+                encoded_blocks_size += encoded_block.len();
+                encoded_blocks.push((encoded_block, block_object_mappings));
 
-                if !new_segment_headers.is_empty() {
-                    segment_headers_store.add_segment_headers(&new_segment_headers)?;
+                if encoded_blocks_size
+                    >= RecordedHistorySegment::SIZE * SEGMENTS_TO_ARCHIVE_CONCURRENCY
+                {
+                    archive_blocks(mem::take(&mut encoded_blocks))?;
+                    encoded_blocks_size = 0;
                 }
             }
+
+            archive_blocks(encoded_blocks)?;
         }
     }
 
@@ -28,6 +28,7 @@
 //! will be sent that [`archiver`](crate::archiver) among other things is subscribed to.
 
 use crate::archiver::SegmentHeadersStore;
+use crate::solution_verifier::SolutionVerifier;
 use crate::verifier::VerificationError;
 use crate::{aux_schema, slot_worker, SubspaceLink};
 use futures::channel::mpsc;
@@ -287,6 +288,7 @@ where
     create_inherent_data_providers: CIDP,
     segment_headers_store: SegmentHeadersStore<AS>,
     pot_verifier: PotVerifier,
+    solution_verifier: SolutionVerifier,
     _pos_table: PhantomData<PosTable>,
 }
 
@@ -305,6 +307,7 @@ where
             create_inherent_data_providers: self.create_inherent_data_providers.clone(),
             segment_headers_store: self.segment_headers_store.clone(),
             pot_verifier: self.pot_verifier.clone(),
+            solution_verifier: self.solution_verifier.clone(),
             _pos_table: PhantomData,
         }
     }
@@ -328,6 +331,7 @@ where
         create_inherent_data_providers: CIDP,
         segment_headers_store: SegmentHeadersStore<AS>,
         pot_verifier: PotVerifier,
+        solution_verifier: SolutionVerifier,
     ) -> Self {
         Self {
             client,
@@ -336,6 +340,7 @@ where
             create_inherent_data_providers,
             segment_headers_store,
             pot_verifier,
+            solution_verifier,
             _pos_table: PhantomData,
         }
     }
@@ -531,30 +536,35 @@ where
             .map(|segment_header| segment_header.segment_commitment());
 
         // Piece is not checked during initial block verification because it requires access to
-        // segment header and runtime, check it now.
-        subspace_verification::verify_solution::<PosTable, _, _>(
-            pre_digest.solution(),
-            // Slot was already checked during initial block verification
-            pre_digest.slot().into(),
-            &VerifySolutionParams {
-                proof_of_time: subspace_digest_items.pre_digest.pot_info().proof_of_time(),
-                solution_range: subspace_digest_items.solution_range,
-                piece_check_params: Some(PieceCheckParams {
-                    max_pieces_in_sector,
-                    segment_commitment,
-                    recent_segments: chain_constants.recent_segments(),
-                    recent_history_fraction: chain_constants.recent_history_fraction(),
-                    min_sector_lifetime: chain_constants.min_sector_lifetime(),
-                    // TODO: Below `skip_runtime_access` has no impact on this, but ideally it
-                    //  should (though we don't support fast sync yet, so doesn't matter in
-                    //  practice)
-                    current_history_size: self.client.runtime_api().history_size(parent_hash)?,
-                    sector_expiration_check_segment_commitment,
-                }),
-            },
-            &self.subspace_link.kzg,
-        )
-        .map_err(|error| VerificationError::VerificationError(pre_digest.slot(), error))?;
+        // segment header and runtime, check it now, together with solutions of blocks that are
+        // verified concurrently.
+        self.solution_verifier
+            .verify_solution(
+                pre_digest.solution().clone(),
+                // Slot was already checked during initial block verification
+                pre_digest.slot().into(),
+                VerifySolutionParams {
+                    proof_of_time: subspace_digest_items.pre_digest.pot_info().proof_of_time(),
+                    solution_range: subspace_digest_items.solution_range,
+                    piece_check_params: Some(PieceCheckParams {
+                        max_pieces_in_sector,
+                        segment_commitment,
+                        recent_segments: chain_constants.recent_segments(),
+                        recent_history_fraction: chain_constants.recent_history_fraction(),
+                        min_sector_lifetime: chain_constants.min_sector_lifetime(),
+                        // TODO: Below `skip_runtime_access` has no impact on this, but ideally it
+                        //  should (though we don't support fast sync yet, so doesn't matter in
+                        //  practice)
+                        current_history_size: self
+                            .client
+                            .runtime_api()
+                            .history_size(parent_hash)?,
+                        sector_expiration_check_segment_commitment,
+                    }),
+                },
+            )
+            .await
+            .map_err(|error| VerificationError::VerificationError(pre_digest.slot(), error))?;
 
         if !skip_runtime_access {
             // If the body is passed through, we need to use the runtime to check that the
@@ -637,6 +647,30 @@ where
         }
 
         let subspace_digest_items = extract_subspace_digest_items(&block.header)?;
+
+        if block.with_state() {
+            // Block with state is inserted by snap sync, parent block is not available and there is
+            // nothing to verify it against. Block itself comes from archived history and state was
+            // verified against state root during download. Weight of the block is used as the
+            // starting point for the weight of the blocks that will be built on top of it.
+            aux_schema::write_block_weight(
+                block_hash,
+                calculate_block_weight(subspace_digest_items.solution_range),
+                |values| {
+                    block
+                        .auxiliary
+                        .extend(values.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec()))))
+                },
+            );
+            block.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+
+            return self
+                .inner
+                .import_block(block)
+                .await
+                .map_err(Error::InnerBlockImportError);
+        }
+
         let skip_execution_checks = block.state_action.skip_execution_checks();
 
         let root_plot_public_key = self
@@ -31,6 +31,7 @@ pub mod aux_schema;
 pub mod block_import;
 pub mod notification;
 pub mod slot_worker;
+pub mod solution_verifier;
 #[cfg(test)]
 mod tests;
 pub mod verifier;
@@ -0,0 +1,139 @@
+//! Batch solution verification shared by [`verifier`](crate::verifier) and
+//! [`block_import`](crate::block_import).
+//!
+//! Verifier checks multiple blocks concurrently and block import checks blocks right after them,
+//! which during sync means that many solutions are submitted for verification at about the same
+//! time. Instead of verifying each of them individually, solutions are collected into batches by a
+//! dedicated thread and verified with [`verify_solutions()`], such that public key hashing and
+//! proof of space verification are amortized across solutions.
+
+use futures::channel::oneshot;
+use rayon::prelude::*;
+use sp_consensus_subspace::FarmerPublicKey;
+use std::sync::mpsc;
+use std::{io, thread};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{SlotNumber, Solution, SolutionRange};
+use subspace_proof_of_space::Table;
+use subspace_verification::{verify_solution, verify_solutions, Error, VerifySolutionParams};
+
+/// Max number of solutions verified in a single batch
+const MAX_BATCH_SIZE: usize = 256;
+
+type VerifySolutionFn = fn(
+    &Solution<FarmerPublicKey, FarmerPublicKey>,
+    SlotNumber,
+    &VerifySolutionParams,
+    &Kzg,
+) -> Result<SolutionRange, Error>;
+
+struct SolutionVerificationRequest {
+    solution: Solution<FarmerPublicKey, FarmerPublicKey>,
+    slot: SlotNumber,
+    params: VerifySolutionParams,
+    result_sender: oneshot::Sender<Result<SolutionRange, Error>>,
+}
+
+/// Verifies solutions in batches, see module-level documentation for details
+#[derive(Clone)]
+pub struct SolutionVerifier {
+    request_sender: mpsc::Sender<SolutionVerificationRequest>,
+    kzg: Kzg,
+    /// Used in case batch verification thread is gone
+    verify_solution: VerifySolutionFn,
+}
+
+impl SolutionVerifier {
+    /// Create new instance, spawns a thread that verifies solutions in batches, the thread exits
+    /// once all instances are dropped
+    pub fn new<PosTable>(kzg: Kzg) -> io::Result<Self>
+    where
+        PosTable: Table,
+    {
+        let (request_sender, request_receiver) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("subspace-solution-verifier".to_string())
+            .spawn({
+                let kzg = kzg.clone();
+
+                move || {
+                    while let Ok(request) = request_receiver.recv() {
+                        let requests = [request]
+                            .into_iter()
+                            .chain(request_receiver.try_iter().take(MAX_BATCH_SIZE - 1))
+                            .collect();
+
+                        verify_batch::<PosTable>(&kzg, requests);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            request_sender,
+            kzg,
+            verify_solution: |solution, slot, params, kzg| {
+                verify_solution::<PosTable, _, _>(solution, slot, params, kzg)
+            },
+        })
+    }
+
+    /// Verify whether solution is valid, returns solution distance that is `<= solution_range/2`
+    /// on success.
+    ///
+    /// Same as [`verify_solution()`], but solution is verified together with other solutions
+    /// submitted at about the same time.
+    pub async fn verify_solution(
+        &self,
+        solution: Solution<FarmerPublicKey, FarmerPublicKey>,
+        slot: SlotNumber,
+        params: VerifySolutionParams,
+    ) -> Result<SolutionRange, Error> {
+        let (result_sender, result_receiver) = oneshot::channel();
+
+        if let Err(mpsc::SendError(request)) =
+            self.request_sender.send(SolutionVerificationRequest {
+                solution,
+                slot,
+                params,
+                result_sender,
+            })
+        {
+            return (self.verify_solution)(
+                &request.solution,
+                request.slot,
+                &request.params,
+                &self.kzg,
+            );
+        }
+
+        result_receiver
+            .await
+            .expect("Result is always sent unless verification thread panicked; qed")
+    }
+}
+
+fn verify_batch<PosTable>(kzg: &Kzg, requests: Vec<SolutionVerificationRequest>)
+where
+    PosTable: Table,
+{
+    // Split batch into as many chunks as there are threads such that all of them are utilized
+    let chunk_size = requests.len().div_ceil(rayon::current_num_threads());
+
+    let results = requests
+        .par_chunks(chunk_size)
+        .flat_map_iter(|requests| {
+            verify_solutions::<PosTable, _, _, _>(
+                requests
+                    .iter()
+                    .map(|request| (&request.solution, request.slot, &request.params)),
+                kzg,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (request, result) in requests.into_iter().zip(results) {
+        // Doesn't matter if the caller is no longer interested in the result
+        let _ = request.result_sender.send(result);
+    }
+}
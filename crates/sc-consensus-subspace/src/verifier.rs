@@ -13,6 +13,7 @@
 //! This is a significant tradeoff in the protocol: having a smaller header vs being able to verify
 //! a lot of things stateless and in parallel.
 
+use crate::solution_verifier::SolutionVerifier;
 use futures::lock::Mutex;
 use rand::prelude::*;
 use rayon::prelude::*;
@@ -44,10 +45,9 @@ use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::thread::available_parallelism;
-use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{BlockNumber, PublicKey, RewardSignature};
 use subspace_proof_of_space::Table;
-use subspace_verification::{check_reward_signature, verify_solution, VerifySolutionParams};
+use subspace_verification::{check_reward_signature, VerifySolutionParams};
 use tokio::sync::Semaphore;
 use tracing::{debug, info, trace, warn};
 
@@ -116,8 +116,8 @@ where
     pub client: Arc<Client>,
     /// Subspace chain constants
     pub chain_constants: ChainConstants,
-    /// Kzg instance
-    pub kzg: Kzg,
+    /// Batch solution verifier, shared with block import
+    pub solution_verifier: SolutionVerifier,
     /// Chain selection rule
     pub select_chain: SelectChain,
     /// Telemetry
@@ -142,7 +142,7 @@ where
     Block: BlockT,
 {
     client: Arc<Client>,
-    kzg: Kzg,
+    solution_verifier: SolutionVerifier,
     select_chain: SelectChain,
     telemetry: Option<TelemetryHandle>,
     offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
@@ -171,7 +171,7 @@ where
         let SubspaceVerifierOptions {
             client,
             chain_constants,
-            kzg,
+            solution_verifier,
             select_chain,
             telemetry,
             offchain_tx_pool_factory,
@@ -183,7 +183,7 @@ where
 
         Self {
             client,
-            kzg,
+            solution_verifier,
             select_chain,
             telemetry,
             offchain_tx_pool_factory,
@@ -376,14 +376,16 @@ where
             return Err(VerificationError::BadRewardSignature(pre_hash));
         }
 
-        // Verify that solution is valid
-        verify_solution::<PosTable, _, _>(
-            pre_digest.solution(),
-            slot.into(),
-            verify_solution_params,
-            &self.kzg,
-        )
-        .map_err(|error| VerificationError::VerificationError(slot, error))?;
+        // Verify that solution is valid, together with solutions of other blocks that are verified
+        // concurrently
+        self.solution_verifier
+            .verify_solution(
+                pre_digest.solution().clone(),
+                slot.into(),
+                verify_solution_params.clone(),
+            )
+            .await
+            .map_err(|error| VerificationError::VerificationError(slot, error))?;
 
         Ok(CheckedHeader {
             pre_header: header,
@@ -483,6 +485,12 @@ where
             "Verifying",
         );
 
+        if block.with_state() {
+            // When importing whole state there is no parent block to verify against, the block
+            // comes from archived history and state is verified during download by snap sync
+            return Ok(block);
+        }
+
         let best_number = self.client.info().best_number;
         if *block.header.number() + self.chain_constants.confirmation_depth_k().into() < best_number
         {
@@ -0,0 +1,146 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC api for submitting domain fraud proofs produced outside of the operator, e.g. by external
+//! watchers or the offline fraud proof tools.
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use parity_scale_codec::Decode;
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_domains_fraud_proof::fraud_proof::FraudProof;
+use sp_domains_fraud_proof::{FraudProofApi as FraudProofRuntimeApi, FraudProofValidationError};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const FRAUD_PROOF_ERROR: i32 = 9200;
+
+fn runtime_error(message: &str, error: ApiError) -> ErrorObjectOwned {
+    ErrorObject::owned(FRAUD_PROOF_ERROR + 1, message, Some(error.to_string()))
+}
+
+fn decode_error(error: parity_scale_codec::Error) -> ErrorObjectOwned {
+    ErrorObject::owned(
+        FRAUD_PROOF_ERROR + 2,
+        "Unable to decode fraud proof",
+        Some(error.to_string()),
+    )
+}
+
+fn validation_error(error: FraudProofValidationError) -> ErrorObjectOwned {
+    ErrorObject::owned(
+        FRAUD_PROOF_ERROR + 3,
+        format!("Invalid fraud proof, failed check: {}", error.check),
+        error.reason,
+    )
+}
+
+fn unsupported_error() -> ErrorObjectOwned {
+    ErrorObject::owned(
+        FRAUD_PROOF_ERROR + 4,
+        "Fraud proof validation is not supported by the runtime",
+        None::<()>,
+    )
+}
+
+/// Fraud proof RPC methods.
+#[rpc(client, server)]
+pub trait FraudProofApi<DomainHash> {
+    /// Validates the SCALE encoded fraud proof against the best block and relays it to the
+    /// network as an unsigned extrinsic, returns the hash of the fraud proof.
+    #[method(name = "domains_submitFraudProof")]
+    fn submit_fraud_proof(&self, encoded_fraud_proof: Bytes) -> RpcResult<DomainHash>;
+}
+
+/// Provides RPC methods for submitting domain fraud proofs.
+pub struct FraudProofRpc<Client, Block: BlockT, DomainHeader> {
+    client: Arc<Client>,
+    offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+    deny_unsafe: DenyUnsafe,
+    _domain_header: PhantomData<DomainHeader>,
+}
+
+impl<Client, Block, DomainHeader> FraudProofRpc<Client, Block, DomainHeader>
+where
+    Block: BlockT,
+{
+    /// Creates a new instance of the fraud proof RPC handler.
+    pub fn new(
+        client: Arc<Client>,
+        offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            client,
+            offchain_tx_pool_factory,
+            deny_unsafe,
+            _domain_header: PhantomData,
+        }
+    }
+}
+
+impl<Client, Block, DomainHeader> FraudProofApiServer<DomainHeader::Hash>
+    for FraudProofRpc<Client, Block, DomainHeader>
+where
+    Block: BlockT,
+    DomainHeader: HeaderT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    Client::Api: FraudProofRuntimeApi<Block, DomainHeader>,
+{
+    fn submit_fraud_proof(&self, encoded_fraud_proof: Bytes) -> RpcResult<DomainHeader::Hash> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let fraud_proof = FraudProof::<NumberFor<Block>, Block::Hash, DomainHeader>::decode(
+            &mut encoded_fraud_proof.as_ref(),
+        )
+        .map_err(decode_error)?;
+
+        let best_hash = self.client.info().best_hash;
+        let mut runtime_api = self.client.runtime_api();
+
+        let api_version = runtime_api
+            .api_version::<dyn FraudProofRuntimeApi<Block, DomainHeader>>(best_hash)
+            .map_err(|error| runtime_error("Unable to query fraud proof api version", error))?
+            .unwrap_or_default();
+        if api_version < 2 {
+            return Err(unsupported_error());
+        }
+
+        runtime_api
+            .validate_fraud_proof(best_hash, fraud_proof.clone())
+            .map_err(|error| runtime_error("Unable to validate fraud proof", error))?
+            .map_err(validation_error)?;
+
+        let fraud_proof_hash = fraud_proof.hash();
+
+        // Register the offchain tx pool to be able to submit the fraud proof from the runtime.
+        runtime_api.register_extension(
+            self.offchain_tx_pool_factory
+                .offchain_transaction_pool(best_hash),
+        );
+        runtime_api
+            .submit_fraud_proof_unsigned(best_hash, fraud_proof)
+            .map_err(|error| runtime_error("Unable to submit fraud proof", error))?;
+
+        Ok(fraud_proof_hash)
+    }
+}
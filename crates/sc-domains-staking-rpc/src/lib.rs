@@ -0,0 +1,111 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC api for querying staking positions of domain nominators and operators.
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use parity_scale_codec::Codec;
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_domains::staking::{NominatorPosition, OperatorSummary};
+use sp_domains::{DomainId, DomainStakingApi as DomainStakingRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const DOMAIN_STAKING_ERROR: i32 = 9100;
+
+fn runtime_error(message: &str, error: ApiError) -> ErrorObjectOwned {
+    ErrorObject::owned(DOMAIN_STAKING_ERROR + 1, message, Some(error.to_string()))
+}
+
+/// Domain staking RPC methods.
+#[rpc(client, server)]
+pub trait DomainStakingApi<BlockHash, AccountId, Balance, DomainBlockNumber> {
+    /// Returns the positions of the nominator under all the operators it nominated, at the given
+    /// block or the best block if not specified.
+    #[method(name = "domains_nominatorPositions")]
+    fn nominator_positions(
+        &self,
+        nominator_id: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<NominatorPosition<Balance, DomainBlockNumber>>>;
+
+    /// Returns the summaries of the current and next epoch operators of the domain, at the given
+    /// block or the best block if not specified.
+    #[method(name = "domains_operatorSummaries")]
+    fn operator_summaries(
+        &self,
+        domain_id: DomainId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<OperatorSummary<Balance>>>;
+}
+
+/// Provides RPC methods for querying domain staking positions.
+pub struct DomainStaking<Client, Block> {
+    client: Arc<Client>,
+    _block: PhantomData<Block>,
+}
+
+impl<Client, Block> DomainStaking<Client, Block> {
+    /// Creates a new instance of the domain staking RPC handler.
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            _block: PhantomData,
+        }
+    }
+}
+
+impl<Client, Block, AccountId, Balance, DomainBlockNumber>
+    DomainStakingApiServer<Block::Hash, AccountId, Balance, DomainBlockNumber>
+    for DomainStaking<Client, Block>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    Client::Api: DomainStakingRuntimeApi<Block, AccountId, Balance, DomainBlockNumber>,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
+    DomainBlockNumber: Codec + Send + Sync + 'static,
+{
+    fn nominator_positions(
+        &self,
+        nominator_id: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<NominatorPosition<Balance, DomainBlockNumber>>> {
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        self.client
+            .runtime_api()
+            .nominator_positions(at_hash, nominator_id)
+            .map_err(|error| runtime_error("Unable to query nominator positions", error))
+    }
+
+    fn operator_summaries(
+        &self,
+        domain_id: DomainId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<OperatorSummary<Balance>>> {
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        self.client
+            .runtime_api()
+            .operator_summaries(at_hash, domain_id)
+            .map_err(|error| runtime_error("Unable to query operator summaries", error))
+    }
+}
@@ -1,4 +1,4 @@
 //! Relay implementation for consensus blocks.
 
 pub mod relay;
-mod types;
+pub(crate) mod types;
@@ -6,7 +6,7 @@ use crate::consensus::types::{
     ProtocolInitialRequest, ProtocolInitialResponse, ProtocolMessage,
 };
 use crate::protocol::compact_block::{
-    CompactBlockClient, CompactBlockHandshake, CompactBlockServer,
+    CompactBlockClient, CompactBlockHandshake, CompactBlockInitialRequest, CompactBlockServer,
 };
 use crate::protocol::{ClientBackend, ProtocolUnitInfo, ServerBackend};
 use crate::types::{RelayError, RequestResponseErr};
@@ -30,17 +30,20 @@ use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TxHash};
 use sp_api::ProvideRuntimeApi;
 use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
 use sp_runtime::generic::BlockId;
-use sp_runtime::traits::{Block as BlockT, Header, One, Zero};
+use sp_runtime::traits::{Block as BlockT, Hash, Header, One, Zero};
+use sp_runtime::StateVersion;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use substrate_prometheus_endpoint::{PrometheusError, Registry};
 use tracing::{debug, info, trace, warn};
 
-const SYNC_PROTOCOL: &str = "/subspace/consensus-block-relay/1";
+const SYNC_PROTOCOL: &str = "/subspace/consensus-block-relay/2";
+/// Previous version of the protocol, without support for short tx Ids.
+const SYNC_PROTOCOL_V1: &str = "/subspace/consensus-block-relay/1";
 
 // TODO: size these properly, or move to config
-const NUM_PEER_HINT: NonZeroUsize = NonZeroUsize::new(100).expect("Not zero; qed");
+pub(crate) const NUM_PEER_HINT: NonZeroUsize = NonZeroUsize::new(100).expect("Not zero; qed");
 
 /// These are the same limits used by substrate block handler.
 /// Maximum response size (bytes).
@@ -50,16 +53,19 @@ const MAX_RESPONSE_BLOCKS: NonZeroU32 = NonZeroU32::new(128).expect("Not zero; q
 
 /// If the encoded size of the extrinsic is less than the threshold,
 /// return the full extrinsic along with the tx hash.
-const TX_SIZE_THRESHOLD: NonZeroUsize = NonZeroUsize::new(32).expect("Not zero; qed");
+pub(crate) const TX_SIZE_THRESHOLD: NonZeroUsize = NonZeroUsize::new(32).expect("Not zero; qed");
 
-/// The client side of the consensus block relay
-struct ConsensusRelayClient<Block, Pool>
+/// The client side of the consensus block relay.
+///
+/// The client doesn't depend on the consensus runtime, domain block relay reuses it as is.
+pub(crate) struct ConsensusRelayClient<Block, Pool>
 where
     Block: BlockT,
     Pool: TransactionPool,
 {
     network: Arc<NetworkWrapper>,
     protocol_name: ProtocolName,
+    fallback_protocol_name: Option<ProtocolName>,
     compact_block: CompactBlockClient<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
     backend: Arc<ConsensusClientBackend<Pool>>,
     metrics: ConsensusClientMetrics,
@@ -72,9 +78,13 @@ where
     Pool: TransactionPool<Block = Block> + 'static,
 {
     /// Creates the consensus relay client.
-    fn new(
+    ///
+    /// Requests are sent with `protocol_name`, or with `fallback_protocol_name`
+    /// (if provided) to the peers that don't support it yet.
+    pub(crate) fn new(
         network: Arc<NetworkWrapper>,
         protocol_name: ProtocolName,
+        fallback_protocol_name: Option<ProtocolName>,
         compact_block: CompactBlockClient<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
         backend: Arc<ConsensusClientBackend<Pool>>,
         metrics: ConsensusClientMetrics,
@@ -82,6 +92,7 @@ where
         Self {
             network,
             protocol_name,
+            fallback_protocol_name,
             compact_block,
             backend,
             metrics,
@@ -101,24 +112,36 @@ where
             .network_peer_handle(self.protocol_name.clone(), who)?;
 
         // Perform the initial request/response
-        let initial_request = InitialRequest {
-            from_block: match request.from {
-                FromBlock::Hash(h) => BlockId::<Block>::Hash(h),
-                FromBlock::Number(n) => BlockId::<Block>::Number(n),
-            },
-            block_attributes: request.fields,
-            protocol_request: ProtocolInitialRequest::from(
-                self.compact_block
-                    .build_initial_request(self.backend.as_ref()),
-            ),
+        let from_block = match request.from {
+            FromBlock::Hash(h) => BlockId::<Block>::Hash(h),
+            FromBlock::Number(n) => BlockId::<Block>::Number(n),
+        };
+        let initial_request = |compact_request: CompactBlockInitialRequest| {
+            ConsensusRequest::<Block, TxHash<Pool>>::from(InitialRequest {
+                from_block,
+                block_attributes: request.fields,
+                protocol_request: ProtocolInitialRequest::from(compact_request),
+            })
         };
-        let initial_response = network_peer_handle
-            .request::<_, InitialResponse<Block, TxHash<Pool>>>(ConsensusRequest::<
-                Block,
-                TxHash<Pool>,
-            >::from(initial_request))
+        let fallback_request = self.fallback_protocol_name.clone().map(|protocol_name| {
+            let compact_request = self
+                .compact_block
+                .build_fallback_initial_request(self.backend.as_ref());
+            (initial_request(compact_request).encode(), protocol_name)
+        });
+        let (initial_response, protocol_name) = network_peer_handle
+            .request_with_fallback::<_, InitialResponse<Block, TxHash<Pool>>>(
+                initial_request(
+                    self.compact_block
+                        .build_initial_request(self.backend.as_ref()),
+                ),
+                fallback_request,
+            )
             .await?;
 
+        // The rest of the handshake uses the negotiated protocol
+        let network_peer_handle = self.network.network_peer_handle(protocol_name, who)?;
+
         // Resolve the protocol response to get the extrinsics
         let (body, local_miss) = if let Some(protocol_response) = initial_response.protocol_response
         {
@@ -133,6 +156,20 @@ where
             (None, 0)
         };
 
+        // A short Id of a missing transaction can collide with the short Id of a single unrelated
+        // transaction in the local pool, which is then resolved to the wrong transaction. Such
+        // body doesn't match the header, download the full block from the peer instead.
+        if let (Some(header), Some(body)) = (&initial_response.partial_block.header, &body) {
+            if !is_extrinsics_root_valid::<Block>(header, body) {
+                debug!(
+                    target: LOG_TARGET,
+                    block_hash = ?initial_response.block_hash,
+                    "block_download: extrinsics root mismatch, falling back to full download",
+                );
+                return self.full_download(who, request).await;
+            }
+        }
+
         // Assemble the final response
         let downloaded = vec![initial_response.partial_block.block_data(body)];
         debug!(
@@ -159,8 +196,16 @@ where
 
         let server_request =
             ConsensusRequest::<Block, TxHash<Pool>>::from(FullDownloadRequest(request.clone()));
-        let full_response = network_peer_handle
-            .request::<_, FullDownloadResponse<Block>>(server_request)
+        // Full download request is the same for all protocol versions
+        let fallback_request = self
+            .fallback_protocol_name
+            .clone()
+            .map(|protocol_name| (server_request.encode(), protocol_name));
+        let (full_response, _protocol_name) = network_peer_handle
+            .request_with_fallback::<_, FullDownloadResponse<Block>>(
+                server_request,
+                fallback_request,
+            )
             .await?;
         let downloaded = full_response.0;
 
@@ -184,15 +229,31 @@ where
     where
         Request: From<CompactBlockHandshake<BlockHash<Block>, TxHash<Pool>>> + Encode + Send + Sync,
     {
-        let ProtocolInitialResponse::CompactBlock(compact_response) = protocol_response;
-        let (block_hash, resolved) = self
-            .compact_block
-            .resolve_initial_response::<Request>(
-                compact_response,
-                network_peer_handle,
-                self.backend.as_ref(),
-            )
-            .await?;
+        let (block_hash, resolved) = match protocol_response {
+            ProtocolInitialResponse::CompactBlock(compact_response) => {
+                self.compact_block
+                    .resolve_initial_response::<Request>(
+                        compact_response,
+                        network_peer_handle,
+                        self.backend.as_ref(),
+                    )
+                    .await?
+            }
+            ProtocolInitialResponse::CompactBlockShortIds(short_ids_response) => {
+                let (block_hash, resolved, bytes_saved) = self
+                    .compact_block
+                    .resolve_short_ids_response::<Request>(
+                        short_ids_response,
+                        network_peer_handle,
+                        self.backend.as_ref(),
+                    )
+                    .await?;
+                self.metrics
+                    .short_ids_bytes_saved
+                    .observe(bytes_saved as f64);
+                (block_hash, resolved)
+            }
+        };
         let mut local_miss = 0;
         let extrinsics = resolved
             .into_iter()
@@ -216,6 +277,25 @@ where
     }
 }
 
+/// Checks the extrinsics root in the header against the resolved extrinsics.
+///
+/// The client doesn't depend on the runtime, so it doesn't know the state version used for the
+/// extrinsics root, the root is accepted if it matches either of them.
+fn is_extrinsics_root_valid<Block: BlockT>(
+    header: &Block::Header,
+    extrinsics: &[Extrinsic<Block>],
+) -> bool {
+    let encoded_extrinsics = extrinsics.iter().map(Encode::encode).collect::<Vec<_>>();
+    [StateVersion::V0, StateVersion::V1]
+        .into_iter()
+        .any(|state_version| {
+            <Block::Header as Header>::Hashing::ordered_trie_root(
+                encoded_extrinsics.clone(),
+                state_version,
+            ) == *header.extrinsics_root()
+        })
+}
+
 #[async_trait]
 impl<Block, Pool> BlockDownloader<Block> for ConsensusRelayClient<Block, Pool>
 where
@@ -281,29 +361,32 @@ where
     }
 }
 
-/// The server side of the consensus block relay
-struct ConsensusRelayServer<Block: BlockT, Client, Pool: TransactionPool> {
+/// The server side of the consensus block relay.
+///
+/// Runtime specific logic lives in the server backend, domain block relay reuses the server with
+/// its own backend.
+pub(crate) struct ConsensusRelayServer<Block: BlockT, Client, Pool: TransactionPool, Backend> {
     client: Arc<Client>,
     compact_block: CompactBlockServer<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
     request_receiver: async_channel::Receiver<IncomingRequest>,
-    backend: Arc<ConsensusServerBackend<Client, Pool>>,
+    backend: Arc<Backend>,
     metrics: ConsensusServerMetrics,
     _block: std::marker::PhantomData<Block>,
 }
 
-impl<Block, Client, Pool> ConsensusRelayServer<Block, Client, Pool>
+impl<Block, Client, Pool, Backend> ConsensusRelayServer<Block, Client, Pool, Backend>
 where
     Block: BlockT,
-    Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>,
-    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    Client: HeaderBackend<Block> + BlockBackend<Block>,
     Pool: TransactionPool<Block = Block> + 'static,
+    Backend: ServerBackend<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
 {
     /// Creates the consensus relay server.
-    fn new(
+    pub(crate) fn new(
         client: Arc<Client>,
         compact_block: CompactBlockServer<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
         request_receiver: async_channel::Receiver<IncomingRequest>,
-        backend: Arc<ConsensusServerBackend<Client, Pool>>,
+        backend: Arc<Backend>,
         metrics: ConsensusServerMetrics,
     ) -> Self {
         Self {
@@ -384,12 +467,20 @@ where
         let ProtocolInitialRequest::CompactBlock(compact_request) =
             initial_request.protocol_request;
         let protocol_response = if block_attributes.contains(BlockAttributes::BODY) {
-            let compact_response = self.compact_block.build_initial_response(
-                &block_hash,
-                compact_request,
-                self.backend.as_ref(),
-            )?;
-            Some(ProtocolInitialResponse::from(compact_response))
+            let protocol_response = match compact_request {
+                CompactBlockInitialRequest::V0 => ProtocolInitialResponse::from(
+                    self.compact_block
+                        .build_initial_response(&block_hash, self.backend.as_ref())?,
+                ),
+                CompactBlockInitialRequest::V1 { salt } => {
+                    ProtocolInitialResponse::from(self.compact_block.build_short_ids_response(
+                        &block_hash,
+                        salt,
+                        self.backend.as_ref(),
+                    )?)
+                }
+            };
+            Some(protocol_response)
         } else {
             None
         };
@@ -549,12 +640,13 @@ where
 }
 
 #[async_trait]
-impl<Block, Client, Pool> BlockServer<Block> for ConsensusRelayServer<Block, Client, Pool>
+impl<Block, Client, Pool, Backend> BlockServer<Block>
+    for ConsensusRelayServer<Block, Client, Pool, Backend>
 where
     Block: BlockT,
-    Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>,
-    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    Client: HeaderBackend<Block> + BlockBackend<Block>,
     Pool: TransactionPool<Block = Block> + 'static,
+    Backend: ServerBackend<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>,
 {
     async fn run(&mut self) {
         info!(
@@ -568,8 +660,8 @@ where
 }
 
 /// The client backend.
-struct ConsensusClientBackend<Pool> {
-    transaction_pool: Arc<Pool>,
+pub(crate) struct ConsensusClientBackend<Pool> {
+    pub(crate) transaction_pool: Arc<Pool>,
 }
 
 impl<Block, Pool> ClientBackend<TxHash<Pool>, Extrinsic<Block>> for ConsensusClientBackend<Pool>
@@ -583,6 +675,17 @@ where
             .ready_transaction(tx_hash)
             .map(|in_pool_tx| in_pool_tx.data().clone())
     }
+
+    fn protocol_unit_ids(&self) -> Vec<TxHash<Pool>> {
+        self.transaction_pool
+            .ready()
+            .map(|in_pool_tx| in_pool_tx.hash().clone())
+            .collect()
+    }
+
+    fn protocol_unit_id(&self, extrinsic: &Extrinsic<Block>) -> TxHash<Pool> {
+        self.transaction_pool.hash_of(extrinsic)
+    }
 }
 
 /// The server backend.
@@ -654,7 +757,7 @@ where
 }
 
 /// Retrieves the block transactions/tx hash from the backend.
-fn block_transactions<Block, Client>(
+pub(crate) fn block_transactions<Block, Client>(
     block_hash: &BlockHash<Block>,
     client: &Client,
 ) -> Result<Vec<Extrinsic<Block>>, RelayError>
@@ -699,6 +802,7 @@ where
     let relay_client: ConsensusRelayClient<Block, Pool> = ConsensusRelayClient::new(
         network,
         SYNC_PROTOCOL.into(),
+        Some(SYNC_PROTOCOL_V1.into()),
         CompactBlockClient::new(),
         backend,
         metrics,
@@ -720,7 +824,7 @@ where
 
     let mut protocol_config = ProtocolConfig {
         name: SYNC_PROTOCOL.into(),
-        fallback_names: Vec::new(),
+        fallback_names: vec![SYNC_PROTOCOL_V1.into()],
         max_request_size: 1024 * 1024,
         max_response_size: 16 * 1024 * 1024,
         request_timeout: Duration::from_secs(20),
@@ -2,16 +2,17 @@
 
 use crate::protocol::compact_block::{
     CompactBlockHandshake, CompactBlockInitialRequest, CompactBlockInitialResponse,
+    CompactBlockShortIdsResponse,
 };
 use crate::types::RelayError;
-use crate::utils::{RelayCounter, RelayCounterVec};
+use crate::utils::{RelayCounter, RelayCounterVec, RelayHistogram};
 use codec::{Decode, Encode};
 use derive_more::From;
 use sc_network_common::sync::message::{BlockAttributes, BlockData, BlockRequest};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 use sp_runtime::Justifications;
-use substrate_prometheus_endpoint::{PrometheusError, Registry};
+use substrate_prometheus_endpoint::{exponential_buckets, PrometheusError, Registry};
 
 pub(crate) type BlockHash<Block> = <Block as BlockT>::Hash;
 pub(crate) type BlockHeader<Block> = <Block as BlockT>::Header;
@@ -101,8 +102,11 @@ pub(crate) enum ProtocolInitialRequest {
 pub(crate) enum ProtocolInitialResponse<Block: BlockT, TxHash> {
     #[codec(index = 0)]
     CompactBlock(CompactBlockInitialResponse<BlockHash<Block>, TxHash, Extrinsic<Block>>),
+    /// Compact block response with short Ids, for `CompactBlockInitialRequest::V1`.
+    #[codec(index = 1)]
+    CompactBlockShortIds(CompactBlockShortIdsResponse<BlockHash<Block>, Extrinsic<Block>>),
     // New protocol goes here:
-    // #[codec(index = 1)]
+    // #[codec(index = 2)]
 }
 
 /// Protocol specific handshake requests.
@@ -171,6 +175,7 @@ pub(crate) struct ConsensusClientMetrics {
     pub(crate) requests: RelayCounterVec,
     pub(crate) downloads: RelayCounterVec,
     pub(crate) tx_pool_miss: RelayCounter,
+    pub(crate) short_ids_bytes_saved: RelayHistogram,
 }
 
 impl ConsensusClientMetrics {
@@ -193,6 +198,12 @@ impl ConsensusClientMetrics {
                 "Number of extrinsics not found in the tx pool",
                 registry,
             )?,
+            short_ids_bytes_saved: RelayHistogram::new(
+                "relay_client_short_ids_bytes_saved",
+                "Bytes saved per block by sending short tx Ids instead of the tx hashes",
+                exponential_buckets(64.0, 2.0, 12)?,
+                registry,
+            )?,
         })
     }
 
@@ -0,0 +1,3 @@
+//! Relay implementation for domain blocks.
+
+pub mod relay;
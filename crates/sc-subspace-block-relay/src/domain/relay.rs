@@ -0,0 +1,183 @@
+//! Domain block relay implementation.
+//!
+//! Domain blocks are relayed the same way as consensus blocks: the compact block protocol sends
+//! the transaction hashes, which the receiving node resolves from its domain transaction pool.
+//! The consensus relay client/server are reused, only the server backend is domain specific.
+
+#[cfg(test)]
+mod tests;
+
+use crate::consensus::relay::{
+    block_transactions, BlockRelayConfigurationError, ConsensusClientBackend, ConsensusRelayClient,
+    ConsensusRelayServer, NUM_PEER_HINT, TX_SIZE_THRESHOLD,
+};
+use crate::consensus::types::{
+    BlockHash, ConsensusClientMetrics, ConsensusServerMetrics, Extrinsic,
+};
+use crate::protocol::compact_block::{CompactBlockClient, CompactBlockServer};
+use crate::protocol::{ProtocolUnitInfo, ServerBackend};
+use crate::types::RelayError;
+use crate::utils::NetworkWrapper;
+use crate::LOG_TARGET;
+use codec::Encode;
+use sc_client_api::{BlockBackend, HeaderBackend};
+use sc_network::request_responses::ProtocolConfig;
+use sc_network_sync::block_relay_protocol::BlockRelayParams;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TxHash};
+use sp_api::ProvideRuntimeApi;
+use sp_domains::core_api::DomainCoreApi;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+use std::time::Duration;
+use substrate_prometheus_endpoint::Registry;
+use tracing::debug;
+
+const SYNC_PROTOCOL: &str = "/subspace/domain-block-relay/1";
+
+/// The server backend for domain blocks.
+struct DomainServerBackend<Client, Pool> {
+    client: Arc<Client>,
+    transaction_pool: Arc<Pool>,
+}
+
+impl<Block, Client, Pool> ServerBackend<BlockHash<Block>, TxHash<Pool>, Extrinsic<Block>>
+    for DomainServerBackend<Client, Pool>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>,
+    Client::Api: DomainCoreApi<Block>,
+    Pool: TransactionPool<Block = Block> + 'static,
+{
+    fn download_unit_members(
+        &self,
+        block_hash: &BlockHash<Block>,
+    ) -> Result<Vec<ProtocolUnitInfo<TxHash<Pool>, Extrinsic<Block>>>, RelayError> {
+        let txns = block_transactions(block_hash, self.client.as_ref())?;
+        Ok(domain_block_members(
+            txns,
+            |extrinsic| self.transaction_pool.hash_of(extrinsic),
+            |extrinsic| {
+                self.client
+                    .runtime_api()
+                    .is_inherent_extrinsic(*block_hash, extrinsic)
+                    .unwrap_or(false)
+            },
+        ))
+    }
+
+    fn protocol_unit(
+        &self,
+        block_hash: &BlockHash<Block>,
+        tx_hash: &TxHash<Pool>,
+    ) -> Option<Extrinsic<Block>> {
+        // Look up the block extrinsics.
+        match block_transactions(block_hash, self.client.as_ref()) {
+            Ok(extrinsics) => {
+                for extrinsic in extrinsics {
+                    if self.transaction_pool.hash_of(&extrinsic) == *tx_hash {
+                        return Some(extrinsic);
+                    }
+                }
+            }
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    ?block_hash,
+                    ?tx_hash,
+                    ?err,
+                    "domain server protocol_unit: "
+                );
+            }
+        }
+
+        // Next look up the transaction pool.
+        self.transaction_pool
+            .ready_transaction(tx_hash)
+            .map(|in_pool_tx| in_pool_tx.data().clone())
+    }
+}
+
+/// Builds the protocol unit info for the extrinsics of a domain block.
+///
+/// Small extrinsics and inherents are returned in full, inherents (timestamp, consensus chain
+/// byte fee, etc) are never in the tx pool of the client.
+fn domain_block_members<TxHash, Extrinsic>(
+    extrinsics: Vec<Extrinsic>,
+    hash_of: impl Fn(&Extrinsic) -> TxHash,
+    is_inherent: impl Fn(&Extrinsic) -> bool,
+) -> Vec<ProtocolUnitInfo<TxHash, Extrinsic>>
+where
+    Extrinsic: Encode,
+{
+    extrinsics
+        .into_iter()
+        .map(|extrinsic| {
+            let send_tx =
+                extrinsic.encoded_size() <= TX_SIZE_THRESHOLD.get() || is_inherent(&extrinsic);
+            ProtocolUnitInfo {
+                id: hash_of(&extrinsic),
+                unit: if send_tx { Some(extrinsic) } else { None },
+            }
+        })
+        .collect()
+}
+
+/// Sets up the relay components for domain blocks.
+pub fn build_domain_relay<Block, Client, Pool>(
+    network: Arc<NetworkWrapper>,
+    client: Arc<Client>,
+    pool: Arc<Pool>,
+    registry: Option<&Registry>,
+) -> Result<BlockRelayParams<Block>, BlockRelayConfigurationError>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block> + 'static,
+    Client::Api: DomainCoreApi<Block>,
+    Pool: TransactionPool<Block = Block> + 'static,
+{
+    let (tx, request_receiver) = async_channel::bounded(NUM_PEER_HINT.get());
+
+    let backend = Arc::new(ConsensusClientBackend {
+        transaction_pool: pool.clone(),
+    });
+    let metrics = ConsensusClientMetrics::new(registry)
+        .map_err(BlockRelayConfigurationError::PrometheusError)?;
+    let relay_client: ConsensusRelayClient<Block, Pool> = ConsensusRelayClient::new(
+        network,
+        SYNC_PROTOCOL.into(),
+        None,
+        CompactBlockClient::new(),
+        backend,
+        metrics,
+    );
+
+    let backend = Arc::new(DomainServerBackend {
+        client: client.clone(),
+        transaction_pool: pool.clone(),
+    });
+    let metrics = ConsensusServerMetrics::new(registry)
+        .map_err(BlockRelayConfigurationError::PrometheusError)?;
+    let relay_server = ConsensusRelayServer::new(
+        client,
+        CompactBlockServer::new(),
+        request_receiver,
+        backend,
+        metrics,
+    );
+
+    let mut protocol_config = ProtocolConfig {
+        name: SYNC_PROTOCOL.into(),
+        fallback_names: Vec::new(),
+        max_request_size: 1024 * 1024,
+        max_response_size: 16 * 1024 * 1024,
+        request_timeout: Duration::from_secs(20),
+        inbound_queue: None,
+    };
+    protocol_config.inbound_queue = Some(tx);
+
+    Ok(BlockRelayParams {
+        server: Box::new(relay_server),
+        downloader: Arc::new(relay_client),
+        request_response_config: protocol_config,
+    })
+}
@@ -0,0 +1,194 @@
+use crate::consensus::relay::TX_SIZE_THRESHOLD;
+use crate::domain::relay::domain_block_members;
+use crate::protocol::compact_block::{CompactBlockClient, CompactBlockServer, MissingEntry};
+use crate::protocol::{ClientBackend, ProtocolUnitInfo, ServerBackend};
+use crate::types::RelayError;
+use codec::Encode;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::collections::BTreeMap;
+
+type BlockHash = u32;
+type TxHash = [u8; 32];
+type Extrinsic = Vec<u8>;
+
+const BLOCK_HASH: BlockHash = 11;
+const SALT: u64 = 0xd0;
+
+fn tx_hash(extrinsic: &Extrinsic) -> TxHash {
+    BlakeTwo256::hash_of(extrinsic).into()
+}
+
+fn inherents() -> Vec<Extrinsic> {
+    // Inherents are bigger than the threshold, but still sent in full
+    vec![vec![1; 40], vec![2; 50]]
+}
+
+fn block_extrinsics() -> Vec<Extrinsic> {
+    let mut extrinsics = inherents();
+    extrinsics.extend([vec![3; 4], vec![4; 100], vec![5; 200]]);
+    extrinsics
+}
+
+/// Server backend with the domain specific selection of the units returned in full.
+struct TestDomainServerBackend {
+    extrinsics: Vec<Extrinsic>,
+    inherents: Vec<Extrinsic>,
+}
+
+impl ServerBackend<BlockHash, TxHash, Extrinsic> for TestDomainServerBackend {
+    fn download_unit_members(
+        &self,
+        _block_hash: &BlockHash,
+    ) -> Result<Vec<ProtocolUnitInfo<TxHash, Extrinsic>>, RelayError> {
+        Ok(domain_block_members(
+            self.extrinsics.clone(),
+            tx_hash,
+            |extrinsic| self.inherents.contains(extrinsic),
+        ))
+    }
+
+    fn protocol_unit(&self, _block_hash: &BlockHash, tx_hash: &TxHash) -> Option<Extrinsic> {
+        self.extrinsics
+            .iter()
+            .find(|extrinsic| self::tx_hash(extrinsic) == *tx_hash)
+            .cloned()
+    }
+}
+
+/// Client backend over the ready transactions of the domain tx pool.
+struct TestPoolBackend {
+    ready: Vec<Extrinsic>,
+}
+
+impl ClientBackend<TxHash, Extrinsic> for TestPoolBackend {
+    fn protocol_unit(&self, tx_hash: &TxHash) -> Option<Extrinsic> {
+        self.ready
+            .iter()
+            .find(|extrinsic| self::tx_hash(extrinsic) == *tx_hash)
+            .cloned()
+    }
+
+    fn protocol_unit_ids(&self) -> Vec<TxHash> {
+        self.ready.iter().map(tx_hash).collect()
+    }
+
+    fn protocol_unit_id(&self, extrinsic: &Extrinsic) -> TxHash {
+        tx_hash(extrinsic)
+    }
+}
+
+fn server_backend() -> TestDomainServerBackend {
+    TestDomainServerBackend {
+        extrinsics: block_extrinsics(),
+        inherents: inherents(),
+    }
+}
+
+/// Ready transactions of the pool: the block extrinsics that are not inherents, plus an
+/// unrelated one.
+fn pool_backend() -> TestPoolBackend {
+    let mut ready = block_extrinsics()
+        .into_iter()
+        .filter(|extrinsic| !inherents().contains(extrinsic))
+        .collect::<Vec<_>>();
+    ready.push(vec![6; 300]);
+    TestPoolBackend { ready }
+}
+
+#[test]
+fn domain_block_members_selection() {
+    let members = server_backend().download_unit_members(&BLOCK_HASH).unwrap();
+
+    assert_eq!(members.len(), block_extrinsics().len());
+    for (member, extrinsic) in members.iter().zip(block_extrinsics()) {
+        assert_eq!(member.id, tx_hash(&extrinsic));
+        let send_tx =
+            inherents().contains(&extrinsic) || extrinsic.encoded_size() <= TX_SIZE_THRESHOLD.get();
+        assert_eq!(member.unit.as_ref(), send_tx.then_some(&extrinsic));
+    }
+    // Only the big extrinsics that are not inherents are sent by tx hash
+    assert_eq!(
+        members
+            .iter()
+            .filter(|member| member.unit.is_none())
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn domain_block_resolved_from_pool() {
+    let server = CompactBlockServer::<BlockHash, TxHash, Extrinsic>::new();
+    let client = CompactBlockClient::<BlockHash, TxHash, Extrinsic>::new();
+
+    // Inherents are not in the pool, they must not be local misses
+    let short_ids_response = server
+        .build_short_ids_response(&BLOCK_HASH, SALT, &server_backend())
+        .unwrap();
+    let context = client.resolve_short_ids_local(&short_ids_response, &pool_backend());
+    assert!(context.local_miss.is_empty());
+    assert_eq!(
+        context
+            .resolved
+            .into_values()
+            .map(|resolved| resolved.protocol_unit)
+            .collect::<Vec<_>>(),
+        block_extrinsics()
+    );
+
+    // Same with the full tx hashes
+    let initial_response = server
+        .build_initial_response(&BLOCK_HASH, &server_backend())
+        .unwrap();
+    let context = client
+        .resolve_local(&initial_response, &pool_backend())
+        .unwrap();
+    assert!(context.local_miss.is_empty());
+    assert_eq!(
+        context
+            .resolved
+            .into_values()
+            .map(|resolved| resolved.protocol_unit)
+            .collect::<Vec<_>>(),
+        block_extrinsics()
+    );
+}
+
+#[test]
+fn domain_block_pool_miss() {
+    let server = CompactBlockServer::<BlockHash, TxHash, Extrinsic>::new();
+    let client = CompactBlockClient::<BlockHash, TxHash, Extrinsic>::new();
+
+    // The last extrinsic of the block is not in the pool
+    let missing = block_extrinsics().pop().unwrap();
+    let mut pool_backend = pool_backend();
+    pool_backend.ready.retain(|extrinsic| *extrinsic != missing);
+
+    let short_ids_response = server
+        .build_short_ids_response(&BLOCK_HASH, SALT, &server_backend())
+        .unwrap();
+    let context = client.resolve_short_ids_local(&short_ids_response, &pool_backend);
+    let missing_index = block_extrinsics().len() as u64 - 1;
+    assert_eq!(
+        context.local_miss,
+        BTreeMap::from([(missing_index, MissingEntry::Unit)])
+    );
+    assert_eq!(context.resolved.len(), block_extrinsics().len() - 1);
+
+    let initial_response = server
+        .build_initial_response(&BLOCK_HASH, &server_backend())
+        .unwrap();
+    let context = client
+        .resolve_local(&initial_response, &pool_backend)
+        .unwrap();
+    assert_eq!(
+        context.local_miss,
+        BTreeMap::from([(missing_index, tx_hash(&missing))])
+    );
+
+    // The server resolves the missing extrinsic from the block
+    assert_eq!(
+        server_backend().protocol_unit(&BLOCK_HASH, &tx_hash(&missing)),
+        Some(missing)
+    );
+}
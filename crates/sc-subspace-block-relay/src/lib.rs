@@ -3,11 +3,13 @@
 #![feature(const_option)]
 
 mod consensus;
+mod domain;
 mod protocol;
 mod types;
 mod utils;
 
 pub use crate::consensus::relay::{build_consensus_relay, BlockRelayConfigurationError};
+pub use crate::domain::relay::build_domain_relay;
 pub use crate::utils::NetworkWrapper;
 
 pub(crate) const LOG_TARGET: &str = "block_relay";
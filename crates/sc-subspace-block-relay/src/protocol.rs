@@ -61,6 +61,13 @@ pub(crate) struct Resolved<ProtocolUnitId, ProtocolUnit> {
 pub(crate) trait ClientBackend<ProtocolUnitId, ProtocolUnit>: Send + Sync {
     /// Returns the protocol unit for the protocol unit id.
     fn protocol_unit(&self, protocol_unit_id: &ProtocolUnitId) -> Option<ProtocolUnit>;
+
+    /// Returns the Ids of all the protocol units available locally (e.g) the
+    /// ready transactions in the tx pool. Used to resolve the short Ids.
+    fn protocol_unit_ids(&self) -> Vec<ProtocolUnitId>;
+
+    /// Returns the protocol unit Id for the protocol unit.
+    fn protocol_unit_id(&self, protocol_unit: &ProtocolUnit) -> ProtocolUnitId;
 }
 
 /// The relay user specific backend for the server side
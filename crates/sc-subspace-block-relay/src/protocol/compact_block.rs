@@ -1,4 +1,18 @@
 //! Compact block implementation.
+//!
+//! Two versions of the protocol are supported:
+//! - V0: the server returns the full protocol unit Ids, the client
+//!   requests the local misses by protocol unit Id.
+//! - V1: the server returns salted short Ids (see `short_id()`), which
+//!   the client matches against the short Ids of the locally available
+//!   protocol units. The entries that are missing locally, or whose short
+//!   Id matched more than one local entry, are reconciled by their position
+//!   in the download unit. A short Id that collides with a single unrelated
+//!   local entry can't be detected here, the caller must verify the resolved
+//!   protocol units (e.g. against the extrinsics root of the block header).
+
+#[cfg(test)]
+mod tests;
 
 use crate::protocol::{ClientBackend, ProtocolUnitInfo, Resolved, ServerBackend};
 use crate::types::RelayError;
@@ -6,17 +20,29 @@ use crate::utils::NetworkPeerHandle;
 use crate::LOG_TARGET;
 use codec::{Decode, Encode};
 use derive_more::From;
-use std::collections::BTreeMap;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
 use tracing::{trace, warn};
 
-/// The initial request(currently we don't need to do send anything
-/// as part of the initial download request for compact blocks).
+/// Size of the short protocol unit Id in bytes.
+pub(crate) const SHORT_ID_SIZE: usize = 6;
+
+/// Short protocol unit Id.
+pub(crate) type ShortId = [u8; SHORT_ID_SIZE];
+
+/// The initial request.
 #[derive(From, Encode, Decode)]
 pub(crate) enum CompactBlockInitialRequest {
+    /// Nothing needs to be sent, the server returns the full protocol unit Ids.
     #[codec(index = 0)]
     V0,
+
+    /// Requests the server to return short Ids derived with the given salt.
+    #[codec(index = 1)]
+    V1 { salt: u64 },
     // Next version/variant goes here:
-    // #[codec(index = 1)]
+    // #[codec(index = 2)]
 }
 
 /// The compact block initial response from the server.
@@ -29,24 +55,58 @@ pub(crate) struct CompactBlockInitialResponse<DownloadUnitId, ProtocolUnitId, Pr
     protocol_units: Vec<ProtocolUnitInfo<ProtocolUnitId, ProtocolUnit>>,
 }
 
+/// The compact block initial response from the server, with short Ids.
+#[derive(Encode, Decode)]
+pub(crate) struct CompactBlockShortIdsResponse<DownloadUnitId, ProtocolUnit> {
+    /// The download unit
+    download_unit_id: DownloadUnitId,
+
+    /// The salt used to derive the short Ids
+    salt: u64,
+
+    /// List of the protocol units.
+    protocol_units: Vec<ShortProtocolUnitInfo<ProtocolUnit>>,
+}
+
+/// The protocol unit info carried in the short Ids initial response
+#[derive(Encode, Decode)]
+pub(crate) enum ShortProtocolUnitInfo<ProtocolUnit> {
+    /// The short Id, to be resolved by the client
+    #[codec(index = 0)]
+    ShortId(ShortId),
+
+    /// The full protocol unit, no further action is needed on client side
+    #[codec(index = 1)]
+    Unit(ProtocolUnit),
+}
+
 /// The handshake messages from the client.
 #[derive(From, Encode, Decode)]
 pub(crate) enum CompactBlockHandshake<DownloadUnitId, ProtocolUnitId> {
     /// Request for missing transactions
     #[codec(index = 0)]
     MissingEntriesV0(MissingEntriesRequest<DownloadUnitId, ProtocolUnitId>),
+
+    /// Request for missing transactions by position, following a short
+    /// Ids response
+    #[codec(index = 1)]
+    MissingEntriesV1(MissingIndexedEntriesRequest<DownloadUnitId>),
     // Next version/variant goes here:
-    // #[codec(index = 1)]
+    // #[codec(index = 2)]
 }
 
 /// The handshake reply from the server.
 #[derive(From, Encode, Decode)]
-pub(crate) enum CompactBlockHandshakeResponse<ProtocolUnit> {
+pub(crate) enum CompactBlockHandshakeResponse<ProtocolUnitId, ProtocolUnit> {
     /// Response for missing transactions
     #[codec(index = 0)]
     MissingEntriesV0(MissingEntriesResponse<ProtocolUnit>),
+
+    /// Response for missing transactions by position
+    #[codec(index = 1)]
+    MissingEntriesV1(MissingIndexedEntriesResponse<ProtocolUnitId, ProtocolUnit>),
     // Next version/variant goes here:
-    // #[codec(index = 1)]
+    // #[codec(index = 2)]
 }
 
 /// Request for missing transactions
@@ -68,9 +128,69 @@ pub(crate) struct MissingEntriesResponse<ProtocolUnit> {
     protocol_units: BTreeMap<u64, ProtocolUnit>,
 }
 
-struct ResolveContext<ProtocolUnitId, ProtocolUnit> {
-    resolved: BTreeMap<u64, Resolved<ProtocolUnitId, ProtocolUnit>>,
-    local_miss: BTreeMap<u64, ProtocolUnitId>,
+/// What the client is missing for an entry in the short Ids response
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Encode, Decode)]
+pub(crate) enum MissingEntry {
+    /// The protocol unit is not available locally
+    #[codec(index = 0)]
+    Unit,
+
+    /// The short Id matched multiple local protocol units, the full
+    /// protocol unit Id is needed to pick the right one
+    #[codec(index = 1)]
+    Id,
+}
+
+/// Request for missing transactions by position
+#[derive(Encode, Decode)]
+pub(crate) struct MissingIndexedEntriesRequest<DownloadUnitId> {
+    /// The download unit
+    download_unit_id: DownloadUnitId,
+
+    /// Map of position of the entry in the download unit -> missing part.
+    entries: BTreeMap<u64, MissingEntry>,
+}
+
+/// Response for missing transactions by position
+#[derive(Encode, Decode)]
+pub(crate) struct MissingIndexedEntriesResponse<ProtocolUnitId, ProtocolUnit> {
+    /// Map of position of the entry in the download unit -> missing part.
+    entries: BTreeMap<u64, ResolvedEntry<ProtocolUnitId, ProtocolUnit>>,
+}
+
+/// The missing part of the entry returned by the server
+#[derive(Encode, Decode)]
+pub(crate) enum ResolvedEntry<ProtocolUnitId, ProtocolUnit> {
+    #[codec(index = 0)]
+    Unit(ProtocolUnit),
+
+    #[codec(index = 1)]
+    Id(ProtocolUnitId),
+}
+
+pub(crate) struct ResolveContext<ProtocolUnitId, ProtocolUnit, LocalMiss = ProtocolUnitId> {
+    pub(crate) resolved: BTreeMap<u64, Resolved<ProtocolUnitId, ProtocolUnit>>,
+    pub(crate) local_miss: BTreeMap<u64, LocalMiss>,
+}
+
+/// Derives the short Id of the protocol unit.
+///
+/// The short Id is salted with the download unit and a random salt picked
+/// by the client, so that collisions can't be crafted in advance by
+/// whoever creates the protocol units.
+pub(crate) fn short_id<DownloadUnitId, ProtocolUnitId>(
+    download_unit_id: &DownloadUnitId,
+    salt: u64,
+    protocol_unit_id: &ProtocolUnitId,
+) -> ShortId
+where
+    DownloadUnitId: Encode,
+    ProtocolUnitId: Encode,
+{
+    let hash = BlakeTwo256::hash_of(&(download_unit_id, salt, protocol_unit_id));
+    let mut short_id = ShortId::default();
+    short_id.copy_from_slice(&hash.as_ref()[..SHORT_ID_SIZE]);
+    short_id
 }
 
 pub(crate) struct CompactBlockClient<DownloadUnitId, ProtocolUnitId, ProtocolUnit> {
@@ -95,6 +215,16 @@ where
     pub(crate) fn build_initial_request(
         &self,
         _backend: &dyn ClientBackend<ProtocolUnitId, ProtocolUnit>,
+    ) -> CompactBlockInitialRequest {
+        CompactBlockInitialRequest::V1 {
+            salt: rand::random(),
+        }
+    }
+
+    /// Builds the initial request for the servers that don't support short Ids.
+    pub(crate) fn build_fallback_initial_request(
+        &self,
+        _backend: &dyn ClientBackend<ProtocolUnitId, ProtocolUnit>,
     ) -> CompactBlockInitialRequest {
         CompactBlockInitialRequest::V0
     }
@@ -141,7 +271,7 @@ where
     }
 
     /// Tries to resolve the entries in InitialResponse locally.
-    fn resolve_local(
+    pub(crate) fn resolve_local(
         &self,
         compact_response: &CompactBlockInitialResponse<
             DownloadUnitId,
@@ -211,9 +341,12 @@ where
             protocol_unit_ids: local_miss.clone(),
         });
 
-        let response: CompactBlockHandshakeResponse<ProtocolUnit> =
+        let response: CompactBlockHandshakeResponse<ProtocolUnitId, ProtocolUnit> =
             network_peer_handle.request(Request::from(request)).await?;
-        let CompactBlockHandshakeResponse::MissingEntriesV0(missing_entries_response) = response;
+        let CompactBlockHandshakeResponse::MissingEntriesV0(missing_entries_response) = response
+        else {
+            return Err(RelayError::UnexpectedHandshakeResponse);
+        };
 
         if missing_entries_response.protocol_units.len() != missing {
             return Err(RelayError::ResolveMismatch {
@@ -240,6 +373,194 @@ where
 
         Ok(resolved.into_values().collect())
     }
+
+    /// Resolves the short Ids initial response to produce the protocol units.
+    ///
+    /// Also returns the number of bytes saved by sending the short Ids
+    /// instead of the full protocol unit Ids.
+    pub(crate) async fn resolve_short_ids_response<Request>(
+        &self,
+        short_ids_response: CompactBlockShortIdsResponse<DownloadUnitId, ProtocolUnit>,
+        network_peer_handle: &NetworkPeerHandle,
+        backend: &dyn ClientBackend<ProtocolUnitId, ProtocolUnit>,
+    ) -> Result<
+        (
+            DownloadUnitId,
+            Vec<Resolved<ProtocolUnitId, ProtocolUnit>>,
+            usize,
+        ),
+        RelayError,
+    >
+    where
+        Request: From<CompactBlockHandshake<DownloadUnitId, ProtocolUnitId>> + Encode + Send + Sync,
+    {
+        let download_unit_id = short_ids_response.download_unit_id.clone();
+        let mut context = self.resolve_short_ids_local(&short_ids_response, backend);
+        let local_miss = context.local_miss.len();
+
+        // Reconcile the misses with the server. The first round fetches the
+        // missing units and the Ids of the ambiguous entries, the second one
+        // (if needed) fetches the units for the Ids not found locally.
+        let mut reconciled_bytes = 0;
+        while !context.local_miss.is_empty() {
+            let missing = context.local_miss.len();
+            let request = CompactBlockHandshake::from(MissingIndexedEntriesRequest {
+                download_unit_id: download_unit_id.clone(),
+                entries: context.local_miss.clone(),
+            });
+            let response: CompactBlockHandshakeResponse<ProtocolUnitId, ProtocolUnit> =
+                network_peer_handle.request(Request::from(request)).await?;
+            let CompactBlockHandshakeResponse::MissingEntriesV1(mut missing_entries_response) =
+                response
+            else {
+                return Err(RelayError::UnexpectedHandshakeResponse);
+            };
+
+            if missing_entries_response.entries.len() != missing {
+                return Err(RelayError::ResolveMismatch {
+                    expected: missing,
+                    actual: missing_entries_response.entries.len(),
+                });
+            }
+
+            for (index, missing_entry) in mem::take(&mut context.local_miss) {
+                match (
+                    missing_entry,
+                    missing_entries_response.entries.remove(&index),
+                ) {
+                    (MissingEntry::Unit, Some(ResolvedEntry::Unit(protocol_unit))) => {
+                        context.resolved.insert(
+                            index,
+                            Resolved {
+                                protocol_unit_id: backend.protocol_unit_id(&protocol_unit),
+                                protocol_unit,
+                                locally_resolved: false,
+                            },
+                        );
+                    }
+                    (MissingEntry::Id, Some(ResolvedEntry::Id(protocol_unit_id))) => {
+                        reconciled_bytes += protocol_unit_id.encoded_size();
+                        match backend.protocol_unit(&protocol_unit_id) {
+                            Some(protocol_unit) => {
+                                context.resolved.insert(
+                                    index,
+                                    Resolved {
+                                        protocol_unit_id,
+                                        protocol_unit,
+                                        locally_resolved: true,
+                                    },
+                                );
+                            }
+                            None => {
+                                context.local_miss.insert(index, MissingEntry::Unit);
+                            }
+                        }
+                    }
+                    _ => return Err(RelayError::ResolvedNotFound(missing)),
+                }
+            }
+        }
+
+        // Compared to sending the full protocol unit Ids for the same entries,
+        // minus the Ids that had to be fetched during reconciliation
+        let bytes_saved = short_ids_response
+            .protocol_units
+            .iter()
+            .zip(context.resolved.values())
+            .filter(|(entry, _resolved)| matches!(entry, ShortProtocolUnitInfo::ShortId(_)))
+            .map(|(_entry, resolved)| {
+                resolved
+                    .protocol_unit_id
+                    .encoded_size()
+                    .saturating_sub(SHORT_ID_SIZE)
+            })
+            .sum::<usize>()
+            .saturating_sub(reconciled_bytes);
+
+        trace!(
+            target: LOG_TARGET,
+            "relay::resolve_short_ids: {:?}: resolved[{}], local miss[{}], bytes saved[{}]",
+            download_unit_id,
+            context.resolved.len(),
+            local_miss,
+            bytes_saved,
+        );
+        Ok((
+            download_unit_id,
+            context.resolved.into_values().collect(),
+            bytes_saved,
+        ))
+    }
+
+    /// Tries to resolve the entries in the short Ids response locally.
+    pub(crate) fn resolve_short_ids_local(
+        &self,
+        short_ids_response: &CompactBlockShortIdsResponse<DownloadUnitId, ProtocolUnit>,
+        backend: &dyn ClientBackend<ProtocolUnitId, ProtocolUnit>,
+    ) -> ResolveContext<ProtocolUnitId, ProtocolUnit, MissingEntry> {
+        let mut context = ResolveContext {
+            resolved: BTreeMap::new(),
+            local_miss: BTreeMap::new(),
+        };
+
+        // Short Id -> local protocol unit Id, `None` if the short Id is ambiguous
+        let mut local_ids = HashMap::<ShortId, Option<ProtocolUnitId>>::new();
+        for protocol_unit_id in backend.protocol_unit_ids() {
+            local_ids
+                .entry(short_id(
+                    &short_ids_response.download_unit_id,
+                    short_ids_response.salt,
+                    &protocol_unit_id,
+                ))
+                .and_modify(|entry| *entry = None)
+                .or_insert(Some(protocol_unit_id));
+        }
+
+        for (index, entry) in short_ids_response.protocol_units.iter().enumerate() {
+            let index = index as u64;
+            let short_id = match entry {
+                ShortProtocolUnitInfo::Unit(protocol_unit) => {
+                    // The full protocol unit was returned
+                    context.resolved.insert(
+                        index,
+                        Resolved {
+                            protocol_unit_id: backend.protocol_unit_id(protocol_unit),
+                            protocol_unit: protocol_unit.clone(),
+                            locally_resolved: true,
+                        },
+                    );
+                    continue;
+                }
+                ShortProtocolUnitInfo::ShortId(short_id) => short_id,
+            };
+
+            match local_ids.get(short_id) {
+                Some(Some(protocol_unit_id)) => match backend.protocol_unit(protocol_unit_id) {
+                    Some(protocol_unit) => {
+                        context.resolved.insert(
+                            index,
+                            Resolved {
+                                protocol_unit_id: protocol_unit_id.clone(),
+                                protocol_unit,
+                                locally_resolved: true,
+                            },
+                        );
+                    }
+                    None => {
+                        context.local_miss.insert(index, MissingEntry::Unit);
+                    }
+                },
+                Some(None) => {
+                    context.local_miss.insert(index, MissingEntry::Id);
+                }
+                None => {
+                    context.local_miss.insert(index, MissingEntry::Unit);
+                }
+            }
+        }
+
+        context
+    }
 }
 
 pub(crate) struct CompactBlockServer<DownloadUnitId, ProtocolUnitId, ProtocolUnit> {
@@ -260,11 +581,10 @@ where
         }
     }
 
-    /// Builds the protocol response to the initial request.
+    /// Builds the protocol response to the V0 initial request.
     pub(crate) fn build_initial_response(
         &self,
         download_unit_id: &DownloadUnitId,
-        _initial_request: CompactBlockInitialRequest,
         backend: &dyn ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit>,
     ) -> Result<CompactBlockInitialResponse<DownloadUnitId, ProtocolUnitId, ProtocolUnit>, RelayError>
     {
@@ -275,14 +595,54 @@ where
         })
     }
 
+    /// Builds the protocol response to the V1 initial request, with the
+    /// protocol unit Ids replaced by the short Ids.
+    pub(crate) fn build_short_ids_response(
+        &self,
+        download_unit_id: &DownloadUnitId,
+        salt: u64,
+        backend: &dyn ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit>,
+    ) -> Result<CompactBlockShortIdsResponse<DownloadUnitId, ProtocolUnit>, RelayError> {
+        let protocol_units = backend
+            .download_unit_members(download_unit_id)?
+            .into_iter()
+            .map(|ProtocolUnitInfo { id, unit }| match unit {
+                Some(unit) => ShortProtocolUnitInfo::Unit(unit),
+                None => ShortProtocolUnitInfo::ShortId(short_id(download_unit_id, salt, &id)),
+            })
+            .collect();
+
+        Ok(CompactBlockShortIdsResponse {
+            download_unit_id: download_unit_id.clone(),
+            salt,
+            protocol_units,
+        })
+    }
+
     /// Handles the additional client messages during the reconcile phase.
     pub(crate) fn on_protocol_message(
         &self,
         message: CompactBlockHandshake<DownloadUnitId, ProtocolUnitId>,
         backend: &dyn ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit>,
-    ) -> Result<CompactBlockHandshakeResponse<ProtocolUnit>, RelayError> {
-        let CompactBlockHandshake::MissingEntriesV0(request) = message;
+    ) -> Result<CompactBlockHandshakeResponse<ProtocolUnitId, ProtocolUnit>, RelayError> {
+        match message {
+            CompactBlockHandshake::MissingEntriesV0(request) => Ok(
+                CompactBlockHandshakeResponse::from(self.on_missing_entries(request, backend)),
+            ),
+            CompactBlockHandshake::MissingEntriesV1(request) => {
+                Ok(CompactBlockHandshakeResponse::from(
+                    self.on_missing_indexed_entries(request, backend)?,
+                ))
+            }
+        }
+    }
 
+    /// Resolves the missing entries by protocol unit Id.
+    fn on_missing_entries(
+        &self,
+        request: MissingEntriesRequest<DownloadUnitId, ProtocolUnitId>,
+        backend: &dyn ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit>,
+    ) -> MissingEntriesResponse<ProtocolUnit> {
         let mut protocol_units = BTreeMap::new();
         let total_len = request.protocol_unit_ids.len();
         for (missing_id, protocol_unit_id) in request.protocol_unit_ids {
@@ -304,8 +664,58 @@ where
                 protocol_units.len()
             );
         }
-        Ok(CompactBlockHandshakeResponse::from(
-            MissingEntriesResponse { protocol_units },
-        ))
+        MissingEntriesResponse { protocol_units }
+    }
+
+    /// Resolves the missing entries by their position in the download unit.
+    fn on_missing_indexed_entries(
+        &self,
+        request: MissingIndexedEntriesRequest<DownloadUnitId>,
+        backend: &dyn ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit>,
+    ) -> Result<MissingIndexedEntriesResponse<ProtocolUnitId, ProtocolUnit>, RelayError> {
+        let mut members = backend.download_unit_members(&request.download_unit_id)?;
+
+        let mut entries = BTreeMap::new();
+        let total_len = request.entries.len();
+        for (index, missing_entry) in request.entries {
+            let Some(member) = usize::try_from(index)
+                .ok()
+                .and_then(|index| members.get_mut(index))
+            else {
+                warn!(
+                    target: LOG_TARGET,
+                    "relay::on_request: missing entry index out of range: {index}"
+                );
+                continue;
+            };
+
+            match missing_entry {
+                MissingEntry::Unit => {
+                    let maybe_protocol_unit = member
+                        .unit
+                        .take()
+                        .or_else(|| backend.protocol_unit(&request.download_unit_id, &member.id));
+                    if let Some(protocol_unit) = maybe_protocol_unit {
+                        entries.insert(index, ResolvedEntry::Unit(protocol_unit));
+                    } else {
+                        warn!(
+                            target: LOG_TARGET,
+                            "relay::on_request: missing entry not found"
+                        );
+                    }
+                }
+                MissingEntry::Id => {
+                    entries.insert(index, ResolvedEntry::Id(member.id.clone()));
+                }
+            }
+        }
+        if total_len != entries.len() {
+            warn!(
+                target: LOG_TARGET,
+                "relay::compact_blocks::on_request: could not resolve all entries: {total_len}/{}",
+                entries.len()
+            );
+        }
+        Ok(MissingIndexedEntriesResponse { entries })
     }
 }
@@ -0,0 +1,263 @@
+use crate::protocol::compact_block::{
+    short_id, CompactBlockClient, CompactBlockHandshake, CompactBlockHandshakeResponse,
+    CompactBlockServer, MissingEntry, MissingIndexedEntriesRequest, ResolvedEntry,
+    ShortProtocolUnitInfo, SHORT_ID_SIZE,
+};
+use crate::protocol::{ClientBackend, ProtocolUnitInfo, ServerBackend};
+use crate::types::RelayError;
+use codec::Encode;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::collections::BTreeMap;
+
+type DownloadUnitId = u32;
+type ProtocolUnitId = [u8; 32];
+type ProtocolUnit = Vec<u8>;
+
+const DOWNLOAD_UNIT_ID: DownloadUnitId = 7;
+const SALT: u64 = 0x5eed;
+/// Units up to this size are returned in full by the server
+const UNIT_SIZE_THRESHOLD: usize = 4;
+
+fn unit_id(protocol_unit: &ProtocolUnit) -> ProtocolUnitId {
+    BlakeTwo256::hash_of(protocol_unit).into()
+}
+
+fn download_unit() -> Vec<ProtocolUnit> {
+    vec![
+        vec![1],
+        vec![2; 100],
+        vec![3; 200],
+        vec![4, 4],
+        vec![5; 300],
+    ]
+}
+
+struct TestServerBackend {
+    protocol_units: Vec<ProtocolUnit>,
+}
+
+impl ServerBackend<DownloadUnitId, ProtocolUnitId, ProtocolUnit> for TestServerBackend {
+    fn download_unit_members(
+        &self,
+        _id: &DownloadUnitId,
+    ) -> Result<Vec<ProtocolUnitInfo<ProtocolUnitId, ProtocolUnit>>, RelayError> {
+        Ok(self
+            .protocol_units
+            .iter()
+            .map(|protocol_unit| ProtocolUnitInfo {
+                id: unit_id(protocol_unit),
+                unit: (protocol_unit.len() <= UNIT_SIZE_THRESHOLD).then(|| protocol_unit.clone()),
+            })
+            .collect())
+    }
+
+    fn protocol_unit(
+        &self,
+        _download_unit_id: &DownloadUnitId,
+        protocol_unit_id: &ProtocolUnitId,
+    ) -> Option<ProtocolUnit> {
+        self.protocol_units
+            .iter()
+            .find(|protocol_unit| unit_id(protocol_unit) == *protocol_unit_id)
+            .cloned()
+    }
+}
+
+struct TestClientBackend {
+    protocol_unit_ids: Vec<ProtocolUnitId>,
+    protocol_units: Vec<ProtocolUnit>,
+}
+
+impl TestClientBackend {
+    fn new(protocol_units: Vec<ProtocolUnit>) -> Self {
+        Self {
+            protocol_unit_ids: protocol_units.iter().map(unit_id).collect(),
+            protocol_units,
+        }
+    }
+}
+
+impl ClientBackend<ProtocolUnitId, ProtocolUnit> for TestClientBackend {
+    fn protocol_unit(&self, protocol_unit_id: &ProtocolUnitId) -> Option<ProtocolUnit> {
+        self.protocol_units
+            .iter()
+            .find(|protocol_unit| unit_id(protocol_unit) == *protocol_unit_id)
+            .cloned()
+    }
+
+    fn protocol_unit_ids(&self) -> Vec<ProtocolUnitId> {
+        self.protocol_unit_ids.clone()
+    }
+
+    fn protocol_unit_id(&self, protocol_unit: &ProtocolUnit) -> ProtocolUnitId {
+        unit_id(protocol_unit)
+    }
+}
+
+#[test]
+fn short_ids_are_salted() {
+    let protocol_unit_id = unit_id(&vec![1, 2, 3]);
+
+    let short_id_1 = short_id(&DOWNLOAD_UNIT_ID, SALT, &protocol_unit_id);
+    assert_eq!(short_id_1.len(), SHORT_ID_SIZE);
+    assert_eq!(
+        short_id_1,
+        short_id(&DOWNLOAD_UNIT_ID, SALT, &protocol_unit_id)
+    );
+    assert_ne!(
+        short_id_1,
+        short_id(&DOWNLOAD_UNIT_ID, SALT + 1, &protocol_unit_id)
+    );
+    assert_ne!(
+        short_id_1,
+        short_id(&(DOWNLOAD_UNIT_ID + 1), SALT, &protocol_unit_id)
+    );
+}
+
+#[test]
+fn short_ids_response() {
+    let server = CompactBlockServer::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let server_backend = TestServerBackend {
+        protocol_units: download_unit(),
+    };
+
+    let short_ids_response = server
+        .build_short_ids_response(&DOWNLOAD_UNIT_ID, SALT, &server_backend)
+        .unwrap();
+    assert_eq!(short_ids_response.download_unit_id, DOWNLOAD_UNIT_ID);
+    assert_eq!(short_ids_response.salt, SALT);
+    assert_eq!(
+        short_ids_response.protocol_units.len(),
+        download_unit().len()
+    );
+    for (entry, protocol_unit) in short_ids_response
+        .protocol_units
+        .iter()
+        .zip(download_unit())
+    {
+        match entry {
+            ShortProtocolUnitInfo::Unit(unit) => {
+                assert!(protocol_unit.len() <= UNIT_SIZE_THRESHOLD);
+                assert_eq!(*unit, protocol_unit);
+            }
+            ShortProtocolUnitInfo::ShortId(short_id_entry) => {
+                assert!(protocol_unit.len() > UNIT_SIZE_THRESHOLD);
+                assert_eq!(
+                    *short_id_entry,
+                    short_id(&DOWNLOAD_UNIT_ID, SALT, &unit_id(&protocol_unit))
+                );
+            }
+        }
+    }
+
+    // Short Ids response is smaller than the one with full Ids
+    let full_response = server
+        .build_initial_response(&DOWNLOAD_UNIT_ID, &server_backend)
+        .unwrap();
+    assert!(short_ids_response.encoded_size() < full_response.encoded_size());
+}
+
+#[test]
+fn short_ids_resolve_locally() {
+    let server = CompactBlockServer::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let client = CompactBlockClient::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let short_ids_response = server
+        .build_short_ids_response(
+            &DOWNLOAD_UNIT_ID,
+            SALT,
+            &TestServerBackend {
+                protocol_units: download_unit(),
+            },
+        )
+        .unwrap();
+
+    // Client has everything, plus some unrelated units
+    let mut local_protocol_units = download_unit();
+    local_protocol_units.push(vec![6; 10]);
+    local_protocol_units.push(vec![7; 20]);
+    let context = client.resolve_short_ids_local(
+        &short_ids_response,
+        &TestClientBackend::new(local_protocol_units),
+    );
+
+    assert!(context.local_miss.is_empty());
+    let resolved = context.resolved.into_values().collect::<Vec<_>>();
+    assert_eq!(resolved.len(), download_unit().len());
+    for (resolved, protocol_unit) in resolved.into_iter().zip(download_unit()) {
+        assert!(resolved.locally_resolved);
+        assert_eq!(resolved.protocol_unit_id, unit_id(&protocol_unit));
+        assert_eq!(resolved.protocol_unit, protocol_unit);
+    }
+}
+
+#[test]
+fn short_ids_local_misses() {
+    let server = CompactBlockServer::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let client = CompactBlockClient::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let short_ids_response = server
+        .build_short_ids_response(
+            &DOWNLOAD_UNIT_ID,
+            SALT,
+            &TestServerBackend {
+                protocol_units: download_unit(),
+            },
+        )
+        .unwrap();
+
+    // Unit at position 2 is missing, unit at position 4 is listed twice, which
+    // makes its short Id ambiguous
+    let local_protocol_units = download_unit()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, protocol_unit)| (index != 2).then_some(protocol_unit))
+        .collect::<Vec<_>>();
+    let mut client_backend = TestClientBackend::new(local_protocol_units);
+    client_backend
+        .protocol_unit_ids
+        .push(unit_id(&download_unit()[4]));
+
+    let context = client.resolve_short_ids_local(&short_ids_response, &client_backend);
+
+    assert_eq!(
+        context.local_miss,
+        BTreeMap::from([(2, MissingEntry::Unit), (4, MissingEntry::Id)])
+    );
+    assert_eq!(
+        context.resolved.keys().copied().collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+}
+
+#[test]
+fn missing_indexed_entries() {
+    let server = CompactBlockServer::<DownloadUnitId, ProtocolUnitId, ProtocolUnit>::new();
+    let server_backend = TestServerBackend {
+        protocol_units: download_unit(),
+    };
+
+    let request = CompactBlockHandshake::from(MissingIndexedEntriesRequest {
+        download_unit_id: DOWNLOAD_UNIT_ID,
+        entries: BTreeMap::from([
+            (2, MissingEntry::Unit),
+            (4, MissingEntry::Id),
+            // Out of range entries are skipped
+            (100, MissingEntry::Unit),
+        ]),
+    });
+    let response = server
+        .on_protocol_message(request, &server_backend)
+        .unwrap();
+
+    let CompactBlockHandshakeResponse::MissingEntriesV1(response) = response else {
+        panic!("Unexpected response");
+    };
+    assert_eq!(response.entries.len(), 2);
+    assert!(matches!(
+        response.entries.get(&2),
+        Some(ResolvedEntry::Unit(protocol_unit)) if *protocol_unit == download_unit()[2]
+    ));
+    assert!(matches!(
+        response.entries.get(&4),
+        Some(ResolvedEntry::Id(protocol_unit_id)) if *protocol_unit_id == unit_id(&download_unit()[4])
+    ));
+}
@@ -47,6 +47,9 @@ pub(crate) enum RelayError {
     #[error("Resolved entry not found: {0}")]
     ResolvedNotFound(usize),
 
+    #[error("Unexpected handshake response")]
+    UnexpectedHandshakeResponse,
+
     #[error("Request/response error: {0}")]
     RequestResponse(#[from] RequestResponseErr),
 }
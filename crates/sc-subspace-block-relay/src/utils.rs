@@ -10,7 +10,7 @@ use sc_network::{NetworkRequest, PeerId};
 use std::collections::HashMap;
 use std::sync::Arc;
 use substrate_prometheus_endpoint::{
-    register, Counter, CounterVec, Opts, PrometheusError, Registry, U64,
+    register, Counter, CounterVec, Histogram, HistogramOpts, Opts, PrometheusError, Registry, U64,
 };
 
 type NetworkRequestService = Arc<dyn NetworkRequest + Send + Sync + 'static>;
@@ -71,6 +71,23 @@ impl NetworkPeerHandle {
         &self,
         request: Request,
     ) -> Result<Response, RequestResponseErr>
+    where
+        Request: Encode,
+        Response: Decode,
+    {
+        self.request_with_fallback(request, None)
+            .await
+            .map(|(response, _protocol_name)| response)
+    }
+
+    /// Performs the request, the fallback request is sent instead if the peer
+    /// doesn't support the protocol. Returns the response along with the name
+    /// of the protocol it was received on.
+    pub(crate) async fn request_with_fallback<Request, Response>(
+        &self,
+        request: Request,
+        fallback_request: Option<(Vec<u8>, ProtocolName)>,
+    ) -> Result<(Response, ProtocolName), RequestResponseErr>
     where
         Request: Encode,
         Response: Decode,
@@ -80,19 +97,20 @@ impl NetworkPeerHandle {
             self.who,
             self.protocol_name.clone(),
             request.encode(),
-            None,
+            fallback_request,
             tx,
             IfDisconnected::ImmediateError,
         );
 
-        let (response_bytes, _protocol_name) = rx
+        let (response_bytes, protocol_name) = rx
             .await
             .map_err(|_cancelled| RequestResponseErr::Canceled)?
             .map_err(RequestResponseErr::RequestFailure)?;
 
         let response_len = response_bytes.len();
-        Response::decode(&mut response_bytes.as_ref())
-            .map_err(|err| RequestResponseErr::DecodeFailed { response_len, err })
+        let response = Response::decode(&mut response_bytes.as_ref())
+            .map_err(|err| RequestResponseErr::DecodeFailed { response_len, err })?;
+        Ok((response, protocol_name))
     }
 }
 
@@ -162,3 +180,33 @@ impl RelayCounterVec {
         }
     }
 }
+
+/// Convenience wrapper around prometheus histogram, which can be optional.
+pub(crate) struct RelayHistogram(Option<Histogram>);
+
+impl RelayHistogram {
+    /// Creates the histogram.
+    pub(crate) fn new(
+        name: &str,
+        help: &str,
+        buckets: Vec<f64>,
+        registry: Option<&Registry>,
+    ) -> Result<Self, PrometheusError> {
+        let histogram = if let Some(registry) = registry {
+            Some(register(
+                Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))?,
+                registry,
+            )?)
+        } else {
+            None
+        };
+        Ok(Self(histogram))
+    }
+
+    /// Adds the observed value.
+    pub(crate) fn observe(&self, v: f64) {
+        if let Some(histogram) = self.0.as_ref() {
+            histogram.observe(v)
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC api for estimating the fees of extrinsics.
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use parity_scale_codec::{Codec, Decode};
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+use sp_transaction_fees::{
+    FeeEstimate, TransactionFeesApi as TransactionFeesRuntimeApi,
+    MAX_STORAGE_FEE_PROJECTION_SEGMENTS,
+};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const TRANSACTION_FEES_ERROR: i32 = 9400;
+
+fn runtime_error(message: &str, error: ApiError) -> ErrorObjectOwned {
+    ErrorObject::owned(TRANSACTION_FEES_ERROR + 1, message, Some(error.to_string()))
+}
+
+fn invalid_params_error(message: &str, error: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(TRANSACTION_FEES_ERROR + 2, message, Some(error.to_string()))
+}
+
+/// Transaction fees RPC methods.
+#[rpc(client, server)]
+pub trait TransactionFeesApi<BlockHash, Balance> {
+    /// Returns the estimated fees of the SCALE encoded extrinsic, with the storage fee projected
+    /// for the next `segments` archived segments, at the given block or the best block if not
+    /// specified.
+    #[method(name = "transactionFees_estimateFees")]
+    fn estimate_fees(
+        &self,
+        encoded_xt: Bytes,
+        segments: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<FeeEstimate<Balance>>;
+}
+
+/// Provides RPC methods for estimating the fees of extrinsics.
+pub struct TransactionFees<Client, Block> {
+    client: Arc<Client>,
+    _block: PhantomData<Block>,
+}
+
+impl<Client, Block> TransactionFees<Client, Block> {
+    /// Creates a new instance of the transaction fees RPC handler.
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            _block: PhantomData,
+        }
+    }
+}
+
+impl<Client, Block, Balance> TransactionFeesApiServer<Block::Hash, Balance>
+    for TransactionFees<Client, Block>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    Client::Api: TransactionFeesRuntimeApi<Block, Balance>,
+    Balance: Codec + Send + Sync + 'static,
+{
+    fn estimate_fees(
+        &self,
+        encoded_xt: Bytes,
+        segments: u32,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<FeeEstimate<Balance>> {
+        if segments > MAX_STORAGE_FEE_PROJECTION_SEGMENTS {
+            return Err(invalid_params_error(
+                "Too many segments to project storage fee for",
+                format!("{segments} > {MAX_STORAGE_FEE_PROJECTION_SEGMENTS}"),
+            ));
+        }
+
+        let uxt = Block::Extrinsic::decode(&mut &*encoded_xt)
+            .map_err(|error| invalid_params_error("Unable to decode extrinsic", error))?;
+        let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        self.client
+            .runtime_api()
+            .estimate_fees(at_hash, uxt, segments)
+            .map_err(|error| runtime_error("Unable to estimate fees", error))
+    }
+}
@@ -722,7 +722,9 @@ sp_api::decl_runtime_apis! {
             >,
         );
 
-        /// Check if `farmer_public_key` is in block list (due to equivocation)
+        /// Check if `farmer_public_key` is in block list (due to equivocation) for the child block
+        /// of the block at which this API is called, taking expiration of block list entries into
+        /// account
         fn is_in_block_list(farmer_public_key: &FarmerPublicKey) -> bool;
 
         /// Size of the blockchain history
@@ -104,17 +104,17 @@ impl<Offender, O: Offence<Offender>> ReportOffence<Offender, O> for () {
 /// Used to decouple the module that handles offences and
 /// the one that should punish for those offences.
 pub trait OnOffenceHandler<Offender> {
-    /// A handler for an offence of a particular kind.
+    /// A handler for an offence of a particular `kind`.
     ///
     /// Note that this contains a list of all previous offenders
     /// as well. The implementer should cater for a case, where
     /// the same farmers were reported for the same offence
     /// in the past (see `OffenceCount`).
-    fn on_offence(offenders: &[OffenceDetails<Offender>]);
+    fn on_offence(kind: Kind, offenders: &[OffenceDetails<Offender>]);
 }
 
 impl<Offender> OnOffenceHandler<Offender> for () {
-    fn on_offence(_offenders: &[OffenceDetails<Offender>]) {}
+    fn on_offence(_kind: Kind, _offenders: &[OffenceDetails<Offender>]) {}
 }
 
 /// A details about an offending authority for a particular kind of offence.
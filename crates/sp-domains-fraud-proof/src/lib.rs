@@ -36,6 +36,8 @@ extern crate alloc;
 
 use crate::fraud_proof::FraudProof;
 #[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
@@ -82,6 +84,16 @@ impl From<InvalidTransactionCode> for TransactionValidity {
     }
 }
 
+/// Error returned when a fraud proof failed the validation of the consensus runtime.
+#[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
+pub struct FraudProofValidationError {
+    /// The check that the fraud proof failed.
+    pub check: String,
+    /// The reason of the failure, only available if the fraud proof failed the verification of
+    /// its proof.
+    pub reason: Option<String>,
+}
+
 /// Type that specifies the request of storage keys
 #[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
 pub enum StorageKeyRequest {
@@ -350,6 +362,7 @@ impl FraudProofVerificationInfoResponse {
 
 sp_api::decl_runtime_apis! {
     /// API necessary for fraud proof.
+    #[api_version(2)]
     pub trait FraudProofApi<DomainHeader: HeaderT> {
         /// Submit the fraud proof via an unsigned extrinsic.
         fn submit_fraud_proof_unsigned(fraud_proof: FraudProof<NumberFor<Block>, Block::Hash, DomainHeader>);
@@ -359,5 +372,12 @@ sp_api::decl_runtime_apis! {
             domain_id: DomainId,
             extrinsics: Vec<Block::Extrinsic>,
         ) -> Vec<FraudProof<NumberFor<Block>, Block::Hash, DomainHeader>>;
+
+        /// Validate the fraud proof against the state of the consensus chain, in the same way as
+        /// the fraud proof is validated before it is included in a block.
+        #[api_version(2)]
+        fn validate_fraud_proof(
+            fraud_proof: FraudProof<NumberFor<Block>, Block::Hash, DomainHeader>,
+        ) -> Result<(), FraudProofValidationError>;
     }
 }
@@ -729,8 +729,11 @@ pub enum InvalidBundleEquivocationError {
 }
 
 /// Verifies Bundle equivocation fraud proof.
+///
+/// `operator_signing_keys` are all the known signing keys of the operator, each of the bundles may
+/// be signed with any of them (the operator may have rotated its signing key).
 pub fn verify_bundle_equivocation_fraud_proof<CBlock, DomainHeader, Balance>(
-    operator_signing_key: &OperatorPublicKey,
+    operator_signing_keys: &[OperatorPublicKey],
     header_1: &SealedBundleHeader<NumberFor<CBlock>, CBlock::Hash, DomainHeader, Balance>,
     header_2: &SealedBundleHeader<NumberFor<CBlock>, CBlock::Hash, DomainHeader, Balance>,
 ) -> Result<(), InvalidBundleEquivocationError>
@@ -739,13 +742,15 @@ where
     DomainHeader: HeaderT,
     Balance: Encode,
 {
-    if !operator_signing_key.verify(&header_1.pre_hash(), &header_1.signature) {
-        return Err(InvalidBundleEquivocationError::BadBundleSignature);
-    }
+    let signing_key_1 = operator_signing_keys
+        .iter()
+        .find(|signing_key| signing_key.verify(&header_1.pre_hash(), &header_1.signature))
+        .ok_or(InvalidBundleEquivocationError::BadBundleSignature)?;
 
-    if !operator_signing_key.verify(&header_2.pre_hash(), &header_2.signature) {
-        return Err(InvalidBundleEquivocationError::BadBundleSignature);
-    }
+    let signing_key_2 = operator_signing_keys
+        .iter()
+        .find(|signing_key| signing_key.verify(&header_2.pre_hash(), &header_2.signature))
+        .ok_or(InvalidBundleEquivocationError::BadBundleSignature)?;
 
     let operator_set_1 = (
         header_1.header.proof_of_election.operator_id,
@@ -781,7 +786,7 @@ where
     .saturated_into();
 
     check_proof_of_election(
-        operator_signing_key,
+        signing_key_1,
         bundle_slot_probability,
         &header_1.header.proof_of_election,
         operator_stake,
@@ -790,7 +795,7 @@ where
     .map_err(InvalidBundleEquivocationError::InvalidProofOfElection)?;
 
     check_proof_of_election(
-        operator_signing_key,
+        signing_key_2,
         bundle_slot_probability,
         &header_2.header.proof_of_election,
         operator_stake,
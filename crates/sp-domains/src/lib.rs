@@ -22,6 +22,7 @@ pub mod core_api;
 pub mod extrinsics;
 pub mod merkle_tree;
 pub mod proof_provider_and_verifier;
+pub mod staking;
 pub mod storage;
 #[cfg(test)]
 mod tests;
@@ -30,6 +31,7 @@ pub mod valued_trie;
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+use crate::staking::{NominatorPosition, OperatorSummary};
 use crate::storage::{RawGenesis, StorageKey};
 #[cfg(not(feature = "std"))]
 use alloc::collections::BTreeSet;
@@ -1209,6 +1211,19 @@ pub trait DomainsTransfersTracker<Balance> {
 
     /// Reduces a given amount from the domain balance
     fn reduce_domain_balance(domain_id: DomainId, amount: Balance) -> Result<(), Self::Error>;
+
+    /// Returns true if all the transfers initiated from the domain are either confirmed or
+    /// rejected by the destination chains.
+    fn is_domain_transfers_settled(domain_id: DomainId) -> bool;
+
+    /// Stops tracking the balance of a sunset domain and returns its remaining balance, fails if
+    /// the transfers from the domain are not settled.
+    ///
+    /// The rejected transfers from the domain that are not claimed yet are added to the remaining
+    /// balance since the domain will never claim them. The transfers to the domain that are still
+    /// unconfirmed are cancelled as if they were rejected by the domain, so the source chains can
+    /// claim them back.
+    fn close_domain_balance(domain_id: DomainId) -> Result<Balance, Self::Error>;
 }
 
 /// Trait to check domain owner.
@@ -1223,6 +1238,19 @@ impl<AccountId> DomainOwner<AccountId> for () {
     }
 }
 
+/// Trait to check if a domain is deactivated.
+pub trait DeactivatedDomains {
+    /// Returns true if the domain is deactivated, the deactivated domain doesn't produce blocks
+    /// anymore thus it never processes new transfers and messages.
+    fn is_domain_deactivated(domain_id: DomainId) -> bool;
+}
+
+impl DeactivatedDomains for () {
+    fn is_domain_deactivated(_domain_id: DomainId) -> bool {
+        false
+    }
+}
+
 /// Post hook to know if the domain had bundle submitted in the previous block.
 pub trait DomainBundleSubmitted {
     /// Called in the next block initialisation if there was a domain bundle in the previous block.
@@ -1343,4 +1371,13 @@ sp_api::decl_runtime_apis! {
 
         fn operator(operator_id: OperatorId) -> Option<(OperatorPublicKey, Balance)>;
     }
+
+    /// API to query staking positions of nominators and operators.
+    pub trait DomainStakingApi<AccountId: Encode + Decode, Balance: Encode + Decode, DomainBlockNumber: Encode + Decode> {
+        /// Returns the positions of the nominator under all the operators it nominated.
+        fn nominator_positions(nominator_id: AccountId) -> Vec<NominatorPosition<Balance, DomainBlockNumber>>;
+
+        /// Returns the summaries of the current and next epoch operators of the given domain.
+        fn operator_summaries(domain_id: DomainId) -> Vec<OperatorSummary<Balance>>;
+    }
 }
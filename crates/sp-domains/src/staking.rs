@@ -0,0 +1,81 @@
+//! Types describing staking positions of nominators and operators, returned by
+//! [`DomainStakingApi`](crate::DomainStakingApi).
+
+use crate::{DomainId, EpochIndex, OperatorId, OperatorPublicKey};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_runtime::Percent;
+
+/// Deposit of a nominator that is not converted to shares yet because the domain epoch in which it
+/// was made is not complete.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDepositPosition<Balance> {
+    /// Domain epoch at the end of which the deposit will be converted to shares.
+    pub effective_epoch_index: EpochIndex,
+    /// Amount that will be staked.
+    pub amount: Balance,
+    /// Amount that was deposited to the operator's storage fund.
+    pub storage_fee_deposit: Balance,
+}
+
+/// Withdrawal of a nominator that is waiting to be unlocked.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawalPosition<Balance, DomainBlockNumber> {
+    /// Amount that will be unlocked.
+    ///
+    /// Withdrawals made in the current domain epoch are valued at the latest share price of the
+    /// operator, the final amount is known once the epoch is complete.
+    pub amount: Balance,
+    /// Amount that will be refunded from the operator's storage fund.
+    pub storage_fee_refund: Balance,
+    /// Confirmed domain block number at which the withdrawal can be unlocked.
+    pub unlock_at_confirmed_domain_block_number: DomainBlockNumber,
+    /// Whether the withdrawal was made in the current domain epoch.
+    pub pending: bool,
+}
+
+/// Position of a nominator under a single operator.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NominatorPosition<Balance, DomainBlockNumber> {
+    pub operator_id: OperatorId,
+    pub domain_id: DomainId,
+    /// Shares owned by the nominator.
+    pub shares: Balance,
+    /// Value of the nominator's shares at the latest share price of the operator.
+    pub current_staked_value: Balance,
+    /// Amount the nominator deposited to the operator's storage fund.
+    pub storage_fee_deposit: Balance,
+    /// Nominator's part of the operator rewards received in the current domain epoch, net of the
+    /// nomination tax. Rewards are staked once the epoch is complete.
+    pub accrued_rewards: Balance,
+    /// Deposit made in the current domain epoch, if any.
+    pub pending_deposit: Option<PendingDepositPosition<Balance>>,
+    /// Withdrawals that are waiting to be unlocked.
+    pub withdrawals: Vec<WithdrawalPosition<Balance, DomainBlockNumber>>,
+}
+
+/// Summary of an operator of a domain.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorSummary<Balance> {
+    pub operator_id: OperatorId,
+    pub signing_key: OperatorPublicKey,
+    pub minimum_nominator_stake: Balance,
+    pub nomination_tax: Percent,
+    /// Stake of the operator used for bundle producer election in the current domain epoch, `None`
+    /// if the operator is not active in the current epoch.
+    pub current_epoch_stake: Option<Balance>,
+    /// Total stake of the operator including changes made in the current domain epoch.
+    pub current_total_stake: Balance,
+    /// Total shares of all the nominators of the operator.
+    pub current_total_shares: Balance,
+    /// Rewards received by the operator in the current domain epoch.
+    pub current_epoch_rewards: Balance,
+    /// Total balance deposited to the operator's storage fund.
+    pub total_storage_fee_deposit: Balance,
+    pub nominator_count: u32,
+    /// Whether the operator will be active in the next domain epoch.
+    pub is_next_operator: bool,
+}
@@ -0,0 +1,43 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives for farmer rewards.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// TODO: Suppression because of https://github.com/paritytech/polkadot-sdk/issues/3533
+#![allow(clippy::multiple_bound_locations)]
+
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Vested rewards of a reward address.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct VestedRewards<Balance> {
+    /// Rewards that are still locked by the vesting schedules.
+    pub locked: Balance,
+    /// Rewards that are vested, but still locked until claimed.
+    pub claimable: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait RewardsApi<AccountId, Balance>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Returns locked and claimable vested rewards of the reward address.
+        fn vested_rewards(account: AccountId) -> VestedRewards<Balance>;
+    }
+}
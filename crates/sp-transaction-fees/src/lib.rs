@@ -0,0 +1,62 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives for transaction fees.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// TODO: Suppression because of https://github.com/paritytech/polkadot-sdk/issues/3533
+#![allow(clippy::multiple_bound_locations)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+
+/// Max number of archived segments the storage fee can be projected for.
+pub const MAX_STORAGE_FEE_PROJECTION_SEGMENTS: u32 = 1024;
+
+/// Estimated fees of an extrinsic.
+#[derive(TypeInfo, Debug, Encode, Decode, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate<Balance> {
+    /// Fee for the execution of the extrinsic, based on its weight.
+    pub compute_fee: Balance,
+    /// Fee for storing the extrinsic in the blockchain history, based on its size.
+    pub storage_fee: Balance,
+    /// Tip of the extrinsic.
+    pub tip: Balance,
+    /// Part of the fees and tip that is expected to be issued to the block author.
+    pub block_author_reward: Balance,
+    /// Part of the fees and tip that is expected to be burned.
+    pub burned: Balance,
+    /// Storage fee of the extrinsic once each of the next archived segments is added to the
+    /// blockchain history, assuming the space pledged to the network and credit supply stay the
+    /// same.
+    pub storage_fee_projection: Vec<Balance>,
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait TransactionFeesApi<Balance>
+    where
+        Balance: Codec,
+    {
+        /// Returns estimated fees of the extrinsic, with the storage fee projected for the next
+        /// `segments` archived segments, up to [`MAX_STORAGE_FEE_PROJECTION_SEGMENTS`].
+        fn estimate_fees(uxt: Block::Extrinsic, segments: u32) -> FeeEstimate<Balance>;
+    }
+}
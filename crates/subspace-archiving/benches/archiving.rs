@@ -3,9 +3,12 @@ use rand::{thread_rng, Rng};
 use subspace_archiving::archiver::Archiver;
 use subspace_core_primitives::crypto::kzg;
 use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::RecordedHistorySegment;
 
 const AMOUNT_OF_DATA: usize = 5 * 1024 * 1024;
 const SMALL_BLOCK_SIZE: usize = 500;
+const MULTI_SEGMENT_SEGMENTS: usize = 4;
+const MULTI_SEGMENT_BLOCK_SIZE: usize = RecordedHistorySegment::SIZE / 8;
 
 fn criterion_benchmark(c: &mut Criterion) {
     let mut input = vec![0u8; AMOUNT_OF_DATA];
@@ -48,6 +51,37 @@ fn criterion_benchmark(c: &mut Criterion) {
             }
         })
     });
+
+    let mut multi_segment_input = vec![0u8; RecordedHistorySegment::SIZE * MULTI_SEGMENT_SEGMENTS];
+    thread_rng().fill(multi_segment_input.as_mut_slice());
+
+    let mut group = c.benchmark_group("multi-segment-archiving");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            let mut archiver = archiver.clone();
+            for chunk in multi_segment_input.chunks(MULTI_SEGMENT_BLOCK_SIZE) {
+                archiver.add_block(
+                    black_box(chunk.to_vec()),
+                    black_box(Default::default()),
+                    black_box(false),
+                );
+            }
+        })
+    });
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            archiver.clone().add_blocks(black_box(
+                multi_segment_input
+                    .chunks(MULTI_SEGMENT_BLOCK_SIZE)
+                    .map(|chunk| (chunk.to_vec(), Default::default())),
+            ));
+        })
+    });
+
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
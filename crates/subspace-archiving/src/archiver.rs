@@ -18,7 +18,7 @@ mod incremental_record_commitments;
 extern crate alloc;
 
 use crate::archiver::incremental_record_commitments::{
-    update_record_commitments, IncrementalRecordCommitmentsState,
+    commit_raw_record, update_record_commitments, IncrementalRecordCommitmentsState,
 };
 use alloc::collections::VecDeque;
 #[cfg(not(feature = "std"))]
@@ -29,20 +29,21 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::num::NonZeroUsize;
+use core::ops::Range;
 use parity_scale_codec::{Compact, CompactLen, Decode, Encode, Input, Output};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Witness};
+use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Polynomial, Witness};
 use subspace_core_primitives::crypto::{blake3_254_hash_to_scalar, Scalar};
 use subspace_core_primitives::objects::{
     BlockObject, BlockObjectMapping, PieceObject, PieceObjectMapping,
 };
 use subspace_core_primitives::{
     ArchivedBlockProgress, ArchivedHistorySegment, Blake3Hash, BlockNumber, LastArchivedBlock,
-    PieceArray, RawRecord, RecordWitness, RecordedHistorySegment, SegmentCommitment, SegmentHeader,
-    SegmentIndex,
+    Piece, PieceArray, PieceIndex, RawRecord, RecordWitness, RecordedHistorySegment,
+    SegmentCommitment, SegmentHeader, SegmentIndex,
 };
-use subspace_erasure_coding::ErasureCoding;
+use subspace_erasure_coding::{ErasureCoding, ErasureCodingBackend};
 
 const INITIAL_LAST_ARCHIVED_BLOCK: LastArchivedBlock = LastArchivedBlock {
     number: 0,
@@ -216,6 +217,32 @@ pub enum ArchiverInstantiationError {
     },
 }
 
+/// Error during streaming of a block with [`BlockStream`]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum BlockStreamError {
+    /// More bytes were provided than remaining in the block
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Too many bytes provided: {provided} bytes, while only {remaining} bytes remaining")
+    )]
+    TooManyBytes {
+        /// Number of bytes provided
+        provided: usize,
+        /// Number of bytes remaining in the block
+        remaining: usize,
+    },
+    /// Block was finished before all of its bytes were provided
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Block finished with {remaining} bytes not provided")
+    )]
+    NotEnoughBytes {
+        /// Number of bytes remaining in the block
+        remaining: usize,
+    },
+}
+
 /// Block archiver for Subspace blockchain.
 ///
 /// It takes new confirmed (at `K` depth) blocks and concatenates them into a buffer, buffer is
@@ -226,7 +253,9 @@ pub enum ArchiverInstantiationError {
 ///
 /// ## Panics
 /// Panics when operating on blocks, whose length doesn't fit into u32 (should never be the case in
-/// blockchain context anyway).
+/// blockchain context anyway) and when adding new blocks after [`BlockStream`] was dropped without
+/// providing all of the block bytes while some of them were already archived (see
+/// [`Archiver::resume_block()`]).
 #[derive(Debug, Clone)]
 pub struct Archiver {
     /// Buffer containing blocks and other buffered items that are pending to be included into the
@@ -244,6 +273,10 @@ pub struct Archiver {
     prev_segment_header_hash: Blake3Hash,
     /// Last archived block
     last_archived_block: LastArchivedBlock,
+    /// Number of bytes of the block that is being streamed, which were not provided yet.
+    ///
+    /// Streamed block is always the last item in the buffer.
+    missing_block_bytes: usize,
 }
 
 impl Archiver {
@@ -272,6 +305,7 @@ impl Archiver {
             segment_index: SegmentIndex::ZERO,
             prev_segment_header_hash: Blake3Hash::default(),
             last_archived_block: INITIAL_LAST_ARCHIVED_BLOCK,
+            missing_block_bytes: 0,
         })
     }
 
@@ -336,6 +370,24 @@ impl Archiver {
         Ok(archiver)
     }
 
+    /// Switch erasure coding to specified backend, all backends produce identical archived
+    /// history, so this can be done at any time.
+    ///
+    /// Can be used after both [`Archiver::new()`] and [`Archiver::with_initial_state()`].
+    pub fn with_erasure_coding_backend(
+        mut self,
+        backend: ErasureCodingBackend,
+    ) -> Result<Self, ArchiverInstantiationError> {
+        self.erasure_coding = ErasureCoding::with_backend(
+            NonZeroUsize::new(ArchivedHistorySegment::NUM_PIECES.ilog2() as usize)
+                .expect("Archived history segment contains at very least one piece; qed"),
+            backend,
+        )
+        .map_err(ArchiverInstantiationError::FailedToInitializeErasureCoding)?;
+
+        Ok(self)
+    }
+
     /// Get last archived block if there was any
     pub fn last_archived_block_number(&self) -> Option<BlockNumber> {
         if self.last_archived_block != INITIAL_LAST_ARCHIVED_BLOCK {
@@ -355,12 +407,68 @@ impl Archiver {
         object_mapping: BlockObjectMapping,
         incremental: bool,
     ) -> Vec<NewArchivedSegment> {
+        self.check_no_streamed_block();
+
         // Append new block to the buffer
         self.buffer.push_back(SegmentItem::Block {
             bytes,
             object_mapping,
         });
 
+        self.produce_archived_segments(incremental)
+    }
+
+    /// Starts adding new block to internal buffer in chunks of bytes with [`BlockStream`].
+    ///
+    /// Segments are produced as soon as enough bytes were provided, such that the whole block never
+    /// needs to be held in memory. Produces exactly the same output as [`Self::add_block()`] once
+    /// all `block_size` bytes were provided.
+    pub fn start_block(
+        &mut self,
+        block_size: u32,
+        object_mapping: BlockObjectMapping,
+        incremental: bool,
+    ) -> BlockStream<'_> {
+        self.check_no_streamed_block();
+
+        // Append new block to the buffer, its bytes will be provided later
+        self.buffer.push_back(SegmentItem::Block {
+            bytes: Vec::new(),
+            object_mapping,
+        });
+        self.missing_block_bytes = block_size as usize;
+
+        BlockStream {
+            archiver: self,
+            incremental,
+        }
+    }
+
+    /// Resumes adding of the block, whose [`BlockStream`] was dropped before all of its bytes were
+    /// provided, returns `None` if there is no such block.
+    ///
+    /// This is only the case when some of the block bytes were already archived into produced
+    /// segments, otherwise incomplete block is removed from the archiver when [`BlockStream`] is
+    /// dropped.
+    pub fn resume_block(&mut self, incremental: bool) -> Option<BlockStream<'_>> {
+        if self.missing_block_bytes == 0 {
+            return None;
+        }
+
+        Some(BlockStream {
+            archiver: self,
+            incremental,
+        })
+    }
+
+    fn check_no_streamed_block(&self) {
+        assert_eq!(
+            self.missing_block_bytes, 0,
+            "Streamed block must be finished (or resumed) before adding new blocks"
+        );
+    }
+
+    fn produce_archived_segments(&mut self, incremental: bool) -> Vec<NewArchivedSegment> {
         let mut archived_segments = Vec::new();
 
         while let Some(segment) = self.produce_segment(incremental) {
@@ -390,8 +498,12 @@ impl Archiver {
                     let existing_commitments = self.incremental_record_commitments.len();
                     let bytes_committed_to = existing_commitments * RawRecord::SIZE;
                     // Run incremental archiver only when there is at least two records to archive,
-                    // otherwise we're wasting CPU cycles encoding segment over and over again
-                    if incremental && segment_size - bytes_committed_to >= RawRecord::SIZE * 2 {
+                    // otherwise we're wasting CPU cycles encoding segment over and over again.
+                    // Segment with streamed block that doesn't have all bytes yet can't be encoded.
+                    if incremental
+                        && self.missing_block_bytes == 0
+                        && segment_size - bytes_committed_to >= RawRecord::SIZE * 2
+                    {
                         update_record_commitments(
                             &mut self.incremental_record_commitments,
                             &segment,
@@ -409,7 +521,21 @@ impl Archiver {
                 }
             };
 
-            let segment_item_encoded_size = segment_item.encoded_size();
+            // Streamed block is always the last item in the buffer, its encoded size accounts for
+            // bytes that were not provided yet
+            let missing_bytes = if self.buffer.is_empty() {
+                self.missing_block_bytes
+            } else {
+                0
+            };
+            let segment_item_encoded_size = match &segment_item {
+                SegmentItem::Block { bytes, .. } | SegmentItem::BlockContinuation { bytes, .. }
+                    if missing_bytes > 0 =>
+                {
+                    block_segment_item_encoded_size(bytes.len() + missing_bytes)
+                }
+                _ => segment_item.encoded_size(),
+            };
             segment_size += segment_item_encoded_size;
 
             // Check if there would be enough data collected with above segment item inserted
@@ -426,11 +552,11 @@ impl Archiver {
                     SegmentItem::Padding => {
                         unreachable!("Buffer never contains SegmentItem::Padding; qed");
                     }
-                    SegmentItem::Block { bytes, .. } => bytes.len(),
+                    SegmentItem::Block { bytes, .. } => bytes.len() + missing_bytes,
                     SegmentItem::BlockStart { .. } => {
                         unreachable!("Buffer never contains SegmentItem::BlockStart; qed");
                     }
-                    SegmentItem::BlockContinuation { bytes, .. } => bytes.len(),
+                    SegmentItem::BlockContinuation { bytes, .. } => bytes.len() + missing_bytes,
                     SegmentItem::ParentSegmentHeader(_) => {
                         unreachable!(
                             "SegmentItem::SegmentHeader is always the first element in the buffer \
@@ -472,7 +598,7 @@ impl Archiver {
                     );
                     last_archived_block.set_partial_archived(
                         archived_bytes
-                            + u32::try_from(bytes.len())
+                            + u32::try_from(bytes.len() + missing_bytes)
                                 .expect("Blocks length is never bigger than u32; qed"),
                     );
                 }
@@ -489,6 +615,23 @@ impl Archiver {
             .checked_sub(RecordedHistorySegment::SIZE)
             .unwrap_or_default();
 
+        // Streamed block is the last item in the segment if buffer is empty, in which case not all
+        // of its bytes that belong to this segment might have been provided yet
+        let missing_bytes = if self.buffer.is_empty() {
+            self.missing_block_bytes
+        } else {
+            0
+        };
+        if spill_over < missing_bytes {
+            let Segment::V0 { items } = segment;
+            // Push all of the items back into the buffer, we don't have enough data yet
+            for segment_item in items.into_iter().rev() {
+                self.buffer.push_front(segment_item);
+            }
+
+            return None;
+        }
+
         if spill_over > 0 {
             let Segment::V0 { items } = &mut segment;
             let segment_item = items
@@ -503,7 +646,7 @@ impl Archiver {
                     mut bytes,
                     mut object_mapping,
                 } => {
-                    let split_point = bytes.len() - spill_over;
+                    let split_point = bytes.len() + missing_bytes - spill_over;
                     let continuation_bytes = bytes[split_point..].to_vec();
 
                     bytes.truncate(split_point);
@@ -547,7 +690,7 @@ impl Archiver {
                     mut bytes,
                     mut object_mapping,
                 } => {
-                    let split_point = bytes.len() - spill_over;
+                    let split_point = bytes.len() + missing_bytes - spill_over;
                     let continuation_bytes = bytes[split_point..].to_vec();
 
                     bytes.truncate(split_point);
@@ -614,114 +757,22 @@ impl Archiver {
     // Take segment as an input, apply necessary transformations and produce archived segment
     fn produce_archived_segment(&mut self, segment: Segment) -> NewArchivedSegment {
         // Create mappings
-        let object_mapping = {
-            let mut corrected_object_mapping =
-                vec![PieceObjectMapping::default(); RecordedHistorySegment::NUM_RAW_RECORDS];
-            let Segment::V0 { items } = &segment;
-            // `+1` corresponds to enum variant encoding
-            let mut base_offset_in_segment = 1;
-            for segment_item in items {
-                match segment_item {
-                    SegmentItem::Padding => {
-                        unreachable!(
-                            "Segment during archiving never contains SegmentItem::Padding; qed"
-                        );
-                    }
-                    SegmentItem::Block {
-                        bytes,
-                        object_mapping,
-                    }
-                    | SegmentItem::BlockStart {
-                        bytes,
-                        object_mapping,
-                    }
-                    | SegmentItem::BlockContinuation {
-                        bytes,
-                        object_mapping,
-                    } => {
-                        for block_object in &object_mapping.objects {
-                            // `+1` corresponds to `SegmentItem::X {}` enum variant encoding
-                            let offset_in_segment = base_offset_in_segment
-                                + 1
-                                + Compact::compact_len(&(bytes.len() as u32))
-                                + block_object.offset() as usize;
-                            let offset = (offset_in_segment % RawRecord::SIZE).try_into().expect(
-                                "Offset within piece should always fit in 16-bit integer; qed",
-                            );
-
-                            if let Some(piece_object_mapping) = corrected_object_mapping
-                                .get_mut(offset_in_segment / RawRecord::SIZE)
-                            {
-                                piece_object_mapping.objects.push(PieceObject::V0 {
-                                    hash: block_object.hash(),
-                                    offset,
-                                });
-                            }
-                        }
-                    }
-                    SegmentItem::ParentSegmentHeader(_) => {
-                        // Ignore, no objects mappings here
-                    }
-                }
-
-                base_offset_in_segment += segment_item.encoded_size();
-            }
-            corrected_object_mapping
-        };
+        let object_mapping = create_object_mapping(&segment);
 
         let mut pieces = {
             // Serialize segment into concatenation of raw records
-            let mut raw_record_shards = Vec::<u8>::with_capacity(RecordedHistorySegment::SIZE);
-            segment.encode_to(&mut raw_record_shards);
-            // Segment might require some padding (see [`Self::produce_segment`] for details)
-            raw_record_shards.resize(raw_record_shards.capacity(), 0);
+            let raw_record_shards = encode_segment(&segment);
 
             // Segment is quite big and no longer necessary
             drop(segment);
 
             let mut pieces = ArchivedHistorySegment::default();
-
-            // Scratch buffer to avoid re-allocation
-            let mut tmp_source_shards_scalars =
-                Vec::<Scalar>::with_capacity(RecordedHistorySegment::NUM_RAW_RECORDS);
-            // Iterate over the chunks of `Scalar::SAFE_BYTES` bytes of all records
-            for record_offset in 0..RawRecord::SIZE / Scalar::SAFE_BYTES {
-                // Collect chunks of each record at the same offset
-                raw_record_shards
-                    .array_chunks::<{ RawRecord::SIZE }>()
-                    .map(|record_bytes| {
-                        record_bytes
-                            .array_chunks::<{ Scalar::SAFE_BYTES }>()
-                            .nth(record_offset)
-                            .expect("Statically known to exist in a record; qed")
-                    })
-                    .map(Scalar::from)
-                    .collect_into(&mut tmp_source_shards_scalars);
-
-                // Extend to obtain corresponding parity shards
-                let parity_shards = self
-                    .erasure_coding
-                    .extend(&tmp_source_shards_scalars)
-                    .expect(
-                        "Erasure coding instance is deliberately configured to support this \
-                        input; qed",
-                    );
-
-                let interleaved_input_chunks = tmp_source_shards_scalars
-                    .drain(..)
-                    .zip(parity_shards)
-                    .flat_map(|(a, b)| [a, b]);
-                let output_chunks = pieces.iter_mut().map(|piece| {
-                    piece
-                        .record_mut()
-                        .get_mut(record_offset)
-                        .expect("Statically known to exist in a record; qed")
-                });
-
-                interleaved_input_chunks
-                    .zip(output_chunks)
-                    .for_each(|(input, output)| output.copy_from_slice(&input.to_bytes()));
-            }
+            erasure_code_records(
+                &self.erasure_coding,
+                &raw_record_shards,
+                &mut pieces,
+                0..RECORD_CHUNKS,
+            );
 
             pieces
         };
@@ -768,53 +819,14 @@ impl Archiver {
             #[cfg(feature = "parallel")]
             self.incremental_record_commitments.par_extend(iter);
         }
-        // Collect hashes to commitments from all records
-        let record_commitments = self
-            .erasure_coding
-            .extend_commitments(&self.incremental_record_commitments)
-            .expect(
-                "Erasure coding instance is deliberately configured to support this input; qed",
-            );
-        self.incremental_record_commitments.clear();
-
-        let polynomial = self
-            .kzg
-            .poly(
-                &record_commitments
-                    .iter()
-                    .map(|commitment| blake3_254_hash_to_scalar(&commitment.to_bytes()))
-                    .collect::<Vec<_>>(),
-            )
-            .expect("Internally produced values must never fail; qed");
-
-        let segment_commitment = SegmentCommitment::from(
-            self.kzg
-                .commit(&polynomial)
-                .expect("Internally produced values must never fail; qed"),
+        let (record_commitments, polynomial, segment_commitment) = commit_to_records(
+            &self.erasure_coding,
+            &self.kzg,
+            &self.incremental_record_commitments,
         );
+        self.incremental_record_commitments.clear();
 
-        // Create witness for every record and write it to corresponding piece.
-        pieces
-            .iter_mut()
-            .zip(record_commitments)
-            .enumerate()
-            .for_each(|(position, (piece, commitment))| {
-                let commitment_bytes = commitment.to_bytes();
-                let (_record, commitment, witness) = piece.split_mut();
-                commitment.copy_from_slice(&commitment_bytes);
-                // TODO: Consider batch witness creation for improved performance
-                witness.copy_from_slice(
-                    &self
-                        .kzg
-                        .create_witness(
-                            &polynomial,
-                            ArchivedHistorySegment::NUM_PIECES,
-                            position as u32,
-                        )
-                        .expect("Position is statically known to be valid; qed")
-                        .to_bytes(),
-                );
-            });
+        write_commitments_and_witnesses(&self.kzg, &mut pieces, record_commitments, &polynomial);
 
         // Now produce segment header
         let segment_header = SegmentHeader::V0 {
@@ -839,45 +851,687 @@ impl Archiver {
             object_mapping,
         }
     }
+
+    /// Adds multiple blocks to internal buffer at once, potentially producing many segments.
+    ///
+    /// Produces exactly the same output as calling [`Self::add_block()`] for every block in order,
+    /// but the most expensive parts of archiving (erasure coding and creation of record
+    /// commitments) are done for all produced segments concurrently. Only the small portion of
+    /// work that depends on the segment header of the previous segment is done sequentially and
+    /// witnesses are again created concurrently afterwards.
+    ///
+    /// This is primarily useful when archiving a long range of already known blocks, like during
+    /// initial archiving or re-archiving of the history from genesis. Note that every segment
+    /// produced at once is kept in memory until all of them are archived, so callers should limit
+    /// the amount of data added in a single call accordingly.
+    pub fn add_blocks<Blocks>(&mut self, blocks: Blocks) -> Vec<NewArchivedSegment>
+    where
+        Blocks: IntoIterator<Item = (Vec<u8>, BlockObjectMapping)>,
+    {
+        self.check_no_streamed_block();
+
+        for (bytes, object_mapping) in blocks {
+            self.buffer.push_back(SegmentItem::Block {
+                bytes,
+                object_mapping,
+            });
+        }
+
+        // Slice buffer into segments first. Segment header of the previous segment is not known
+        // for all but the first produced segment yet, so placeholder with the same encoded size is
+        // inserted instead and replaced with the real one later.
+        let mut pending_segments = Vec::<PendingSegment>::new();
+        while let Some(segment) = self.produce_segment(false) {
+            let parent_segment_header_known = pending_segments.is_empty();
+            let segment_index = self.segment_index
+                + SegmentIndex::from(
+                    u64::try_from(pending_segments.len())
+                        .expect("Number of segments always fits into u64; qed"),
+                );
+
+            self.buffer
+                .push_front(SegmentItem::ParentSegmentHeader(SegmentHeader::V0 {
+                    segment_index,
+                    segment_commitment: SegmentCommitment::default(),
+                    prev_segment_header_hash: Blake3Hash::default(),
+                    last_archived_block: self.last_archived_block,
+                }));
+
+            pending_segments.push(PendingSegment {
+                segment,
+                last_archived_block: self.last_archived_block,
+                parent_segment_header_known,
+            });
+        }
+
+        if pending_segments.is_empty() {
+            return Vec::new();
+        }
+
+        // Commitments created incrementally (if any) belong to the first produced segment
+        let mut existing_commitments = Some(
+            self.incremental_record_commitments
+                .drain(..)
+                .collect::<Vec<_>>(),
+        );
+        let pending_segments = pending_segments
+            .into_iter()
+            .map(|pending_segment| {
+                let source_record_commitments = existing_commitments.take().unwrap_or_default();
+                (pending_segment, source_record_commitments)
+            })
+            .collect::<Vec<_>>();
+
+        // Do all the work that doesn't depend on the segment header of the previous segment
+        // concurrently
+        let prepare_segment = |(pending_segment, mut source_record_commitments): (
+            PendingSegment,
+            Vec<Commitment>,
+        )| {
+            let PendingSegment {
+                segment,
+                last_archived_block,
+                parent_segment_header_known,
+            } = pending_segment;
+
+            let object_mapping = create_object_mapping(&segment);
+            let raw_record_shards = encode_segment(&segment);
+
+            // Number of bytes at the beginning of the segment that are not known yet
+            let unknown_bytes = if parent_segment_header_known {
+                0
+            } else {
+                let Segment::V0 { items } = &segment;
+                // `+1` corresponds to enum variant encoding
+                1 + items
+                    .first()
+                    .expect("Segment contains parent segment header at the beginning; qed")
+                    .encoded_size()
+            };
+            drop(segment);
+
+            let mut pieces = ArchivedHistorySegment::default();
+            erasure_code_records(
+                &self.erasure_coding,
+                &raw_record_shards,
+                &mut pieces,
+                unknown_bytes.div_ceil(Scalar::SAFE_BYTES)..RECORD_CHUNKS,
+            );
+
+            // Commitment to the first record is created later if it contains unknown bytes
+            let skip_records = source_record_commitments
+                .len()
+                .max(unknown_bytes.div_ceil(RawRecord::SIZE));
+
+            #[cfg(not(feature = "parallel"))]
+            let raw_records_bytes = raw_record_shards.chunks_exact(RawRecord::SIZE);
+            #[cfg(feature = "parallel")]
+            let raw_records_bytes = raw_record_shards.par_chunks_exact(RawRecord::SIZE);
+
+            let iter = raw_records_bytes
+                .skip(skip_records)
+                .map(|raw_record_bytes| commit_raw_record(&self.kzg, raw_record_bytes));
+
+            #[cfg(not(feature = "parallel"))]
+            iter.collect_into(&mut source_record_commitments);
+            #[cfg(feature = "parallel")]
+            source_record_commitments.par_extend(iter);
+
+            PreparedSegment {
+                raw_record_shards,
+                pieces,
+                object_mapping,
+                source_record_commitments,
+                last_archived_block,
+                unknown_bytes,
+            }
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let prepared_segments = pending_segments
+            .into_iter()
+            .map(prepare_segment)
+            .collect::<Vec<_>>();
+        #[cfg(feature = "parallel")]
+        let prepared_segments = pending_segments
+            .into_par_iter()
+            .map(prepare_segment)
+            .collect::<Vec<_>>();
+
+        // Finish segments that depend on the segment header of the previous segment sequentially
+        let mut previous_segment_header = None;
+        let mut committed_segments = Vec::with_capacity(prepared_segments.len());
+        for prepared_segment in prepared_segments {
+            let PreparedSegment {
+                mut raw_record_shards,
+                mut pieces,
+                object_mapping,
+                mut source_record_commitments,
+                last_archived_block,
+                unknown_bytes,
+            } = prepared_segment;
+
+            if unknown_bytes > 0 {
+                let segment_header = previous_segment_header
+                    .expect("Only the first segment has known parent segment header; qed");
+                let segment_item = SegmentItem::ParentSegmentHeader(segment_header).encode();
+                // `1` corresponds to enum variant encoding
+                raw_record_shards[1..][..segment_item.len()].copy_from_slice(&segment_item);
+
+                erasure_code_records(
+                    &self.erasure_coding,
+                    &raw_record_shards,
+                    &mut pieces,
+                    0..unknown_bytes.div_ceil(Scalar::SAFE_BYTES),
+                );
+                source_record_commitments.insert(
+                    0,
+                    commit_raw_record(&self.kzg, &raw_record_shards[..RawRecord::SIZE]),
+                );
+            }
+            drop(raw_record_shards);
+
+            let (record_commitments, polynomial, segment_commitment) =
+                commit_to_records(&self.erasure_coding, &self.kzg, &source_record_commitments);
+
+            let segment_header = SegmentHeader::V0 {
+                segment_index: self.segment_index,
+                segment_commitment,
+                prev_segment_header_hash: self.prev_segment_header_hash,
+                last_archived_block,
+            };
+
+            // Update state
+            self.segment_index += SegmentIndex::ONE;
+            self.prev_segment_header_hash = segment_header.hash();
+            previous_segment_header.replace(segment_header);
+
+            committed_segments.push((
+                segment_header,
+                pieces,
+                object_mapping,
+                record_commitments,
+                polynomial,
+            ));
+        }
+
+        // Replace placeholder at the beginning of the buffer with the real segment header
+        match self.buffer.front_mut() {
+            Some(SegmentItem::ParentSegmentHeader(segment_header)) => {
+                *segment_header =
+                    previous_segment_header.expect("At least one segment was produced above; qed");
+            }
+            _ => {
+                unreachable!(
+                    "Placeholder segment header was inserted at the beginning of the buffer \
+                    above; qed"
+                );
+            }
+        }
+
+        // Witnesses only depend on the segment itself and can be created concurrently again
+        let finish_segment =
+            |(segment_header, mut pieces, object_mapping, record_commitments, polynomial): (
+                SegmentHeader,
+                ArchivedHistorySegment,
+                Vec<PieceObjectMapping>,
+                Vec<Commitment>,
+                Polynomial,
+            )| {
+                write_commitments_and_witnesses(
+                    &self.kzg,
+                    &mut pieces,
+                    record_commitments,
+                    &polynomial,
+                );
+
+                NewArchivedSegment {
+                    segment_header,
+                    pieces,
+                    object_mapping,
+                }
+            };
+
+        #[cfg(not(feature = "parallel"))]
+        let archived_segments = committed_segments.into_iter().map(finish_segment).collect();
+        #[cfg(feature = "parallel")]
+        let archived_segments = committed_segments
+            .into_par_iter()
+            .map(finish_segment)
+            .collect();
+
+        archived_segments
+    }
+}
+
+/// Block that is being added to [`Archiver`] in chunks of bytes, created with
+/// [`Archiver::start_block()`].
+///
+/// If the stream is dropped before all of the block bytes were provided, incomplete block is removed
+/// from the archiver as if it was never started. This is not possible if some of the block bytes
+/// were already archived into produced segments, in which case the block must be completed with
+/// [`Archiver::resume_block()`], otherwise archiver will panic on subsequent attempts to add blocks.
+#[derive(Debug)]
+pub struct BlockStream<'a> {
+    archiver: &'a mut Archiver,
+    incremental: bool,
+}
+
+impl BlockStream<'_> {
+    /// Number of block bytes that were not provided yet
+    pub fn remaining_bytes(&self) -> usize {
+        self.archiver.missing_block_bytes
+    }
+
+    /// Add next chunk of block bytes, potentially producing pieces and segment headers
+    pub fn add_bytes(&mut self, bytes: &[u8]) -> Result<Vec<NewArchivedSegment>, BlockStreamError> {
+        let remaining = self.archiver.missing_block_bytes;
+        if bytes.len() > remaining {
+            return Err(BlockStreamError::TooManyBytes {
+                provided: bytes.len(),
+                remaining,
+            });
+        }
+
+        match self.archiver.buffer.back_mut() {
+            Some(
+                SegmentItem::Block {
+                    bytes: block_bytes, ..
+                }
+                | SegmentItem::BlockContinuation {
+                    bytes: block_bytes, ..
+                },
+            ) => {
+                block_bytes.extend_from_slice(bytes);
+            }
+            _ => {
+                unreachable!(
+                    "Streamed block with remaining bytes is always the last item in the buffer; qed"
+                );
+            }
+        }
+        self.archiver.missing_block_bytes -= bytes.len();
+
+        Ok(self.archiver.produce_archived_segments(self.incremental))
+    }
+
+    /// Finish adding block, all of its bytes must have been provided at this point.
+    ///
+    /// Returns segments that were not produced by [`Self::add_bytes()`] yet, which is only possible
+    /// for empty blocks. In case of error stream can still be used to provide remaining bytes.
+    pub fn finish(&mut self) -> Result<Vec<NewArchivedSegment>, BlockStreamError> {
+        let remaining = self.archiver.missing_block_bytes;
+        if remaining > 0 {
+            return Err(BlockStreamError::NotEnoughBytes { remaining });
+        }
+
+        Ok(self.archiver.produce_archived_segments(self.incremental))
+    }
+}
+
+impl Drop for BlockStream<'_> {
+    fn drop(&mut self) {
+        if self.archiver.missing_block_bytes == 0 {
+            return;
+        }
+
+        // Block that was not archived into any segment yet can be removed as if it was never
+        // started, block continuation means some of the bytes were already archived and the block
+        // must be resumed instead
+        if matches!(self.archiver.buffer.back(), Some(SegmentItem::Block { .. })) {
+            self.archiver.buffer.pop_back();
+            self.archiver.missing_block_bytes = 0;
+        }
+    }
+}
+
+/// Encoded size of [`SegmentItem::Block`] or [`SegmentItem::BlockContinuation`] with `bytes_len`
+/// bytes
+fn block_segment_item_encoded_size(bytes_len: usize) -> usize {
+    let bytes_len_u32 =
+        u32::try_from(bytes_len).expect("Blocks length is never bigger than u32; qed");
+    // `1` corresponds to enum variant encoding
+    1 + Compact::<u32>::compact_len(&bytes_len_u32) + bytes_len
+}
+
+/// Number of [`Scalar::SAFE_BYTES`] chunks in a raw record
+const RECORD_CHUNKS: usize = RawRecord::SIZE / Scalar::SAFE_BYTES;
+
+/// Segment that was sliced from the buffer, but not archived yet
+struct PendingSegment {
+    segment: Segment,
+    last_archived_block: LastArchivedBlock,
+    /// Whether parent segment header within the segment is real or a placeholder
+    parent_segment_header_known: bool,
+}
+
+/// Segment with all the work that doesn't depend on the segment header of the previous segment done
+struct PreparedSegment {
+    raw_record_shards: Vec<u8>,
+    pieces: ArchivedHistorySegment,
+    object_mapping: Vec<PieceObjectMapping>,
+    source_record_commitments: Vec<Commitment>,
+    last_archived_block: LastArchivedBlock,
+    /// Number of bytes at the beginning of the segment that were not known during preparation
+    unknown_bytes: usize,
+}
+
+/// Create object mappings for pieces of the segment
+fn create_object_mapping(segment: &Segment) -> Vec<PieceObjectMapping> {
+    let mut corrected_object_mapping =
+        vec![PieceObjectMapping::default(); RecordedHistorySegment::NUM_RAW_RECORDS];
+    let Segment::V0 { items } = segment;
+    // `+1` corresponds to enum variant encoding
+    let mut base_offset_in_segment = 1;
+    for segment_item in items {
+        match segment_item {
+            SegmentItem::Padding => {
+                unreachable!("Segment during archiving never contains SegmentItem::Padding; qed");
+            }
+            SegmentItem::Block {
+                bytes,
+                object_mapping,
+            }
+            | SegmentItem::BlockStart {
+                bytes,
+                object_mapping,
+            }
+            | SegmentItem::BlockContinuation {
+                bytes,
+                object_mapping,
+            } => {
+                for block_object in &object_mapping.objects {
+                    // `+1` corresponds to `SegmentItem::X {}` enum variant encoding
+                    let offset_in_segment = base_offset_in_segment
+                        + 1
+                        + Compact::compact_len(&(bytes.len() as u32))
+                        + block_object.offset() as usize;
+                    let offset = (offset_in_segment % RawRecord::SIZE)
+                        .try_into()
+                        .expect("Offset within piece should always fit in 16-bit integer; qed");
+
+                    if let Some(piece_object_mapping) =
+                        corrected_object_mapping.get_mut(offset_in_segment / RawRecord::SIZE)
+                    {
+                        piece_object_mapping.objects.push(PieceObject::V0 {
+                            hash: block_object.hash(),
+                            offset,
+                        });
+                    }
+                }
+            }
+            SegmentItem::ParentSegmentHeader(_) => {
+                // Ignore, no objects mappings here
+            }
+        }
+
+        base_offset_in_segment += segment_item.encoded_size();
+    }
+    corrected_object_mapping
+}
+
+/// Serialize segment into concatenation of raw records
+fn encode_segment(segment: &Segment) -> Vec<u8> {
+    let mut raw_record_shards = Vec::<u8>::with_capacity(RecordedHistorySegment::SIZE);
+    segment.encode_to(&mut raw_record_shards);
+    // Segment might require some padding (see [`Archiver::produce_segment`] for details)
+    raw_record_shards.resize(raw_record_shards.capacity(), 0);
+    raw_record_shards
+}
+
+/// Erasure code chunks of [`Scalar::SAFE_BYTES`] bytes at `record_offsets` of all raw records and
+/// write both source and parity chunks into corresponding pieces
+fn erasure_code_records(
+    erasure_coding: &ErasureCoding,
+    raw_record_shards: &[u8],
+    pieces: &mut ArchivedHistorySegment,
+    record_offsets: Range<usize>,
+) {
+    // Scratch buffer to avoid re-allocation
+    let mut tmp_source_shards_scalars =
+        Vec::<Scalar>::with_capacity(RecordedHistorySegment::NUM_RAW_RECORDS);
+    // Iterate over the chunks of `Scalar::SAFE_BYTES` bytes of all records
+    for record_offset in record_offsets {
+        // Collect chunks of each record at the same offset
+        raw_record_shards
+            .array_chunks::<{ RawRecord::SIZE }>()
+            .map(|record_bytes| {
+                record_bytes
+                    .array_chunks::<{ Scalar::SAFE_BYTES }>()
+                    .nth(record_offset)
+                    .expect("Statically known to exist in a record; qed")
+            })
+            .map(Scalar::from)
+            .collect_into(&mut tmp_source_shards_scalars);
+
+        // Extend to obtain corresponding parity shards
+        let parity_shards = erasure_coding.extend(&tmp_source_shards_scalars).expect(
+            "Erasure coding instance is deliberately configured to support this input; qed",
+        );
+
+        let interleaved_input_chunks = tmp_source_shards_scalars
+            .drain(..)
+            .zip(parity_shards)
+            .flat_map(|(a, b)| [a, b]);
+        let output_chunks = pieces.iter_mut().map(|piece| {
+            piece
+                .record_mut()
+                .get_mut(record_offset)
+                .expect("Statically known to exist in a record; qed")
+        });
+
+        interleaved_input_chunks
+            .zip(output_chunks)
+            .for_each(|(input, output)| output.copy_from_slice(&input.to_bytes()));
+    }
+}
+
+/// Extend commitments to source records with commitments to parity records and commit to all of
+/// them, returning commitments to all records, corresponding polynomial and segment commitment
+fn commit_to_records(
+    erasure_coding: &ErasureCoding,
+    kzg: &Kzg,
+    source_record_commitments: &[Commitment],
+) -> (Vec<Commitment>, Polynomial, SegmentCommitment) {
+    // Collect hashes to commitments from all records
+    let record_commitments = erasure_coding
+        .extend_commitments(source_record_commitments)
+        .expect("Erasure coding instance is deliberately configured to support this input; qed");
+
+    let polynomial = kzg
+        .poly(
+            &record_commitments
+                .iter()
+                .map(|commitment| blake3_254_hash_to_scalar(&commitment.to_bytes()))
+                .collect::<Vec<_>>(),
+        )
+        .expect("Internally produced values must never fail; qed");
+
+    let segment_commitment = SegmentCommitment::from(
+        kzg.commit(&polynomial)
+            .expect("Internally produced values must never fail; qed"),
+    );
+
+    (record_commitments, polynomial, segment_commitment)
+}
+
+/// Write record commitment and create witness for every record, writing both to corresponding
+/// piece
+fn write_commitments_and_witnesses(
+    kzg: &Kzg,
+    pieces: &mut ArchivedHistorySegment,
+    record_commitments: Vec<Commitment>,
+    polynomial: &Polynomial,
+) {
+    pieces
+        .iter_mut()
+        .zip(record_commitments)
+        .enumerate()
+        .for_each(|(position, (piece, commitment))| {
+            let commitment_bytes = commitment.to_bytes();
+            let (_record, commitment, witness) = piece.split_mut();
+            commitment.copy_from_slice(&commitment_bytes);
+            // TODO: Consider batch witness creation for improved performance
+            witness.copy_from_slice(
+                &kzg.create_witness(
+                    polynomial,
+                    ArchivedHistorySegment::NUM_PIECES,
+                    position as u32,
+                )
+                .expect("Position is statically known to be valid; qed")
+                .to_bytes(),
+            );
+        });
 }
 
 /// Validate witness embedded within a piece produced by archiver
+///
+/// Prefer [`is_pieces_valid()`] when validating multiple pieces at once.
 pub fn is_piece_valid(
     kzg: &Kzg,
     piece: &PieceArray,
     segment_commitment: &SegmentCommitment,
     position: u32,
 ) -> bool {
-    let (record, commitment, witness) = piece.split();
-    let witness = match Witness::try_from_bytes(witness) {
-        Ok(witness) => witness,
-        _ => {
-            return false;
-        }
+    let Some((commitment_hash, witness)) = check_record_commitment(kzg, piece) else {
+        return false;
     };
 
-    let mut scalars = Vec::with_capacity(record.len().next_power_of_two());
+    let Ok(segment_commitment) = Commitment::try_from(segment_commitment) else {
+        return false;
+    };
 
-    for record_chunk in record.iter() {
-        match Scalar::try_from(record_chunk) {
-            Ok(scalar) => {
-                scalars.push(scalar);
+    kzg.verify(
+        &segment_commitment,
+        ArchivedHistorySegment::NUM_PIECES,
+        position,
+        &commitment_hash,
+        &witness,
+    )
+}
+
+/// Validate witnesses embedded within multiple pieces produced by archiver at once, each piece is
+/// accompanied by segment commitment and position with the same meaning as in
+/// [`is_piece_valid()`].
+///
+/// Record commitment is still checked for each piece individually, but witnesses of all pieces are
+/// checked with a single pairing check, which is much faster than calling [`is_piece_valid()`] for
+/// each piece. Returns `true` only if all pieces are valid, use [`is_piece_valid()`] to find which
+/// pieces are invalid if necessary.
+pub fn is_pieces_valid(kzg: &Kzg, pieces: &[(&PieceArray, &SegmentCommitment, u32)]) -> bool {
+    let check_piece =
+        |&(piece, segment_commitment, position): &(&PieceArray, &SegmentCommitment, u32)| {
+            let (commitment_hash, witness) = check_record_commitment(kzg, piece)?;
+            let segment_commitment = Commitment::try_from(segment_commitment).ok()?;
+
+            Some((segment_commitment, position, commitment_hash, witness))
+        };
+
+    #[cfg(not(feature = "parallel"))]
+    let checked_pieces = pieces.iter().map(check_piece).collect::<Option<Vec<_>>>();
+    #[cfg(feature = "parallel")]
+    let checked_pieces = pieces
+        .par_iter()
+        .map(check_piece)
+        .collect::<Option<Vec<_>>>();
+
+    let Some(checked_pieces) = checked_pieces else {
+        return false;
+    };
+
+    kzg.verify_batch(
+        ArchivedHistorySegment::NUM_PIECES,
+        &checked_pieces
+            .iter()
+            .map(|(segment_commitment, position, commitment_hash, witness)| {
+                (segment_commitment, *position, commitment_hash, witness)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Same as [`is_pieces_valid()`], but returns validity of each piece in the same order as pieces
+/// were provided.
+///
+/// Pieces are only validated individually if batch validation fails.
+pub fn are_pieces_valid(kzg: &Kzg, pieces: &[(&PieceArray, &SegmentCommitment, u32)]) -> Vec<bool> {
+    if is_pieces_valid(kzg, pieces) {
+        return vec![true; pieces.len()];
+    }
+
+    let check_piece =
+        |&(piece, segment_commitment, position): &(&PieceArray, &SegmentCommitment, u32)| {
+            is_piece_valid(kzg, piece, segment_commitment, position)
+        };
+
+    #[cfg(not(feature = "parallel"))]
+    let results = pieces.iter().map(check_piece).collect();
+    #[cfg(feature = "parallel")]
+    let results = pieces.par_iter().map(check_piece).collect();
+
+    results
+}
+
+/// Validates pieces with [`are_pieces_valid()`], returning pieces in the same order as provided
+/// with `None` in place of invalid pieces, together with indexes of invalid pieces.
+///
+/// Pieces without segment commitment are considered invalid, but are not included in the list of
+/// invalid piece indexes since it is not known whether they are actually invalid.
+pub fn validate_pieces(
+    kzg: &Kzg,
+    pieces: Vec<(PieceIndex, Piece, Option<SegmentCommitment>)>,
+) -> (Vec<Option<Piece>>, Vec<PieceIndex>) {
+    let mut results = are_pieces_valid(
+        kzg,
+        &pieces
+            .iter()
+            .filter_map(|(piece_index, piece, segment_commitment)| {
+                Some((
+                    &**piece,
+                    segment_commitment.as_ref()?,
+                    piece_index.position(),
+                ))
+            })
+            .collect::<Vec<_>>(),
+    )
+    .into_iter();
+
+    let mut invalid_piece_indexes = Vec::new();
+    let pieces = pieces
+        .into_iter()
+        .map(|(piece_index, piece, segment_commitment)| {
+            if segment_commitment.is_none() {
+                return None;
             }
-            _ => {
-                return false;
+
+            if results.next().unwrap_or_default() {
+                Some(piece)
+            } else {
+                invalid_piece_indexes.push(piece_index);
+                None
             }
-        }
+        })
+        .collect();
+
+    (pieces, invalid_piece_indexes)
+}
+
+/// Checks that record commitment embedded within a piece matches record, returns hash of the
+/// record commitment and witness embedded within a piece on success
+fn check_record_commitment(kzg: &Kzg, piece: &PieceArray) -> Option<(Scalar, Witness)> {
+    let (record, commitment, witness) = piece.split();
+    let witness = Witness::try_from_bytes(witness).ok()?;
+
+    let mut scalars = Vec::with_capacity(record.len().next_power_of_two());
+
+    for record_chunk in record.iter() {
+        scalars.push(Scalar::try_from(record_chunk).ok()?);
     }
 
     // Number of scalars for KZG must be a power of two elements
     scalars.resize(scalars.capacity(), Scalar::default());
 
-    let polynomial = match kzg.poly(&scalars) {
-        Ok(polynomial) => polynomial,
-        _ => {
-            return false;
-        }
-    };
+    let polynomial = kzg.poly(&scalars).ok()?;
 
     if kzg
         .commit(&polynomial)
@@ -885,22 +1539,10 @@ pub fn is_piece_valid(
         .as_ref()
         != Ok(commitment)
     {
-        return false;
+        return None;
     }
 
-    let Ok(segment_commitment) = Commitment::try_from(segment_commitment) else {
-        return false;
-    };
-
-    let commitment_hash = blake3_254_hash_to_scalar(commitment.as_ref());
-
-    kzg.verify(
-        &segment_commitment,
-        ArchivedHistorySegment::NUM_PIECES,
-        position,
-        &commitment_hash,
-        &witness,
-    )
+    Some((blake3_254_hash_to_scalar(commitment.as_ref()), witness))
 }
 
 /// Validate witness for record commitment hash produced by archiver
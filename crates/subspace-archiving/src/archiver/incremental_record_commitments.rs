@@ -65,6 +65,28 @@ pub(super) fn update_record_commitments(
     ));
 }
 
+/// Create commitment to a single raw record (source record before erasure coding).
+///
+/// `raw_record_bytes` must be exactly [`RawRecord::SIZE`] bytes.
+pub(super) fn commit_raw_record(kzg: &Kzg, raw_record_bytes: &[u8]) -> Commitment {
+    let record_chunks = raw_record_bytes
+        .array_chunks::<{ Scalar::SAFE_BYTES }>()
+        .map(Scalar::from);
+    let number_of_chunks = record_chunks.len();
+    let mut scalars = Vec::with_capacity(number_of_chunks.next_power_of_two());
+
+    record_chunks.collect_into(&mut scalars);
+
+    // Number of scalars for KZG must be a power of two elements
+    scalars.resize(scalars.capacity(), Scalar::default());
+
+    let polynomial = kzg
+        .poly(&scalars)
+        .expect("KZG instance must be configured to support this many scalars; qed");
+    kzg.commit(&polynomial)
+        .expect("KZG instance must be configured to support this many scalars; qed")
+}
+
 /// Processor is hidden to not expose unnecessary implementation details (like `Output` trait
 /// implementation)
 struct IncrementalRecordCommitmentsProcessor<'a> {
@@ -85,29 +107,8 @@ impl<'a> Drop for IncrementalRecordCommitmentsProcessor<'a> {
         #[cfg(feature = "parallel")]
         let raw_records_bytes = self.buffer.par_chunks_exact(RawRecord::SIZE);
 
-        let iter = raw_records_bytes
-            .map(|raw_record_bytes| {
-                raw_record_bytes
-                    .array_chunks::<{ Scalar::SAFE_BYTES }>()
-                    .map(Scalar::from)
-            })
-            .map(|record_chunks| {
-                let number_of_chunks = record_chunks.len();
-                let mut scalars = Vec::with_capacity(number_of_chunks.next_power_of_two());
-
-                record_chunks.collect_into(&mut scalars);
-
-                // Number of scalars for KZG must be a power of two elements
-                scalars.resize(scalars.capacity(), Scalar::default());
-
-                let polynomial = self
-                    .kzg
-                    .poly(&scalars)
-                    .expect("KZG instance must be configured to support this many scalars; qed");
-                self.kzg
-                    .commit(&polynomial)
-                    .expect("KZG instance must be configured to support this many scalars; qed")
-            });
+        let iter =
+            raw_records_bytes.map(|raw_record_bytes| commit_raw_record(self.kzg, raw_record_bytes));
 
         #[cfg(not(feature = "parallel"))]
         iter.collect_into(&mut self.incremental_record_commitments.state);
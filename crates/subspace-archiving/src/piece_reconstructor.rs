@@ -11,7 +11,7 @@ use rayon::prelude::*;
 use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Polynomial};
 use subspace_core_primitives::crypto::{blake3_254_hash_to_scalar, Scalar};
 use subspace_core_primitives::{ArchivedHistorySegment, Piece, RawRecord};
-use subspace_erasure_coding::ErasureCoding;
+use subspace_erasure_coding::{ErasureCoding, ErasureCodingBackend};
 
 /// Reconstructor-related instantiation error.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -72,6 +72,21 @@ impl PiecesReconstructor {
         })
     }
 
+    /// Switch erasure coding to specified backend, all backends produce identical pieces.
+    pub fn with_erasure_coding_backend(
+        mut self,
+        backend: ErasureCodingBackend,
+    ) -> Result<Self, ReconstructorInstantiationError> {
+        self.erasure_coding = ErasureCoding::with_backend(
+            NonZeroUsize::new(ArchivedHistorySegment::NUM_PIECES.ilog2() as usize)
+                .expect("Archived history segment contains at very least one piece; qed"),
+            backend,
+        )
+        .map_err(ReconstructorInstantiationError::FailedToInitializeErasureCoding)?;
+
+        Ok(self)
+    }
+
     /// Returns incomplete pieces (witness missing) and polynomial that can be used to generate
     /// necessary witnesses later.
     fn reconstruct_shards(
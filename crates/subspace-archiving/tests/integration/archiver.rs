@@ -6,7 +6,9 @@ use std::assert_matches::assert_matches;
 use std::io::Write;
 use std::iter;
 use subspace_archiving::archiver;
-use subspace_archiving::archiver::{Archiver, ArchiverInstantiationError, SegmentItem};
+use subspace_archiving::archiver::{
+    Archiver, ArchiverInstantiationError, BlockStreamError, SegmentItem,
+};
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping, PieceObject};
@@ -205,6 +207,23 @@ fn archiver() {
         assert!(valid, "Piece at position {position} is valid");
     }
 
+    {
+        let segment_commitment = first_archived_segment.segment_header.segment_commitment();
+        let mut pieces = first_archived_segment
+            .pieces
+            .iter()
+            .zip(0_u32..)
+            .map(|(piece, position)| (piece, &segment_commitment, position))
+            .collect::<Vec<_>>();
+        assert!(archiver::is_pieces_valid(&kzg, &pieces));
+
+        // Swapping positions of two pieces must make the batch invalid
+        pieces.swap(0, 1);
+        pieces[0].2 = 0;
+        pieces[1].2 = 1;
+        assert!(!archiver::is_pieces_valid(&kzg, &pieces));
+    }
+
     let block_2 = {
         let mut block = vec![0u8; RecordedHistorySegment::SIZE * 2];
         thread_rng().fill(block.as_mut_slice());
@@ -388,6 +407,229 @@ fn archiver() {
     }
 }
 
+#[test]
+fn add_blocks_matches_add_block() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let archiver = Archiver::new(kzg).unwrap();
+
+    let blocks = [
+        RecordedHistorySegment::SIZE / 3,
+        RecordedHistorySegment::SIZE / 2,
+        RecordedHistorySegment::SIZE,
+        RecordedHistorySegment::SIZE / 5,
+        RecordedHistorySegment::SIZE * 2 / 3,
+    ]
+    .into_iter()
+    .map(|block_size| {
+        let mut block = vec![0u8; block_size];
+        thread_rng().fill(block.as_mut_slice());
+        let block_object_mapping = BlockObjectMapping {
+            objects: vec![
+                BlockObject::V0 {
+                    hash: Blake3Hash::default(),
+                    offset: 0,
+                },
+                BlockObject::V0 {
+                    hash: Blake3Hash::default(),
+                    offset: block_size as u32 / 2,
+                },
+            ],
+        };
+
+        (block, block_object_mapping)
+    })
+    .collect::<Vec<_>>();
+
+    // Start with a small block archived incrementally, such that some record commitments are
+    // already created by the time blocks are added
+    let mut small_block = vec![0u8; RecordedHistorySegment::SIZE / 10];
+    thread_rng().fill(small_block.as_mut_slice());
+
+    let mut sequential_archiver = archiver.clone();
+    let mut parallel_archiver = archiver;
+    assert!(sequential_archiver
+        .add_block(small_block.clone(), BlockObjectMapping::default(), true)
+        .is_empty());
+    assert!(parallel_archiver
+        .add_block(small_block, BlockObjectMapping::default(), true)
+        .is_empty());
+
+    let sequential_archived_segments = blocks
+        .iter()
+        .cloned()
+        .flat_map(|(block, block_object_mapping)| {
+            sequential_archiver.add_block(block, block_object_mapping, false)
+        })
+        .collect::<Vec<_>>();
+    let parallel_archived_segments = parallel_archiver.add_blocks(blocks.clone());
+
+    assert!(sequential_archived_segments.len() > 2);
+    assert_eq!(
+        sequential_archived_segments.len(),
+        parallel_archived_segments.len()
+    );
+    for (sequential_archived_segment, parallel_archived_segment) in sequential_archived_segments
+        .iter()
+        .zip(&parallel_archived_segments)
+    {
+        assert_eq!(
+            sequential_archived_segment.segment_header,
+            parallel_archived_segment.segment_header
+        );
+        assert_eq!(
+            sequential_archived_segment.object_mapping,
+            parallel_archived_segment.object_mapping
+        );
+        assert!(sequential_archived_segment.pieces == parallel_archived_segment.pieces);
+    }
+
+    // Internal state must be identical as well, so that archiving can continue as usual
+    let mut block = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block.as_mut_slice());
+    assert_eq!(
+        sequential_archiver.add_block(block.clone(), BlockObjectMapping::default(), true),
+        parallel_archiver.add_block(block, BlockObjectMapping::default(), true)
+    );
+}
+
+#[test]
+fn streaming_matches_add_block() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let archiver = Archiver::new(kzg).unwrap();
+
+    let blocks = [
+        RecordedHistorySegment::SIZE / 3,
+        0,
+        RecordedHistorySegment::SIZE * 5 / 2,
+        RecordedHistorySegment::SIZE / 5,
+        RecordedHistorySegment::SIZE * 2 / 3,
+    ]
+    .into_iter()
+    .map(|block_size| {
+        let mut block = vec![0u8; block_size];
+        thread_rng().fill(block.as_mut_slice());
+        let block_object_mapping = BlockObjectMapping {
+            objects: vec![
+                BlockObject::V0 {
+                    hash: Blake3Hash::default(),
+                    offset: 0,
+                },
+                BlockObject::V0 {
+                    hash: Blake3Hash::default(),
+                    offset: block_size as u32 / 2,
+                },
+            ],
+        };
+
+        (block, block_object_mapping)
+    })
+    .collect::<Vec<_>>();
+
+    let mut archiver_full = archiver.clone();
+    let mut archiver_streaming = archiver;
+
+    let mut archived_segments_full = Vec::new();
+    let mut archived_segments_streaming = Vec::new();
+    for (block, block_object_mapping) in blocks {
+        archived_segments_full.extend(archiver_full.add_block(
+            block.clone(),
+            block_object_mapping.clone(),
+            true,
+        ));
+
+        let mut block_stream =
+            archiver_streaming.start_block(block.len() as u32, block_object_mapping, true);
+        let mut remaining_bytes = block.as_slice();
+        while !remaining_bytes.is_empty() {
+            let chunk_size = thread_rng().gen_range(1..=RecordedHistorySegment::SIZE / 4);
+            let (chunk, rest) = remaining_bytes.split_at(chunk_size.min(remaining_bytes.len()));
+            remaining_bytes = rest;
+
+            archived_segments_streaming.extend(block_stream.add_bytes(chunk).unwrap());
+            assert_eq!(block_stream.remaining_bytes(), remaining_bytes.len());
+        }
+        assert_eq!(
+            block_stream.add_bytes(&[0]),
+            Err(BlockStreamError::TooManyBytes {
+                provided: 1,
+                remaining: 0
+            })
+        );
+        archived_segments_streaming.extend(block_stream.finish().unwrap());
+
+        // Segments must be produced as soon as enough bytes were provided
+        assert_eq!(
+            archived_segments_full.len(),
+            archived_segments_streaming.len()
+        );
+    }
+
+    assert!(archived_segments_full.len() > 3);
+    assert_eq!(archived_segments_full, archived_segments_streaming);
+
+    // Block can't be finished before all bytes are provided, but can be continued afterwards
+    {
+        let mut archiver = archiver_streaming.clone();
+        let mut block_stream = archiver.start_block(10, BlockObjectMapping::default(), false);
+        block_stream.add_bytes(&[0; 4]).unwrap();
+        assert_eq!(
+            block_stream.finish(),
+            Err(BlockStreamError::NotEnoughBytes { remaining: 6 })
+        );
+        block_stream.add_bytes(&[0; 6]).unwrap();
+        assert_eq!(block_stream.finish(), Ok(Vec::new()));
+    }
+
+    // Incomplete block that wasn't archived yet is removed when stream is dropped
+    {
+        let mut block_stream =
+            archiver_streaming.start_block(10, BlockObjectMapping::default(), true);
+        block_stream.add_bytes(&[0; 4]).unwrap();
+        drop(block_stream);
+        assert!(archiver_streaming.resume_block(true).is_none());
+    }
+
+    // Incomplete block that was partially archived already can be resumed after stream is dropped
+    {
+        let mut archiver_full = archiver_full.clone();
+        let mut archiver_streaming = archiver_streaming.clone();
+
+        let mut block = vec![0u8; RecordedHistorySegment::SIZE * 3 / 2];
+        thread_rng().fill(block.as_mut_slice());
+
+        let archived_segments_full =
+            archiver_full.add_block(block.clone(), BlockObjectMapping::default(), true);
+
+        let (first_chunk, second_chunk) = block.split_at(RecordedHistorySegment::SIZE);
+        let mut archived_segments_streaming = {
+            let mut block_stream = archiver_streaming.start_block(
+                block.len() as u32,
+                BlockObjectMapping::default(),
+                true,
+            );
+            block_stream.add_bytes(first_chunk).unwrap()
+        };
+        assert!(!archived_segments_streaming.is_empty());
+
+        let mut block_stream = archiver_streaming.resume_block(true).unwrap();
+        assert_eq!(block_stream.remaining_bytes(), second_chunk.len());
+        archived_segments_streaming.extend(block_stream.add_bytes(second_chunk).unwrap());
+        archived_segments_streaming.extend(block_stream.finish().unwrap());
+        drop(block_stream);
+
+        assert_eq!(archived_segments_full, archived_segments_streaming);
+        assert!(archiver_streaming.resume_block(true).is_none());
+    }
+
+    // Internal state must be identical as well, so that archiving can continue as usual
+    let mut block = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block.as_mut_slice());
+    assert_eq!(
+        archiver_full.add_block(block.clone(), BlockObjectMapping::default(), true),
+        archiver_streaming.add_block(block, BlockObjectMapping::default(), true)
+    );
+}
+
 #[test]
 fn invalid_usage() {
     let kzg = Kzg::new(embedded_kzg_settings());
@@ -58,6 +58,27 @@ fn criterion_benchmark(c: &mut Criterion) {
             })
         });
     }
+
+    {
+        let polynomial = kzg.poly(&values).unwrap();
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let witnesses = (0..num_values as u32)
+            .take(256)
+            .map(|index| kzg.create_witness(&polynomial, num_values, index).unwrap())
+            .collect::<Vec<_>>();
+        let evaluations = values
+            .iter()
+            .zip(&witnesses)
+            .zip(0_u32..)
+            .map(|((value, witness), index)| (&commitment, index, value, witness))
+            .collect::<Vec<_>>();
+
+        c.bench_function("verify-batch-256", |b| {
+            b.iter(|| {
+                kzg.verify_batch(black_box(num_values), black_box(&evaluations));
+            })
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -5,7 +5,8 @@ mod tests;
 
 extern crate alloc;
 
-use crate::crypto::Scalar;
+use crate::crypto::{blake3_254_hash_to_scalar, Scalar};
+use crate::Blake3Hash;
 use alloc::collections::btree_map::Entry;
 use alloc::collections::BTreeMap;
 #[cfg(not(feature = "std"))]
@@ -19,7 +20,9 @@ use kzg::eip_4844::{BYTES_PER_G1, BYTES_PER_G2};
 use kzg::{FFTFr, FFTSettings, Fr, KZGSettings, G1, G2};
 #[cfg(feature = "std")]
 use parking_lot::Mutex;
+use rust_kzg_blst::kzg_proofs::{g1_linear_combination, pairings_verify};
 use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
 use rust_kzg_blst::types::g1::FsG1;
 use rust_kzg_blst::types::g2::FsG2;
 use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
@@ -443,6 +446,92 @@ impl Kzg {
         }
     }
 
+    /// Verifies multiple evaluations at once, each evaluation is a tuple of `commitment`, `index`,
+    /// `value` and `witness` with the same meaning as in [`Self::verify()`], `num_values` is shared
+    /// by all evaluations.
+    ///
+    /// Evaluations are combined into a random linear combination with coefficients derived from
+    /// the hash of all inputs, which allows checking all of them with a single pairing check and is
+    /// much faster than calling [`Self::verify()`] for each evaluation. Returns `true` only if all
+    /// evaluations are valid, use [`Self::verify()`] to find which evaluations are invalid if
+    /// necessary.
+    pub fn verify_batch(
+        &self,
+        num_values: usize,
+        evaluations: &[(&Commitment, u32, &Scalar, &Witness)],
+    ) -> bool {
+        if evaluations.is_empty() {
+            return true;
+        }
+
+        let fft_settings = match self.get_fft_settings(num_values) {
+            Ok(fft_settings) => fft_settings,
+            Err(error) => {
+                debug!(error, "Failed to derive fft settings");
+                return false;
+            }
+        };
+
+        // Coefficients must not be known before all inputs are fixed, otherwise invalid
+        // evaluations could be crafted to cancel each other out
+        let coefficients_seed: Blake3Hash = {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(&(num_values as u64).to_le_bytes());
+            for (commitment, index, value, witness) in evaluations {
+                hasher.update(&commitment.to_bytes());
+                hasher.update(&index.to_le_bytes());
+                hasher.update(&value.to_bytes());
+                hasher.update(&witness.to_bytes());
+            }
+            *hasher.finalize().as_bytes()
+        };
+
+        // Single evaluation is checked as `e(C - [y]G1, G2) == e(W, [s - x]G2)`, which is
+        // equivalent to `e(C - [y]G1 + [x]W, G2) == e(W, [s]G2)`, the latter can be summed with
+        // random coefficients `r` across all evaluations:
+        // `e(sum(r * C) - [sum(r * y)]G1 + sum(r * x * W), G2) == e(sum(r * W), [s]G2)`
+        let mut lhs_points = Vec::with_capacity(evaluations.len() * 2 + 1);
+        let mut lhs_scalars = Vec::with_capacity(evaluations.len() * 2 + 1);
+        let mut rhs_points = Vec::with_capacity(evaluations.len());
+        let mut rhs_scalars = Vec::with_capacity(evaluations.len());
+        let mut combined_value = FsFr::zero();
+
+        for ((commitment, index, value, witness), coefficient_index) in
+            evaluations.iter().zip(0_u64..)
+        {
+            let mut coefficient_input = [0; mem::size_of::<Blake3Hash>() + mem::size_of::<u64>()];
+            coefficient_input[..mem::size_of::<Blake3Hash>()].copy_from_slice(&coefficients_seed);
+            coefficient_input[mem::size_of::<Blake3Hash>()..]
+                .copy_from_slice(&coefficient_index.to_le_bytes());
+            let coefficient = blake3_254_hash_to_scalar(&coefficient_input).0;
+
+            let x = fft_settings.get_expanded_roots_of_unity_at(*index as usize);
+
+            lhs_points.push(commitment.0);
+            lhs_scalars.push(coefficient);
+            lhs_points.push(witness.0);
+            lhs_scalars.push(coefficient.mul(&x));
+            rhs_points.push(witness.0);
+            rhs_scalars.push(coefficient);
+            combined_value = combined_value.add(&coefficient.mul(&value.0));
+        }
+
+        lhs_points.push(FsG1::generator());
+        lhs_scalars.push(combined_value.negate());
+
+        let mut lhs = FsG1::default();
+        g1_linear_combination(&mut lhs, &lhs_points, &lhs_scalars, lhs_points.len());
+        let mut rhs = FsG1::default();
+        g1_linear_combination(&mut rhs, &rhs_points, &rhs_scalars, rhs_points.len());
+
+        pairings_verify(
+            &lhs,
+            &FsG2::generator(),
+            &rhs,
+            &self.inner.kzg_settings.secret_g2[1],
+        )
+    }
+
     /// Get FFT settings for specified number of values, uses internal cache to avoid derivation
     /// every time.
     pub fn get_fft_settings(&self, num_values: usize) -> Result<Arc<FsFFTSettings>, String> {
@@ -1,4 +1,4 @@
-use crate::crypto::kzg::{embedded_kzg_settings, Kzg};
+use crate::crypto::kzg::{embedded_kzg_settings, Kzg, Witness};
 use crate::crypto::Scalar;
 
 #[test]
@@ -24,3 +24,49 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn verify_batch() {
+    let values = (0..8)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+
+    let num_values = values.len();
+
+    let witnesses = (0..num_values as u32)
+        .map(|index| kzg.create_witness(&polynomial, num_values, index).unwrap())
+        .collect::<Vec<_>>();
+    let evaluations = values
+        .iter()
+        .zip(&witnesses)
+        .zip(0_u32..)
+        .map(|((value, witness), index)| (&commitment, index, value, witness))
+        .collect::<Vec<_>>();
+
+    assert!(kzg.verify_batch(num_values, &[]));
+    assert!(kzg.verify_batch(num_values, &evaluations));
+
+    // Wrong value
+    {
+        let mut evaluations = evaluations.clone();
+        evaluations[1].2 = &values[2];
+        assert!(!kzg.verify_batch(num_values, &evaluations));
+    }
+    // Wrong index
+    {
+        let mut evaluations = evaluations.clone();
+        evaluations[1].1 = 2;
+        assert!(!kzg.verify_batch(num_values, &evaluations));
+    }
+    // Wrong witness
+    {
+        let mut evaluations = evaluations.clone();
+        let witness = Witness::default();
+        evaluations[1].3 = &witness;
+        assert!(!kzg.verify_batch(num_values, &evaluations));
+    }
+}
@@ -3,25 +3,50 @@ use kzg::G1;
 use rust_kzg_blst::types::g1::FsG1;
 use std::num::NonZeroUsize;
 use subspace_core_primitives::crypto::kzg::Commitment;
+use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::ArchivedHistorySegment;
-use subspace_erasure_coding::ErasureCoding;
+use subspace_erasure_coding::{ErasureCoding, ErasureCodingBackend};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let num_shards = ArchivedHistorySegment::NUM_PIECES;
     let scale = NonZeroUsize::new(num_shards.ilog2() as usize)
         .expect("Recorded history segment contains at very least one record; qed");
-    let ec = ErasureCoding::new(scale).unwrap();
 
     let source_commitments = (0..num_shards / 2)
         .map(|_| Commitment::from(FsG1::rand()))
         .collect::<Vec<_>>();
+    let source_shards = (0..num_shards / 2)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    for (name, backend) in [
+        ("blst", ErasureCodingBackend::Blst),
+        ("native", ErasureCodingBackend::Native),
+    ] {
+        let ec = ErasureCoding::with_backend(scale, backend).unwrap();
+
+        c.bench_function(&format!("extend/{name}"), |b| {
+            b.iter(|| {
+                ec.extend_commitments(black_box(&source_commitments))
+                    .unwrap()
+            })
+        });
+
+        c.bench_function(&format!("extend-data/{name}"), |b| {
+            b.iter(|| ec.extend(black_box(&source_shards)).unwrap())
+        });
+
+        // Half of the shards are missing: all source shards
+        let parity_shards = ec.extend(&source_shards).unwrap();
+        let partial_shards = parity_shards
+            .iter()
+            .flat_map(|parity| [None, Some(*parity)])
+            .collect::<Vec<_>>();
 
-    c.bench_function("extend", |b| {
-        b.iter(|| {
-            ec.extend_commitments(black_box(&source_commitments))
-                .unwrap()
-        })
-    });
+        c.bench_function(&format!("recover/{name}"), |b| {
+            b.iter(|| ec.recover(black_box(&partial_shards)).unwrap())
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
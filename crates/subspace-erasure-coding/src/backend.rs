@@ -0,0 +1,36 @@
+//! Erasure coding backends.
+
+pub(crate) mod blst;
+pub(crate) mod native;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+/// Implementation of erasure coding operations used by [`crate::ErasureCoding`].
+///
+/// All backends must produce identical outputs for identical inputs, they only differ in
+/// performance characteristics.
+pub(crate) trait Backend: fmt::Debug + Send + Sync {
+    /// Max number of shards supported (both source and parity together)
+    fn max_shards(&self) -> usize;
+
+    /// Extend sources using erasure coding, returns parity data.
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String>;
+
+    /// Recovery of missing shards from given shards (at least 1/2 should be `Some`), source and
+    /// parity shards are interleaved.
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String>;
+
+    /// Recovery of missing shards from given shards (at least 1/2 should be `Some`) in form of
+    /// normalized polynomial, source and parity shards are interleaved.
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String>;
+
+    /// Extend commitments using erasure coding, returns both source and parity commitments
+    /// interleaved.
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String>;
+}
@@ -0,0 +1,75 @@
+//! Backend based on FFT implementation from `rust-kzg-blst`.
+
+use crate::backend::Backend;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use kzg::{FFTSettings, PolyRecover, DAS, FFTG1, G1};
+use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_blst::types::poly::FsPoly;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+#[derive(Debug)]
+pub(crate) struct BlstBackend {
+    fft_settings: FsFFTSettings,
+}
+
+impl BlstBackend {
+    pub(crate) fn new(scale: usize) -> Result<Self, String> {
+        let fft_settings = FsFFTSettings::new(scale)?;
+
+        Ok(Self { fft_settings })
+    }
+}
+
+impl Backend for BlstBackend {
+    fn max_shards(&self) -> usize {
+        self.fft_settings.max_width
+    }
+
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
+        // TODO: das_fft_extension modifies buffer internally, it needs to change to use
+        //  pre-allocated buffer instead of allocating a new one
+        self.fft_settings
+            .das_fft_extension(Scalar::slice_to_repr(source))
+            .map(Scalar::vec_from_repr)
+    }
+
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
+        let poly = FsPoly::recover_poly_from_samples(
+            Scalar::slice_option_to_repr(shards),
+            &self.fft_settings,
+        )?;
+
+        Ok(Scalar::vec_from_repr(poly.coeffs))
+    }
+
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
+        let mut poly = Polynomial::from(FsPoly::recover_poly_coeffs_from_samples(
+            Scalar::slice_option_to_repr(shards),
+            &self.fft_settings,
+        )?);
+
+        poly.normalize();
+
+        Ok(poly)
+    }
+
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String> {
+        // Inverse FFT to interpolate polynomial over source commitments
+        let mut coeffs = self
+            .fft_settings
+            .fft_g1(Commitment::slice_to_repr(commitments), true)?;
+
+        // Double the size
+        coeffs.resize(coeffs.len() * 2, FsG1::identity());
+
+        // FFT to get extended commitments
+        self.fft_settings
+            .fft_g1(&coeffs, false)
+            .map(Commitment::vec_from_repr)
+    }
+}
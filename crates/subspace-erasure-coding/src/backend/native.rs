@@ -0,0 +1,392 @@
+//! Backend with FFT, recovery and commitments extension implemented in pure Rust in this crate.
+//!
+//! Only field and group arithmetic is used from `rust-kzg-blst`, which makes it possible to tune
+//! algorithms here independently of upstream library.
+
+use crate::backend::Backend;
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use kzg::{FFTSettings, Fr, G1Mul, G1};
+use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_blst::types::poly::FsPoly;
+use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
+use subspace_core_primitives::crypto::Scalar;
+
+/// Polynomials with this many coefficients or fewer are multiplied naively, FFT-based
+/// multiplication is used for larger polynomials
+const NAIVE_MULTIPLICATION_THRESHOLD: usize = 64;
+/// Shift used to evaluate polynomials over a coset of the domain during recovery, must not be a
+/// root of unity of any supported order
+const COSET_SHIFT: u64 = 5;
+
+/// Element that FFT can be computed over
+trait FftElement: Copy {
+    fn add_element(&self, other: &Self) -> Self;
+
+    fn sub_element(&self, other: &Self) -> Self;
+
+    fn mul_scalar(&self, scalar: &FsFr) -> Self;
+}
+
+impl FftElement for FsFr {
+    #[inline(always)]
+    fn add_element(&self, other: &Self) -> Self {
+        Fr::add(self, other)
+    }
+
+    #[inline(always)]
+    fn sub_element(&self, other: &Self) -> Self {
+        Fr::sub(self, other)
+    }
+
+    #[inline(always)]
+    fn mul_scalar(&self, scalar: &FsFr) -> Self {
+        Fr::mul(self, scalar)
+    }
+}
+
+impl FftElement for FsG1 {
+    #[inline(always)]
+    fn add_element(&self, other: &Self) -> Self {
+        let mut value = *self;
+        G1::add_or_dbl(&mut value, other)
+    }
+
+    #[inline(always)]
+    fn sub_element(&self, other: &Self) -> Self {
+        G1::sub(self, other)
+    }
+
+    #[inline(always)]
+    fn mul_scalar(&self, scalar: &FsFr) -> Self {
+        G1Mul::mul(self, scalar)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct NativeBackend {
+    max_width: usize,
+    /// Powers of the primitive root of unity of order `max_width`
+    roots_of_unity: Vec<FsFr>,
+}
+
+impl NativeBackend {
+    pub(crate) fn new(scale: usize) -> Result<Self, String> {
+        // Roots of unity must be exactly the same as in `rust-kzg-blst` for results to match
+        let fft_settings = FsFFTSettings::new(scale)?;
+        let max_width = fft_settings.max_width;
+        let roots_of_unity = (0..max_width)
+            .map(|index| fft_settings.get_expanded_roots_of_unity_at(index))
+            .collect();
+
+        Ok(Self {
+            max_width,
+            roots_of_unity,
+        })
+    }
+
+    /// Root of unity `ω_n^power` for domain of size `n`, negative powers are supported through
+    /// `inverse`
+    #[inline(always)]
+    fn root_of_unity(&self, n: usize, power: usize, inverse: bool) -> &FsFr {
+        let index = power * (self.max_width / n) % self.max_width;
+        if inverse {
+            &self.roots_of_unity[(self.max_width - index) % self.max_width]
+        } else {
+            &self.roots_of_unity[index]
+        }
+    }
+
+    fn check_domain_size(&self, n: usize) -> Result<(), String> {
+        if n == 0 || !n.is_power_of_two() {
+            return Err(format!(
+                "Number of values {n} must be a non-zero power of two"
+            ));
+        }
+        if n > self.max_width {
+            return Err(format!(
+                "Number of values {n} exceeds max supported {}",
+                self.max_width
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// In-place radix-2 FFT over domain of size `values.len()`, inverse FFT when `inverse` is
+    /// `true`.
+    ///
+    /// Forward FFT evaluates polynomial with coefficients `values` at `ω^i`, inverse FFT
+    /// interpolates polynomial from evaluations.
+    fn fft<T>(&self, values: &mut [T], inverse: bool) -> Result<(), String>
+    where
+        T: FftElement,
+    {
+        let n = values.len();
+        self.check_domain_size(n)?;
+
+        bit_reverse_permutation(values);
+
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let power_step = n / len;
+            for chunk in values.chunks_exact_mut(len) {
+                let (low, high) = chunk.split_at_mut(half);
+                for (j, (a, b)) in low.iter_mut().zip(high).enumerate() {
+                    let t = b.mul_scalar(self.root_of_unity(n, j * power_step, inverse));
+                    *b = a.sub_element(&t);
+                    *a = a.add_element(&t);
+                }
+            }
+            len *= 2;
+        }
+
+        if inverse {
+            let inverse_n = FsFr::from_u64(n as u64).inverse();
+            values.iter_mut().for_each(|value| {
+                *value = value.mul_scalar(&inverse_n);
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Multiply two polynomials given by their coefficients
+    fn poly_mul(&self, a: &[FsFr], b: &[FsFr]) -> Result<Vec<FsFr>, String> {
+        let result_len = a.len() + b.len() - 1;
+
+        if a.len().min(b.len()) <= NAIVE_MULTIPLICATION_THRESHOLD {
+            let mut result = vec![FsFr::zero(); result_len];
+            for (i, a) in a.iter().enumerate() {
+                for (result, b) in result[i..].iter_mut().zip(b) {
+                    *result = result.add(&a.mul(b));
+                }
+            }
+
+            return Ok(result);
+        }
+
+        let size = result_len.next_power_of_two();
+        let mut a_evals = a.to_vec();
+        a_evals.resize(size, FsFr::zero());
+        self.fft(&mut a_evals, false)?;
+        let mut b_evals = b.to_vec();
+        b_evals.resize(size, FsFr::zero());
+        self.fft(&mut b_evals, false)?;
+
+        a_evals
+            .iter_mut()
+            .zip(&b_evals)
+            .for_each(|(a, b)| *a = a.mul(b));
+        self.fft(&mut a_evals, true)?;
+        a_evals.truncate(result_len);
+
+        Ok(a_evals)
+    }
+
+    /// Coefficients of polynomial that is zero at `ω_n^i` for every `i` in `missing`
+    fn zero_poly(&self, n: usize, missing: &[usize]) -> Result<Vec<FsFr>, String> {
+        let mut polys = missing
+            .iter()
+            .map(|&index| vec![self.root_of_unity(n, index, false).negate(), FsFr::one()])
+            .collect::<Vec<_>>();
+
+        // Product tree, multiplying pairs of polynomials of similar degree
+        while polys.len() > 1 {
+            polys = polys
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => self.poly_mul(a, b),
+                    [a] => Ok(a.clone()),
+                    _ => unreachable!("Chunks are never empty or larger than 2; qed"),
+                })
+                .collect::<Result<_, _>>()?;
+        }
+
+        Ok(polys.pop().unwrap_or_else(|| vec![FsFr::one()]))
+    }
+
+    /// Recover coefficients of polynomial of degree less than `shards.len() / 2` from its
+    /// evaluations at `ω_n^i`, where `n` is `shards.len()`
+    fn recover_coeffs(&self, shards: &[Option<FsFr>]) -> Result<Vec<FsFr>, String> {
+        let n = shards.len();
+        self.check_domain_size(n)?;
+
+        let missing = shards
+            .iter()
+            .enumerate()
+            .filter_map(|(index, shard)| shard.is_none().then_some(index))
+            .collect::<Vec<_>>();
+
+        if missing.len() > n / 2 {
+            return Err(format!(
+                "Impossible to recover, too many shards are missing: {} out of {n}",
+                missing.len()
+            ));
+        }
+
+        let mut coeffs = shards
+            .iter()
+            .map(|shard| shard.unwrap_or_else(FsFr::zero))
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            self.fft(&mut coeffs, true)?;
+            return Ok(coeffs);
+        }
+
+        // Zero polynomial `Z(x)` vanishes at missing positions, so `E(x) * Z(x)`, where `E(x)` is
+        // interpolated from known evaluations with zeroes at missing positions, is equal to
+        // `P(x) * Z(x)`
+        let mut zero_poly = self.zero_poly(n, &missing)?;
+        zero_poly.resize(n, FsFr::zero());
+        let mut zero_evals = zero_poly.clone();
+        self.fft(&mut zero_evals, false)?;
+
+        coeffs
+            .iter_mut()
+            .zip(&zero_evals)
+            .for_each(|(value, zero_eval)| *value = value.mul(zero_eval));
+        self.fft(&mut coeffs, true)?;
+
+        // Evaluate both polynomials over a coset where `Z(x)` has no zeroes and divide
+        let shift = FsFr::from_u64(COSET_SHIFT);
+        scale_poly(&mut coeffs, &shift);
+        scale_poly(&mut zero_poly, &shift);
+        self.fft(&mut coeffs, false)?;
+        self.fft(&mut zero_poly, false)?;
+
+        batch_inverse(&mut zero_poly)?;
+        coeffs
+            .iter_mut()
+            .zip(&zero_poly)
+            .for_each(|(value, inverse_zero_eval)| *value = value.mul(inverse_zero_eval));
+
+        self.fft(&mut coeffs, true)?;
+        scale_poly(&mut coeffs, &shift.inverse());
+
+        Ok(coeffs)
+    }
+}
+
+impl Backend for NativeBackend {
+    fn max_shards(&self) -> usize {
+        self.max_width
+    }
+
+    fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
+        let n = source.len();
+        self.check_domain_size(n * 2)?;
+
+        // Source values are evaluations at even powers of `ω_2n`, which is the same as powers of
+        // `ω_n`
+        let mut values = Scalar::slice_to_repr(source).to_vec();
+        self.fft(&mut values, true)?;
+
+        // Parity values are evaluations at odd powers of `ω_2n`, which is FFT of coefficients
+        // scaled by powers of `ω_2n`
+        values.iter_mut().enumerate().for_each(|(power, value)| {
+            *value = value.mul(self.root_of_unity(n * 2, power, false));
+        });
+        self.fft(&mut values, false)?;
+
+        Ok(Scalar::vec_from_repr(values))
+    }
+
+    fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
+        let shards = Scalar::slice_option_to_repr(shards);
+        let mut values = self.recover_coeffs(shards)?;
+        self.fft(&mut values, false)?;
+
+        let consistent = shards
+            .iter()
+            .zip(&values)
+            .all(|(shard, value)| shard.map_or(true, |shard| shard.equals(value)));
+        if !consistent {
+            return Err("Recovered data doesn't match provided shards".into());
+        }
+
+        Ok(Scalar::vec_from_repr(values))
+    }
+
+    fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
+        let coeffs = self.recover_coeffs(Scalar::slice_option_to_repr(shards))?;
+        let mut poly = Polynomial::from(FsPoly { coeffs });
+
+        poly.normalize();
+
+        Ok(poly)
+    }
+
+    fn extend_commitments(&self, commitments: &[Commitment]) -> Result<Vec<Commitment>, String> {
+        let n = commitments.len();
+        self.check_domain_size(n * 2)?;
+
+        // Inverse FFT to interpolate polynomial over source commitments
+        let mut coeffs = Commitment::slice_to_repr(commitments).to_vec();
+        self.fft(&mut coeffs, true)?;
+
+        // Double the size
+        coeffs.resize(n * 2, FsG1::identity());
+
+        // FFT to get extended commitments
+        self.fft(&mut coeffs, false)?;
+
+        Ok(Commitment::vec_from_repr(coeffs))
+    }
+}
+
+fn bit_reverse_permutation<T>(values: &mut [T]) {
+    let n = values.len();
+    if n <= 2 {
+        return;
+    }
+
+    let shift = usize::BITS - n.ilog2();
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Replace `p(x)` with `p(shift * x)`
+fn scale_poly(coeffs: &mut [FsFr], shift: &FsFr) {
+    let mut factor = FsFr::one();
+    for coeff in coeffs {
+        *coeff = coeff.mul(&factor);
+        factor = factor.mul(shift);
+    }
+}
+
+/// Invert all values in place using Montgomery's trick (single field inversion)
+fn batch_inverse(values: &mut [FsFr]) -> Result<(), String> {
+    let mut prefix_products = Vec::with_capacity(values.len());
+    let mut accumulator = FsFr::one();
+    for value in values.iter() {
+        if value.is_zero() {
+            return Err("Can't invert zero value".into());
+        }
+        prefix_products.push(accumulator);
+        accumulator = accumulator.mul(value);
+    }
+
+    let mut inverse = accumulator.inverse();
+    for (value, prefix_product) in values.iter_mut().zip(prefix_products).rev() {
+        let value_inverse = inverse.mul(&prefix_product);
+        inverse = inverse.mul(value);
+        *value = value_inverse;
+    }
+
+    Ok(())
+}
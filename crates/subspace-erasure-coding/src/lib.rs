@@ -1,29 +1,70 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod backend;
 #[cfg(test)]
 mod tests;
 
 extern crate alloc;
 
+#[cfg(not(feature = "std"))]
+use alloc::format;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
+use backend::blst::BlstBackend;
+use backend::native::NativeBackend;
+use backend::Backend;
+use core::fmt;
 use core::num::NonZeroUsize;
-use kzg::{FFTSettings, PolyRecover, DAS, FFTG1, G1};
-use rust_kzg_blst::types::fft_settings::FsFFTSettings;
-use rust_kzg_blst::types::g1::FsG1;
-use rust_kzg_blst::types::poly::FsPoly;
+use core::str::FromStr;
 use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
 use subspace_core_primitives::crypto::Scalar;
 
+/// Backend used by [`ErasureCoding`].
+///
+/// All backends produce identical results, they only differ in performance, so the fastest one
+/// for the platform can be used.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ErasureCodingBackend {
+    /// FFT implementation from `rust-kzg-blst`
+    #[default]
+    Blst,
+    /// FFT implementation in pure Rust from this crate
+    Native,
+}
+
+impl FromStr for ErasureCodingBackend {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blst" => Ok(Self::Blst),
+            "native" => Ok(Self::Native),
+            s => Err(format!("Erasure coding backend {s} is not valid")),
+        }
+    }
+}
+
+impl fmt::Display for ErasureCodingBackend {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Blst => "blst",
+            Self::Native => "native",
+        })
+    }
+}
+
 /// Erasure coding abstraction.
 ///
 /// Supports creation of parity records and recovery of missing data.
 #[derive(Debug, Clone)]
 pub struct ErasureCoding {
-    fft_settings: Arc<FsFFTSettings>,
+    backend: Arc<dyn Backend>,
+    backend_kind: ErasureCodingBackend,
 }
 
 impl ErasureCoding {
@@ -32,25 +73,42 @@ impl ErasureCoding {
     /// Number of shards supported is `2^scale`, half of shards are source data and the other half
     /// are parity.
     pub fn new(scale: NonZeroUsize) -> Result<Self, String> {
-        let fft_settings = Arc::new(FsFFTSettings::new(scale.get())?);
+        Self::with_backend(scale, ErasureCodingBackend::default())
+    }
 
-        Ok(Self { fft_settings })
+    /// Create new erasure coding instance with specified backend.
+    ///
+    /// Same as [`ErasureCoding::new()`], but allows to choose the backend.
+    pub fn with_backend(
+        scale: NonZeroUsize,
+        backend_kind: ErasureCodingBackend,
+    ) -> Result<Self, String> {
+        let backend: Arc<dyn Backend> = match backend_kind {
+            ErasureCodingBackend::Blst => Arc::new(BlstBackend::new(scale.get())?),
+            ErasureCodingBackend::Native => Arc::new(NativeBackend::new(scale.get())?),
+        };
+
+        Ok(Self {
+            backend,
+            backend_kind,
+        })
+    }
+
+    /// Backend used by this instance
+    pub fn backend(&self) -> ErasureCodingBackend {
+        self.backend_kind
     }
 
     /// Max number of shards supported (both source and parity together)
     pub fn max_shards(&self) -> usize {
-        self.fft_settings.max_width
+        self.backend.max_shards()
     }
 
     /// Extend sources using erasure coding.
     ///
     /// Returns parity data.
     pub fn extend(&self, source: &[Scalar]) -> Result<Vec<Scalar>, String> {
-        // TODO: das_fft_extension modifies buffer internally, it needs to change to use
-        //  pre-allocated buffer instead of allocating a new one
-        self.fft_settings
-            .das_fft_extension(Scalar::slice_to_repr(source))
-            .map(Scalar::vec_from_repr)
+        self.backend.extend(source)
     }
 
     /// Recovery of missing shards from given shards (at least 1/2 should be `Some`).
@@ -58,12 +116,7 @@ impl ErasureCoding {
     /// Both in input and output source shards are interleaved with parity shards:
     /// source, parity, source, parity, ...
     pub fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
-        let poly = FsPoly::recover_poly_from_samples(
-            Scalar::slice_option_to_repr(shards),
-            &self.fft_settings,
-        )?;
-
-        Ok(Scalar::vec_from_repr(poly.coeffs))
+        self.backend.recover(shards)
     }
 
     /// Recovery of missing shards from given shards (at least 1/2 should be `Some`) in form of
@@ -72,14 +125,7 @@ impl ErasureCoding {
     /// Both in input and output source shards are interleaved with parity shards:
     /// source, parity, source, parity, ...
     pub fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
-        let mut poly = Polynomial::from(FsPoly::recover_poly_coeffs_from_samples(
-            Scalar::slice_option_to_repr(shards),
-            &self.fft_settings,
-        )?);
-
-        poly.normalize();
-
-        Ok(poly)
+        self.backend.recover_poly(shards)
     }
 
     /// Recovery of source shards from given shards (at least 1/2 should be `Some`).
@@ -100,17 +146,6 @@ impl ErasureCoding {
         &self,
         commitments: &[Commitment],
     ) -> Result<Vec<Commitment>, String> {
-        // Inverse FFT to interpolate polynomial over source commitments
-        let mut coeffs = self
-            .fft_settings
-            .fft_g1(Commitment::slice_to_repr(commitments), true)?;
-
-        // Double the size
-        coeffs.resize(coeffs.len() * 2, FsG1::identity());
-
-        // FFT to get extended commitments
-        self.fft_settings
-            .fft_g1(&coeffs, false)
-            .map(Commitment::vec_from_repr)
+        self.backend.extend_commitments(commitments)
     }
 }
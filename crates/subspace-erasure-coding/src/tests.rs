@@ -1,5 +1,6 @@
-use crate::ErasureCoding;
+use crate::{ErasureCoding, ErasureCodingBackend};
 use kzg::G1;
+use rand::prelude::*;
 use rust_kzg_blst::types::g1::FsG1;
 use std::iter;
 use std::num::NonZeroUsize;
@@ -36,6 +37,13 @@ where
     first_half.chain(second_half).cloned().collect()
 }
 
+fn random_scalars(count: usize) -> Vec<Scalar> {
+    (0..count)
+        .map(|_| rand::random::<[u8; Scalar::SAFE_BYTES]>())
+        .map(Scalar::from)
+        .collect()
+}
+
 #[test]
 fn basic_data() {
     let scale = NonZeroUsize::new(8).unwrap();
@@ -135,3 +143,136 @@ fn not_enough_partial() {
         .replace(Scalar::default());
     assert!(ec.recover(&partial_shards).is_ok());
 }
+
+#[test]
+fn native_basic_data() {
+    let scale = NonZeroUsize::new(8).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::with_backend(scale, ErasureCodingBackend::Native).unwrap();
+
+    let source_shards = random_scalars(num_shards / 2);
+
+    let parity_shards = ec.extend(&source_shards).unwrap();
+
+    assert_ne!(source_shards, parity_shards);
+
+    let partial_shards = concatenated_to_interleaved(
+        iter::repeat(None)
+            .take(num_shards / 4)
+            .chain(source_shards.iter().skip(num_shards / 4).copied().map(Some))
+            .chain(parity_shards.iter().take(num_shards / 4).copied().map(Some))
+            .chain(iter::repeat(None).take(num_shards / 4))
+            .collect::<Vec<_>>(),
+    );
+
+    let recovered = interleaved_to_concatenated(ec.recover(&partial_shards).unwrap());
+
+    assert_eq!(
+        recovered,
+        source_shards
+            .iter()
+            .chain(&parity_shards)
+            .copied()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn native_basic_commitments() {
+    let scale = NonZeroUsize::new(7).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::with_backend(scale, ErasureCodingBackend::Native).unwrap();
+
+    let source_commitments = (0..num_shards / 2)
+        .map(|_| Commitment::from(FsG1::rand()))
+        .collect::<Vec<_>>();
+
+    let parity_commitments = ec.extend_commitments(&source_commitments).unwrap();
+
+    assert_eq!(source_commitments.len() * 2, parity_commitments.len());
+
+    // Even indices must be source
+    assert_eq!(
+        source_commitments,
+        parity_commitments
+            .iter()
+            .step_by(2)
+            .copied()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn native_bad_input() {
+    let scale = NonZeroUsize::new(8).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::with_backend(scale, ErasureCodingBackend::Native).unwrap();
+
+    let source_shards = vec![Default::default(); num_shards - 1];
+    assert!(ec.extend(&source_shards).is_err());
+
+    let mut partial_shards = vec![None; num_shards];
+    assert!(ec.recover(&partial_shards[1..]).is_err());
+
+    // Less than half is not sufficient
+    partial_shards
+        .iter_mut()
+        .take(num_shards / 2 - 1)
+        .for_each(|maybe_scalar| {
+            maybe_scalar.replace(Scalar::default());
+        });
+    assert!(ec.recover(&partial_shards).is_err());
+
+    // Any half is sufficient
+    partial_shards
+        .last_mut()
+        .unwrap()
+        .replace(Scalar::default());
+    assert!(ec.recover(&partial_shards).is_ok());
+}
+
+#[test]
+fn backends_equivalence() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..10 {
+        let scale = NonZeroUsize::new(rng.gen_range(2..=9)).unwrap();
+        let num_shards = 2usize.pow(scale.get() as u32);
+        let blst = ErasureCoding::with_backend(scale, ErasureCodingBackend::Blst).unwrap();
+        let native = ErasureCoding::with_backend(scale, ErasureCodingBackend::Native).unwrap();
+
+        // Extension with smaller number of shards than max supported must work too
+        let num_source_shards = (num_shards / 2) >> rng.gen_range(0..scale.get());
+        let source_shards = random_scalars(num_source_shards);
+        let parity_shards = blst.extend(&source_shards).unwrap();
+        assert_eq!(parity_shards, native.extend(&source_shards).unwrap());
+
+        let shards = source_shards
+            .iter()
+            .zip(&parity_shards)
+            .flat_map(|(source, parity)| [*source, *parity])
+            .collect::<Vec<_>>();
+        let mut partial_shards = shards.iter().copied().map(Some).collect::<Vec<_>>();
+        let num_missing = rng.gen_range(0..=partial_shards.len() / 2);
+        for index in rand::seq::index::sample(&mut rng, partial_shards.len(), num_missing) {
+            partial_shards[index] = None;
+        }
+
+        let recovered = native.recover(&partial_shards).unwrap();
+        assert_eq!(recovered, shards);
+        assert_eq!(recovered, blst.recover(&partial_shards).unwrap());
+        // Polynomial doesn't implement `PartialEq`, compare coefficients through debug output
+        assert_eq!(
+            format!("{:?}", native.recover_poly(&partial_shards).unwrap()),
+            format!("{:?}", blst.recover_poly(&partial_shards).unwrap())
+        );
+
+        let source_commitments = (0..num_source_shards)
+            .map(|_| Commitment::from(FsG1::rand()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            native.extend_commitments(&source_commitments).unwrap(),
+            blst.extend_commitments(&source_commitments).unwrap()
+        );
+    }
+}
@@ -25,17 +25,22 @@ use sp_consensus_subspace::{
 use sp_core::crypto::KeyTypeId;
 use sp_core::{OpaqueMetadata, H256};
 use sp_domains::bundle_producer_election::BundleProducerElectionParams;
+use sp_domains::staking::{NominatorPosition, OperatorSummary};
 use sp_domains::{
     DomainAllowlistUpdates, DomainId, DomainInstanceData, ExecutionReceiptFor, OpaqueBundle,
     OperatorId, OperatorPublicKey,
 };
 use sp_domains_fraud_proof::fraud_proof::FraudProof;
+use sp_domains_fraud_proof::FraudProofValidationError;
 use sp_messenger::messages::{
-    BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, MessageId, MessageKey,
+    BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, InboxMessageStatus, MessageId,
+    MessageKey, OutboxMessageStatus,
 };
+use sp_rewards::VestedRewards;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 use sp_runtime::transaction_validity::{TransactionSource, TransactionValidity};
 use sp_runtime::{ApplyExtrinsicResult, ExtrinsicInclusionMode};
+use sp_transaction_fees::FeeEstimate;
 use sp_version::RuntimeVersion;
 use std::collections::btree_map::BTreeMap;
 use subspace_core_primitives::objects::BlockObjectMapping;
@@ -121,6 +126,18 @@ sp_api::impl_runtime_apis! {
         }
     }
 
+    impl sp_transaction_fees::TransactionFeesApi<Block, Balance> for Runtime {
+        fn estimate_fees(_uxt: <Block as BlockT>::Extrinsic, _segments: u32) -> FeeEstimate<Balance> {
+            unreachable!()
+        }
+    }
+
+    impl sp_rewards::RewardsApi<Block, AccountId, Balance> for Runtime {
+        fn vested_rewards(_account: AccountId) -> VestedRewards<Balance> {
+            unreachable!()
+        }
+    }
+
     impl sp_objects::ObjectsApi<Block> for Runtime {
         fn extract_block_object_mapping(_block: Block, _successful_calls: Vec<Hash>) -> BlockObjectMapping {
             unreachable!()
@@ -311,6 +328,16 @@ sp_api::impl_runtime_apis! {
         }
     }
 
+    impl sp_domains::DomainStakingApi<Block, AccountId, Balance, DomainNumber> for Runtime {
+        fn nominator_positions(_nominator_id: AccountId) -> Vec<NominatorPosition<Balance, DomainNumber>> {
+            unreachable!()
+        }
+
+        fn operator_summaries(_domain_id: DomainId) -> Vec<OperatorSummary<Balance>> {
+            unreachable!()
+        }
+    }
+
     impl sp_session::SessionKeys<Block> for Runtime {
         fn generate_session_keys(_seed: Option<Vec<u8>>) -> Vec<u8> {
             unreachable!()
@@ -394,6 +421,14 @@ sp_api::impl_runtime_apis! {
         fn should_relay_inbox_message_response(_dst_chain_id: ChainId, _msg_id: MessageId) -> bool {
             unreachable!()
         }
+
+        fn outbox_message_status(_dst_chain_id: ChainId, _msg_id: MessageId) -> OutboxMessageStatus {
+            unreachable!()
+        }
+
+        fn inbox_message_status(_src_chain_id: ChainId, _msg_id: MessageId) -> InboxMessageStatus {
+            unreachable!()
+        }
     }
 
     impl sp_domains_fraud_proof::FraudProofApi<Block, DomainHeader> for Runtime {
@@ -407,6 +442,12 @@ sp_api::impl_runtime_apis! {
         ) -> Vec<FraudProof<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>> {
             unreachable!()
         }
+
+        fn validate_fraud_proof(
+            _fraud_proof: FraudProof<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>,
+        ) -> Result<(), FraudProofValidationError> {
+            unreachable!()
+        }
     }
 
     impl mmr::MmrApi<Block, mmr::Hash, BlockNumber> for Runtime {
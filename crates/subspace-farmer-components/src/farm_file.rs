@@ -0,0 +1,278 @@
+//! Versioned on-disk format of farm files.
+//!
+//! Every farm file contains [`FarmFileHeader`] that describes the kind of the file, version of its
+//! format and the farm it belongs to, which allows to detect format changes and files that belong
+//! to a different farm or chain.
+//!
+//! Header is stored in the last [`FarmFileHeader::RESERVED_SIZE`] bytes of the file rather than at
+//! the beginning, such that offsets of the data in files created before header was introduced stay
+//! the same and such files can be upgraded in place (they are treated as
+//! [`FarmFileHeader::LEGACY_VERSION`]).
+//!
+//! Header is always written before the file is resized (see [`FarmFileHeader::write_to()`]), such
+//! that file that has a header keeps ending with a valid header even if the process is interrupted
+//! in the middle of resizing.
+
+#[cfg(test)]
+mod tests;
+
+use crate::file_ext::FileExt;
+use parity_scale_codec::{Decode, Encode};
+use std::{fmt, io};
+use subspace_core_primitives::checksum::Blake3Checksummed;
+use thiserror::Error;
+
+/// Kind of the farm file
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub enum FarmFileKind {
+    /// Plot file with plotted sectors
+    #[codec(index = 0)]
+    Plot,
+    /// Metadata of plotted sectors
+    #[codec(index = 1)]
+    Metadata,
+    /// Piece cache
+    #[codec(index = 2)]
+    PieceCache,
+}
+
+impl fmt::Display for FarmFileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plot => write!(f, "plot"),
+            Self::Metadata => write!(f, "metadata"),
+            Self::PieceCache => write!(f, "piece cache"),
+        }
+    }
+}
+
+/// Errors related to farm files format
+#[derive(Debug, Error)]
+pub enum FarmFileError {
+    /// I/O error occurred
+    #[error("Farm file I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Failed to decode header, it is likely corrupted
+    #[error("Failed to decode {kind} file header, it is likely corrupted: {error}")]
+    FailedToDecodeHeader {
+        /// Kind of the file
+        kind: FarmFileKind,
+        /// Low-level error
+        error: parity_scale_codec::Error,
+    },
+    /// File is not empty, but has no header even though legacy files are not expected, it is
+    /// likely corrupted
+    #[error("{kind} file has no header, it is likely corrupted")]
+    MissingHeader {
+        /// Kind of the file
+        kind: FarmFileKind,
+    },
+    /// File is of a different kind than expected
+    #[error("Expected {expected} file, but found {found} file")]
+    UnexpectedKind {
+        /// Expected kind of the file
+        expected: FarmFileKind,
+        /// Kind of the file found
+        found: FarmFileKind,
+    },
+    /// File was created by newer version of the farmer and is not supported by this version
+    #[error(
+        "Format version {version} of {kind} file is not supported, max supported version is \
+        {max_supported}, file was likely created by a newer version of the farmer"
+    )]
+    UnsupportedVersion {
+        /// Kind of the file
+        kind: FarmFileKind,
+        /// Version of the file format
+        version: u8,
+        /// Max supported version of the file format
+        max_supported: u8,
+    },
+    /// File was created for a different chain
+    #[error(
+        "Genesis hash {found} of {kind} file is different from expected {expected}, file \
+        belongs to a different chain"
+    )]
+    WrongGenesisHash {
+        /// Kind of the file
+        kind: FarmFileKind,
+        /// Hex-encoded expected genesis hash
+        expected: String,
+        /// Hex-encoded genesis hash found in the file
+        found: String,
+    },
+    /// File belongs to a different farm
+    #[error("Farm ID in {kind} file is different from expected, file belongs to a different farm")]
+    WrongFarmId {
+        /// Kind of the file
+        kind: FarmFileKind,
+    },
+    /// Failed to migrate file to the next version of the format
+    #[error("Failed to migrate {kind} file from version {from_version}: {error}")]
+    MigrationFailed {
+        /// Kind of the file
+        kind: FarmFileKind,
+        /// Version the file was migrated from
+        from_version: u8,
+        /// Low-level error
+        error: io::Error,
+    },
+}
+
+/// Header of the farm file
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct FarmFileHeader {
+    /// Kind of the file
+    pub kind: FarmFileKind,
+    /// Version of the file format
+    pub version: u8,
+    /// Genesis hash of the chain farm was created for
+    pub genesis_hash: [u8; 32],
+    /// SCALE-encoded ID of the farm file belongs to
+    pub farm_id: Vec<u8>,
+}
+
+impl FarmFileHeader {
+    /// Magic bytes at the beginning of the header
+    pub const MAGIC: [u8; 8] = *b"SSPCFARM";
+    /// Number of bytes reserved for the header at the end of the file, multiple of the disk sector
+    /// size, such that data in the file remains aligned
+    pub const RESERVED_SIZE: u64 = 4096;
+    /// Version of files created before header was introduced
+    pub const LEGACY_VERSION: u8 = 0;
+
+    /// Read header from the end of the file.
+    ///
+    /// Returns `None` for empty files and files created before header was introduced.
+    pub fn read_from<F>(file: &F, kind: FarmFileKind) -> Result<Option<Self>, FarmFileError>
+    where
+        F: FileExt,
+    {
+        let file_size = file.size()?;
+        if file_size < Self::RESERVED_SIZE {
+            return Ok(None);
+        }
+
+        let mut header_bytes = vec![0; Self::RESERVED_SIZE as usize];
+        file.read_exact_at(&mut header_bytes, file_size - Self::RESERVED_SIZE)?;
+
+        let Some(header_bytes) = header_bytes.strip_prefix(&Self::MAGIC) else {
+            return Ok(None);
+        };
+
+        Blake3Checksummed::<Self>::decode(&mut &*header_bytes)
+            .map(|Blake3Checksummed(header)| Some(header))
+            .map_err(|error| FarmFileError::FailedToDecodeHeader { kind, error })
+    }
+
+    /// Write header right after `data_size` bytes of data.
+    ///
+    /// When file needs to be resized to `data_size` plus [`Self::RESERVED_SIZE`] bytes, header must
+    /// be written before resizing: when file grows, header is written beyond the current end of the
+    /// file and when file shrinks, previous header stays at the end of the file until it is
+    /// truncated. This way file ends with a valid header at all times. Writing beyond the end of
+    /// the file may create a sparse file, so the file should be preallocated afterwards.
+    pub fn write_to<F>(&self, file: &F, data_size: u64) -> io::Result<()>
+    where
+        F: FileExt,
+    {
+        let mut header_bytes = Vec::with_capacity(Self::RESERVED_SIZE as usize);
+        header_bytes.extend_from_slice(&Self::MAGIC);
+        Blake3Checksummed(self).encode_to(&mut header_bytes);
+        debug_assert!(header_bytes.len() as u64 <= Self::RESERVED_SIZE);
+        header_bytes.resize(Self::RESERVED_SIZE as usize, 0);
+
+        file.write_all_at(&header_bytes, data_size)
+    }
+
+    /// Check header of the existing file against this (expected) header and migrate file to the
+    /// version of this header if necessary.
+    ///
+    /// Empty files are considered to be new. Non-empty files without header are considered to be of
+    /// [`Self::LEGACY_VERSION`] if `allow_legacy` is `true` and result in
+    /// [`FarmFileError::MissingHeader`] otherwise, `allow_legacy` should only be `true` if files
+    /// might have been created before header was introduced.
+    ///
+    /// `migrate` is called with every version from the version of the file up to (but not
+    /// including) the version of this header in ascending order and must migrate file to the next
+    /// version of the format without changing the size of the file. Header with the next version is
+    /// written after every successful migration, such that interrupted migration resumes from the
+    /// last completed version.
+    pub fn check_and_migrate<F, Migrate>(
+        &self,
+        file: &F,
+        allow_legacy: bool,
+        mut migrate: Migrate,
+    ) -> Result<(), FarmFileError>
+    where
+        F: FileExt,
+        Migrate: FnMut(&F, u8) -> io::Result<()>,
+    {
+        let (version, mut has_header) = match Self::read_from(file, self.kind)? {
+            Some(header) => {
+                if header.kind != self.kind {
+                    return Err(FarmFileError::UnexpectedKind {
+                        expected: self.kind,
+                        found: header.kind,
+                    });
+                }
+                if header.genesis_hash != self.genesis_hash {
+                    return Err(FarmFileError::WrongGenesisHash {
+                        kind: self.kind,
+                        expected: hex::encode(self.genesis_hash),
+                        found: hex::encode(header.genesis_hash),
+                    });
+                }
+                if header.farm_id != self.farm_id {
+                    return Err(FarmFileError::WrongFarmId { kind: self.kind });
+                }
+
+                (header.version, true)
+            }
+            None => {
+                if file.size()? == 0 {
+                    // New file, nothing to migrate
+                    return Ok(());
+                }
+
+                if !allow_legacy {
+                    return Err(FarmFileError::MissingHeader { kind: self.kind });
+                }
+
+                (Self::LEGACY_VERSION, false)
+            }
+        };
+
+        if version > self.version {
+            return Err(FarmFileError::UnsupportedVersion {
+                kind: self.kind,
+                version,
+                max_supported: self.version,
+            });
+        }
+
+        for from_version in version..self.version {
+            migrate(file, from_version).map_err(|error| FarmFileError::MigrationFailed {
+                kind: self.kind,
+                from_version,
+                error,
+            })?;
+
+            // Legacy files get header appended, others have it replaced at the end of the file
+            let file_size = file.size()?;
+            let data_size = if has_header {
+                file_size - Self::RESERVED_SIZE
+            } else {
+                file_size
+            };
+            let header = Self {
+                version: from_version + 1,
+                ..self.clone()
+            };
+            header.write_to(file, data_size)?;
+            has_header = true;
+        }
+
+        Ok(())
+    }
+}
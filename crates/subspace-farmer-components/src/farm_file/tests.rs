@@ -0,0 +1,254 @@
+use crate::farm_file::{FarmFileError, FarmFileHeader, FarmFileKind};
+use crate::file_ext::FileExt;
+use parking_lot::Mutex;
+use std::assert_matches::assert_matches;
+use std::io;
+
+/// In-memory file
+#[derive(Default)]
+struct TestFile(Mutex<Vec<u8>>);
+
+impl TestFile {
+    fn with_contents(contents: Vec<u8>) -> Self {
+        Self(Mutex::new(contents))
+    }
+
+    fn contents(&self) -> Vec<u8> {
+        self.0.lock().clone()
+    }
+
+    fn set_len(&self, len: u64) {
+        self.0.lock().resize(len as usize, 0);
+    }
+}
+
+impl FileExt for TestFile {
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.0.lock().len() as u64)
+    }
+
+    fn preallocate(&self, len: u64) -> io::Result<()> {
+        let mut contents = self.0.lock();
+        if (contents.len() as u64) < len {
+            contents.resize(len as usize, 0);
+        }
+        Ok(())
+    }
+
+    fn advise_random_access(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn advise_sequential_access(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        let contents = self.0.lock();
+        let offset = offset as usize;
+        let bytes = contents
+            .get(offset..offset + buf.len())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn write_all_at(&self, buf: &[u8], offset: u64) -> io::Result<()> {
+        let mut contents = self.0.lock();
+        let offset = offset as usize;
+        if contents.len() < offset + buf.len() {
+            contents.resize(offset + buf.len(), 0);
+        }
+        contents[offset..][..buf.len()].copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+fn header(kind: FarmFileKind, version: u8) -> FarmFileHeader {
+    FarmFileHeader {
+        kind,
+        version,
+        genesis_hash: [1; 32],
+        farm_id: vec![2; 16],
+    }
+}
+
+fn no_migrations(_file: &TestFile, from_version: u8) -> io::Result<()> {
+    panic!("Unexpected migration from version {from_version}");
+}
+
+#[test]
+fn new_file() {
+    let file = TestFile::default();
+    let header = header(FarmFileKind::Plot, 1);
+
+    // Empty file is new regardless of whether legacy files are allowed
+    header
+        .check_and_migrate(&file, false, no_migrations)
+        .unwrap();
+    assert_eq!(FarmFileHeader::read_from(&file, header.kind).unwrap(), None);
+
+    let data_size = 8192;
+    header.write_to(&file, data_size).unwrap();
+    assert_eq!(
+        file.size().unwrap(),
+        data_size + FarmFileHeader::RESERVED_SIZE
+    );
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind).unwrap(),
+        Some(header.clone())
+    );
+
+    header
+        .check_and_migrate(&file, false, no_migrations)
+        .unwrap();
+}
+
+#[test]
+fn legacy_upgrade() {
+    let data = vec![3; 8192];
+    let file = TestFile::with_contents(data.clone());
+    let header = header(FarmFileKind::Metadata, 2);
+
+    // Missing header is an error unless legacy files are expected
+    assert_matches!(
+        header.check_and_migrate(&file, false, no_migrations),
+        Err(FarmFileError::MissingHeader {
+            kind: FarmFileKind::Metadata
+        })
+    );
+
+    let mut migrated_versions = Vec::new();
+    header
+        .check_and_migrate(&file, true, |file, from_version| {
+            // Header with the version migrated from must be written before the next migration
+            let found_version = FarmFileHeader::read_from(file, FarmFileKind::Metadata)
+                .unwrap()
+                .map(|header| header.version);
+            if from_version == FarmFileHeader::LEGACY_VERSION {
+                assert_eq!(found_version, None);
+            } else {
+                assert_eq!(found_version, Some(from_version));
+            }
+
+            migrated_versions.push(from_version);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(migrated_versions, vec![0, 1]);
+
+    // Header was appended without touching the data
+    let contents = file.contents();
+    assert_eq!(
+        contents.len() as u64,
+        data.len() as u64 + FarmFileHeader::RESERVED_SIZE
+    );
+    assert_eq!(&contents[..data.len()], data.as_slice());
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind).unwrap(),
+        Some(header.clone())
+    );
+
+    // Already upgraded
+    header
+        .check_and_migrate(&file, false, no_migrations)
+        .unwrap();
+}
+
+#[test]
+fn interrupted_migration_resumes() {
+    let file = TestFile::default();
+    header(FarmFileKind::PieceCache, 0)
+        .write_to(&file, 4096)
+        .unwrap();
+    let header = header(FarmFileKind::PieceCache, 3);
+
+    assert_matches!(
+        header.check_and_migrate(&file, false, |_file, from_version| {
+            if from_version == 1 {
+                Err(io::Error::from(io::ErrorKind::Other))
+            } else {
+                Ok(())
+            }
+        }),
+        Err(FarmFileError::MigrationFailed {
+            kind: FarmFileKind::PieceCache,
+            from_version: 1,
+            ..
+        })
+    );
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind)
+            .unwrap()
+            .map(|header| header.version),
+        Some(1)
+    );
+
+    let mut migrated_versions = Vec::new();
+    header
+        .check_and_migrate(&file, false, |_file, from_version| {
+            migrated_versions.push(from_version);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(migrated_versions, vec![1, 2]);
+}
+
+#[test]
+fn unsupported_version() {
+    let file = TestFile::default();
+    header(FarmFileKind::Plot, 3).write_to(&file, 4096).unwrap();
+
+    assert_matches!(
+        header(FarmFileKind::Plot, 2).check_and_migrate(&file, true, no_migrations),
+        Err(FarmFileError::UnsupportedVersion {
+            kind: FarmFileKind::Plot,
+            version: 3,
+            max_supported: 2,
+        })
+    );
+}
+
+#[test]
+fn unexpected_kind() {
+    let file = TestFile::default();
+    header(FarmFileKind::Plot, 0).write_to(&file, 4096).unwrap();
+
+    assert_matches!(
+        header(FarmFileKind::Metadata, 0).check_and_migrate(&file, true, no_migrations),
+        Err(FarmFileError::UnexpectedKind {
+            expected: FarmFileKind::Metadata,
+            found: FarmFileKind::Plot,
+        })
+    );
+}
+
+#[test]
+fn resize() {
+    let file = TestFile::default();
+    let header = header(FarmFileKind::Plot, 0);
+    header.write_to(&file, 8192).unwrap();
+
+    // Shrinking: previous header stays at the end of the file until it is truncated
+    header.write_to(&file, 4096).unwrap();
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind).unwrap(),
+        Some(header.clone())
+    );
+    file.set_len(4096 + FarmFileHeader::RESERVED_SIZE);
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind).unwrap(),
+        Some(header.clone())
+    );
+
+    // Growing: header is written beyond the end of the file
+    header.write_to(&file, 16384).unwrap();
+    assert_eq!(file.size().unwrap(), 16384 + FarmFileHeader::RESERVED_SIZE);
+    assert_eq!(
+        FarmFileHeader::read_from(&file, header.kind).unwrap(),
+        Some(header.clone())
+    );
+    header
+        .check_and_migrate(&file, false, no_migrations)
+        .unwrap();
+}
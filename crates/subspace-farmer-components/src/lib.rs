@@ -1,5 +1,6 @@
 #![feature(
     array_chunks,
+    assert_matches,
     const_option,
     const_trait_impl,
     int_roundings,
@@ -16,6 +17,7 @@
 //! These components are used to implement farmer itself, but can also be used independently if necessary.
 
 pub mod auditing;
+pub mod farm_file;
 pub mod file_ext;
 pub mod plotting;
 pub mod proving;
@@ -25,6 +27,8 @@ mod segment_reconstruction;
 
 use crate::file_ext::FileExt;
 use async_trait::async_trait;
+use futures::stream::FuturesOrdered;
+use futures::StreamExt;
 use parity_scale_codec::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
@@ -42,6 +46,28 @@ pub trait PieceGetter {
         &self,
         piece_index: PieceIndex,
     ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>>;
+
+    /// Get multiple pieces at once, returns pieces in the same order as provided with `None` in
+    /// place of pieces that were not found.
+    ///
+    /// Default implementation gets pieces concurrently one by one, implementations should override
+    /// it if pieces can be retrieved or validated more efficiently in batches.
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>>
+    where
+        Self: Sync,
+    {
+        piece_indices
+            .into_iter()
+            .map(|piece_index| self.get_piece(piece_index))
+            .collect::<FuturesOrdered<_>>()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -55,6 +81,13 @@ where
     ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
         self.as_ref().get_piece(piece_index).await
     }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        self.as_ref().get_pieces(piece_indices).await
+    }
 }
 
 #[async_trait]
@@ -21,7 +21,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::{PublicKey, Record};
-use subspace_erasure_coding::ErasureCoding;
+use subspace_erasure_coding::{ErasureCoding, ErasureCodingBackend};
 use subspace_farmer::farm::{
     Farm, FarmingNotification, SectorExpirationDetails, SectorPlottingDetails, SectorUpdate,
 };
@@ -187,6 +187,12 @@ pub(crate) struct FarmingArgs {
     /// farming is successful and computer can be used comfortably for other things
     #[arg(long, default_value_t = PlottingThreadPriority::Min)]
     plotting_thread_priority: PlottingThreadPriority,
+    /// Erasure coding backend used for plotting, "blst" or "native".
+    ///
+    /// All backends produce identical plots, they only differ in performance, which depends on
+    /// the platform.
+    #[arg(long, default_value_t = ErasureCodingBackend::default())]
+    erasure_coding_backend: ErasureCodingBackend,
     /// Enable plot cache.
     ///
     /// Plot cache uses unplotted space as additional cache improving plotting speeds, especially
@@ -249,6 +255,7 @@ where
         replotting_thread_pool_size,
         replotting_cpu_cores,
         plotting_thread_priority,
+        erasure_coding_backend,
         plot_cache,
         disable_farm_locking,
         create,
@@ -368,9 +375,10 @@ where
     };
 
     let kzg = Kzg::new(embedded_kzg_settings());
-    let erasure_coding = ErasureCoding::new(
+    let erasure_coding = ErasureCoding::with_backend(
         NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
             .expect("Not zero; qed"),
+        erasure_coding_backend,
     )
     .map_err(|error| anyhow!("Failed to instantiate erasure coding: {error}"))?;
     let validator = Some(SegmentCommitmentPieceValidator::new(
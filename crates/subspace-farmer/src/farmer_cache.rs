@@ -27,6 +27,9 @@ use tracing::{debug, error, info, trace, warn};
 
 const WORKER_CHANNEL_CAPACITY: usize = 100;
 const CONCURRENT_PIECES_TO_DOWNLOAD: usize = 1_000;
+/// Pieces are downloaded in batches, such that pieces received from the same peer can be
+/// validated together
+const PIECES_TO_DOWNLOAD_BATCH_SIZE: usize = 100;
 /// Make caches available as they are building without waiting for the initialization to finish,
 /// this number defines an interval in pieces after which cache is updated
 const INTERMEDIATE_CACHE_UPDATE_INTERVAL: usize = 100;
@@ -90,7 +93,7 @@ where
     /// NOTE: Piece getter must not depend on farmer cache in order to avoid reference cycles!
     pub async fn run<PG>(mut self, piece_getter: PG)
     where
-        PG: PieceGetter,
+        PG: PieceGetter + Sync,
     {
         // Limit is dynamically set later
         let mut worker_state = CacheWorkerState {
@@ -157,7 +160,7 @@ where
         piece_getter: &PG,
         worker_state: &mut CacheWorkerState,
     ) where
-        PG: PieceGetter,
+        PG: PieceGetter + Sync,
     {
         match command {
             WorkerCommand::ReplaceBackingCaches { new_piece_caches } => {
@@ -210,7 +213,7 @@ where
         worker_state: &mut CacheWorkerState,
         new_piece_caches: Vec<Arc<dyn PieceCache>>,
     ) where
-        PG: PieceGetter,
+        PG: PieceGetter + Sync,
     {
         info!("Initializing piece cache");
         // Pull old cache state since it will be replaced with a new one and reuse its allocations
@@ -420,90 +423,109 @@ where
         piece_indices_to_store.par_sort_unstable();
         let mut piece_indices_to_store = piece_indices_to_store.into_iter();
 
-        let download_piece = |piece_index| async move {
-            trace!(%piece_index, "Downloading piece");
+        let download_pieces = |piece_indices: Vec<PieceIndex>| async move {
+            trace!(count = %piece_indices.len(), "Downloading pieces");
 
-            let result = piece_getter.get_piece(piece_index).await;
+            let result = piece_getter.get_pieces(piece_indices.clone()).await;
 
             match result {
-                Ok(Some(piece)) => {
-                    trace!(%piece_index, "Downloaded piece successfully");
+                Ok(maybe_pieces) => piece_indices
+                    .into_iter()
+                    .zip(maybe_pieces)
+                    .filter_map(|(piece_index, maybe_piece)| {
+                        if maybe_piece.is_some() {
+                            trace!(%piece_index, "Downloaded piece successfully");
+                        } else {
+                            debug!(%piece_index, "Couldn't find piece");
+                        }
 
-                    Some((piece_index, piece))
-                }
-                Ok(None) => {
-                    debug!(%piece_index, "Couldn't find piece");
-                    None
-                }
+                        Some((piece_index, maybe_piece?))
+                    })
+                    .collect::<Vec<_>>(),
                 Err(error) => {
-                    debug!(%error, %piece_index, "Failed to get piece for piece cache");
-                    None
+                    debug!(
+                        %error,
+                        ?piece_indices,
+                        "Failed to get pieces for piece cache"
+                    );
+                    Vec::new()
                 }
             }
         };
 
         let pieces_to_download_total = piece_indices_to_store.len();
-        let mut downloading_pieces = piece_indices_to_store
-            .by_ref()
-            .take(CONCURRENT_PIECES_TO_DOWNLOAD)
-            .map(download_piece)
+        let concurrent_batches_to_download =
+            CONCURRENT_PIECES_TO_DOWNLOAD / PIECES_TO_DOWNLOAD_BATCH_SIZE;
+        let mut downloading_pieces = (0..concurrent_batches_to_download)
+            .map(|_| {
+                piece_indices_to_store
+                    .by_ref()
+                    .take(PIECES_TO_DOWNLOAD_BATCH_SIZE)
+                    .collect::<Vec<_>>()
+            })
+            .take_while(|piece_indices| !piece_indices.is_empty())
+            .map(download_pieces)
             .collect::<FuturesUnordered<_>>();
 
         let mut downloaded_pieces_count = 0;
         self.handlers.progress.call_simple(&0.0);
-        while let Some(maybe_piece) = downloading_pieces.next().await {
-            // Push another piece to download
-            if let Some(piece_index_to_download) = piece_indices_to_store.next() {
-                downloading_pieces.push(download_piece(piece_index_to_download));
+        while let Some(downloaded_pieces) = downloading_pieces.next().await {
+            // Push another batch of pieces to download
+            let piece_indices_to_download = piece_indices_to_store
+                .by_ref()
+                .take(PIECES_TO_DOWNLOAD_BATCH_SIZE)
+                .collect::<Vec<_>>();
+            if !piece_indices_to_download.is_empty() {
+                downloading_pieces.push(download_pieces(piece_indices_to_download));
             }
 
-            let Some((piece_index, piece)) = &maybe_piece else {
-                continue;
-            };
-
-            // Find plot in which there is a place for new piece to be stored
-            let mut sorted_caches = caches.iter_mut().enumerate().collect::<Vec<_>>();
-            // Sort piece caches by number of stored pieces to fill those that are less
-            // populated first
-            sorted_caches.sort_by_key(|(_, cache)| cache.stored_pieces.len());
-            if !stream::iter(sorted_caches)
-                .any(|(farm_index, cache)| async move {
-                    let Some(offset) = cache.free_offsets.pop_front() else {
-                        return false;
-                    };
+            for (piece_index, piece) in &downloaded_pieces {
+                // Find plot in which there is a place for new piece to be stored
+                let mut sorted_caches = caches.iter_mut().enumerate().collect::<Vec<_>>();
+                // Sort piece caches by number of stored pieces to fill those that are less
+                // populated first
+                sorted_caches.sort_by_key(|(_, cache)| cache.stored_pieces.len());
+                if !stream::iter(sorted_caches)
+                    .any(|(farm_index, cache)| async move {
+                        let Some(offset) = cache.free_offsets.pop_front() else {
+                            return false;
+                        };
 
-                    if let Err(error) = cache.backend.write_piece(offset, *piece_index, piece).await
-                    {
-                        error!(
-                            %error,
-                            %farm_index,
-                            %piece_index,
-                            %offset,
-                            "Failed to write piece into cache"
-                        );
-                        return false;
-                    }
-                    cache
-                        .stored_pieces
-                        .insert(RecordKey::from(piece_index.to_multihash()), offset);
-                    true
-                })
-                .await
-            {
-                error!(
-                    %piece_index,
-                    "Failed to store piece in cache, there was no space"
-                );
-            }
+                        if let Err(error) =
+                            cache.backend.write_piece(offset, *piece_index, piece).await
+                        {
+                            error!(
+                                %error,
+                                %farm_index,
+                                %piece_index,
+                                %offset,
+                                "Failed to write piece into cache"
+                            );
+                            return false;
+                        }
+                        cache
+                            .stored_pieces
+                            .insert(RecordKey::from(piece_index.to_multihash()), offset);
+                        true
+                    })
+                    .await
+                {
+                    error!(
+                        %piece_index,
+                        "Failed to store piece in cache, there was no space"
+                    );
+                }
 
-            downloaded_pieces_count += 1;
-            let progress = downloaded_pieces_count as f32 / pieces_to_download_total as f32 * 100.0;
-            if downloaded_pieces_count % INTERMEDIATE_CACHE_UPDATE_INTERVAL == 0 {
-                self.piece_caches.write().await.clone_from(&caches);
+                downloaded_pieces_count += 1;
+                let progress =
+                    downloaded_pieces_count as f32 / pieces_to_download_total as f32 * 100.0;
+                if downloaded_pieces_count % INTERMEDIATE_CACHE_UPDATE_INTERVAL == 0 {
+                    self.piece_caches.write().await.clone_from(&caches);
 
-                info!("Piece cache sync {progress:.2}% complete");
+                    info!("Piece cache sync {progress:.2}% complete");
+                }
+                self.handlers.progress.call_simple(&progress);
             }
-            self.handlers.progress.call_simple(&progress);
         }
 
         *self.piece_caches.write().await = caches;
@@ -643,7 +665,7 @@ where
         piece_getter: &PG,
         worker_state: &mut CacheWorkerState,
     ) where
-        PG: PieceGetter,
+        PG: PieceGetter + Sync,
     {
         let last_segment_index = match self.node_client.farmer_app_info().await {
             Ok(farmer_app_info) => farmer_app_info.protocol_info.history_size.segment_index(),
@@ -666,41 +688,63 @@ where
             takes too long"
         );
 
-        // Keep up with segment indices that were potentially created since reinitialization
-        let piece_indices = (worker_state.last_segment_index..=last_segment_index)
-            .flat_map(|segment_index| segment_index.segment_piece_indexes());
+        // Keep up with segment indices that were potentially created since reinitialization, pieces
+        // of each segment are downloaded as a batch
+        for segment_index in worker_state.last_segment_index..=last_segment_index {
+            let piece_indices = segment_index
+                .segment_piece_indexes()
+                .into_iter()
+                .filter(|&piece_index| {
+                    if !worker_state
+                        .heap
+                        .should_include_key(KeyWrapper(piece_index))
+                    {
+                        trace!(%piece_index, "Piece doesn't need to be cached #3");
 
-        // TODO: Can probably do concurrency here
-        for piece_index in piece_indices {
-            let key = KeyWrapper(piece_index);
-            if !worker_state.heap.should_include_key(key) {
-                trace!(%piece_index, "Piece doesn't need to be cached #3");
+                        return false;
+                    }
 
-                continue;
-            }
+                    trace!(%piece_index, "Piece needs to be cached #2");
 
-            trace!(%piece_index, "Piece needs to be cached #2");
+                    true
+                })
+                .collect::<Vec<_>>();
 
-            let result = piece_getter.get_piece(piece_index).await;
+            if piece_indices.is_empty() {
+                continue;
+            }
 
-            let piece = match result {
-                Ok(Some(piece)) => piece,
-                Ok(None) => {
-                    debug!(%piece_index, "Couldn't find piece");
-                    continue;
-                }
+            let maybe_pieces = match piece_getter.get_pieces(piece_indices.clone()).await {
+                Ok(maybe_pieces) => maybe_pieces,
                 Err(error) => {
                     debug!(
                         %error,
-                        %piece_index,
-                        "Failed to get piece for piece cache"
+                        %segment_index,
+                        "Failed to get pieces for piece cache"
                     );
                     continue;
                 }
             };
 
-            self.persist_piece_in_cache(piece_index, piece, worker_state)
-                .await;
+            for (piece_index, maybe_piece) in piece_indices.into_iter().zip(maybe_pieces) {
+                let Some(piece) = maybe_piece else {
+                    debug!(%piece_index, "Couldn't find piece");
+                    continue;
+                };
+
+                // Other pieces of the batch might have been stored since the check above
+                if !worker_state
+                    .heap
+                    .should_include_key(KeyWrapper(piece_index))
+                {
+                    trace!(%piece_index, "Piece doesn't need to be cached #4");
+
+                    continue;
+                }
+
+                self.persist_piece_in_cache(piece_index, piece, worker_state)
+                    .await;
+            }
         }
 
         info!("Finished syncing piece cache to the latest history size");
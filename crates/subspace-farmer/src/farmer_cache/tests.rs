@@ -1,3 +1,4 @@
+use crate::farm::FarmId;
 use crate::farmer_cache::FarmerCache;
 use crate::node_client::{Error, NodeClient};
 use crate::piece_cache::PieceCache;
@@ -182,6 +183,7 @@ async fn basic() {
         identity::PublicKey::from(identity::ed25519::PublicKey::try_from_bytes(&[42; 32]).unwrap());
     let path1 = tempdir().unwrap();
     let path2 = tempdir().unwrap();
+    let farm_id = FarmId::new();
 
     {
         let (farmer_cache, farmer_cache_worker) =
@@ -207,8 +209,12 @@ async fn basic() {
         farmer_cache
             .replace_backing_caches(
                 vec![
-                    Arc::new(PieceCache::open(path1.as_ref(), 1).unwrap()),
-                    Arc::new(PieceCache::open(path2.as_ref(), 1).unwrap()),
+                    Arc::new(
+                        PieceCache::open(path1.as_ref(), 1, [0; 32], &farm_id, false).unwrap(),
+                    ),
+                    Arc::new(
+                        PieceCache::open(path2.as_ref(), 1, [0; 32], &farm_id, false).unwrap(),
+                    ),
                 ],
                 vec![],
             )
@@ -407,8 +413,12 @@ async fn basic() {
         farmer_cache
             .replace_backing_caches(
                 vec![
-                    Arc::new(PieceCache::open(path1.as_ref(), 1).unwrap()),
-                    Arc::new(PieceCache::open(path2.as_ref(), 1).unwrap()),
+                    Arc::new(
+                        PieceCache::open(path1.as_ref(), 1, [0; 32], &farm_id, false).unwrap(),
+                    ),
+                    Arc::new(
+                        PieceCache::open(path2.as_ref(), 1, [0; 32], &farm_id, false).unwrap(),
+                    ),
                 ],
                 vec![],
             )
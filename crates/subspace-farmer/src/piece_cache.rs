@@ -2,7 +2,7 @@
 mod tests;
 
 use crate::farm;
-use crate::farm::{FarmError, PieceCacheOffset};
+use crate::farm::{FarmError, FarmId, PieceCacheOffset};
 #[cfg(windows)]
 use crate::single_disk_farm::unbuffered_io_file_windows::UnbufferedIoFileWindows;
 use crate::single_disk_farm::unbuffered_io_file_windows::DISK_SECTOR_SIZE;
@@ -10,6 +10,7 @@ use crate::utils::AsyncJoinOnDrop;
 use async_trait::async_trait;
 use futures::channel::mpsc;
 use futures::{stream, SinkExt, Stream, StreamExt};
+use parity_scale_codec::Encode;
 use parking_lot::Mutex;
 #[cfg(not(windows))]
 use std::fs::{File, OpenOptions};
@@ -19,6 +20,7 @@ use std::task::Poll;
 use std::{fs, io, mem};
 use subspace_core_primitives::crypto::blake3_hash_list;
 use subspace_core_primitives::{Blake3Hash, Piece, PieceIndex};
+use subspace_farmer_components::farm_file::{FarmFileError, FarmFileHeader, FarmFileKind};
 use subspace_farmer_components::file_ext::FileExt;
 #[cfg(not(windows))]
 use subspace_farmer_components::file_ext::OpenOptionsExt;
@@ -54,6 +56,9 @@ pub enum PieceCacheError {
     /// Checksum mismatch
     #[error("Checksum mismatch")]
     ChecksumMismatch,
+    /// Cache file format error
+    #[error("Cache file format error: {0}")]
+    FarmFile(#[from] FarmFileError),
 }
 
 #[derive(Debug)]
@@ -157,9 +162,20 @@ impl farm::PieceCache for PieceCache {
 
 impl PieceCache {
     pub(crate) const FILE_NAME: &'static str = "piece_cache.bin";
+    const FILE_FORMAT_VERSION: u8 = 0;
 
-    /// Open cache, capacity is measured in elements of [`PieceCache::element_size()`] size
-    pub fn open(directory: &Path, capacity: u32) -> Result<Self, PieceCacheError> {
+    /// Open cache, capacity is measured in elements of [`PieceCache::element_size()`] size.
+    ///
+    /// `genesis_hash` and `farm_id` are stored in the header of the cache file and checked when
+    /// existing cache is opened. `allow_legacy` must only be `true` if cache file might have been
+    /// created before header was introduced, see [`FarmFileHeader::check_and_migrate()`].
+    pub fn open(
+        directory: &Path,
+        capacity: u32,
+        genesis_hash: [u8; 32],
+        farm_id: &FarmId,
+        allow_legacy: bool,
+    ) -> Result<Self, PieceCacheError> {
         if capacity == 0 {
             return Err(PieceCacheError::ZeroCapacity);
         }
@@ -178,18 +194,27 @@ impl PieceCache {
         #[cfg(windows)]
         let file = UnbufferedIoFileWindows::open(&directory.join(Self::FILE_NAME))?;
 
-        let expected_size = u64::from(Self::element_size()) * u64::from(capacity);
+        let file_header = FarmFileHeader {
+            kind: FarmFileKind::PieceCache,
+            version: Self::FILE_FORMAT_VERSION,
+            genesis_hash,
+            farm_id: farm_id.encode(),
+        };
+        file_header.check_and_migrate(&file, allow_legacy, Self::migrate)?;
+
+        let data_size = u64::from(Self::element_size()) * u64::from(capacity);
         // Align plot file size for disk sector size
-        let expected_size =
-            expected_size.div_ceil(DISK_SECTOR_SIZE as u64) * DISK_SECTOR_SIZE as u64;
-        if file.size()? != expected_size {
-            // Allocating the whole file (`set_len` below can create a sparse file, which will cause
-            // writes to fail later)
-            file.preallocate(expected_size)
-                .map_err(PieceCacheError::CantPreallocateCacheFile)?;
-            // Truncating file (if necessary)
-            file.set_len(expected_size)?;
-        }
+        let data_size = data_size.div_ceil(DISK_SECTOR_SIZE as u64) * DISK_SECTOR_SIZE as u64;
+        let expected_size = data_size + FarmFileHeader::RESERVED_SIZE;
+        // Header is written before file is resized, such that file ends with a valid header even if
+        // farmer is interrupted while resizing
+        file_header.write_to(&file, data_size)?;
+        // Allocating the whole file (writing header above and `set_len` below can create a sparse
+        // file, which will cause writes to fail later)
+        file.preallocate(expected_size)
+            .map_err(PieceCacheError::CantPreallocateCacheFile)?;
+        // Truncating file (if necessary)
+        file.set_len(expected_size)?;
 
         Ok(Self {
             inner: Arc::new(Inner {
@@ -199,6 +224,14 @@ impl PieceCache {
         })
     }
 
+    /// Forward migrations of the cache file format, there are none yet
+    fn migrate<F>(_file: &F, from_version: u8) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Migration from version {from_version} is not implemented"),
+        ))
+    }
+
     pub const fn element_size() -> u32 {
         (PieceIndex::SIZE + Piece::SIZE + mem::size_of::<Blake3Hash>()) as u32
     }
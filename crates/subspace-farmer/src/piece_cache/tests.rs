@@ -1,14 +1,19 @@
+use crate::farm::FarmId;
 use crate::piece_cache::{PieceCache, PieceCacheError, PieceCacheOffset};
 use rand::prelude::*;
 use std::assert_matches::assert_matches;
+use std::fs;
 use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_farmer_components::farm_file::FarmFileError;
 use tempfile::tempdir;
 
 #[test]
 fn basic() {
     let path = tempdir().unwrap();
+    let farm_id = FarmId::new();
     {
-        let disk_piece_cache = PieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache =
+            PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, false).unwrap();
 
         // Initially empty
         assert_eq!(
@@ -115,7 +120,8 @@ fn basic() {
 
     // Reopening works
     {
-        let disk_piece_cache = PieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache =
+            PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, false).unwrap();
         // Two pieces stored
         assert_eq!(
             disk_piece_cache
@@ -126,11 +132,24 @@ fn basic() {
         );
     }
 
+    // Cache of a different chain or farm can't be opened
+    assert_matches!(
+        PieceCache::open(path.as_ref(), 2, [1; 32], &farm_id, false),
+        Err(PieceCacheError::FarmFile(
+            FarmFileError::WrongGenesisHash { .. }
+        ))
+    );
+    assert_matches!(
+        PieceCache::open(path.as_ref(), 2, [0; 32], &FarmId::new(), false),
+        Err(PieceCacheError::FarmFile(FarmFileError::WrongFarmId { .. }))
+    );
+
     // Wiping works
     {
         PieceCache::wipe(path.as_ref()).unwrap();
 
-        let disk_piece_cache = PieceCache::open(path.as_ref(), 2).unwrap();
+        let disk_piece_cache =
+            PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, false).unwrap();
         // Wiped successfully
         assert_eq!(
             disk_piece_cache
@@ -141,3 +160,28 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn legacy_file() {
+    let path = tempdir().unwrap();
+    let farm_id = FarmId::new();
+
+    // Cache file created before header was introduced
+    fs::write(
+        path.as_ref().join(PieceCache::FILE_NAME),
+        vec![0; PieceCache::element_size() as usize * 2],
+    )
+    .unwrap();
+
+    // Files without header are rejected unless legacy files are expected
+    assert_matches!(
+        PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, false),
+        Err(PieceCacheError::FarmFile(
+            FarmFileError::MissingHeader { .. }
+        ))
+    );
+
+    // Legacy file is upgraded in place and has header afterwards
+    PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, true).unwrap();
+    PieceCache::open(path.as_ref(), 2, [0; 32], &farm_id, false).unwrap();
+}
@@ -47,7 +47,7 @@ use std::collections::HashSet;
 use std::error::Error;
 use std::fs::{File, OpenOptions};
 use std::future::Future;
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -60,6 +60,7 @@ use subspace_core_primitives::{
     Blake3Hash, HistorySize, PublicKey, Record, SectorIndex, SegmentIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
+use subspace_farmer_components::farm_file::{FarmFileError, FarmFileHeader, FarmFileKind};
 use subspace_farmer_components::file_ext::FileExt;
 #[cfg(not(windows))]
 use subspace_farmer_components::file_ext::OpenOptionsExt;
@@ -77,6 +78,8 @@ use tracing::{debug, error, info, trace, warn, Instrument, Span};
 // Refuse to compile on non-64-bit platforms, offsets may fail on those when converting from u64 to
 // usize depending on chain parameters
 const_assert!(mem::size_of::<usize>() >= mem::size_of::<u64>());
+// Farm file header must not break alignment of the data for unbuffered I/O
+const_assert!(FarmFileHeader::RESERVED_SIZE % DISK_SECTOR_SIZE as u64 == 0);
 
 /// Reserve 1M of space for plot metadata (for potential future expansion)
 const RESERVED_PLOT_METADATA: u64 = 1024 * 1024;
@@ -91,7 +94,18 @@ const INTERNAL_BENCHMARK_READ_TIMEOUT: Duration = Duration::from_millis(3500);
 /// Exclusive lock for single disk farm info file, ensuring no concurrent edits by cooperating processes is done
 #[must_use = "Lock file must be kept around or as long as farm is used"]
 pub struct SingleDiskFarmInfoLock {
-    _file: File,
+    file: File,
+}
+
+impl SingleDiskFarmInfoLock {
+    /// Store `SingleDiskFarm` info while holding the lock, same as [`SingleDiskFarmInfo::store_to()`]
+    /// otherwise
+    fn store(&self, info: &SingleDiskFarmInfo) -> io::Result<()> {
+        let mut file = &self.file;
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&serde_json::to_vec(info).expect("Info serialization never fails; qed"))
+    }
 }
 
 /// Important information about the contents of the `SingleDiskFarm`
@@ -112,6 +126,10 @@ pub enum SingleDiskFarmInfo {
         pieces_in_sector: u16,
         /// How much space in bytes is allocated for this farm
         allocated_space: u64,
+        /// Whether all farm files are known to have [`FarmFileHeader`], files without header are
+        /// only treated as files created before header was introduced when this is `false`
+        #[serde(default)]
+        farm_file_headers: bool,
     },
 }
 
@@ -131,6 +149,7 @@ impl SingleDiskFarmInfo {
             public_key,
             pieces_in_sector,
             allocated_space,
+            farm_file_headers: true,
         }
     }
 
@@ -173,10 +192,14 @@ impl SingleDiskFarmInfo {
     /// Try to acquire exclusive lock on the single disk farm info file, ensuring no concurrent edits by cooperating
     /// processes is done
     pub fn try_lock(directory: &Path) -> io::Result<SingleDiskFarmInfoLock> {
-        let file = File::open(directory.join(Self::FILE_NAME))?;
+        // Opened for writing as well, such that info can be updated while lock is held
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(directory.join(Self::FILE_NAME))?;
         fs4::FileExt::try_lock_exclusive(&file)?;
 
-        Ok(SingleDiskFarmInfoLock { _file: file })
+        Ok(SingleDiskFarmInfoLock { file })
     }
 
     // ID of the farm
@@ -212,6 +235,14 @@ impl SingleDiskFarmInfo {
         } = self;
         *allocated_space
     }
+
+    /// Whether all farm files are known to have [`FarmFileHeader`]
+    pub fn farm_file_headers(&self) -> bool {
+        let Self::V0 {
+            farm_file_headers, ..
+        } = self;
+        *farm_file_headers
+    }
 }
 
 /// Summary of single disk farm for presentational purposes
@@ -399,6 +430,50 @@ pub enum SingleDiskFarmError {
     /// Failed to create thread pool
     #[error("Failed to create thread pool: {0}")]
     FailedToCreateThreadPool(ThreadPoolBuildError),
+    /// Farm file was created by a newer version of the farmer
+    #[error(
+        "File {file} of farm {id} was created by a newer version of the farmer (format version \
+        {version}, max supported {max_supported}), upgrade the farmer to use this farm"
+    )]
+    FarmFileCreatedByNewerFarmer {
+        /// Farm ID
+        id: FarmId,
+        /// Affected file
+        file: PathBuf,
+        /// Format version of the file
+        version: u8,
+        /// Max format version supported by this farmer
+        max_supported: u8,
+    },
+    /// Farm file is incompatible with this farm
+    #[error("File {file} of farm {id} is incompatible with this farm: {error}")]
+    IncompatibleFarmFile {
+        /// Farm ID
+        id: FarmId,
+        /// Affected file
+        file: PathBuf,
+        /// Low-level error
+        error: FarmFileError,
+    },
+}
+
+impl SingleDiskFarmError {
+    fn from_farm_file_error(id: FarmId, file: PathBuf, error: FarmFileError) -> Self {
+        match error {
+            FarmFileError::UnsupportedVersion {
+                version,
+                max_supported,
+                ..
+            } => Self::FarmFileCreatedByNewerFarmer {
+                id,
+                file,
+                version,
+                max_supported,
+            },
+            FarmFileError::Io(error) => Self::Io(error),
+            error => Self::IncompatibleFarmFile { id, file, error },
+        }
+    }
 }
 
 /// Errors happening during scrubbing
@@ -662,6 +737,8 @@ impl SingleDiskFarm {
     pub const PLOT_FILE: &'static str = "plot.bin";
     pub const METADATA_FILE: &'static str = "metadata.bin";
     const SUPPORTED_PLOT_VERSION: u8 = 0;
+    const PLOT_FILE_FORMAT_VERSION: u8 = 0;
+    const METADATA_FILE_FORMAT_VERSION: u8 = 0;
 
     /// Create new single disk farm instance
     pub async fn new<NC, P, PosTable>(
@@ -1085,7 +1162,7 @@ impl SingleDiskFarm {
         };
         let public_key = identity.public_key().to_bytes().into();
 
-        let single_disk_farm_info = match SingleDiskFarmInfo::load_from(directory)? {
+        let mut single_disk_farm_info = match SingleDiskFarmInfo::load_from(directory)? {
             Some(mut single_disk_farm_info) => {
                 if &farmer_app_info.genesis_hash != single_disk_farm_info.genesis_hash() {
                     return Err(SingleDiskFarmError::WrongChain {
@@ -1212,7 +1289,9 @@ impl SingleDiskFarm {
             let cache_space = allocated_space
                 - fixed_space_usage
                 - plot_file_size
-                - (sector_metadata_size as u64 * target_sector_count);
+                - (sector_metadata_size as u64 * target_sector_count)
+                // Headers of plot, metadata and piece cache files
+                - 3 * FarmFileHeader::RESERVED_SIZE;
             (cache_space / u64::from(PieceCache::element_size())) as u32
         } else {
             0
@@ -1249,35 +1328,60 @@ impl SingleDiskFarm {
         #[cfg(windows)]
         let metadata_file = UnbufferedIoFileWindows::open(&metadata_file_path)?;
 
+        let farm_file_header = |kind, version| FarmFileHeader {
+            kind,
+            version,
+            genesis_hash: farmer_app_info.genesis_hash,
+            farm_id: single_disk_farm_info.id().encode(),
+        };
+
+        // Files without header are only expected if farm was created before header was introduced
+        let allow_legacy_farm_files = !single_disk_farm_info.farm_file_headers();
+
+        let metadata_file_header =
+            farm_file_header(FarmFileKind::Metadata, Self::METADATA_FILE_FORMAT_VERSION);
+        metadata_file_header
+            .check_and_migrate(
+                &metadata_file,
+                allow_legacy_farm_files,
+                Self::migrate_farm_file,
+            )
+            .map_err(|error| {
+                SingleDiskFarmError::from_farm_file_error(
+                    *single_disk_farm_info.id(),
+                    metadata_file_path.clone(),
+                    error,
+                )
+            })?;
+
         let metadata_size = metadata_file.size()?;
-        let expected_metadata_size =
+        let metadata_data_size =
             RESERVED_PLOT_METADATA + sector_metadata_size as u64 * u64::from(target_sector_count);
         // Align plot file size for disk sector size
-        let expected_metadata_size =
-            expected_metadata_size.div_ceil(DISK_SECTOR_SIZE as u64) * DISK_SECTOR_SIZE as u64;
+        let metadata_data_size =
+            metadata_data_size.div_ceil(DISK_SECTOR_SIZE as u64) * DISK_SECTOR_SIZE as u64;
+        let expected_metadata_size = metadata_data_size + FarmFileHeader::RESERVED_SIZE;
+        // Header is written before file is resized, such that file ends with a valid header even if
+        // farmer is interrupted while resizing
+        metadata_file_header.write_to(&metadata_file, metadata_data_size)?;
+        // Allocating the whole file (writing header above and `set_len` below can create a sparse
+        // file, which will cause writes to fail later)
+        metadata_file
+            .preallocate(expected_metadata_size)
+            .map_err(SingleDiskFarmError::CantPreallocateMetadataFile)?;
+        // Truncating file (if necessary)
+        metadata_file.set_len(expected_metadata_size)?;
+
         let metadata_header = if metadata_size == 0 {
             let metadata_header = PlotMetadataHeader {
                 version: 0,
                 plotted_sector_count: 0,
             };
 
-            metadata_file
-                .preallocate(expected_metadata_size)
-                .map_err(SingleDiskFarmError::CantPreallocateMetadataFile)?;
             metadata_file.write_all_at(metadata_header.encode().as_slice(), 0)?;
 
             metadata_header
         } else {
-            if metadata_size != expected_metadata_size {
-                // Allocating the whole file (`set_len` below can create a sparse file, which will
-                // cause writes to fail later)
-                metadata_file
-                    .preallocate(expected_metadata_size)
-                    .map_err(SingleDiskFarmError::CantPreallocateMetadataFile)?;
-                // Truncating file (if necessary)
-                metadata_file.set_len(expected_metadata_size)?;
-            }
-
             let mut metadata_header_bytes = vec![0; PlotMetadataHeader::encoded_size()];
             metadata_file.read_exact_at(&mut metadata_header_bytes, 0)?;
 
@@ -1352,22 +1456,52 @@ impl SingleDiskFarm {
         #[cfg(windows)]
         let plot_file = UnbufferedIoFileWindows::open(&directory.join(Self::PLOT_FILE))?;
 
-        if plot_file.size()? != plot_file_size {
-            // Allocating the whole file (`set_len` below can create a sparse file, which will cause
-            // writes to fail later)
-            plot_file
-                .preallocate(plot_file_size)
-                .map_err(SingleDiskFarmError::CantPreallocatePlotFile)?;
-            // Truncating file (if necessary)
-            plot_file.set_len(plot_file_size)?;
-        }
+        let plot_file_header = farm_file_header(FarmFileKind::Plot, Self::PLOT_FILE_FORMAT_VERSION);
+        plot_file_header
+            .check_and_migrate(&plot_file, allow_legacy_farm_files, Self::migrate_farm_file)
+            .map_err(|error| {
+                SingleDiskFarmError::from_farm_file_error(
+                    *single_disk_farm_info.id(),
+                    directory.join(Self::PLOT_FILE),
+                    error,
+                )
+            })?;
+
+        // Header is written before file is resized, such that file ends with a valid header even if
+        // farmer is interrupted while resizing
+        plot_file_header.write_to(&plot_file, plot_file_size)?;
+        // Allocating the whole file (writing header above and `set_len` below can create a sparse
+        // file, which will cause writes to fail later)
+        plot_file
+            .preallocate(plot_file_size + FarmFileHeader::RESERVED_SIZE)
+            .map_err(SingleDiskFarmError::CantPreallocatePlotFile)?;
+        // Truncating file (if necessary)
+        plot_file.set_len(plot_file_size + FarmFileHeader::RESERVED_SIZE)?;
 
         let plot_file = Arc::new(plot_file);
 
         let piece_cache = DiskPieceCache::new(if cache_capacity == 0 {
             None
         } else {
-            Some(PieceCache::open(directory, cache_capacity)?)
+            match PieceCache::open(
+                directory,
+                cache_capacity,
+                farmer_app_info.genesis_hash,
+                single_disk_farm_info.id(),
+                allow_legacy_farm_files,
+            ) {
+                Ok(piece_cache) => Some(piece_cache),
+                Err(PieceCacheError::FarmFile(error)) => {
+                    return Err(SingleDiskFarmError::from_farm_file_error(
+                        *single_disk_farm_info.id(),
+                        directory.join(PieceCache::FILE_NAME),
+                        error,
+                    ));
+                }
+                Err(error) => {
+                    return Err(error.into());
+                }
+            }
         });
         let plot_cache = DiskPlotCache::new(
             &plot_file,
@@ -1376,6 +1510,28 @@ impl SingleDiskFarm {
             sector_size,
         );
 
+        // All farm files have header now, so files without header must not be treated as legacy
+        // files anymore (unless piece cache file was left from before header was introduced)
+        if allow_legacy_farm_files
+            && (cache_capacity != 0 || !directory.join(PieceCache::FILE_NAME).exists())
+        {
+            {
+                let SingleDiskFarmInfo::V0 {
+                    farm_file_headers, ..
+                } = &mut single_disk_farm_info;
+                *farm_file_headers = true;
+            }
+
+            match &single_disk_farm_info_lock {
+                Some(single_disk_farm_info_lock) => {
+                    single_disk_farm_info_lock.store(&single_disk_farm_info)?;
+                }
+                None => {
+                    single_disk_farm_info.store_to(directory)?;
+                }
+            }
+        }
+
         Ok(SingleDiskFarmInit {
             identity,
             single_disk_farm_info,
@@ -1390,6 +1546,14 @@ impl SingleDiskFarm {
         })
     }
 
+    /// Forward migrations of farm files format, there are none yet
+    fn migrate_farm_file<F>(_file: &F, from_version: u8) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Migration from version {from_version} is not implemented"),
+        ))
+    }
+
     /// Collect summary of single disk farm for presentational purposes
     pub fn collect_summary(directory: PathBuf) -> SingleDiskFarmSummary {
         let single_disk_farm_info = match SingleDiskFarmInfo::load_from(&directory) {
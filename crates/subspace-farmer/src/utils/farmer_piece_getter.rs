@@ -6,6 +6,8 @@ use async_trait::async_trait;
 use backoff::backoff::Backoff;
 use backoff::future::retry;
 use backoff::ExponentialBackoff;
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::error::Error;
@@ -353,6 +355,65 @@ where
         );
         Ok(None)
     }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        let inner = &self.inner;
+
+        let mut results = Vec::with_capacity(piece_indices.len());
+        let mut missing_pieces = Vec::new();
+        for (position, piece_index) in piece_indices.into_iter().enumerate() {
+            let key = RecordKey::from(piece_index.to_multihash());
+            let maybe_piece = inner.farmer_cache.get_piece(key).await;
+            if maybe_piece.is_none() {
+                missing_pieces.push((position, piece_index));
+            }
+            results.push(maybe_piece);
+        }
+
+        if missing_pieces.is_empty() {
+            return Ok(results);
+        }
+
+        // L2 piece acquisition, pieces received from the same peer are validated together
+        trace!(count = %missing_pieces.len(), "Getting pieces from DSN L2 cache");
+        let maybe_pieces = inner
+            .piece_provider
+            .get_pieces_from_cache(
+                missing_pieces
+                    .iter()
+                    .map(|&(_position, piece_index)| piece_index)
+                    .collect(),
+            )
+            .await;
+
+        let mut remaining_pieces = FuturesUnordered::new();
+        for ((position, piece_index), maybe_piece) in missing_pieces.into_iter().zip(maybe_pieces) {
+            match maybe_piece {
+                Some(piece) => {
+                    trace!(%piece_index, "Got piece from DSN L2 cache");
+                    inner
+                        .farmer_cache
+                        .maybe_store_additional_piece(piece_index, &piece)
+                        .await;
+                    results[position] = Some(piece);
+                }
+                None => {
+                    // Remaining pieces are retrieved individually using all other methods
+                    remaining_pieces
+                        .push(async move { (position, self.get_piece(piece_index).await) });
+                }
+            }
+        }
+
+        while let Some((position, result)) = remaining_pieces.next().await {
+            results[position] = result?;
+        }
+
+        Ok(results)
+    }
 }
 
 /// Weak farmer piece getter, can be upgraded to [`FarmerPieceGetter`]
@@ -396,6 +457,18 @@ where
 
         piece_getter.get_piece(piece_index).await
     }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        let Some(piece_getter) = self.upgrade() else {
+            debug!("Farmer piece getter upgrade didn't succeed");
+            return Ok(vec![None; piece_indices.len()]);
+        };
+
+        piece_getter.get_pieces(piece_indices).await
+    }
 }
 
 impl<FarmIndex, PV, NC> WeakFarmerPieceGetter<FarmIndex, PV, NC> {
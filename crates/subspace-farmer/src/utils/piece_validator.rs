@@ -1,6 +1,7 @@
 use crate::node_client::NodeClient;
 use async_trait::async_trait;
-use subspace_archiving::archiver::is_piece_valid;
+use std::collections::HashMap;
+use subspace_archiving::archiver::{is_piece_valid, validate_pieces};
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::{Piece, PieceIndex};
 use subspace_networking::libp2p::PeerId;
@@ -90,4 +91,84 @@ where
             }
         }
     }
+
+    async fn validate_pieces(
+        &self,
+        source_peer_id: PeerId,
+        pieces: Vec<(PieceIndex, Piece)>,
+    ) -> Vec<Option<Piece>> {
+        if source_peer_id == self.dsn_node.id() {
+            return pieces.into_iter().map(|(_, piece)| Some(piece)).collect();
+        }
+
+        let mut segment_indexes = pieces
+            .iter()
+            .map(|(piece_index, _piece)| piece_index.segment_index())
+            .collect::<Vec<_>>();
+        segment_indexes.sort_unstable();
+        segment_indexes.dedup();
+
+        // Retrieve segment headers for all pieces with a single request
+        let segment_commitments = match self
+            .node_client
+            .segment_headers(segment_indexes.clone())
+            .await
+        {
+            Ok(segment_headers) => segment_indexes
+                .into_iter()
+                .zip(segment_headers)
+                .filter_map(|(segment_index, maybe_segment_header)| {
+                    let Some(segment_header) = maybe_segment_header else {
+                        error!(
+                            %segment_index,
+                            "Segment commitment for segment index wasn't found on node"
+                        );
+                        return None;
+                    };
+
+                    Some((segment_index, segment_header.segment_commitment()))
+                })
+                .collect::<HashMap<_, _>>(),
+            Err(error) => {
+                error!(?error, "Failed to retrieve segment headers from node");
+                return vec![None; pieces.len()];
+            }
+        };
+
+        let num_pieces = pieces.len();
+        let validation_fut = tokio::task::spawn_blocking({
+            let kzg = self.kzg.clone();
+
+            move || {
+                let pieces = pieces
+                    .into_iter()
+                    .map(|(piece_index, piece)| {
+                        let segment_commitment = segment_commitments
+                            .get(&piece_index.segment_index())
+                            .copied();
+                        (piece_index, piece, segment_commitment)
+                    })
+                    .collect();
+
+                validate_pieces(&kzg, pieces)
+            }
+        });
+
+        let Ok((results, invalid_piece_indexes)) = validation_fut.await else {
+            return vec![None; num_pieces];
+        };
+
+        if !invalid_piece_indexes.is_empty() {
+            warn!(
+                ?invalid_piece_indexes,
+                %source_peer_id,
+                "Received invalid pieces from peer"
+            );
+
+            // We don't care about result here
+            let _ = self.dsn_node.ban_peer(source_peer_id).await;
+        }
+
+        results
+    }
 }
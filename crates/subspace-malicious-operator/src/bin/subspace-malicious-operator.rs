@@ -208,6 +208,8 @@ fn main() -> Result<(), Error> {
                 subspace_networking: SubspaceNetworking::Create { config: dsn_config },
                 dsn_piece_getter: None,
                 sync_from_dsn: true,
+                sync: Default::default(),
+                segment_archives_path: None,
                 is_timekeeper: false,
                 timekeeper_cpu_cores: Default::default(),
             };
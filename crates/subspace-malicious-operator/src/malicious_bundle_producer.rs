@@ -30,7 +30,8 @@ use std::error::Error;
 use std::sync::Arc;
 use subspace_core_primitives::PotOutput;
 use subspace_runtime::{
-    CheckStorageAccess, DisablePallets, Runtime, RuntimeCall, SignedExtra, UncheckedExtrinsic,
+    CheckCallPermissions, CheckStorageAccess, DisablePallets, Runtime, RuntimeCall, SignedExtra,
+    UncheckedExtrinsic,
 };
 use subspace_runtime_primitives::opaque::Block as CBlock;
 use subspace_runtime_primitives::{AccountId, Balance, Nonce};
@@ -411,6 +412,7 @@ pub fn construct_signed_extrinsic(
         pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0u128),
         CheckStorageAccess,
         DisablePallets,
+        CheckCallPermissions,
     );
     let raw_payload = generic::SignedPayload::<RuntimeCall, SignedExtra>::from_raw(
         call.clone(),
@@ -426,6 +428,7 @@ pub fn construct_signed_extrinsic(
             (),
             (),
             (),
+            (),
         ),
     );
 
@@ -3,9 +3,10 @@
 use crate::utils::multihash::ToMultihash;
 use crate::{Node, PieceByIndexRequest, PieceByIndexResponse};
 use async_trait::async_trait;
+use futures::stream::FuturesOrdered;
 use futures::StreamExt;
 use libp2p::PeerId;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use subspace_core_primitives::{Piece, PieceIndex};
 use tracing::{debug, trace, warn};
@@ -20,6 +21,26 @@ pub trait PieceValidator: Sync + Send {
         piece_index: PieceIndex,
         piece: Piece,
     ) -> Option<Piece>;
+
+    /// Validates multiple pieces received from the same peer at once, returns pieces in the same
+    /// order as provided with `None` in place of invalid pieces.
+    ///
+    /// Default implementation validates pieces one by one, implementations should override it if
+    /// more efficient batch validation is possible.
+    async fn validate_pieces(
+        &self,
+        source_peer_id: PeerId,
+        pieces: Vec<(PieceIndex, Piece)>,
+    ) -> Vec<Option<Piece>> {
+        let mut results = Vec::with_capacity(pieces.len());
+        for (piece_index, piece) in pieces {
+            results.push(
+                self.validate_piece(source_peer_id, piece_index, piece)
+                    .await,
+            );
+        }
+        results
+    }
 }
 
 /// Stub implementation for piece validation.
@@ -59,6 +80,80 @@ where
 
     /// Returns piece by its index from farmer's piece cache (L2)
     pub async fn get_piece_from_cache(&self, piece_index: PieceIndex) -> Option<Piece> {
+        let (provider_id, piece) = self.get_unvalidated_piece_from_cache(piece_index).await?;
+
+        if let Some(validator) = &self.piece_validator {
+            validator
+                .validate_piece(provider_id, piece_index, piece)
+                .await
+        } else {
+            Some(piece)
+        }
+    }
+
+    /// Returns pieces by their indices from farmer's piece cache (L2), returns pieces in the same
+    /// order as provided with `None` in place of pieces that were not found or are invalid.
+    ///
+    /// Same as [`Self::get_piece_from_cache()`], but pieces are downloaded concurrently and pieces
+    /// received from the same peer are validated at once with
+    /// [`PieceValidator::validate_pieces()`].
+    pub async fn get_pieces_from_cache(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Vec<Option<Piece>> {
+        let mut results = vec![None; piece_indices.len()];
+
+        let mut downloaded_pieces = piece_indices
+            .into_iter()
+            .enumerate()
+            .map(|(position, piece_index)| async move {
+                let maybe_piece = self.get_unvalidated_piece_from_cache(piece_index).await;
+                (position, piece_index, maybe_piece)
+            })
+            .collect::<FuturesOrdered<_>>();
+
+        let Some(validator) = &self.piece_validator else {
+            while let Some((position, _piece_index, maybe_piece)) = downloaded_pieces.next().await {
+                results[position] = maybe_piece.map(|(_provider_id, piece)| piece);
+            }
+
+            return results;
+        };
+
+        // Group pieces by peers they were received from, such that they can be validated together
+        let mut pieces_by_provider = HashMap::<PeerId, Vec<_>>::new();
+        while let Some((position, piece_index, maybe_piece)) = downloaded_pieces.next().await {
+            if let Some((provider_id, piece)) = maybe_piece {
+                pieces_by_provider.entry(provider_id).or_default().push((
+                    position,
+                    piece_index,
+                    piece,
+                ));
+            }
+        }
+
+        for (provider_id, pieces) in pieces_by_provider {
+            let (positions, pieces) = pieces
+                .into_iter()
+                .map(|(position, piece_index, piece)| (position, (piece_index, piece)))
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+
+            let validated_pieces = validator.validate_pieces(provider_id, pieces).await;
+
+            for (position, maybe_piece) in positions.into_iter().zip(validated_pieces) {
+                results[position] = maybe_piece;
+            }
+        }
+
+        results
+    }
+
+    /// Returns piece by its index from farmer's piece cache (L2) along with the peer it was
+    /// received from, piece is not validated
+    async fn get_unvalidated_piece_from_cache(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Option<(PeerId, Piece)> {
         let key = piece_index.to_multihash();
 
         let mut request_batch = self.node.get_requests_batch_handle().await;
@@ -77,13 +172,7 @@ where
                         Ok(PieceByIndexResponse { piece: Some(piece) }) => {
                             trace!(%provider_id, %piece_index, ?key, "Piece request succeeded.");
 
-                            if let Some(validator) = &self.piece_validator {
-                                return validator
-                                    .validate_piece(provider_id, piece_index, piece)
-                                    .await;
-                            } else {
-                                return Some(piece);
-                            }
+                            return Some((provider_id, piece));
                         }
                         Ok(PieceByIndexResponse { piece: None }) => {
                             debug!(%provider_id, %piece_index, ?key, "Piece request returned empty piece.");
@@ -15,7 +15,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::chain_spec;
-use crate::commands::{RunOptions, WipeOptions};
+use crate::commands::{ExportSegmentsCmd, ImportSegmentsCmd, RunOptions, WipeOptions};
 use clap::Parser;
 use sc_chain_spec::GenericChainSpec;
 use sc_cli::SubstrateCli;
@@ -44,6 +44,12 @@ pub enum Cli {
     /// Import blocks.
     ImportBlocks(sc_cli::ImportBlocksCmd),
 
+    /// Export archived segments into a segment archive file.
+    ExportSegments(ExportSegmentsCmd),
+
+    /// Verify and import segment archive file.
+    ImportSegments(ImportSegmentsCmd),
+
     /// Remove all node's data
     Wipe(WipeOptions),
 
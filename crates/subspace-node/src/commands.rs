@@ -1,5 +1,6 @@
 mod domain_key;
 mod run;
+mod segments;
 mod shared;
 mod wipe;
 
@@ -7,4 +8,5 @@ pub use domain_key::{
     create_domain_key, insert_domain_key, CreateDomainKeyOptions, InsertDomainKeyOptions,
 };
 pub use run::{run, RunOptions};
+pub use segments::{ExportSegmentsCmd, ImportSegmentsCmd};
 pub use wipe::{wipe, WipeOptions};
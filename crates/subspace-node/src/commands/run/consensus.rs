@@ -20,7 +20,7 @@ use std::str::FromStr;
 use subspace_networking::libp2p::multiaddr::Protocol;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_service::config::{
-    SubspaceConfiguration, SubspaceNetworking, SubstrateConfiguration,
+    ChainSyncMode, SubspaceConfiguration, SubspaceNetworking, SubstrateConfiguration,
     SubstrateNetworkConfiguration, SubstrateRpcConfiguration,
 };
 use subspace_service::dsn::DsnConfig;
@@ -391,6 +391,14 @@ pub(super) struct ConsensusChainOptions {
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     sync_from_dsn: bool,
 
+    /// Sync mode of the consensus chain.
+    ///
+    /// `full` imports all blocks starting from genesis, `snap` downloads the last archived blocks
+    /// and the state at that point from the network instead (only works with empty database and
+    /// requires DSN sync to be enabled).
+    #[arg(long, default_value_t = ChainSyncMode::Full)]
+    sync: ChainSyncMode,
+
     /// Parameters used to create the storage monitor.
     #[clap(flatten)]
     storage_monitor: StorageMonitorParams,
@@ -438,6 +446,7 @@ pub(super) fn create_consensus_chain_configuration(
         pot_external_entropy,
         dsn_options,
         sync_from_dsn,
+        sync,
         storage_monitor,
         mut timekeeper_options,
     } = consensus_node_options;
@@ -488,6 +497,11 @@ pub(super) fn create_consensus_chain_configuration(
             ));
         }
     };
+    if sync == ChainSyncMode::Snap && !sync_from_dsn {
+        return Err(Error::Other(
+            "Snap sync requires DSN sync to be enabled".to_string(),
+        ));
+    }
     let mut maybe_tmp_dir = None;
     let base_path = match base_path {
         Some(base_path) => base_path,
@@ -651,6 +665,8 @@ pub(super) fn create_consensus_chain_configuration(
             subspace_networking: SubspaceNetworking::Create { config: dsn_config },
             dsn_piece_getter: None,
             sync_from_dsn,
+            sync,
+            segment_archives_path: Some(base_path.join("segments")),
             is_timekeeper: timekeeper_options.timekeeper,
             timekeeper_cpu_cores: timekeeper_options.timekeeper_cpu_cores,
         },
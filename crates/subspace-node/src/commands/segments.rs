@@ -0,0 +1,228 @@
+use clap::Parser;
+use sc_cli::{CliConfiguration, Database, DatabaseParams, SharedParams};
+use sc_client_api::{AuxStore, BlockBackend};
+use sc_consensus_subspace::archiver::{recreate_archived_segments, SegmentHeadersStore};
+use sc_service::DatabaseSource;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
+use sp_objects::ObjectsApi;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::SegmentIndex;
+use subspace_runtime_primitives::opaque::Block;
+use subspace_service::segment_archive::{
+    import_segment_archive, SegmentArchiveHeader, SegmentArchiveWriter, SegmentHeaderCheckpoint,
+};
+use tracing::info;
+
+/// Base path of the node.
+///
+/// Unlike Substrate's commands, `run` command doesn't use per-chain directories inside of the base
+/// path, so segment commands need to use the same layout in order to access node's database and
+/// segment archives.
+fn node_base_path(shared_params: &SharedParams) -> sc_cli::Result<PathBuf> {
+    shared_params
+        .base_path()?
+        .map(|base_path| base_path.path().to_path_buf())
+        .ok_or_else(|| sc_cli::Error::Input("--base-path is required".to_string()))
+}
+
+/// Database of the node at `base_path`, same as used by `run` command
+fn node_database(base_path: &Path) -> DatabaseSource {
+    DatabaseSource::ParityDb {
+        path: base_path.join("db"),
+    }
+}
+
+/// The `export-segments` command used to export archived segments into a segment archive file
+#[derive(Debug, Clone, Parser)]
+pub struct ExportSegmentsCmd {
+    /// First segment index to export
+    #[arg(long, default_value_t = 0)]
+    pub from: u64,
+
+    /// Last segment index to export (inclusive), defaults to the last known segment
+    #[arg(long)]
+    pub to: Option<u64>,
+
+    /// Path to the segment archive file to create
+    #[arg(long)]
+    pub output: PathBuf,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for ExportSegmentsCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+
+    fn database_config(
+        &self,
+        _base_path: &PathBuf,
+        _cache_size: usize,
+        _database: Database,
+    ) -> sc_cli::Result<DatabaseSource> {
+        Ok(node_database(&node_base_path(&self.shared_params)?))
+    }
+}
+
+impl ExportSegmentsCmd {
+    /// Run the export-segments command
+    pub fn run<Client, AS>(
+        &self,
+        client: &Client,
+        segment_headers_store: &SegmentHeadersStore<AS>,
+        kzg: Kzg,
+    ) -> sc_cli::Result<()>
+    where
+        Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+        Client::Api: ObjectsApi<Block>,
+        AS: AuxStore,
+    {
+        let first_segment_index = SegmentIndex::from(self.from);
+        let last_segment_index = match self.to {
+            Some(to) => SegmentIndex::from(to),
+            None => segment_headers_store.max_segment_index().ok_or_else(|| {
+                sc_cli::Error::Input("No archived segments available for export".to_string())
+            })?,
+        };
+
+        if first_segment_index > last_segment_index {
+            return Err(sc_cli::Error::Input(format!(
+                "First segment index {first_segment_index} is larger than last segment index \
+                {last_segment_index}"
+            )));
+        }
+
+        let segment_count = u64::from(last_segment_index - first_segment_index) + 1;
+        let mut writer = SegmentArchiveWriter::new(
+            BufWriter::new(File::create(&self.output)?),
+            SegmentArchiveHeader::new(
+                client.info().genesis_hash,
+                first_segment_index,
+                segment_count,
+            ),
+        )
+        .map_err(|error| sc_cli::Error::Application(error.into()))?;
+
+        recreate_archived_segments(
+            client,
+            segment_headers_store,
+            kzg,
+            first_segment_index..=last_segment_index,
+            |archived_segment| {
+                writer.write_segment(&archived_segment)?;
+
+                info!(
+                    segment_index = %archived_segment.segment_header.segment_index(),
+                    segment_header_hash = %hex::encode(archived_segment.segment_header.hash()),
+                    "Segment exported"
+                );
+
+                Ok(())
+            },
+        )
+        .map_err(|error| sc_cli::Error::Application(error.to_string().into()))?;
+
+        writer
+            .finish()
+            .map_err(|error| sc_cli::Error::Application(error.into()))?;
+
+        info!(
+            %first_segment_index,
+            %last_segment_index,
+            output = %self.output.display(),
+            "Segments exported successfully"
+        );
+
+        Ok(())
+    }
+}
+
+/// The `import-segments` command used to verify and import a segment archive file, such that
+/// pieces from it can be used for syncing instead of downloading them from DSN
+#[derive(Debug, Clone, Parser)]
+pub struct ImportSegmentsCmd {
+    /// Path to the segment archive file to import
+    #[arg(long)]
+    pub input: PathBuf,
+
+    /// Trusted segment header hash in `<segment index>:<segment header hash>` format.
+    ///
+    /// Segment headers of the archive must be known to the node, which is not the case for a node
+    /// that didn't sync the corresponding blocks yet. For such node the hash of the last segment
+    /// header of the archive needs to be obtained from a trusted source, e.g. another node.
+    #[arg(long)]
+    pub trusted_checkpoint: Option<SegmentHeaderCheckpoint>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for ImportSegmentsCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+
+    fn database_config(
+        &self,
+        _base_path: &PathBuf,
+        _cache_size: usize,
+        _database: Database,
+    ) -> sc_cli::Result<DatabaseSource> {
+        Ok(node_database(&node_base_path(&self.shared_params)?))
+    }
+}
+
+impl ImportSegmentsCmd {
+    /// Run the import-segments command
+    pub fn run<Client, AS>(
+        &self,
+        client: &Client,
+        segment_headers_store: &SegmentHeadersStore<AS>,
+        kzg: &Kzg,
+    ) -> sc_cli::Result<()>
+    where
+        Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+        Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+        AS: AuxStore,
+    {
+        // Same directory as used by `run` command
+        let segment_archives_path = node_base_path(&self.shared_params)?.join("segments");
+
+        import_segment_archive(
+            &self.input,
+            client,
+            kzg,
+            segment_headers_store,
+            &segment_archives_path,
+            self.trusted_checkpoint.as_ref(),
+        )
+        .map_err(|error| sc_cli::Error::Application(error.into()))?;
+
+        Ok(())
+    }
+}
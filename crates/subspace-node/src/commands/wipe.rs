@@ -18,6 +18,7 @@ pub fn wipe(WipeOptions { base_path }: WipeOptions) -> Result<(), io::Error> {
         base_path.join("db"),
         base_path.join("domains"),
         base_path.join("network"),
+        base_path.join("segments"),
         // TODO: Following three are temporary workaround for wiping old chains, remove once enough time has passed
         base_path.join("chains"),
         base_path.join("domain-0"),
@@ -17,30 +17,54 @@
 use crate::commands::{CreateDomainKeyOptions, InsertDomainKeyOptions};
 use crate::domain::{auto_id_chain_spec, evm_chain_spec};
 use clap::Parser;
-use domain_runtime_primitives::opaque::Block as DomainBlock;
+use domain_runtime_primitives::opaque::{Block as DomainBlock, Header as DomainHeader};
 use domain_runtime_primitives::MultiAccountId;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
 use sc_cli::{
     BlockNumberOrHash, ChainSpec, CliConfiguration, DefaultConfigurationValues, ImportParams,
     KeystoreParams, NetworkParams, Role, RunCmd as SubstrateRunCmd, RuntimeVersion, SharedParams,
     SubstrateCli,
 };
 use sc_client_api::backend::AuxStore;
+use sc_client_api::execution_extensions::ExtensionsFactory;
+use sc_client_api::StorageProvider;
 use sc_network::config::NodeKeyConfig;
 use sc_service::config::{KeystoreConfig, PrometheusConfig};
 use sc_service::{BasePath, Configuration, DatabaseSource};
+use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
 use sp_domain_digests::AsPredigest;
 use sp_domains::storage::RawGenesis;
-use sp_domains::{DomainId, OperatorAllowList, OperatorId, OperatorPublicKey, RuntimeType};
+use sp_domains::{
+    DomainId, DomainsApi, HeaderHashFor, HeaderNumberFor, OperatorAllowList, OperatorId,
+    OperatorPublicKey, RuntimeType,
+};
+use sp_domains_fraud_proof::fraud_proof::{
+    FraudProof, InvalidBlockFeesProof, InvalidDomainBlockHashProof,
+};
+use sp_domains_fraud_proof::verification::{
+    verify_bundle_equivocation_fraud_proof, verify_invalid_block_fees_fraud_proof,
+    verify_invalid_bundles_fraud_proof, verify_invalid_domain_block_hash_fraud_proof,
+    verify_invalid_domain_extrinsics_root_fraud_proof, verify_invalid_state_transition_fraud_proof,
+    verify_invalid_transfers_fraud_proof, verify_valid_bundle_fraud_proof,
+};
+use sp_domains_fraud_proof::{
+    FraudProofExtension, FraudProofHostFunctionsImpl, FraudProofValidationError,
+};
 use sp_runtime::generic::BlockId;
-use sp_runtime::traits::Header;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header, NumberFor};
 use sp_runtime::DigestItem;
+use sp_state_machine::BasicExternalities;
+use std::fmt::Debug;
 use std::io::Write;
 use std::net::SocketAddr;
-use std::path::Path;
-use subspace_runtime::Block;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use subspace_core_primitives::SlotNumber;
+use subspace_runtime::{Block, RuntimeApi};
 use subspace_runtime_primitives::{AccountId, Balance};
+use subspace_service::FullClient;
 
 /// Sub-commands supported by the operator.
 #[derive(Debug, clap::Subcommand)]
@@ -65,6 +89,10 @@ pub enum Subcommand {
 
     /// The `export-execution-receipt` command used to get the ER from the auxiliary storage of the operator client
     ExportExecutionReceipt(ExportExecutionReceiptCmd),
+
+    /// The `verify-fraud-proof` command used to check a fraud proof against the local consensus
+    /// chain state
+    VerifyFraudProof(VerifyFraudProofCmd),
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -455,6 +483,290 @@ impl ExportExecutionReceiptCmd {
     }
 }
 
+/// The `verify-fraud-proof` command used to check a fraud proof against the local consensus
+/// chain state
+///
+/// The fraud proof is checked natively with the verification functions of `sp-domains-fraud-proof`,
+/// the receipts it targets, the operator signing keys and the rest of the verification info are
+/// read from the local consensus chain database, so the runtime doesn't need to support fraud
+/// proof validation. Whether the challenge period of the domain is over is not checked.
+#[derive(Debug, Clone, Parser)]
+pub struct VerifyFraudProofCmd {
+    /// The hex encoded fraud proof
+    #[arg(long, conflicts_with_all = &["fraud_proof_file"])]
+    pub fraud_proof: Option<String>,
+
+    /// Path to the file that contains the SCALE encoded fraud proof
+    #[arg(long, conflicts_with_all = &["fraud_proof"])]
+    pub fraud_proof_file: Option<PathBuf>,
+
+    /// Verify the fraud proof at the given consensus block number or hash, default to the best
+    /// block
+    #[arg(long)]
+    pub consensus_block: Option<BlockNumberOrHash>,
+
+    /// The base struct of the verify-fraud-proof command.
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+}
+
+impl CliConfiguration for VerifyFraudProofCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+}
+
+impl VerifyFraudProofCmd {
+    /// Run the verify-fraud-proof command
+    pub fn run(
+        &self,
+        consensus_client: Arc<FullClient<RuntimeApi>>,
+        domains_executor: Arc<sc_domains::RuntimeExecutor>,
+    ) -> sc_cli::Result<()> {
+        let encoded_fraud_proof = match (&self.fraud_proof, &self.fraud_proof_file) {
+            (Some(raw_fraud_proof), None) => hex::decode(raw_fraud_proof.trim_start_matches("0x"))
+                .map_err(|error| {
+                    sc_cli::Error::Input(format!("Invalid hex encoded fraud proof: {error}"))
+                })?,
+            (None, Some(fraud_proof_file)) => std::fs::read(fraud_proof_file)?,
+            _ => {
+                return Err(sc_cli::Error::Input(
+                    "Expect the fraud-proof or fraud-proof-file argument".to_string(),
+                ));
+            }
+        };
+        let fraud_proof =
+            FraudProof::<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>::decode(
+                &mut encoded_fraud_proof.as_slice(),
+            )
+            .map_err(|error| {
+                sc_cli::Error::Input(format!("Failed to decode fraud proof: {error}"))
+            })?;
+
+        let consensus_block_hash = match &self.consensus_block {
+            Some(raw_consensus_block) => match raw_consensus_block.parse::<Block>()? {
+                BlockId::Hash(h) => h,
+                BlockId::Number(number) => consensus_client.hash(number)?.ok_or_else(|| {
+                    sp_blockchain::Error::Backend(format!(
+                        "Consensus block hash for #{number:?} not found",
+                    ))
+                })?,
+            },
+            None => consensus_client.info().best_hash,
+        };
+
+        // The verification functions get the verification info through the host functions of
+        // the fraud proof extension, same as the consensus runtime.
+        let mut ext = BasicExternalities::new_empty();
+        ext.register_extension(FraudProofExtension::new(Arc::new(
+            FraudProofHostFunctionsImpl::<_, _, DomainBlock, _, _>::new(
+                consensus_client.clone(),
+                domains_executor,
+                |client, executor| {
+                    let extension_factory =
+                        sc_domains::ExtensionsFactory::<_, Block, DomainBlock, _>::new(
+                            client, executor,
+                        );
+                    Box::new(extension_factory) as Box<dyn ExtensionsFactory<DomainBlock>>
+                },
+            ),
+        )));
+        let verification_result = ext.execute_with(|| {
+            verify_fraud_proof(&consensus_client, consensus_block_hash, &fraud_proof)
+        })?;
+
+        match verification_result {
+            Ok(()) => {
+                println!(
+                    "Fraud proof {:?} is valid at consensus block {consensus_block_hash:?}:\n{fraud_proof:?}",
+                    fraud_proof.hash(),
+                );
+            }
+            Err(error) => {
+                println!(
+                    "Fraud proof {:?} is invalid at consensus block {consensus_block_hash:?}:\n{fraud_proof:?}",
+                    fraud_proof.hash(),
+                );
+                let mut message = format!("Failed check: {}", error.check);
+                if let Some(reason) = error.reason {
+                    message.push_str(&format!(", reason: {reason}"));
+                }
+                return Err(sc_cli::Error::Input(message));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn verification_failed(check: &str, reason: impl Debug) -> FraudProofValidationError {
+    FraudProofValidationError {
+        check: check.to_string(),
+        reason: Some(format!("{reason:?}")),
+    }
+}
+
+fn check_failed(check: &str) -> FraudProofValidationError {
+    FraudProofValidationError {
+        check: check.to_string(),
+        reason: None,
+    }
+}
+
+/// Verify the fraud proof at the given consensus block in the same way as `pallet-domains`, the
+/// failed checks are named after the `FraudProofError` of the pallet.
+///
+/// Must be called with the `FraudProofExtension` registered.
+fn verify_fraud_proof(
+    consensus_client: &FullClient<RuntimeApi>,
+    consensus_block_hash: <Block as BlockT>::Hash,
+    fraud_proof: &FraudProof<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>,
+) -> sp_blockchain::Result<Result<(), FraudProofValidationError>> {
+    let runtime_api = consensus_client.runtime_api();
+    let domain_id = fraud_proof.domain_id();
+
+    if let Some(bad_receipt_hash) = fraud_proof.targeted_bad_receipt_hash() {
+        let Some(bad_receipt) =
+            runtime_api.execution_receipt(consensus_block_hash, bad_receipt_hash)?
+        else {
+            return Ok(Err(check_failed("BadReceiptNotFound")));
+        };
+        if bad_receipt.domain_block_number == 0 {
+            return Ok(Err(check_failed("ChallengingGenesisReceipt")));
+        }
+        if runtime_api.is_bad_er_pending_to_prune(
+            consensus_block_hash,
+            domain_id,
+            bad_receipt_hash,
+        )? {
+            return Ok(Err(check_failed("BadReceiptAlreadyReported")));
+        }
+        let bad_receipt_parent_hash = bad_receipt.parent_domain_block_receipt_hash;
+        let bad_receipt_parent =
+            || runtime_api.execution_receipt(consensus_block_hash, bad_receipt_parent_hash);
+
+        let verification_result = match fraud_proof {
+            FraudProof::InvalidBlockFees(InvalidBlockFeesProof { storage_proof, .. }) => {
+                verify_invalid_block_fees_fraud_proof::<
+                    Block,
+                    HeaderNumberFor<DomainHeader>,
+                    HeaderHashFor<DomainHeader>,
+                    Balance,
+                    BlakeTwo256,
+                >(bad_receipt, storage_proof)
+                .map_err(|err| verification_failed("InvalidBlockFeesFraudProof", err))
+            }
+            FraudProof::InvalidTransfers(proof) => verify_invalid_transfers_fraud_proof::<
+                Block,
+                HeaderNumberFor<DomainHeader>,
+                HeaderHashFor<DomainHeader>,
+                Balance,
+                BlakeTwo256,
+            >(bad_receipt, proof)
+            .map_err(|err| verification_failed("InvalidTransfersFraudProof", err)),
+            FraudProof::InvalidDomainBlockHash(InvalidDomainBlockHashProof {
+                digest_storage_proof,
+                ..
+            }) => {
+                let Some(bad_receipt_parent) = bad_receipt_parent()? else {
+                    return Ok(Err(check_failed("ParentReceiptNotFound")));
+                };
+                verify_invalid_domain_block_hash_fraud_proof::<Block, Balance, DomainHeader>(
+                    bad_receipt,
+                    digest_storage_proof.clone(),
+                    bad_receipt_parent.domain_block_hash,
+                )
+                .map_err(|err| verification_failed("InvalidDomainBlockHashFraudProof", err))
+            }
+            FraudProof::InvalidExtrinsicsRoot(proof) => {
+                verify_invalid_domain_extrinsics_root_fraud_proof::<
+                    Block,
+                    Balance,
+                    BlakeTwo256,
+                    DomainHeader,
+                >(bad_receipt, proof)
+                .map_err(|err| verification_failed("InvalidExtrinsicRootFraudProof", err))
+            }
+            FraudProof::InvalidStateTransition(proof) => {
+                let Some(bad_receipt_parent) = bad_receipt_parent()? else {
+                    return Ok(Err(check_failed("ParentReceiptNotFound")));
+                };
+                verify_invalid_state_transition_fraud_proof::<Block, DomainHeader, Balance>(
+                    bad_receipt,
+                    bad_receipt_parent,
+                    proof,
+                )
+                .map_err(|err| verification_failed("InvalidStateTransitionFraudProof", err))
+            }
+            FraudProof::InvalidBundles(proof) => {
+                let Some(bad_receipt_parent) = bad_receipt_parent()? else {
+                    return Ok(Err(check_failed("ParentReceiptNotFound")));
+                };
+                verify_invalid_bundles_fraud_proof::<Block, DomainHeader, Balance>(
+                    bad_receipt,
+                    bad_receipt_parent,
+                    proof,
+                )
+                .map_err(|err| verification_failed("InvalidBundleFraudProof", err))
+            }
+            FraudProof::ValidBundle(proof) => verify_valid_bundle_fraud_proof::<
+                Block,
+                HeaderNumberFor<DomainHeader>,
+                HeaderHashFor<DomainHeader>,
+                Balance,
+            >(bad_receipt, proof)
+            .map_err(|err| verification_failed("BadValidBundleFraudProof", err)),
+            _ => Err(check_failed("UnexpectedFraudProof")),
+        };
+
+        Ok(verification_result)
+    } else if let Some((bad_operator_id, _)) =
+        fraud_proof.targeted_bad_operator_and_slot_for_bundle_equivocation()
+    {
+        let FraudProof::BundleEquivocation(proof) = fraud_proof else {
+            return Ok(Err(check_failed("UnexpectedFraudProof")));
+        };
+        let Some((signing_key, _)) = runtime_api.operator(consensus_block_hash, bad_operator_id)?
+        else {
+            return Ok(Err(check_failed("MissingOperator")));
+        };
+
+        // The equivocated bundles may be signed with the key that was rotated at the last epoch
+        // transition, which is not exposed by the runtime api thus read from the storage.
+        let mut previous_signing_key_storage_key =
+            frame_support::storage::storage_prefix(b"Domains", b"PreviousOperatorSigningKeys")
+                .to_vec();
+        previous_signing_key_storage_key.extend(domain_id.encode());
+        previous_signing_key_storage_key.extend(bad_operator_id.encode());
+        let previous_signing_key = consensus_client
+            .storage(
+                consensus_block_hash,
+                &StorageKey(previous_signing_key_storage_key),
+            )?
+            .map(|data| <(OperatorPublicKey, SlotNumber)>::decode(&mut data.0.as_slice()))
+            .transpose()
+            .map_err(|error| {
+                sp_blockchain::Error::Backend(format!(
+                    "Failed to decode previous signing key of operator {bad_operator_id}: {error}"
+                ))
+            })?
+            .map(|(previous_signing_key, _rotated_at)| previous_signing_key);
+        let operator_signing_keys = std::iter::once(signing_key)
+            .chain(previous_signing_key)
+            .collect::<Vec<_>>();
+
+        Ok(
+            verify_bundle_equivocation_fraud_proof::<Block, DomainHeader, Balance>(
+                &operator_signing_keys,
+                &proof.first_header,
+                &proof.second_header,
+            )
+            .map_err(|err| verification_failed("BadBundleEquivocationFraudProof", err)),
+        )
+    } else {
+        Ok(Err(check_failed("UnexpectedFraudProof")))
+    }
+}
+
 /// Genesis domain
 pub struct GenesisDomain {
     /// encoded raw genesis
@@ -36,6 +36,7 @@ use sc_service::{Configuration, PartialComponents};
 use serde_json::Value;
 use sp_core::crypto::Ss58AddressFormat;
 use sp_runtime::traits::HashingFor;
+use std::sync::Arc;
 use subspace_proof_of_space::chia::ChiaTable;
 use subspace_runtime::{Block, RuntimeApi};
 use subspace_service::HostFunctions;
@@ -211,6 +212,40 @@ fn main() -> Result<(), Error> {
                 ))
             })?;
         }
+        Cli::ExportSegments(cmd) => {
+            let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+            set_default_ss58_version(runner.config().chain_spec.as_ref());
+            runner.sync_run(|config| {
+                let PartialComponents { client, other, .. } =
+                    subspace_service::new_partial::<PosTable, RuntimeApi>(
+                        &config,
+                        &derive_pot_external_entropy(&config, None)?,
+                    )?;
+
+                cmd.run(
+                    client.as_ref(),
+                    &other.segment_headers_store,
+                    other.subspace_link.kzg().clone(),
+                )
+            })?;
+        }
+        Cli::ImportSegments(cmd) => {
+            let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+            set_default_ss58_version(runner.config().chain_spec.as_ref());
+            runner.sync_run(|config| {
+                let PartialComponents { client, other, .. } =
+                    subspace_service::new_partial::<PosTable, RuntimeApi>(
+                        &config,
+                        &derive_pot_external_entropy(&config, None)?,
+                    )?;
+
+                cmd.run(
+                    client.as_ref(),
+                    &other.segment_headers_store,
+                    other.subspace_link.kzg(),
+                )
+            })?;
+        }
         Cli::Wipe(wipe_options) => {
             commands::wipe(wipe_options).map_err(|error| Error::Other(error.to_string()))?;
         }
@@ -386,6 +421,22 @@ fn main() -> Result<(), Error> {
                     cmd.run(&client, &client)
                 })?;
             }
+            DomainSubcommand::VerifyFraudProof(cmd) => {
+                let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+                set_default_ss58_version(runner.config().chain_spec.as_ref());
+                runner.sync_run(|consensus_chain_config| {
+                    let PartialComponents { client, .. } =
+                        subspace_service::new_partial::<PosTable, RuntimeApi>(
+                            &consensus_chain_config,
+                            &derive_pot_external_entropy(&consensus_chain_config, None)?,
+                        )?;
+                    let domains_executor = sc_service::new_wasm_executor::<DomainsHostFunctions>(
+                        &consensus_chain_config,
+                    );
+
+                    cmd.run(client, Arc::new(domains_executor))
+                })?;
+            }
             _ => unimplemented!("Domain subcommand"),
         },
     }
@@ -39,6 +39,29 @@ fn pos_bench<PosTable>(
 
     #[cfg(feature = "parallel")]
     {
+        // Typical plotting workload: many tables are created concurrently, one per thread
+        let mut generator_instances = [
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+            PosTable::generator(),
+        ];
+        group.bench_function("table/single/8x", |b| {
+            b.iter(|| {
+                rayon::scope(|scope| {
+                    for g in &mut generator_instances {
+                        scope.spawn(|_scope| {
+                            g.generate(black_box(&seed));
+                        });
+                    }
+                });
+            });
+        });
+
         let mut generator_instance = PosTable::generator();
         group.bench_function("table/parallel/1x", |b| {
             b.iter(|| {
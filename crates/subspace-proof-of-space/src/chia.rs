@@ -1,6 +1,8 @@
 //! Chia proof of space implementation
 use crate::chiapos::{Tables, TablesCache};
 use crate::{PosTableType, Table, TableGenerator};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use core::mem;
 use subspace_core_primitives::{PosProof, PosSeed};
 
@@ -71,6 +73,30 @@ impl Table for ChiaTable {
         challenge[..mem::size_of::<u32>()].copy_from_slice(&challenge_index.to_le_bytes());
         Tables::<K>::verify(**seed, &challenge, proof).is_some()
     }
+
+    fn are_proofs_valid<'a, Proofs>(proofs: Proofs) -> Vec<bool>
+    where
+        Proofs: IntoIterator<Item = (&'a PosSeed, u32, &'a PosProof)>,
+    {
+        let proofs = proofs
+            .into_iter()
+            .map(|(seed, challenge_index, proof)| {
+                let mut challenge = [0; 32];
+                challenge[..mem::size_of::<u32>()].copy_from_slice(&challenge_index.to_le_bytes());
+
+                (**seed, challenge, proof)
+            })
+            .collect::<Vec<_>>();
+
+        Tables::<K>::verify_batch(
+            proofs
+                .iter()
+                .map(|(seed, challenge, proof)| (*seed, challenge, &***proof)),
+        )
+        .into_iter()
+        .map(|quality| quality.is_some())
+        .collect()
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +121,13 @@ mod tests {
             let proof = table.find_proof(challenge_index).unwrap();
             assert_eq!(proof, table_parallel.find_proof(challenge_index).unwrap());
             assert!(ChiaTable::is_proof_valid(&seed, challenge_index, &proof));
+            assert_eq!(
+                ChiaTable::are_proofs_valid([
+                    (&seed, challenge_index, &proof),
+                    (&seed, challenge_index + 1, &proof),
+                ]),
+                vec![true, false]
+            );
         }
     }
 }
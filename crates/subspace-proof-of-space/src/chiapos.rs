@@ -7,10 +7,15 @@ mod tables;
 mod tests;
 mod utils;
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 use crate::chiapos::table::metadata_size_bytes;
 pub use crate::chiapos::table::TablesCache;
 use crate::chiapos::tables::TablesGeneric;
 use crate::chiapos::utils::EvaluatableUsize;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 type Seed = [u8; 32];
 type Challenge = [u8; 32];
@@ -83,6 +88,17 @@ impl Tables<$k> {
     ) -> Option<Quality> {
         TablesGeneric::<$k>::verify(seed, challenge, proof_of_space)
     }
+
+    /// Verify multiple proofs of space at once, more efficient than calling [`Self::verify()`] for
+    /// each proof individually, especially when proofs with the same seed follow each other.
+    ///
+    /// Returns quality for each proof in the same order as proofs were provided.
+    pub fn verify_batch<'a, Proofs>(proofs: Proofs) -> Vec<Option<Quality>>
+    where
+        Proofs: IntoIterator<Item = (Seed, &'a Challenge, &'a [u8; 64 * $k as usize / 8])>,
+    {
+        TablesGeneric::<$k>::verify_batch(proofs)
+    }
 }
         )*
     }
@@ -16,6 +16,7 @@ use alloc::vec::Vec;
 use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
 use chacha20::{ChaCha8, Key, Nonce};
 use core::mem;
+use core::ops::Range;
 use core::simd::num::SimdUint;
 use core::simd::Simd;
 #[cfg(any(feature = "parallel", test))]
@@ -26,6 +27,55 @@ use seq_macro::seq;
 use subspace_core_primitives::crypto::{blake3_hash, blake3_hash_list};
 
 pub(super) const COMPUTE_F1_SIMD_FACTOR: usize = 8;
+/// Number of most significant bits of `y` used for splitting entries into buckets before sorting,
+/// which makes sorting of each bucket cache-friendly
+const SORT_BUCKET_BITS: usize = 12;
+
+/// CPU feature level that hot loops of table generation are compiled for, detected at runtime
+/// when `std` feature is enabled and at compile time otherwise.
+///
+/// This is function multiversioning: the same generic code (portable SIMD in
+/// [`compute_f1_simd()`] and scalar matching) is compiled several times with different target
+/// features enabled, which allows compiler to use wider registers and instructions, and the best
+/// version supported by current CPU is picked at runtime. There are no hand-written intrinsics.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum CpuFeatureLevel {
+    /// AVX-512 (F and VL extensions)
+    #[cfg(target_arch = "x86_64")]
+    Avx512,
+    /// AVX2
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    /// Whatever instruction set the crate was compiled for
+    Fallback,
+}
+
+impl CpuFeatureLevel {
+    /// Detect the best feature level supported by current CPU
+    pub(super) fn detect() -> Self {
+        #[cfg(all(target_arch = "x86_64", feature = "std"))]
+        {
+            if std::is_x86_feature_detected!("avx512f") && std::is_x86_feature_detected!("avx512vl")
+            {
+                return Self::Avx512;
+            }
+            if std::is_x86_feature_detected!("avx2") {
+                return Self::Avx2;
+            }
+        }
+        #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+        {
+            if cfg!(all(target_feature = "avx512f", target_feature = "avx512vl")) {
+                return Self::Avx512;
+            }
+            if cfg!(target_feature = "avx2") {
+                return Self::Avx2;
+            }
+        }
+
+        Self::Fallback
+    }
+}
 
 /// Compute the size of `y` in bits
 pub(super) const fn y_size_bits(k: u8) -> usize {
@@ -52,7 +102,7 @@ pub(super) const fn metadata_size_bits(k: u8, table_number: u8) -> usize {
 }
 
 /// ChaCha8 [`Vec`] sufficient for the whole first table for [`K`].
-/// Prefer [`partial_y`] if you need partial y just for a single `x`.
+/// Prefer [`partial_y_with_cipher`] if you need partial y just for a few `x`s.
 fn partial_ys<const K: u8>(seed: Seed) -> Vec<u8> {
     let output_len_bits = usize::from(K) * (1 << K);
     let mut output = vec![0; output_len_bits.div_ceil(u8::BITS as usize)];
@@ -69,10 +119,29 @@ fn partial_ys<const K: u8>(seed: Seed) -> Vec<u8> {
 
 /// ChaCha8 byte for a single `y` at `x` in the first table for [`K`], returns bytes and offset (in
 /// bits) within those bytes at which data start.
-/// Prefer [`partial_ys`] if you process the whole first table.
+/// Prefer [`partial_ys`] if you process the whole first table and [`partial_y_with_cipher`] if you
+/// need partial ys for multiple `x`s with the same seed.
+#[cfg(test)]
 pub(super) fn partial_y<const K: u8>(
     seed: Seed,
     x: X,
+) -> ([u8; (K as usize * 2).div_ceil(u8::BITS as usize)], usize) {
+    partial_y_with_cipher::<K>(&mut seed_cipher(seed), x)
+}
+
+/// ChaCha8 cipher for the first table, can be used with [`partial_y_with_cipher`]
+pub(super) fn seed_cipher(seed: Seed) -> ChaCha8 {
+    let key = Key::from(seed);
+    let nonce = Nonce::default();
+
+    ChaCha8::new(&key, &nonce)
+}
+
+/// Same as [`partial_y`], but reuses cipher created with [`seed_cipher`], which avoids repeated
+/// cipher initialization when partial ys for multiple `x`s with the same seed are needed
+pub(super) fn partial_y_with_cipher<const K: u8>(
+    cipher: &mut ChaCha8,
+    x: X,
 ) -> ([u8; (K as usize * 2).div_ceil(u8::BITS as usize)], usize) {
     let skip_bits = usize::from(K) * usize::from(x);
     let skip_bytes = skip_bits / u8::BITS as usize;
@@ -80,11 +149,6 @@ pub(super) fn partial_y<const K: u8>(
 
     let mut output = [0; (K as usize * 2).div_ceil(u8::BITS as usize)];
 
-    let key = Key::from(seed);
-    let nonce = Nonce::default();
-
-    let mut cipher = ChaCha8::new(&key, &nonce);
-
     cipher.seek(skip_bytes);
     cipher.apply_keystream(&mut output);
 
@@ -192,6 +256,7 @@ pub(super) fn compute_f1<const K: u8>(x: X, partial_y: &[u8], partial_y_offset:
     Y::from((pre_y & pre_y_mask) | (pre_ext & pre_ext_mask))
 }
 
+#[inline(always)]
 pub(super) fn compute_f1_simd<const K: u8>(
     xs: [X; COMPUTE_F1_SIMD_FACTOR],
     partial_ys: &[u8; K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize],
@@ -257,11 +322,162 @@ pub(super) fn compute_f1_simd<const K: u8>(
     unsafe { mem::transmute(ys.to_array()) }
 }
 
+/// Computes `y`s for all `x`s of the first table using instruction set corresponding to
+/// `feature_level`, results are appended to `t_1` in order of `x`s
+fn compute_f1_table<const K: u8>(
+    feature_level: CpuFeatureLevel,
+    partial_ys: &[u8],
+    t_1: &mut Vec<(Y, X)>,
+) where
+    EvaluatableUsize<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>: Sized,
+{
+    match feature_level {
+        // SAFETY: Support for AVX-512 was checked by `CpuFeatureLevel::detect()`
+        #[cfg(target_arch = "x86_64")]
+        CpuFeatureLevel::Avx512 => unsafe { compute_f1_table_avx512::<K>(partial_ys, t_1) },
+        // SAFETY: Support for AVX2 was checked by `CpuFeatureLevel::detect()`
+        #[cfg(target_arch = "x86_64")]
+        CpuFeatureLevel::Avx2 => unsafe { compute_f1_table_avx2::<K>(partial_ys, t_1) },
+        CpuFeatureLevel::Fallback => compute_f1_table_generic::<K>(partial_ys, t_1),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn compute_f1_table_avx512<const K: u8>(partial_ys: &[u8], t_1: &mut Vec<(Y, X)>)
+where
+    EvaluatableUsize<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>: Sized,
+{
+    compute_f1_table_generic::<K>(partial_ys, t_1)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn compute_f1_table_avx2<const K: u8>(partial_ys: &[u8], t_1: &mut Vec<(Y, X)>)
+where
+    EvaluatableUsize<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>: Sized,
+{
+    compute_f1_table_generic::<K>(partial_ys, t_1)
+}
+
+/// Inlined into functions with different target features (see [`CpuFeatureLevel`]), such that
+/// portable SIMD operations are compiled into native instructions of the corresponding instruction
+/// set
+#[inline(always)]
+fn compute_f1_table_generic<const K: u8>(partial_ys: &[u8], t_1: &mut Vec<(Y, X)>)
+where
+    EvaluatableUsize<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>: Sized,
+{
+    for (x_start, partial_ys) in X::all::<K>().step_by(COMPUTE_F1_SIMD_FACTOR).zip(
+        partial_ys
+            .array_chunks::<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>()
+            .copied(),
+    ) {
+        let xs: [_; COMPUTE_F1_SIMD_FACTOR] = seq!(N in 0..8 {
+            [
+            #(
+            #[allow(clippy::erasing_op, clippy::identity_op)]
+            {
+                x_start + X::from(N)
+            },
+            )*
+            ]
+        });
+
+        let ys = compute_f1_simd::<K>(xs, &partial_ys);
+        t_1.extend(ys.into_iter().zip(xs));
+    }
+}
+
+/// Sorts entries in place, producing exactly the same result as [`slice::sort_unstable()`] as long
+/// as `y` (extracted with `entry_y`) is the first element of the `entries` ordering.
+///
+/// Entries are first distributed into buckets by [`SORT_BUCKET_BITS`] most significant bits of `y`
+/// in place (American flag sort), after which each bucket is small enough to be sorted
+/// efficiently. Returns ranges of non-empty buckets that still need to be sorted, this is left to
+/// the caller such that buckets can be sorted in parallel.
+fn distribute_into_buckets<const K: u8, T>(
+    entries: &mut [T],
+    entry_y: impl Fn(&T) -> Y,
+) -> Vec<Range<usize>> {
+    let shift = y_size_bits(K).saturating_sub(SORT_BUCKET_BITS);
+    let bucket_index = |entry: &T| (u32::from(entry_y(entry)) >> shift) as usize;
+
+    let mut bucket_heads = vec![0_usize; 1 << SORT_BUCKET_BITS];
+    for entry in entries.iter() {
+        bucket_heads[bucket_index(entry)] += 1;
+    }
+
+    // Convert sizes into start offsets and remember ranges of all buckets
+    let mut bucket_ranges = Vec::with_capacity(bucket_heads.len());
+    let mut offset = 0;
+    for bucket_head in &mut bucket_heads {
+        let size = *bucket_head;
+        *bucket_head = offset;
+        bucket_ranges.push(offset..offset + size);
+        offset += size;
+    }
+
+    for (bucket, bucket_range) in bucket_ranges.iter().enumerate() {
+        while bucket_heads[bucket] < bucket_range.end {
+            // Keep swapping entries at the head of this bucket into buckets they belong to until
+            // head contains an entry that belongs to this bucket
+            loop {
+                let destination_bucket = bucket_index(&entries[bucket_heads[bucket]]);
+                if destination_bucket == bucket {
+                    break;
+                }
+                entries.swap(bucket_heads[bucket], bucket_heads[destination_bucket]);
+                bucket_heads[destination_bucket] += 1;
+            }
+            bucket_heads[bucket] += 1;
+        }
+    }
+
+    bucket_ranges.retain(|bucket_range| bucket_range.len() > 1);
+    bucket_ranges
+}
+
+/// Sort entries by `y` using bucketed in-place sorting, see [`distribute_into_buckets()`]
+fn sort_by_buckets<const K: u8, T>(entries: &mut [T], entry_y: impl Fn(&T) -> Y)
+where
+    T: Ord,
+{
+    for bucket_range in distribute_into_buckets::<K, _>(entries, entry_y) {
+        entries[bucket_range].sort_unstable();
+    }
+}
+
+/// Same as [`sort_by_buckets()`], but buckets are sorted in parallel
+#[cfg(any(feature = "parallel", test))]
+fn par_sort_by_buckets<const K: u8, T>(entries: &mut [T], entry_y: impl Fn(&T) -> Y)
+where
+    T: Ord + Send,
+{
+    let bucket_ranges = distribute_into_buckets::<K, _>(entries, entry_y);
+
+    let mut buckets = Vec::with_capacity(bucket_ranges.len());
+    let mut remaining = entries;
+    let mut offset = 0;
+    for bucket_range in bucket_ranges {
+        let (_skipped, rest) = remaining.split_at_mut(bucket_range.start - offset);
+        let (bucket, rest) = rest.split_at_mut(bucket_range.len());
+        buckets.push(bucket);
+        remaining = rest;
+        offset = bucket_range.end;
+    }
+
+    buckets.into_par_iter().for_each(|bucket| {
+        bucket.sort_unstable();
+    });
+}
+
 /// `rmap_scratch` is just an optimization to reuse allocations between calls.
 ///
 /// For verification purposes use [`num_matches`] instead.
 ///
 /// Returns `None` if either of buckets is empty.
+#[inline(always)]
 fn find_matches<'a>(
     left_bucket_ys: &'a [Y],
     left_bucket_start_position: Position,
@@ -451,7 +667,120 @@ where
     (y, [m.left_position, m.right_position], metadata)
 }
 
-fn match_and_compute_fn<'a, const K: u8, const TABLE_NUMBER: u8, const PARENT_TABLE_NUMBER: u8>(
+/// Finds matches between two buckets and computes entries of the next table for them using
+/// instruction set corresponding to `feature_level`, results are appended to `results_table`
+#[allow(clippy::too_many_arguments)]
+fn match_and_compute_fn<const K: u8, const TABLE_NUMBER: u8, const PARENT_TABLE_NUMBER: u8>(
+    feature_level: CpuFeatureLevel,
+    last_table: &Table<K, PARENT_TABLE_NUMBER>,
+    left_bucket: Bucket,
+    right_bucket: Bucket,
+    rmap_scratch: &mut Vec<RmapItem>,
+    left_targets: &[Vec<Vec<Position>>],
+    results_table: &mut Vec<(Y, [Position; 2], Metadata<K, TABLE_NUMBER>)>,
+) where
+    EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
+    EvaluatableUsize<{ metadata_size_bytes(K, TABLE_NUMBER) }>: Sized,
+{
+    match feature_level {
+        // SAFETY: Support for AVX-512 was checked by `CpuFeatureLevel::detect()`
+        #[cfg(target_arch = "x86_64")]
+        CpuFeatureLevel::Avx512 => unsafe {
+            match_and_compute_fn_avx512(
+                last_table,
+                left_bucket,
+                right_bucket,
+                rmap_scratch,
+                left_targets,
+                results_table,
+            )
+        },
+        // SAFETY: Support for AVX2 was checked by `CpuFeatureLevel::detect()`
+        #[cfg(target_arch = "x86_64")]
+        CpuFeatureLevel::Avx2 => unsafe {
+            match_and_compute_fn_avx2(
+                last_table,
+                left_bucket,
+                right_bucket,
+                rmap_scratch,
+                left_targets,
+                results_table,
+            )
+        },
+        CpuFeatureLevel::Fallback => match_and_compute_fn_generic(
+            last_table,
+            left_bucket,
+            right_bucket,
+            rmap_scratch,
+            left_targets,
+            results_table,
+        ),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn match_and_compute_fn_avx512<
+    const K: u8,
+    const TABLE_NUMBER: u8,
+    const PARENT_TABLE_NUMBER: u8,
+>(
+    last_table: &Table<K, PARENT_TABLE_NUMBER>,
+    left_bucket: Bucket,
+    right_bucket: Bucket,
+    rmap_scratch: &mut Vec<RmapItem>,
+    left_targets: &[Vec<Vec<Position>>],
+    results_table: &mut Vec<(Y, [Position; 2], Metadata<K, TABLE_NUMBER>)>,
+) where
+    EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
+    EvaluatableUsize<{ metadata_size_bytes(K, TABLE_NUMBER) }>: Sized,
+{
+    match_and_compute_fn_generic(
+        last_table,
+        left_bucket,
+        right_bucket,
+        rmap_scratch,
+        left_targets,
+        results_table,
+    )
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn match_and_compute_fn_avx2<
+    const K: u8,
+    const TABLE_NUMBER: u8,
+    const PARENT_TABLE_NUMBER: u8,
+>(
+    last_table: &Table<K, PARENT_TABLE_NUMBER>,
+    left_bucket: Bucket,
+    right_bucket: Bucket,
+    rmap_scratch: &mut Vec<RmapItem>,
+    left_targets: &[Vec<Vec<Position>>],
+    results_table: &mut Vec<(Y, [Position; 2], Metadata<K, TABLE_NUMBER>)>,
+) where
+    EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
+    EvaluatableUsize<{ metadata_size_bytes(K, TABLE_NUMBER) }>: Sized,
+{
+    match_and_compute_fn_generic(
+        last_table,
+        left_bucket,
+        right_bucket,
+        rmap_scratch,
+        left_targets,
+        results_table,
+    )
+}
+
+/// Inlined into functions with different target features (see [`CpuFeatureLevel`]), such that
+/// matching is auto-vectorized with the corresponding instruction set where possible
+#[inline(always)]
+fn match_and_compute_fn_generic<
+    'a,
+    const K: u8,
+    const TABLE_NUMBER: u8,
+    const PARENT_TABLE_NUMBER: u8,
+>(
     last_table: &'a Table<K, PARENT_TABLE_NUMBER>,
     left_bucket: Bucket,
     right_bucket: Bucket,
@@ -515,27 +844,10 @@ where
         let partial_ys = partial_ys::<K>(seed);
 
         let mut t_1 = Vec::with_capacity(1_usize << K);
-        for (x_start, partial_ys) in X::all::<K>().step_by(COMPUTE_F1_SIMD_FACTOR).zip(
-            partial_ys
-                .array_chunks::<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>()
-                .copied(),
-        ) {
-            let xs: [_; COMPUTE_F1_SIMD_FACTOR] = seq!(N in 0..8 {
-                [
-                #(
-                #[allow(clippy::erasing_op, clippy::identity_op)]
-                {
-                    x_start + X::from(N)
-                },
-                )*
-                ]
-            });
+        compute_f1_table::<K>(CpuFeatureLevel::detect(), &partial_ys, &mut t_1);
+        drop(partial_ys);
 
-            let ys = compute_f1_simd::<K>(xs, &partial_ys);
-            t_1.extend(ys.into_iter().zip(xs));
-        }
-
-        t_1.sort_unstable();
+        sort_by_buckets::<K, _>(&mut t_1, |&(y, _x)| y);
 
         let (ys, xs) = t_1.into_iter().unzip();
 
@@ -551,27 +863,10 @@ where
         let partial_ys = partial_ys::<K>(seed);
 
         let mut t_1 = Vec::with_capacity(1_usize << K);
-        for (x_start, partial_ys) in X::all::<K>().step_by(COMPUTE_F1_SIMD_FACTOR).zip(
-            partial_ys
-                .array_chunks::<{ K as usize * COMPUTE_F1_SIMD_FACTOR / u8::BITS as usize }>()
-                .copied(),
-        ) {
-            let xs: [_; COMPUTE_F1_SIMD_FACTOR] = seq!(N in 0..8 {
-                [
-                #(
-                #[allow(clippy::erasing_op, clippy::identity_op)]
-                {
-                    x_start + X::from(N)
-                },
-                )*
-                ]
-            });
+        compute_f1_table::<K>(CpuFeatureLevel::detect(), &partial_ys, &mut t_1);
+        drop(partial_ys);
 
-            let ys = compute_f1_simd::<K>(xs, &partial_ys);
-            t_1.extend(ys.into_iter().zip(xs));
-        }
-
-        t_1.par_sort_unstable();
+        par_sort_by_buckets::<K, _>(&mut t_1, |&(y, _x)| y);
 
         let (ys, xs) = t_1.into_iter().unzip();
 
@@ -637,6 +932,7 @@ where
     where
         EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
     {
+        let feature_level = CpuFeatureLevel::detect();
         let buckets = &mut cache.buckets;
         let rmap_scratch = &mut cache.rmap_scratch;
         let left_targets = &cache.left_targets;
@@ -682,6 +978,7 @@ where
             .array_windows::<2>()
             .for_each(|&[left_bucket, right_bucket]| {
                 match_and_compute_fn::<K, TABLE_NUMBER, PARENT_TABLE_NUMBER>(
+                    feature_level,
                     last_table,
                     left_bucket,
                     right_bucket,
@@ -691,7 +988,7 @@ where
                 );
             });
 
-        t_n.sort_unstable();
+        sort_by_buckets::<K, _>(&mut t_n, |&(y, ..)| y);
 
         let mut ys = Vec::with_capacity(t_n.len());
         let mut positions = Vec::with_capacity(t_n.len());
@@ -725,6 +1022,7 @@ where
     where
         EvaluatableUsize<{ metadata_size_bytes(K, PARENT_TABLE_NUMBER) }>: Sized,
     {
+        let feature_level = CpuFeatureLevel::detect();
         let buckets = &mut cache.buckets;
         let left_targets = &cache.left_targets;
 
@@ -776,6 +1074,7 @@ where
                 }
 
                 match_and_compute_fn::<K, TABLE_NUMBER, PARENT_TABLE_NUMBER>(
+                    feature_level,
                     last_table,
                     buckets[offset],
                     buckets[offset + 1],
@@ -788,8 +1087,16 @@ where
             entries
         });
 
-        let mut t_n = t_n.into_iter().flatten().collect::<Vec<_>>();
-        t_n.par_sort_unstable();
+        // Move entries produced by each thread into a single vector, freeing memory of each thread's
+        // vector as soon as possible to reduce peak memory usage
+        let mut t_n = {
+            let mut t_n_merged = Vec::with_capacity(t_n.iter().map(Vec::len).sum());
+            for mut t_n_part in t_n {
+                t_n_merged.append(&mut t_n_part);
+            }
+            t_n_merged
+        };
+        par_sort_by_buckets::<K, _>(&mut t_n, |&(y, ..)| y);
 
         let mut ys = Vec::with_capacity(t_n.len());
         let mut positions = Vec::with_capacity(t_n.len());
@@ -4,8 +4,9 @@
 use crate::chiapos::constants::{PARAM_B, PARAM_BC, PARAM_C, PARAM_EXT};
 use crate::chiapos::table::types::{Metadata, Position, X, Y};
 use crate::chiapos::table::{
-    calculate_left_targets, compute_f1, compute_f1_simd, compute_fn, find_matches,
-    metadata_size_bytes, partial_y, COMPUTE_F1_SIMD_FACTOR,
+    calculate_left_targets, compute_f1, compute_f1_simd, compute_f1_table, compute_fn,
+    find_matches, metadata_size_bytes, par_sort_by_buckets, partial_y, partial_ys, sort_by_buckets,
+    y_size_bits, CpuFeatureLevel, COMPUTE_F1_SIMD_FACTOR,
 };
 use crate::chiapos::utils::EvaluatableUsize;
 use crate::chiapos::Seed;
@@ -252,3 +253,57 @@ fn test_verify_fn() {
     verify_fn::<K, 7, 6>(0x5fec898f, 0x82283d15, 0x14f410, 0x24c3c2, 0x0);
     verify_fn::<K, 7, 6>(0x64ac5db9, 0x7923986, 0x590fd, 0x1c74a2, 0x0);
 }
+
+#[test]
+fn test_compute_f1_table_feature_levels() {
+    const K: u8 = 17;
+    let seed = to_chia_seed(&[
+        0, 2, 3, 4, 5, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 1, 2, 3, 41, 5, 6, 7, 8, 9, 10, 11,
+        12, 13, 11, 15, 16,
+    ]);
+    let partial_ys = partial_ys::<K>(seed);
+
+    let mut expected_t_1 = Vec::new();
+    compute_f1_table::<K>(CpuFeatureLevel::Fallback, &partial_ys, &mut expected_t_1);
+    assert_eq!(expected_t_1.len(), 1 << K);
+
+    // Spot-check against non-SIMD version
+    for &(y, x) in expected_t_1.iter().step_by(1001) {
+        let (partial_y, partial_y_offset) = partial_y::<K>(seed, x);
+        assert_eq!(y, compute_f1::<K>(x, &partial_y, partial_y_offset));
+    }
+
+    let feature_level = CpuFeatureLevel::detect();
+    let mut t_1 = Vec::new();
+    compute_f1_table::<K>(feature_level, &partial_ys, &mut t_1);
+    assert_eq!(t_1, expected_t_1, "{feature_level:?} must match fallback");
+}
+
+#[test]
+fn test_sort_by_buckets() {
+    const K: u8 = 17;
+
+    let mut entries = (0..1_u32 << K)
+        .map(|position| {
+            (
+                Y::from(rand::random::<u32>() >> (u32::BITS as usize - y_size_bits(K))),
+                // Small range of values to make sure ties in `y` are broken the same way too
+                Position::from(rand::random::<u32>() % 4),
+                Position::from(position),
+            )
+        })
+        .collect::<Vec<_>>();
+    // Also include duplicates
+    entries.extend_from_within(..1000);
+
+    let mut expected_entries = entries.clone();
+    expected_entries.sort_unstable();
+
+    let mut sorted_entries = entries.clone();
+    sort_by_buckets::<K, _>(&mut sorted_entries, |&(y, ..)| y);
+    assert_eq!(sorted_entries, expected_entries);
+
+    let mut sorted_entries = entries;
+    par_sort_by_buckets::<K, _>(&mut sorted_entries, |&(y, ..)| y);
+    assert_eq!(sorted_entries, expected_entries);
+}
@@ -7,13 +7,16 @@ extern crate alloc;
 use crate::chiapos::table::types::{Metadata, Position, X, Y};
 pub use crate::chiapos::table::TablesCache;
 use crate::chiapos::table::{
-    compute_f1, compute_fn, metadata_size_bytes, num_matches, partial_y, Table,
-    COMPUTE_F1_SIMD_FACTOR,
+    compute_f1, compute_fn, metadata_size_bytes, num_matches, partial_y_with_cipher, seed_cipher,
+    Table, COMPUTE_F1_SIMD_FACTOR,
 };
 use crate::chiapos::utils::EvaluatableUsize;
 use crate::chiapos::{Challenge, Quality, Seed};
 #[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
+use chacha20::ChaCha8;
 use core::mem;
 use sha2::{Digest, Sha256};
 
@@ -283,6 +286,63 @@ where
         challenge: &Challenge,
         proof_of_space: &[u8; 64 * K as usize / 8],
     ) -> Option<Quality>
+    where
+        EvaluatableUsize<{ (K as usize * 2).div_ceil(u8::BITS as usize) }>: Sized,
+    {
+        Self::verify_with_cipher(
+            &mut seed_cipher(seed),
+            challenge,
+            proof_of_space,
+            &mut Vec::with_capacity(64),
+        )
+    }
+
+    /// Verify multiple proofs of space at once.
+    ///
+    /// More efficient than calling [`Self::verify()`] for each proof individually: intermediate
+    /// buffers are reused between proofs and proofs are grouped by seed, such that cipher is
+    /// initialized once per unique seed rather than once per proof. Seeds are derived from sector
+    /// and piece, so only proofs for the same piece (like the same piece audited in different
+    /// slots) share the cipher.
+    ///
+    /// Returns quality for each proof in the same order as proofs were provided, `None` for proofs
+    /// that failed verification.
+    pub(super) fn verify_batch<'a, Proofs>(proofs: Proofs) -> Vec<Option<Quality>>
+    where
+        Proofs: IntoIterator<Item = (Seed, &'a Challenge, &'a [u8; 64 * K as usize / 8])>,
+        EvaluatableUsize<{ (K as usize * 2).div_ceil(u8::BITS as usize) }>: Sized,
+    {
+        let proofs = proofs.into_iter().collect::<Vec<_>>();
+        // Verify proofs with the same seed one after another regardless of their position in the
+        // batch
+        let mut proof_indices = (0..proofs.len()).collect::<Vec<_>>();
+        proof_indices.sort_unstable_by_key(|&proof_index| proofs[proof_index].0);
+
+        let mut qualities = vec![None; proofs.len()];
+        let mut cipher = None::<(Seed, ChaCha8)>;
+        let mut ys_and_metadata = Vec::with_capacity(64);
+
+        for proof_index in proof_indices {
+            let (seed, challenge, proof_of_space) = proofs[proof_index];
+            let cipher = match &mut cipher {
+                Some((cipher_seed, cipher)) if *cipher_seed == seed => cipher,
+                cipher => &mut cipher.insert((seed, seed_cipher(seed))).1,
+            };
+
+            qualities[proof_index] =
+                Self::verify_with_cipher(cipher, challenge, proof_of_space, &mut ys_and_metadata);
+        }
+
+        qualities
+    }
+
+    /// `ys_and_metadata` is just an optimization to reuse allocations between calls
+    fn verify_with_cipher(
+        cipher: &mut ChaCha8,
+        challenge: &Challenge,
+        proof_of_space: &[u8; 64 * K as usize / 8],
+        ys_and_metadata: &mut Vec<(Y, Metadata<K, 1>)>,
+    ) -> Option<Quality>
     where
         EvaluatableUsize<{ (K as usize * 2).div_ceil(u8::BITS as usize) }>: Sized,
     {
@@ -293,30 +353,29 @@ where
                 .expect("Challenge is known to statically have enough bytes; qed"),
         ) >> (u32::BITS as usize - usize::from(K));
 
-        let ys_and_metadata = (0..64_usize)
-            .map(|offset| {
-                let mut pre_x_bytes = 0u64.to_be_bytes();
-                let offset_in_bits = usize::from(K) * offset;
-                let bytes_to_copy = (offset_in_bits % u8::BITS as usize + usize::from(K))
-                    .div_ceil(u8::BITS as usize);
-                // Copy full bytes that contain bits of `x`
-                pre_x_bytes[..bytes_to_copy].copy_from_slice(
-                    &proof_of_space[offset_in_bits / u8::BITS as usize..][..bytes_to_copy],
-                );
-                // Extract `pre_x` whose last `K` bits start with `x`
-                let pre_x = u64::from_be_bytes(pre_x_bytes)
-                    >> (u64::BITS as usize - (usize::from(K) + offset_in_bits % u8::BITS as usize));
-                // Convert to desired type and clear extra bits
-                let x = X::from(pre_x as u32 & (u32::MAX >> (u32::BITS as usize - usize::from(K))));
+        ys_and_metadata.clear();
+        ys_and_metadata.extend((0..64_usize).map(|offset| {
+            let mut pre_x_bytes = 0u64.to_be_bytes();
+            let offset_in_bits = usize::from(K) * offset;
+            let bytes_to_copy =
+                (offset_in_bits % u8::BITS as usize + usize::from(K)).div_ceil(u8::BITS as usize);
+            // Copy full bytes that contain bits of `x`
+            pre_x_bytes[..bytes_to_copy].copy_from_slice(
+                &proof_of_space[offset_in_bits / u8::BITS as usize..][..bytes_to_copy],
+            );
+            // Extract `pre_x` whose last `K` bits start with `x`
+            let pre_x = u64::from_be_bytes(pre_x_bytes)
+                >> (u64::BITS as usize - (usize::from(K) + offset_in_bits % u8::BITS as usize));
+            // Convert to desired type and clear extra bits
+            let x = X::from(pre_x as u32 & (u32::MAX >> (u32::BITS as usize - usize::from(K))));
 
-                let (partial_y, partial_y_offset) = partial_y::<K>(seed, x);
-                let y = compute_f1::<K>(x, &partial_y, partial_y_offset);
+            let (partial_y, partial_y_offset) = partial_y_with_cipher::<K>(cipher, x);
+            let y = compute_f1::<K>(x, &partial_y, partial_y_offset);
 
-                (y, Metadata::from(x))
-            })
-            .collect::<Vec<_>>();
+            (y, Metadata::from(x))
+        }));
 
-        Self::collect_ys_and_metadata::<2, 1>(&ys_and_metadata)
+        Self::collect_ys_and_metadata::<2, 1>(ys_and_metadata)
             .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<3, 2>(&ys_and_metadata))
             .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<4, 3>(&ys_and_metadata))
             .and_then(|ys_and_metadata| Self::collect_ys_and_metadata::<5, 4>(&ys_and_metadata))
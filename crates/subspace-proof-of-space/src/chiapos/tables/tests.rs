@@ -8,6 +8,7 @@ fn self_verification() {
     let seed = [1; 32];
     let tables = Tables::<K>::create_simple(seed);
     let tables_parallel = Tables::<K>::create_parallel(seed, &mut TablesCache::default());
+    let mut batch = Vec::new();
 
     for challenge_index in 0..1000_u32 {
         let mut challenge = [0; 32];
@@ -38,6 +39,38 @@ fn self_verification() {
                 Tables::<K>::verify(seed, &bad_challenge, proof).is_none(),
                 "challenge index {challenge_index}"
             );
+
+            batch.push((challenge, *proof, Some(quality)));
+            batch.push((bad_challenge, *proof, None));
         }
     }
+
+    assert!(!batch.is_empty());
+    let other_seed = [2; 32];
+    // Proof must not be valid for a different seed, proofs with different seeds are interleaved
+    // to make sure results are returned in the original order after grouping by seed
+    let qualities = Tables::<K>::verify_batch(
+        batch
+            .first()
+            .map(|(challenge, proof, _quality)| (other_seed, challenge, proof))
+            .into_iter()
+            .chain(
+                batch
+                    .iter()
+                    .map(|(challenge, proof, _quality)| (seed, challenge, proof)),
+            )
+            .chain(
+                batch
+                    .last()
+                    .map(|(challenge, proof, _quality)| (other_seed, challenge, proof)),
+            ),
+    );
+    assert_eq!(
+        qualities,
+        [None]
+            .into_iter()
+            .chain(batch.iter().map(|(_challenge, _proof, quality)| *quality))
+            .chain([None])
+            .collect::<Vec<_>>()
+    );
 }
@@ -5,6 +5,7 @@
 #![warn(rust_2018_idioms, missing_debug_implementations, missing_docs)]
 #![feature(
     array_chunks,
+    avx512_target_feature,
     array_windows,
     const_trait_impl,
     generic_const_exprs,
@@ -17,6 +18,11 @@ pub mod chia;
 pub mod chiapos;
 pub mod shim;
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use core::fmt;
 use subspace_core_primitives::{PosProof, PosSeed};
 
@@ -73,6 +79,23 @@ pub trait Table: Sized + Send + Sync + 'static {
     /// Check whether proof created earlier is valid and return quality bytes if yes
     fn is_proof_valid(seed: &PosSeed, challenge_index: u32, proof: &PosProof) -> bool;
 
+    /// Check whether multiple proofs created earlier are valid at once, returns validity of each
+    /// proof in the same order as proofs were provided.
+    ///
+    /// Implementations amortize work shared between proofs, so this should be preferred over
+    /// calling [`Self::is_proof_valid()`] for each proof individually.
+    fn are_proofs_valid<'a, Proofs>(proofs: Proofs) -> Vec<bool>
+    where
+        Proofs: IntoIterator<Item = (&'a PosSeed, u32, &'a PosProof)>,
+    {
+        proofs
+            .into_iter()
+            .map(|(seed, challenge_index, proof)| {
+                Self::is_proof_valid(seed, challenge_index, proof)
+            })
+            .collect()
+    }
+
     /// Returns a stateful table generator with better performance
     fn generator() -> Self::Generator {
         Self::Generator::default()
@@ -1,5 +1,7 @@
-use crate::{Runtime, RuntimeCall, RuntimeConfigs};
+use crate::{Runtime, RuntimeCall, RuntimeConfigs, SudoCalls};
 use codec::{Decode, Encode};
+use frame_support::traits::Contains;
+use pallet_runtime_configs::{CallPermission, CallPermissionsFilter};
 use scale_info::TypeInfo;
 use sp_runtime::traits::{DispatchInfoOf, SignedExtension};
 use sp_runtime::transaction_validity::{
@@ -70,7 +72,7 @@ impl SignedExtension for DisablePallets {
         _info: &DispatchInfoOf<Self::Call>,
         _len: usize,
     ) -> TransactionValidity {
-        // Disable normal balance transfers.
+        // Disable normal balance transfers, unless explicitly allowed by the call permissions.
         if matches!(
             call,
             RuntimeCall::Balances(
@@ -79,6 +81,7 @@ impl SignedExtension for DisablePallets {
                     | pallet_balances::Call::transfer_all { .. }
             )
         ) && !RuntimeConfigs::enable_balance_transfers()
+            && RuntimeConfigs::call_permission(call) != Some(CallPermission::Allow)
         {
             InvalidTransaction::Call.into()
         } else {
@@ -109,3 +112,48 @@ impl SignedExtension for DisablePallets {
         }
     }
 }
+
+/// Rejects denied calls according to the call permissions in `pallet-runtime-configs` early, when
+/// entering the transaction pool.
+///
+/// Only the outer call is checked here, the actual enforcement (including the calls nested in
+/// other calls) is done by the base call filter of the runtime.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Default, TypeInfo)]
+pub struct CheckCallPermissions;
+
+impl SignedExtension for CheckCallPermissions {
+    const IDENTIFIER: &'static str = "CheckCallPermissions";
+    type AccountId = <Runtime as frame_system::Config>::AccountId;
+    type Call = <Runtime as frame_system::Config>::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        _who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        if !CallPermissionsFilter::<Runtime, SudoCalls>::contains(call) {
+            InvalidTransaction::Call.into()
+        } else {
+            Ok(ValidTransaction::default())
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        self.validate(who, call, info, len)?;
+        Ok(())
+    }
+}
@@ -14,10 +14,12 @@ use sc_service::{
 };
 use sc_telemetry::TelemetryEndpoints;
 use std::collections::HashSet;
+use std::fmt;
 use std::net::SocketAddr;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use subspace_networking::libp2p::Multiaddr;
@@ -251,6 +253,38 @@ pub enum SubspaceNetworking {
     },
 }
 
+/// Syncing mode of the consensus chain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSyncMode {
+    /// Full sync, download and import all blocks starting from genesis.
+    #[default]
+    Full,
+    /// Snap sync, download the last archived blocks and state at the last of them from the network
+    /// and continue syncing from there.
+    Snap,
+}
+
+impl FromStr for ChainSyncMode {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "full" => Ok(Self::Full),
+            "snap" => Ok(Self::Snap),
+            _ => Err("Unsupported sync mode, supported modes: full, snap".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ChainSyncMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => f.write_str("full"),
+            Self::Snap => f.write_str("snap"),
+        }
+    }
+}
+
 /// Subspace-specific service configuration.
 #[derive(Debug)]
 pub struct SubspaceConfiguration {
@@ -265,6 +299,11 @@ pub struct SubspaceConfiguration {
     pub dsn_piece_getter: Option<Arc<dyn DsnSyncPieceGetter + Send + Sync + 'static>>,
     /// Enables DSN-sync on startup.
     pub sync_from_dsn: bool,
+    /// Sync mode of the consensus chain
+    pub sync: ChainSyncMode,
+    /// Directory with imported segment archives that DSN sync will use before going to the
+    /// network and that pieces are served from to other DSN peers
+    pub segment_archives_path: Option<PathBuf>,
     /// Is this node a Timekeeper
     pub is_timekeeper: bool,
     /// CPU cores that timekeeper can use
@@ -1,18 +1,20 @@
+use crate::segment_archive::SegmentArchives;
 use prometheus_client::registry::Registry;
 use std::collections::HashSet;
 use std::fs;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::sync::Arc;
 use subspace_networking::libp2p::kad::Mode;
 use subspace_networking::libp2p::{identity, Multiaddr};
 use subspace_networking::utils::strip_peer_id;
 use subspace_networking::{
     CreationError, KademliaMode, KnownPeersManager, KnownPeersManagerConfig,
-    KnownPeersManagerPersistenceError, Node, NodeRunner, PieceByIndexRequestHandler,
-    SegmentHeaderBySegmentIndexesRequestHandler,
+    KnownPeersManagerPersistenceError, Node, NodeRunner, PieceByIndexRequest,
+    PieceByIndexRequestHandler, PieceByIndexResponse, SegmentHeaderBySegmentIndexesRequestHandler,
 };
 use thiserror::Error;
-use tracing::{error, trace};
+use tracing::{debug, error, trace};
 
 /// Size of the LRU cache for peers.
 pub const KNOWN_PEERS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(100).expect("Not zero; qed");
@@ -68,6 +70,7 @@ pub struct DsnConfig {
 pub(crate) fn create_dsn_instance(
     dsn_protocol_version: String,
     dsn_config: DsnConfig,
+    segment_archives: Option<Arc<SegmentArchives>>,
     prometheus_registry: Option<&mut Registry>,
 ) -> Result<(Node, NodeRunner<()>), DsnConfigurationError> {
     trace!("Subspace networking starting.");
@@ -103,8 +106,28 @@ pub(crate) fn create_dsn_instance(
         allow_non_global_addresses_in_dht: dsn_config.allow_non_global_addresses_in_dht,
         networking_parameters_registry,
         request_response_protocols: vec![
-            // We need to enable protocol to request pieces
-            PieceByIndexRequestHandler::create(|_, _| async { None }),
+            // We need to enable protocol to request pieces, pieces from imported segment archives
+            // are also served to other peers
+            PieceByIndexRequestHandler::create(move |_, &PieceByIndexRequest { piece_index }| {
+                let segment_archives = segment_archives.clone();
+
+                async move {
+                    let segment_archives = segment_archives?;
+                    match segment_archives.read_piece(piece_index).await {
+                        Ok(maybe_piece) => {
+                            maybe_piece.map(|piece| PieceByIndexResponse { piece: Some(piece) })
+                        }
+                        Err(error) => {
+                            debug!(
+                                %piece_index,
+                                %error,
+                                "Failed to read piece from segment archives"
+                            );
+                            None
+                        }
+                    }
+                }
+            }),
             SegmentHeaderBySegmentIndexesRequestHandler::create(move |_, _| async move { None }),
         ],
         max_established_incoming_connections: dsn_config.max_in_connections,
@@ -29,17 +29,21 @@ pub mod config;
 pub mod dsn;
 mod metrics;
 pub mod rpc;
+pub mod segment_archive;
 pub mod sync_from_dsn;
 pub mod transaction_pool;
 
 use crate::config::{SubspaceConfiguration, SubspaceNetworking};
 use crate::dsn::{create_dsn_instance, DsnConfigurationError};
 use crate::metrics::NodeMetrics;
+use crate::segment_archive::{SegmentArchiveError, SegmentArchivePieceGetter, SegmentArchives};
 use crate::sync_from_dsn::piece_validator::SegmentCommitmentPieceValidator;
+use crate::sync_from_dsn::DsnSyncPieceGetter;
 use crate::transaction_pool::FullPool;
 use core::sync::atomic::{AtomicU32, Ordering};
 use cross_domain_message_gossip::xdm_gossip_peers_set_config;
 use domain_runtime_primitives::opaque::{Block as DomainBlock, Header as DomainHeader};
+use domain_runtime_primitives::BlockNumber as DomainNumber;
 use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::channel::oneshot;
 use futures::FutureExt;
@@ -66,6 +70,7 @@ use sc_consensus_subspace::slot_worker::{
     NewSlotNotification, RewardSigningNotification, SubspaceSlotWorker, SubspaceSlotWorkerOptions,
     SubspaceSyncOracle,
 };
+use sc_consensus_subspace::solution_verifier::SolutionVerifier;
 use sc_consensus_subspace::verifier::{SubspaceVerifier, SubspaceVerifierOptions};
 use sc_consensus_subspace::SubspaceLink;
 use sc_domains::ExtensionsFactory as DomainsExtensionFactory;
@@ -92,7 +97,7 @@ use sp_core::offchain::storage::OffchainDb;
 use sp_core::offchain::OffchainDbExt;
 use sp_core::traits::SpawnEssentialNamed;
 use sp_core::H256;
-use sp_domains::{BundleProducerElectionApi, DomainsApi};
+use sp_domains::{BundleProducerElectionApi, DomainStakingApi, DomainsApi};
 use sp_domains_fraud_proof::{FraudProofApi, FraudProofExtension, FraudProofHostFunctionsImpl};
 use sp_externalities::Extensions;
 use sp_messenger::MessengerApi;
@@ -103,6 +108,7 @@ use sp_offchain::OffchainWorkerApi;
 use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header, NumberFor, Zero};
 use sp_session::SessionKeys;
 use sp_subspace_mmr::host_functions::{SubspaceMmrExtension, SubspaceMmrHostFunctionsImpl};
+use sp_transaction_fees::TransactionFeesApi;
 use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use static_assertions::const_assert;
 use std::marker::PhantomData;
@@ -159,6 +165,10 @@ pub enum Error {
     #[error(transparent)]
     BlockRelay(#[from] BlockRelayConfigurationError),
 
+    /// Segment archive error.
+    #[error(transparent)]
+    SegmentArchive(#[from] SegmentArchiveError),
+
     /// Other.
     #[error(transparent)]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -523,6 +533,8 @@ where
 
     let subspace_link = SubspaceLink::new(chain_constants, kzg.clone());
     let segment_headers_store = segment_headers_store.clone();
+    let solution_verifier = SolutionVerifier::new::<PosTable>(kzg)
+        .map_err(|error| ServiceError::Application(error.into()))?;
 
     let block_import = SubspaceBlockImport::<PosTable, _, _, _, _, _>::new(
         client.clone(),
@@ -557,6 +569,7 @@ where
         },
         segment_headers_store.clone(),
         pot_verifier.clone(),
+        solution_verifier.clone(),
     );
 
     let sync_target_block_number = Arc::new(AtomicU32::new(0));
@@ -572,7 +585,7 @@ where
     let verifier = SubspaceVerifier::<PosTable, _, _, _>::new(SubspaceVerifierOptions {
         client: client.clone(),
         chain_constants,
-        kzg,
+        solution_verifier,
         select_chain: select_chain.clone(),
         telemetry: telemetry.as_ref().map(|x| x.handle()),
         offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
@@ -685,8 +698,10 @@ where
         + SessionKeys<Block>
         + TaggedTransactionQueue<Block>
         + TransactionPaymentApi<Block, Balance>
+        + TransactionFeesApi<Block, Balance>
         + SubspaceApi<Block, FarmerPublicKey>
         + DomainsApi<Block, DomainHeader>
+        + DomainStakingApi<Block, AccountId, Balance, DomainNumber>
         + FraudProofApi<Block, DomainHeader>
         + ObjectsApi<Block>
         + MmrApi<Block, Hash, BlockNumber>
@@ -712,6 +727,13 @@ where
     } = other;
 
     let offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
+    let maybe_segment_archives = config
+        .segment_archives_path
+        .as_ref()
+        .map(|segment_archives_path| {
+            SegmentArchives::new(segment_archives_path, client.info().genesis_hash).map(Arc::new)
+        })
+        .transpose()?;
     let (node, bootstrap_nodes) = match config.subspace_networking {
         SubspaceNetworking::Reuse {
             node,
@@ -729,6 +751,7 @@ where
             let (node, mut node_runner) = create_dsn_instance(
                 dsn_protocol_version,
                 dsn_config.clone(),
+                maybe_segment_archives.clone(),
                 prometheus_registry,
             )?;
 
@@ -901,6 +924,14 @@ where
                 )),
             ))
         });
+        let dsn_sync_piece_getter: Arc<dyn DsnSyncPieceGetter + Send + Sync> =
+            match maybe_segment_archives {
+                Some(segment_archives) => Arc::new(SegmentArchivePieceGetter::new(
+                    segment_archives,
+                    dsn_sync_piece_getter,
+                )),
+                None => dsn_sync_piece_getter,
+            };
 
         if !config.base.network.force_synced {
             // Start with DSN sync in this case
@@ -910,12 +941,15 @@ where
         let (observer, worker) = sync_from_dsn::create_observer_and_worker(
             segment_headers_store.clone(),
             Arc::clone(&network_service),
+            sync_service.clone(),
             node.clone(),
             Arc::clone(&client),
             import_queue_service,
             sync_target_block_number,
             pause_sync,
             dsn_sync_piece_getter,
+            config.sync,
+            config.base.chain_spec.fork_id().map(String::from),
         );
         task_manager
             .spawn_handle()
@@ -1108,6 +1142,8 @@ where
             let transaction_pool = transaction_pool.clone();
             let chain_spec = config.base.chain_spec.cloned_box();
             let backend = backend.clone();
+            let offchain_tx_pool_factory =
+                OffchainTransactionPoolFactory::new(transaction_pool.clone());
 
             Box::new(move |deny_unsafe, subscription_executor| {
                 let deps = rpc::FullDeps {
@@ -1125,6 +1161,7 @@ where
                     sync_oracle: sync_oracle.clone(),
                     kzg: subspace_link.kzg().clone(),
                     backend: backend.clone(),
+                    offchain_tx_pool_factory: offchain_tx_pool_factory.clone(),
                 };
 
                 rpc::create_full(deps).map_err(Into::into)
@@ -21,6 +21,8 @@
 
 #![warn(missing_docs)]
 
+use domain_runtime_primitives::opaque::Header as DomainHeader;
+use domain_runtime_primitives::BlockNumber as DomainNumber;
 use jsonrpsee::RpcModule;
 use mmr_rpc::{Mmr, MmrApiServer};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
@@ -31,16 +33,22 @@ use sc_consensus_subspace::slot_worker::{
     NewSlotNotification, RewardSigningNotification, SubspaceSyncOracle,
 };
 use sc_consensus_subspace_rpc::{SubspaceRpc, SubspaceRpcApiServer, SubspaceRpcConfig};
+use sc_domains_fraud_proof_rpc::{FraudProofApiServer, FraudProofRpc};
+use sc_domains_staking_rpc::{DomainStaking, DomainStakingApiServer};
 use sc_rpc::SubscriptionTaskExecutor;
 use sc_rpc_api::DenyUnsafe;
 use sc_rpc_spec_v2::chain_spec::{ChainSpec, ChainSpecApiServer};
-use sc_transaction_pool_api::TransactionPool;
+use sc_transaction_fees_rpc::{TransactionFees, TransactionFeesApiServer};
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SyncOracle;
 use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
+use sp_domains::DomainStakingApi;
+use sp_domains_fraud_proof::FraudProofApi;
 use sp_objects::ObjectsApi;
+use sp_transaction_fees::TransactionFeesApi;
 use std::sync::Arc;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::BlockNumber;
@@ -82,6 +90,8 @@ where
     pub kzg: Kzg,
     /// Backend used by the node.
     pub backend: Arc<B>,
+    /// Offchain transaction pool factory used to submit fraud proofs.
+    pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -98,10 +108,13 @@ where
         + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
         + pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
+        + TransactionFeesApi<Block, Balance>
         + BlockBuilder<Block>
         + SubspaceApi<Block, FarmerPublicKey>
         + mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>
-        + ObjectsApi<Block>,
+        + ObjectsApi<Block>
+        + DomainStakingApi<Block, AccountId, Balance, DomainNumber>
+        + FraudProofApi<Block, DomainHeader>,
     P: TransactionPool + 'static,
     SO: SyncOracle + Send + Sync + Clone + 'static,
     AS: AuxStore + Send + Sync + 'static,
@@ -123,6 +136,7 @@ where
         sync_oracle,
         kzg,
         backend,
+        offchain_tx_pool_factory,
     } = deps;
 
     let chain_name = chain_spec.name().to_string();
@@ -132,6 +146,7 @@ where
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
     module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    module.merge(TransactionFees::new(client.clone()).into_rpc())?;
 
     module.merge(
         SubspaceRpc::new(SubspaceRpcConfig {
@@ -148,6 +163,10 @@ where
         })?
         .into_rpc(),
     )?;
+    module.merge(DomainStaking::new(client.clone()).into_rpc())?;
+    module.merge(
+        FraudProofRpc::new(client.clone(), offchain_tx_pool_factory, deny_unsafe).into_rpc(),
+    )?;
     module.merge(
         Mmr::new(
             client,
@@ -0,0 +1,724 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Portable file format for archived history.
+//!
+//! Segment archive contains segment headers, object mappings and pieces of a continuous range of
+//! segments. It can be exported by any node that has corresponding blocks and imported by another
+//! node after verification, such that pieces can be served from a local file instead of being
+//! downloaded from DSN.
+//!
+//! Layout of the file is the following:
+//! * [`SEGMENT_ARCHIVE_MAGIC`] bytes
+//! * SCALE-encoded [`SegmentArchiveHeader`]
+//! * for every segment in the archive:
+//!   * SCALE-encoded [`SegmentHeader`]
+//!   * SCALE-encoded `Vec<PieceObjectMapping>`
+//!   * [`ArchivedHistorySegment::NUM_PIECES`] pieces, [`Piece::SIZE`] bytes each
+
+#[cfg(test)]
+mod tests;
+
+use crate::sync_from_dsn::DsnSyncPieceGetter;
+use async_trait::async_trait;
+use parity_scale_codec::{Decode, Encode, IoReader};
+use rayon::prelude::*;
+use sc_client_api::AuxStore;
+use sc_consensus_subspace::archiver::SegmentHeadersStore;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
+use sp_core::H256;
+use sp_runtime::traits::Block as BlockT;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::{fs, io};
+use subspace_archiving::archiver::{is_piece_valid, is_pieces_valid, NewArchivedSegment};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::objects::PieceObjectMapping;
+use subspace_core_primitives::{
+    ArchivedHistorySegment, Blake3Hash, Piece, PieceIndex, SegmentCommitment, SegmentHeader,
+    SegmentIndex,
+};
+use tracing::{debug, info};
+
+/// Magic bytes at the beginning of every segment archive
+pub const SEGMENT_ARCHIVE_MAGIC: [u8; 8] = *b"SUBSEGAR";
+/// Current version of segment archive format
+const SEGMENT_ARCHIVE_VERSION: u8 = 0;
+/// Size of all pieces of a single segment in segment archive
+const SEGMENT_PIECES_SIZE: u64 = (ArchivedHistorySegment::NUM_PIECES * Piece::SIZE) as u64;
+
+/// Segment archive error
+#[derive(Debug, thiserror::Error)]
+pub enum SegmentArchiveError {
+    /// I/O error
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Decoding error
+    #[error("Decoding error: {0}")]
+    Decoding(#[from] parity_scale_codec::Error),
+    /// Not a segment archive
+    #[error("Not a segment archive, magic bytes mismatch")]
+    InvalidMagic,
+    /// Unsupported segment archive version
+    #[error("Unsupported segment archive version {0}")]
+    UnsupportedVersion(u8),
+    /// Segment archive belongs to a different chain
+    #[error("Segment archive belongs to a different chain with genesis hash {0}")]
+    GenesisHashMismatch(H256),
+    /// Unexpected segment index
+    #[error("Unexpected segment index {actual}, expected {expected}")]
+    UnexpectedSegmentIndex {
+        /// Expected segment index
+        expected: SegmentIndex,
+        /// Actual segment index
+        actual: SegmentIndex,
+    },
+    /// Segment archive ended before all segments were written
+    #[error("Segment archive is incomplete, {0} segments are missing")]
+    Incomplete(u64),
+    /// Segment header of the segment before the first segment in the archive is not known
+    #[error("Segment header {0} that precedes segment archive is not known")]
+    MissingPreviousSegmentHeader(SegmentIndex),
+    /// Segment header doesn't extend previous segment header
+    #[error("Segment header {0} doesn't extend previous segment header")]
+    InvalidSegmentHeaderChain(SegmentIndex),
+    /// Segment header doesn't match already known segment header
+    #[error("Segment header {0} doesn't match already known segment header")]
+    SegmentHeaderMismatch(SegmentIndex),
+    /// Segment header is not known to the chain and is not followed by a known segment header
+    #[error(
+        "Segment header {0} is not known to the chain, import the segment archive with a trusted \
+        checkpoint"
+    )]
+    UnknownSegmentHeader(SegmentIndex),
+    /// Invalid piece
+    #[error("Piece {0} is invalid")]
+    InvalidPiece(PieceIndex),
+    /// Failed to store segment headers
+    #[error("Failed to store segment headers: {0}")]
+    SegmentHeadersStore(#[from] sp_blockchain::Error),
+    /// Runtime API error
+    #[error("Runtime API error: {0}")]
+    RuntimeApi(#[from] sp_api::ApiError),
+}
+
+/// Header of the segment archive
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct SegmentArchiveHeader {
+    /// Format version
+    pub version: u8,
+    /// Genesis hash of the chain segments belong to
+    pub genesis_hash: H256,
+    /// Index of the first segment in the archive
+    pub first_segment_index: SegmentIndex,
+    /// Number of segments in the archive
+    pub segment_count: u64,
+}
+
+impl SegmentArchiveHeader {
+    /// Create header for the latest supported format version
+    pub fn new(genesis_hash: H256, first_segment_index: SegmentIndex, segment_count: u64) -> Self {
+        Self {
+            version: SEGMENT_ARCHIVE_VERSION,
+            genesis_hash,
+            first_segment_index,
+            segment_count,
+        }
+    }
+}
+
+/// Segment header hash obtained from a trusted source, used to import segment archive into a node
+/// that doesn't know segment headers of the archive yet, e.g. a freshly started node.
+///
+/// Parsed from `<segment index>:<hex encoded segment header hash>`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SegmentHeaderCheckpoint {
+    /// Index of the segment
+    pub segment_index: SegmentIndex,
+    /// Hash of the segment header
+    pub segment_header_hash: Blake3Hash,
+}
+
+impl FromStr for SegmentHeaderCheckpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (segment_index, segment_header_hash) = s.split_once(':').ok_or_else(|| {
+            "Expected checkpoint in `<segment index>:<segment header hash>` format".to_string()
+        })?;
+        let segment_index = segment_index
+            .parse::<u64>()
+            .map_err(|error| format!("Invalid segment index: {error}"))?;
+        let mut hash = Blake3Hash::default();
+        hex::decode_to_slice(segment_header_hash.trim_start_matches("0x"), &mut hash)
+            .map_err(|error| format!("Invalid segment header hash: {error}"))?;
+
+        Ok(Self {
+            segment_index: SegmentIndex::from(segment_index),
+            segment_header_hash: hash,
+        })
+    }
+}
+
+/// Writes archived segments into segment archive
+#[derive(Debug)]
+pub struct SegmentArchiveWriter<W> {
+    writer: W,
+    next_segment_index: SegmentIndex,
+    remaining_segments: u64,
+}
+
+impl<W> SegmentArchiveWriter<W>
+where
+    W: Write,
+{
+    /// Create new writer, writes archive header right away
+    pub fn new(mut writer: W, header: SegmentArchiveHeader) -> Result<Self, SegmentArchiveError> {
+        writer.write_all(&SEGMENT_ARCHIVE_MAGIC)?;
+        writer.write_all(&header.encode())?;
+
+        Ok(Self {
+            writer,
+            next_segment_index: header.first_segment_index,
+            remaining_segments: header.segment_count,
+        })
+    }
+
+    /// Write next archived segment, segments must be written in order
+    pub fn write_segment(
+        &mut self,
+        archived_segment: &NewArchivedSegment,
+    ) -> Result<(), SegmentArchiveError> {
+        let segment_index = archived_segment.segment_header.segment_index();
+        if self.remaining_segments == 0 || segment_index != self.next_segment_index {
+            return Err(SegmentArchiveError::UnexpectedSegmentIndex {
+                expected: self.next_segment_index,
+                actual: segment_index,
+            });
+        }
+
+        self.writer
+            .write_all(&archived_segment.segment_header.encode())?;
+        self.writer
+            .write_all(&archived_segment.object_mapping.encode())?;
+        self.writer.write_all(archived_segment.pieces.as_ref())?;
+
+        self.next_segment_index += SegmentIndex::ONE;
+        self.remaining_segments -= 1;
+
+        Ok(())
+    }
+
+    /// Finish writing, checking that all segments declared in the header were written
+    pub fn finish(mut self) -> Result<W, SegmentArchiveError> {
+        if self.remaining_segments > 0 {
+            return Err(SegmentArchiveError::Incomplete(self.remaining_segments));
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Reads archived segments from segment archive
+#[derive(Debug)]
+pub struct SegmentArchiveReader<R> {
+    reader: R,
+    header: SegmentArchiveHeader,
+    next_segment_index: SegmentIndex,
+    remaining_segments: u64,
+}
+
+impl<R> SegmentArchiveReader<R>
+where
+    R: Read,
+{
+    /// Create new reader, reads and checks archive header right away
+    pub fn new(mut reader: R) -> Result<Self, SegmentArchiveError> {
+        let mut magic = [0; SEGMENT_ARCHIVE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != SEGMENT_ARCHIVE_MAGIC {
+            return Err(SegmentArchiveError::InvalidMagic);
+        }
+
+        let header = SegmentArchiveHeader::decode(&mut IoReader(&mut reader))?;
+        if header.version != SEGMENT_ARCHIVE_VERSION {
+            return Err(SegmentArchiveError::UnsupportedVersion(header.version));
+        }
+
+        Ok(Self {
+            reader,
+            header,
+            next_segment_index: header.first_segment_index,
+            remaining_segments: header.segment_count,
+        })
+    }
+
+    /// Archive header
+    pub fn header(&self) -> &SegmentArchiveHeader {
+        &self.header
+    }
+
+    /// Read segment header and object mappings of the next segment, pieces must be read or
+    /// skipped afterwards.
+    ///
+    /// Returns `Ok(None)` when there are no more segments in the archive.
+    fn read_segment_header(
+        &mut self,
+    ) -> Result<Option<(SegmentHeader, Vec<PieceObjectMapping>)>, SegmentArchiveError> {
+        if self.remaining_segments == 0 {
+            return Ok(None);
+        }
+
+        let segment_header = SegmentHeader::decode(&mut IoReader(&mut self.reader))?;
+        let segment_index = segment_header.segment_index();
+        if segment_index != self.next_segment_index {
+            return Err(SegmentArchiveError::UnexpectedSegmentIndex {
+                expected: self.next_segment_index,
+                actual: segment_index,
+            });
+        }
+        let object_mapping = Vec::<PieceObjectMapping>::decode(&mut IoReader(&mut self.reader))?;
+
+        self.next_segment_index += SegmentIndex::ONE;
+        self.remaining_segments -= 1;
+
+        Ok(Some((segment_header, object_mapping)))
+    }
+
+    /// Read next archived segment.
+    ///
+    /// Returns `Ok(None)` when there are no more segments in the archive.
+    pub fn read_segment(&mut self) -> Result<Option<NewArchivedSegment>, SegmentArchiveError> {
+        let Some((segment_header, object_mapping)) = self.read_segment_header()? else {
+            return Ok(None);
+        };
+
+        let mut pieces = ArchivedHistorySegment::default();
+        self.reader.read_exact(pieces.as_mut())?;
+
+        Ok(Some(NewArchivedSegment {
+            segment_header,
+            pieces,
+            object_mapping,
+        }))
+    }
+}
+
+/// Verify archived segment read from segment archive.
+///
+/// Segment header must extend `previous_segment_header` (which is only allowed to be `None` for
+/// the very first segment) and all pieces must be valid according to segment commitment.
+pub fn verify_archived_segment(
+    kzg: &Kzg,
+    archived_segment: &NewArchivedSegment,
+    previous_segment_header: Option<&SegmentHeader>,
+) -> Result<(), SegmentArchiveError> {
+    let segment_header = &archived_segment.segment_header;
+    let segment_index = segment_header.segment_index();
+
+    let expected_prev_segment_header_hash = match previous_segment_header {
+        Some(previous_segment_header) => {
+            if previous_segment_header.segment_index() + SegmentIndex::ONE != segment_index {
+                return Err(SegmentArchiveError::InvalidSegmentHeaderChain(
+                    segment_index,
+                ));
+            }
+            previous_segment_header.hash()
+        }
+        None => {
+            if segment_index != SegmentIndex::ZERO {
+                return Err(SegmentArchiveError::MissingPreviousSegmentHeader(
+                    segment_index - SegmentIndex::ONE,
+                ));
+            }
+            Blake3Hash::default()
+        }
+    };
+    if segment_header.prev_segment_header_hash() != expected_prev_segment_header_hash {
+        return Err(SegmentArchiveError::InvalidSegmentHeaderChain(
+            segment_index,
+        ));
+    }
+
+    let segment_commitment = segment_header.segment_commitment();
+    // Check all pieces of the segment with a single pairing check first and only look for specific
+    // invalid piece if batch verification failed
+    let pieces = archived_segment
+        .pieces
+        .iter()
+        .zip(segment_index.segment_piece_indexes())
+        .map(|(piece, piece_index)| (piece, &segment_commitment, piece_index.position()))
+        .collect::<Vec<_>>();
+    if is_pieces_valid(kzg, &pieces) {
+        return Ok(());
+    }
+
+    let maybe_invalid_piece_index = segment_index
+        .segment_piece_indexes()
+        .into_par_iter()
+        .zip(archived_segment.pieces.par_iter())
+        .find_any(|(piece_index, piece)| {
+            !is_piece_valid(kzg, piece, &segment_commitment, piece_index.position())
+        })
+        .map(|(piece_index, _piece)| piece_index);
+
+    match maybe_invalid_piece_index {
+        Some(piece_index) => Err(SegmentArchiveError::InvalidPiece(piece_index)),
+        // Individual verification is authoritative
+        None => Ok(()),
+    }
+}
+
+/// Check whether segment header read from segment archive is the one the chain knows about.
+///
+/// Segment header is known if it matches segment header that is already stored in
+/// `segment_headers_store`, its segment commitment matches the one stored in the runtime at the
+/// best block or its hash matches trusted checkpoint. Returns an error if segment header is known,
+/// but doesn't match.
+fn is_segment_header_known<AS, SC>(
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    segment_commitment_of: &SC,
+    trusted_checkpoint: Option<&SegmentHeaderCheckpoint>,
+    segment_header: &SegmentHeader,
+) -> Result<bool, SegmentArchiveError>
+where
+    AS: AuxStore,
+    SC: Fn(SegmentIndex) -> Result<Option<SegmentCommitment>, SegmentArchiveError>,
+{
+    let segment_index = segment_header.segment_index();
+
+    if let Some(known_segment_header) = segment_headers_store.get_segment_header(segment_index) {
+        return if known_segment_header == *segment_header {
+            Ok(true)
+        } else {
+            Err(SegmentArchiveError::SegmentHeaderMismatch(segment_index))
+        };
+    }
+
+    if let Some(segment_commitment) = segment_commitment_of(segment_index)? {
+        return if segment_commitment == segment_header.segment_commitment() {
+            Ok(true)
+        } else {
+            Err(SegmentArchiveError::SegmentHeaderMismatch(segment_index))
+        };
+    }
+
+    match trusted_checkpoint {
+        Some(checkpoint) if checkpoint.segment_index == segment_index => {
+            if checkpoint.segment_header_hash == segment_header.hash() {
+                Ok(true)
+            } else {
+                Err(SegmentArchiveError::SegmentHeaderMismatch(segment_index))
+            }
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Import segment archive at `path`.
+///
+/// All segments are verified first, both against each other and against segment headers known to
+/// the chain, then segment headers are added to `segment_headers_store` and archive is copied
+/// into `segment_archives_path` directory, such that pieces can be served by [`SegmentArchives`].
+///
+/// Segment headers are chained, so every segment header that is followed by a segment header
+/// known to the chain (see [`is_segment_header_known()`]) is known as well. A node that didn't sync
+/// the corresponding blocks yet (freshly started node in particular) doesn't know any of them,
+/// such node needs `trusted_checkpoint` for the last segment of the archive.
+pub fn import_segment_archive<Block, Client, AS>(
+    path: &Path,
+    client: &Client,
+    kzg: &Kzg,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    segment_archives_path: &Path,
+    trusted_checkpoint: Option<&SegmentHeaderCheckpoint>,
+) -> Result<SegmentArchiveHeader, SegmentArchiveError>
+where
+    Block: BlockT<Hash = H256>,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    AS: AuxStore,
+{
+    let best_hash = client.info().best_hash;
+
+    import_segment_archive_with(
+        path,
+        client.info().genesis_hash,
+        |segment_index| {
+            client
+                .runtime_api()
+                .segment_commitment(best_hash, segment_index)
+                .map_err(SegmentArchiveError::from)
+        },
+        kzg,
+        segment_headers_store,
+        segment_archives_path,
+        trusted_checkpoint,
+    )
+}
+
+/// Same as [`import_segment_archive()`], but with segment commitments stored in the runtime
+/// provided by `segment_commitment_of`
+fn import_segment_archive_with<AS, SC>(
+    path: &Path,
+    genesis_hash: H256,
+    segment_commitment_of: SC,
+    kzg: &Kzg,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    segment_archives_path: &Path,
+    trusted_checkpoint: Option<&SegmentHeaderCheckpoint>,
+) -> Result<SegmentArchiveHeader, SegmentArchiveError>
+where
+    AS: AuxStore,
+    SC: Fn(SegmentIndex) -> Result<Option<SegmentCommitment>, SegmentArchiveError>,
+{
+    let mut reader = SegmentArchiveReader::new(BufReader::new(File::open(path)?))?;
+    let header = *reader.header();
+
+    if header.genesis_hash != genesis_hash {
+        return Err(SegmentArchiveError::GenesisHashMismatch(
+            header.genesis_hash,
+        ));
+    }
+
+    let mut previous_segment_header = if header.first_segment_index == SegmentIndex::ZERO {
+        None
+    } else {
+        let previous_segment_index = header.first_segment_index - SegmentIndex::ONE;
+        Some(
+            segment_headers_store
+                .get_segment_header(previous_segment_index)
+                .ok_or(SegmentArchiveError::MissingPreviousSegmentHeader(
+                    previous_segment_index,
+                ))?,
+        )
+    };
+
+    let mut segment_headers = Vec::new();
+    while let Some(archived_segment) = reader.read_segment()? {
+        let segment_header = archived_segment.segment_header;
+        let segment_index = segment_header.segment_index();
+
+        verify_archived_segment(kzg, &archived_segment, previous_segment_header.as_ref())?;
+
+        debug!(%segment_index, "Segment verified successfully");
+
+        segment_headers.push(segment_header);
+        previous_segment_header.replace(segment_header);
+    }
+
+    if segment_headers.len() as u64 != header.segment_count {
+        return Err(SegmentArchiveError::Incomplete(
+            header.segment_count - segment_headers.len() as u64,
+        ));
+    }
+
+    // Archive that only extends itself can't be trusted, the last segment header must be known
+    // to the chain and earlier segment headers are chained to it. Earlier segment headers are
+    // still checked to not conflict with segment headers known to the chain.
+    let mut known = false;
+    for segment_header in segment_headers.iter().rev() {
+        known |= is_segment_header_known(
+            segment_headers_store,
+            &segment_commitment_of,
+            trusted_checkpoint,
+            segment_header,
+        )?;
+
+        if !known {
+            return Err(SegmentArchiveError::UnknownSegmentHeader(
+                segment_header.segment_index(),
+            ));
+        }
+    }
+
+    segment_headers_store.add_segment_headers(&segment_headers)?;
+
+    fs::create_dir_all(segment_archives_path)?;
+    let last_segment_index =
+        header.first_segment_index + SegmentIndex::from(header.segment_count.saturating_sub(1));
+    fs::copy(
+        path,
+        segment_archives_path.join(format!(
+            "{}-{last_segment_index}.bin",
+            header.first_segment_index
+        )),
+    )?;
+
+    info!(
+        first_segment_index = %header.first_segment_index,
+        %last_segment_index,
+        "Segment archive imported successfully"
+    );
+
+    Ok(header)
+}
+
+/// Location of pieces of a segment within segment archive
+#[derive(Debug, Clone)]
+struct SegmentLocation {
+    path: PathBuf,
+    pieces_offset: u64,
+}
+
+/// Index of segment archives previously imported with [`import_segment_archive()`] that pieces
+/// can be read from, used both for DSN sync and for serving pieces to other DSN peers
+#[derive(Debug, Default)]
+pub struct SegmentArchives {
+    segments: HashMap<SegmentIndex, SegmentLocation>,
+}
+
+impl SegmentArchives {
+    /// Create new instance, indexing all segment archives in `segment_archives_path` directory
+    pub fn new(
+        segment_archives_path: &Path,
+        genesis_hash: H256,
+    ) -> Result<Self, SegmentArchiveError> {
+        let mut segments = HashMap::new();
+
+        if segment_archives_path.is_dir() {
+            for entry in fs::read_dir(segment_archives_path)? {
+                let path = entry?.path();
+                if !path.is_file() {
+                    continue;
+                }
+
+                let mut reader = SegmentArchiveReader::new(BufReader::new(File::open(&path)?))?;
+                if reader.header().genesis_hash != genesis_hash {
+                    return Err(SegmentArchiveError::GenesisHashMismatch(
+                        reader.header().genesis_hash,
+                    ));
+                }
+
+                while let Some((segment_header, _object_mapping)) = reader.read_segment_header()? {
+                    let pieces_offset = reader.reader.stream_position()?;
+                    reader
+                        .reader
+                        .seek(SeekFrom::Current(SEGMENT_PIECES_SIZE as i64))?;
+
+                    segments.insert(
+                        segment_header.segment_index(),
+                        SegmentLocation {
+                            path: path.clone(),
+                            pieces_offset,
+                        },
+                    );
+                }
+            }
+        }
+
+        debug!(
+            segments = %segments.len(),
+            "Indexed segments in local segment archives"
+        );
+
+        Ok(Self { segments })
+    }
+
+    /// Read piece from local segment archives, returns `Ok(None)` if piece is not available
+    /// locally
+    pub async fn read_piece(&self, piece_index: PieceIndex) -> Result<Option<Piece>, io::Error> {
+        let Some(segment_location) = self.segments.get(&piece_index.segment_index()).cloned()
+        else {
+            return Ok(None);
+        };
+
+        let read_piece_fut = tokio::task::spawn_blocking(move || {
+            let mut file = File::open(&segment_location.path)?;
+            file.seek(SeekFrom::Start(
+                segment_location.pieces_offset
+                    + u64::from(piece_index.position()) * Piece::SIZE as u64,
+            ))?;
+            let mut piece = Piece::default();
+            file.read_exact(piece.as_mut())?;
+
+            Ok::<_, io::Error>(piece)
+        });
+
+        read_piece_fut.await?.map(Some)
+    }
+}
+
+/// Piece getter that serves pieces from [`SegmentArchives`], falling back to another piece getter
+/// for pieces that are not available locally
+#[derive(Debug)]
+pub struct SegmentArchivePieceGetter<PG> {
+    segment_archives: Arc<SegmentArchives>,
+    fallback: PG,
+}
+
+impl<PG> SegmentArchivePieceGetter<PG> {
+    /// Create new instance
+    pub fn new(segment_archives: Arc<SegmentArchives>, fallback: PG) -> Self {
+        Self {
+            segment_archives,
+            fallback,
+        }
+    }
+}
+
+#[async_trait]
+impl<PG> DsnSyncPieceGetter for SegmentArchivePieceGetter<PG>
+where
+    PG: DsnSyncPieceGetter + Send + Sync,
+{
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        match self.segment_archives.read_piece(piece_index).await? {
+            Some(piece) => Ok(Some(piece)),
+            None => self.fallback.get_piece(piece_index).await,
+        }
+    }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        let mut results = Vec::with_capacity(piece_indices.len());
+        let mut missing_pieces = Vec::new();
+        for (position, piece_index) in piece_indices.into_iter().enumerate() {
+            let maybe_piece = self.segment_archives.read_piece(piece_index).await?;
+            if maybe_piece.is_none() {
+                missing_pieces.push((position, piece_index));
+            }
+            results.push(maybe_piece);
+        }
+
+        if missing_pieces.is_empty() {
+            return Ok(results);
+        }
+
+        let (positions, missing_piece_indices) =
+            missing_pieces.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        let fallback_pieces = self.fallback.get_pieces(missing_piece_indices).await?;
+        for (position, maybe_piece) in positions.into_iter().zip(fallback_pieces) {
+            results[position] = maybe_piece;
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::segment_archive::{
+    import_segment_archive_with, SegmentArchiveError, SegmentArchiveHeader, SegmentArchiveWriter,
+    SegmentArchives, SegmentHeaderCheckpoint,
+};
+use parking_lot::RwLock;
+use sc_client_api::AuxStore;
+use sc_consensus_subspace::archiver::SegmentHeadersStore;
+use sp_core::H256;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::{
+    Blake3Hash, PieceIndex, RecordedHistorySegment, SegmentCommitment, SegmentIndex,
+};
+
+const CONFIRMATION_DEPTH_K: u32 = 100;
+
+struct MemAuxStore {
+    store: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MemAuxStore {
+    fn new() -> Self {
+        Self {
+            store: RwLock::new(Default::default()),
+        }
+    }
+}
+
+impl AuxStore for MemAuxStore {
+    fn insert_aux<
+        'a,
+        'b: 'a,
+        'c: 'a,
+        I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+        D: IntoIterator<Item = &'a &'b [u8]>,
+    >(
+        &self,
+        insert: I,
+        delete: D,
+    ) -> sp_blockchain::Result<()> {
+        let mut storage = self.store.write();
+        for (k, v) in insert {
+            storage.insert(k.to_vec(), v.to_vec());
+        }
+        for k in delete {
+            storage.remove(*k);
+        }
+        Ok(())
+    }
+
+    fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+        Ok(self.store.read().get(key).cloned())
+    }
+}
+
+/// Runtime of a node that didn't import any blocks yet doesn't have any segment commitments
+fn no_segment_commitments(
+    _segment_index: SegmentIndex,
+) -> Result<Option<SegmentCommitment>, SegmentArchiveError> {
+    Ok(None)
+}
+
+fn first_archived_segment(kzg: &Kzg) -> NewArchivedSegment {
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    archiver
+        .add_block(
+            vec![0u8; RecordedHistorySegment::SIZE],
+            BlockObjectMapping::default(),
+            true,
+        )
+        .into_iter()
+        .next()
+        .expect("Block is larger than a segment; qed")
+}
+
+fn write_segment_archive(path: &Path, genesis_hash: H256, archived_segment: &NewArchivedSegment) {
+    let mut writer = SegmentArchiveWriter::new(
+        File::create(path).unwrap(),
+        SegmentArchiveHeader::new(genesis_hash, SegmentIndex::ZERO, 1),
+    )
+    .unwrap();
+    writer.write_segment(archived_segment).unwrap();
+    writer.finish().unwrap();
+}
+
+#[test]
+fn import_into_empty_node() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let genesis_hash = H256::repeat_byte(1);
+    let archived_segment = first_archived_segment(&kzg);
+    let segment_header = archived_segment.segment_header;
+
+    let directory = tempfile::tempdir().unwrap();
+    let archive_path = directory.path().join("archive.bin");
+    let segment_archives_path = directory.path().join("segments");
+    write_segment_archive(&archive_path, genesis_hash, &archived_segment);
+
+    let segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(MemAuxStore::new()), CONFIRMATION_DEPTH_K).unwrap();
+    assert_eq!(segment_headers_store.max_segment_index(), None);
+
+    // Archive that only extends itself can't be imported without a checkpoint
+    let result = import_segment_archive_with(
+        &archive_path,
+        genesis_hash,
+        no_segment_commitments,
+        &kzg,
+        &segment_headers_store,
+        &segment_archives_path,
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(SegmentArchiveError::UnknownSegmentHeader(segment_index))
+            if segment_index == SegmentIndex::ZERO
+    ));
+
+    // Checkpoint of a different segment header
+    let result = import_segment_archive_with(
+        &archive_path,
+        genesis_hash,
+        no_segment_commitments,
+        &kzg,
+        &segment_headers_store,
+        &segment_archives_path,
+        Some(&SegmentHeaderCheckpoint {
+            segment_index: SegmentIndex::ZERO,
+            segment_header_hash: Blake3Hash::default(),
+        }),
+    );
+    assert!(matches!(
+        result,
+        Err(SegmentArchiveError::SegmentHeaderMismatch(segment_index))
+            if segment_index == SegmentIndex::ZERO
+    ));
+    assert_eq!(segment_headers_store.max_segment_index(), None);
+
+    let checkpoint = format!("0:{}", hex::encode(segment_header.hash()))
+        .parse::<SegmentHeaderCheckpoint>()
+        .unwrap();
+    let header = import_segment_archive_with(
+        &archive_path,
+        genesis_hash,
+        no_segment_commitments,
+        &kzg,
+        &segment_headers_store,
+        &segment_archives_path,
+        Some(&checkpoint),
+    )
+    .unwrap();
+    assert_eq!(header.first_segment_index, SegmentIndex::ZERO);
+    assert_eq!(header.segment_count, 1);
+    assert_eq!(
+        segment_headers_store.get_segment_header(SegmentIndex::ZERO),
+        Some(segment_header)
+    );
+
+    // Segment header is known to the node now
+    import_segment_archive_with(
+        &archive_path,
+        genesis_hash,
+        no_segment_commitments,
+        &kzg,
+        &segment_headers_store,
+        &segment_archives_path,
+        None,
+    )
+    .unwrap();
+
+    // Pieces are served from the imported archive
+    let segment_archives = SegmentArchives::new(&segment_archives_path, genesis_hash).unwrap();
+    let piece = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(segment_archives.read_piece(PieceIndex::ZERO))
+        .unwrap();
+    assert_eq!(piece.as_deref(), Some(&archived_segment.pieces[0]));
+}
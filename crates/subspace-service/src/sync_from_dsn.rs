@@ -1,16 +1,21 @@
 mod import_blocks;
 pub(super) mod piece_validator;
 mod segment_header_downloader;
+mod snap_sync;
+mod state_downloader;
 
+use crate::config::ChainSyncMode;
 use crate::sync_from_dsn::import_blocks::import_blocks_from_dsn;
 pub use crate::sync_from_dsn::import_blocks::DsnSyncPieceGetter;
 use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
+use crate::sync_from_dsn::snap_sync::snap_sync;
 use futures::channel::mpsc;
 use futures::{select, FutureExt, StreamExt};
-use sc_client_api::{AuxStore, BlockBackend, BlockchainEvents};
+use sc_client_api::{AuxStore, BlockBackend, BlockchainEvents, ProofProvider};
 use sc_consensus::import_queue::ImportQueueService;
 use sc_consensus_subspace::archiver::SegmentHeadersStore;
 use sc_network::{NetworkPeers, NetworkService};
+use sc_network_sync::SyncingService;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
@@ -44,16 +49,21 @@ enum NotificationReason {
 
 /// Create node observer that will track node state and send notifications to worker to start sync
 /// from DSN.
+///
+/// With [`ChainSyncMode::Snap`] worker will snap sync the chain before processing notifications.
 #[allow(clippy::too_many_arguments)]
 pub(super) fn create_observer_and_worker<Block, AS, Client, PG>(
     segment_headers_store: SegmentHeadersStore<AS>,
     network_service: Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
+    sync_service: Arc<SyncingService<Block>>,
     node: Node,
     client: Arc<Client>,
     mut import_queue_service: Box<dyn ImportQueueService<Block>>,
     sync_target_block_number: Arc<AtomicU32>,
     pause_sync: Arc<AtomicBool>,
     piece_getter: PG,
+    sync_mode: ChainSyncMode,
+    fork_id: Option<String>,
 ) -> (
     impl Future<Output = ()> + Send + 'static,
     impl Future<Output = Result<(), sc_service::Error>> + Send + 'static,
@@ -65,6 +75,7 @@ where
         + BlockBackend<Block>
         + BlockchainEvents<Block>
         + ProvideRuntimeApi<Block>
+        + ProofProvider<Block>
         + Send
         + Sync
         + 'static,
@@ -73,12 +84,33 @@ where
 {
     let (tx, rx) = mpsc::channel(0);
     let observer_fut = {
+        let network_service = Arc::clone(&network_service);
         let node = node.clone();
         let client = Arc::clone(&client);
 
         async move { create_observer(network_service.as_ref(), &node, client.as_ref(), tx).await }
     };
     let worker_fut = async move {
+        if sync_mode == ChainSyncMode::Snap {
+            let state_protocol_name =
+                state_request_protocol_name(client.info().genesis_hash, fork_id.as_deref());
+
+            if let Err(error) = snap_sync(
+                &segment_headers_store,
+                &node,
+                client.as_ref(),
+                import_queue_service.as_mut(),
+                &piece_getter,
+                network_service.as_ref(),
+                sync_service.as_ref(),
+                state_protocol_name.into(),
+            )
+            .await
+            {
+                warn!(%error, "Snap sync failed, continuing with regular sync");
+            }
+        }
+
         create_worker(
             segment_headers_store,
             &node,
@@ -94,6 +126,18 @@ where
     (observer_fut, worker_fut)
 }
 
+/// Name of Substrate's state request protocol, must match the one used by state request handler
+fn state_request_protocol_name<Hash>(genesis_hash: Hash, fork_id: Option<&str>) -> String
+where
+    Hash: AsRef<[u8]>,
+{
+    let genesis_hash = hex::encode(genesis_hash);
+    match fork_id {
+        Some(fork_id) => format!("/{genesis_hash}/{fork_id}/state/2"),
+        None => format!("/{genesis_hash}/state/2"),
+    }
+}
+
 async fn create_observer<Block, Client>(
     network_service: &NetworkService<Block, <Block as BlockT>::Hash>,
     _node: &Node,
@@ -16,7 +16,7 @@
 
 use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
 use async_trait::async_trait;
-use futures::stream::FuturesUnordered;
+use futures::stream::FuturesOrdered;
 use futures::StreamExt;
 use sc_client_api::{AuxStore, BlockBackend, HeaderBackend};
 use sc_consensus::import_queue::ImportQueueService;
@@ -36,8 +36,6 @@ use subspace_core_primitives::{
     ArchivedHistorySegment, BlockNumber, Piece, PieceIndex, RecordedHistorySegment, SegmentIndex,
 };
 use subspace_networking::utils::piece_provider::{PieceProvider, PieceValidator};
-use tokio::sync::Semaphore;
-use tracing::warn;
 
 /// Trait representing a way to get pieces for DSN sync purposes
 #[async_trait]
@@ -46,6 +44,28 @@ pub trait DsnSyncPieceGetter: fmt::Debug {
         &self,
         piece_index: PieceIndex,
     ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>>;
+
+    /// Get multiple pieces at once, returns pieces in the same order as provided with `None` in
+    /// place of pieces that were not found.
+    ///
+    /// Default implementation gets pieces concurrently one by one, implementations should override
+    /// it if pieces can be retrieved or validated more efficiently in batches.
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>>
+    where
+        Self: Sync,
+    {
+        piece_indices
+            .into_iter()
+            .map(|piece_index| self.get_piece(piece_index))
+            .collect::<FuturesOrdered<_>>()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -59,6 +79,13 @@ where
     ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
         self.as_ref().get_piece(piece_index).await
     }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        self.as_ref().get_pieces(piece_indices).await
+    }
 }
 
 #[async_trait]
@@ -72,6 +99,13 @@ where
     ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
         Ok(self.get_piece_from_cache(piece_index).await)
     }
+
+    async fn get_pieces(
+        &self,
+        piece_indices: Vec<PieceIndex>,
+    ) -> Result<Vec<Option<Piece>>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(self.get_pieces_from_cache(piece_indices).await)
+    }
 }
 
 /// How many blocks to queue before pausing and waiting for blocks to be imported, this is
@@ -278,7 +312,7 @@ where
     Ok(downloaded_blocks)
 }
 
-async fn download_and_reconstruct_blocks<PG>(
+pub(super) async fn download_and_reconstruct_blocks<PG>(
     segment_index: SegmentIndex,
     piece_getter: &PG,
     reconstructor: &mut Reconstructor,
@@ -288,81 +322,60 @@ where
 {
     debug!(%segment_index, "Retrieving pieces of the segment");
 
-    let semaphore = &Semaphore::new(RecordedHistorySegment::NUM_RAW_RECORDS);
-
-    let mut received_segment_pieces = segment_index
+    let mut segment_pieces = vec![None::<Piece>; ArchivedHistorySegment::NUM_PIECES];
+    let mut pieces_received = 0;
+    // Source pieces are requested first, parity pieces are only requested to replace pieces that
+    // were not received
+    let mut piece_indexes = segment_index
         .segment_piece_indexes_source_first()
-        .into_iter()
-        .map(|piece_index| {
-            // Source pieces will acquire permit here right away
-            let maybe_permit = semaphore.try_acquire().ok();
-
-            async move {
-                let permit = match maybe_permit {
-                    Some(permit) => permit,
-                    None => {
-                        // Other pieces will acquire permit here instead
-                        match semaphore.acquire().await {
-                            Ok(permit) => permit,
-                            Err(error) => {
-                                warn!(
-                                    %piece_index,
-                                    %error,
-                                    "Semaphore was closed, interrupting piece retrieval"
-                                );
-                                return None;
-                            }
-                        }
-                    }
-                };
-                let maybe_piece = match piece_getter.get_piece(piece_index).await {
-                    Ok(maybe_piece) => maybe_piece,
-                    Err(error) => {
-                        trace!(
-                            %error,
-                            ?piece_index,
-                            "Piece request failed",
-                        );
-                        return None;
-                    }
-                };
+        .into_iter();
+
+    while pieces_received < RecordedHistorySegment::NUM_RAW_RECORDS {
+        let piece_indexes_batch = piece_indexes
+            .by_ref()
+            .take(RecordedHistorySegment::NUM_RAW_RECORDS - pieces_received)
+            .collect::<Vec<_>>();
 
+        if piece_indexes_batch.is_empty() {
+            break;
+        }
+
+        let maybe_pieces = match piece_getter.get_pieces(piece_indexes_batch.clone()).await {
+            Ok(maybe_pieces) => maybe_pieces,
+            Err(error) => {
                 trace!(
-                    ?piece_index,
-                    piece_found = maybe_piece.is_some(),
-                    "Piece request succeeded",
+                    %error,
+                    ?piece_indexes_batch,
+                    "Pieces request failed",
                 );
-
-                maybe_piece.map(|received_piece| {
-                    // Piece was received successfully, "remove" this slot from semaphore
-                    permit.forget();
-                    (piece_index, received_piece)
-                })
+                continue;
             }
-        })
-        .collect::<FuturesUnordered<_>>();
-
-    let mut segment_pieces = vec![None::<Piece>; ArchivedHistorySegment::NUM_PIECES];
-    let mut pieces_received = 0;
-
-    while let Some(maybe_result) = received_segment_pieces.next().await {
-        let Some((piece_index, piece)) = maybe_result else {
-            continue;
         };
 
-        segment_pieces
-            .get_mut(piece_index.position() as usize)
-            .expect("Piece position is by definition within segment; qed")
-            .replace(piece);
+        for (piece_index, maybe_piece) in piece_indexes_batch.into_iter().zip(maybe_pieces) {
+            trace!(
+                ?piece_index,
+                piece_found = maybe_piece.is_some(),
+                "Piece request succeeded",
+            );
 
-        pieces_received += 1;
+            let Some(piece) = maybe_piece else {
+                continue;
+            };
 
-        if pieces_received >= RecordedHistorySegment::NUM_RAW_RECORDS {
-            trace!(%segment_index, "Received half of the segment.");
-            break;
+            segment_pieces
+                .get_mut(piece_index.position() as usize)
+                .expect("Piece position is by definition within segment; qed")
+                .replace(piece);
+
+            pieces_received += 1;
         }
     }
 
+    if pieces_received >= RecordedHistorySegment::NUM_RAW_RECORDS {
+        trace!(%segment_index, "Received half of the segment.");
+    }
+
     let reconstructed_contents = reconstructor
         .add_segment(segment_pieces.as_ref())
         .map_err(|error| error.to_string())?;
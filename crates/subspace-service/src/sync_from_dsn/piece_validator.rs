@@ -1,9 +1,11 @@
 use async_trait::async_trait;
 use sc_client_api::AuxStore;
 use sc_consensus_subspace::archiver::SegmentHeadersStore;
-use subspace_archiving::archiver::is_piece_valid;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use subspace_archiving::archiver::{is_piece_valid, validate_pieces};
 use subspace_core_primitives::crypto::kzg::Kzg;
-use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_core_primitives::{Piece, PieceIndex, SegmentCommitment};
 use subspace_networking::libp2p::PeerId;
 use subspace_networking::utils::piece_provider::PieceValidator;
 use subspace_networking::Node;
@@ -84,4 +86,61 @@ where
             }
         }
     }
+
+    async fn validate_pieces(
+        &self,
+        source_peer_id: PeerId,
+        pieces: Vec<(PieceIndex, Piece)>,
+    ) -> Vec<Option<Piece>> {
+        if source_peer_id == self.dsn_node.id() {
+            return pieces.into_iter().map(|(_, piece)| Some(piece)).collect();
+        }
+
+        let mut segment_commitments = HashMap::<_, Option<SegmentCommitment>>::new();
+        let pieces = pieces
+            .into_iter()
+            .map(|(piece_index, piece)| {
+                let segment_index = piece_index.segment_index();
+                let segment_commitment = match segment_commitments.entry(segment_index) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let segment_commitment = self
+                            .segment_headers_store
+                            .get_segment_header(segment_index)
+                            .map(|segment_header| segment_header.segment_commitment());
+                        if segment_commitment.is_none() {
+                            error!(%segment_index, "No segment commitment in the cache.");
+                        }
+                        *entry.insert(segment_commitment)
+                    }
+                };
+
+                (piece_index, piece, segment_commitment)
+            })
+            .collect::<Vec<_>>();
+        let num_pieces = pieces.len();
+
+        let validation_fut = tokio::task::spawn_blocking({
+            let kzg = self.kzg.clone();
+
+            move || validate_pieces(&kzg, pieces)
+        });
+
+        let Ok((results, invalid_piece_indexes)) = validation_fut.await else {
+            return vec![None; num_pieces];
+        };
+
+        if !invalid_piece_indexes.is_empty() {
+            warn!(
+                ?invalid_piece_indexes,
+                %source_peer_id,
+                "Received invalid pieces from peer"
+            );
+
+            // We don't care about result here
+            let _ = self.dsn_node.ban_peer(source_peer_id).await;
+        }
+
+        results
+    }
 }
@@ -0,0 +1,343 @@
+use crate::sync_from_dsn::import_blocks::download_and_reconstruct_blocks;
+use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
+use crate::sync_from_dsn::state_downloader::download_state;
+use crate::sync_from_dsn::DsnSyncPieceGetter;
+use sc_client_api::{AuxStore, ProofProvider};
+use sc_consensus::block_import::ImportedState;
+use sc_consensus::import_queue::ImportQueueService;
+use sc_consensus::IncomingBlock;
+use sc_consensus_subspace::archiver::{decode_block, SegmentHeadersStore};
+use sc_network::types::ProtocolName;
+use sc_network::{NetworkRequest, PeerId};
+use sc_network_sync::SyncingService;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi};
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor, Zero};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use subspace_archiving::reconstructor::Reconstructor;
+use subspace_core_primitives::SegmentIndex;
+use subspace_networking::Node;
+use tracing::{debug, info, warn};
+
+/// Number of peers to try downloading state from before giving up
+const STATE_DOWNLOAD_ATTEMPTS: usize = 5;
+/// How long to wait for a suitable peer to download state from
+const WAIT_FOR_PEERS_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Frequency with which to check for peers that state can be downloaded from
+const CHECK_PEERS_INTERVAL: Duration = Duration::from_secs(5);
+/// How long to wait for blocks to be imported after they were added to the import queue
+const IMPORT_BLOCKS_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Frequency with which to check whether blocks were imported
+const CHECK_IMPORTED_BLOCKS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snap sync the consensus chain.
+///
+/// Instead of importing all blocks starting from genesis, the last archived blocks are downloaded
+/// from DSN, state at the first of them is downloaded from Substrate peers and the rest of the
+/// blocks is imported on top of it. Regular sync continues from there afterwards.
+///
+/// Snap sync only works with an empty database, otherwise it is skipped.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn snap_sync<Block, AS, Client, PG, NR>(
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    node: &Node,
+    client: &Client,
+    import_queue_service: &mut dyn ImportQueueService<Block>,
+    piece_getter: &PG,
+    network_request: &NR,
+    sync_service: &SyncingService<Block>,
+    state_protocol_name: ProtocolName,
+) -> Result<(), sc_service::Error>
+where
+    Block: BlockT,
+    AS: AuxStore + Send + Sync + 'static,
+    Client: HeaderBackend<Block>
+        + ProvideRuntimeApi<Block>
+        + ProofProvider<Block>
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    PG: DsnSyncPieceGetter,
+    NR: NetworkRequest + ?Sized,
+{
+    let info = client.info();
+    if !info.best_number.is_zero() {
+        info!(
+            best_number = %info.best_number,
+            "Snap sync only works with empty database, skipping"
+        );
+        return Ok(());
+    }
+
+    let confirmation_depth_k = client
+        .runtime_api()
+        .chain_constants(info.best_hash)
+        .map_err(|error| error.to_string())?
+        .confirmation_depth_k();
+
+    // Download segment headers, they are needed to find blocks to download and to verify pieces
+    {
+        let max_segment_index = segment_headers_store.max_segment_index().ok_or_else(|| {
+            sc_service::Error::Other(
+                "Archiver needs to be initialized before snap sync to populate the very first \
+                segment"
+                    .to_string(),
+            )
+        })?;
+
+        let new_segment_headers = SegmentHeaderDownloader::new(node)
+            .get_segment_headers(max_segment_index)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        debug!("Found {} new segment headers", new_segment_headers.len());
+
+        if !new_segment_headers.is_empty() {
+            segment_headers_store.add_segment_headers(&new_segment_headers)?;
+        }
+    }
+
+    let last_segment_index = segment_headers_store
+        .max_segment_index()
+        .expect("Checked above; qed");
+    let Some(previous_segment_index) = last_segment_index.checked_sub(SegmentIndex::ONE) else {
+        info!("No archived history besides genesis segment, skipping snap sync");
+        return Ok(());
+    };
+
+    // State will be downloaded for the last archived block of the previous segment. Archiver can
+    // resume from that block, while the last segment contains the rest of it (if it was archived
+    // partially) and following blocks.
+    let target_block_number = segment_headers_store
+        .get_segment_header(previous_segment_index)
+        .expect("Segment index is below max segment index; qed")
+        .last_archived_block()
+        .number;
+
+    if target_block_number <= confirmation_depth_k {
+        info!(
+            %target_block_number,
+            "Not enough archived history for snap sync, skipping"
+        );
+        return Ok(());
+    }
+
+    // Target block might have started in one of the earlier segments
+    let mut first_segment_index = previous_segment_index;
+    while let Some(segment_index) = first_segment_index.checked_sub(SegmentIndex::ONE) {
+        let segment_header = segment_headers_store
+            .get_segment_header(segment_index)
+            .expect("Segment index is below max segment index; qed");
+        if segment_header.last_archived_block().number != target_block_number {
+            break;
+        }
+        first_segment_index = segment_index;
+    }
+
+    info!(
+        %first_segment_index,
+        %last_segment_index,
+        %target_block_number,
+        "Starting snap sync"
+    );
+
+    let mut blocks = Vec::new();
+    let mut reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
+    for segment_index in first_segment_index..=last_segment_index {
+        blocks.extend(
+            download_and_reconstruct_blocks(segment_index, piece_getter, &mut reconstructor)
+                .await?
+                .into_iter()
+                .filter(|(block_number, _block_bytes)| *block_number >= target_block_number),
+        );
+    }
+
+    let mut blocks = blocks.into_iter().map(|(block_number, block_bytes)| {
+        decode_block::<Block>(&block_bytes)
+            .map(|signed_block| (block_number, signed_block))
+            .map_err(|error| error.to_string())
+    });
+
+    let (block_number, target_block) = blocks.next().transpose()?.ok_or_else(|| {
+        sc_service::Error::Other(format!(
+            "Target block {target_block_number} was not found in reconstructed segments"
+        ))
+    })?;
+    if block_number != target_block_number {
+        return Err(sc_service::Error::Other(format!(
+            "Expected target block {target_block_number}, found block {block_number} instead"
+        )));
+    }
+
+    let state = download_state_with_retries(
+        target_block.block.header(),
+        client,
+        network_request,
+        sync_service,
+        &state_protocol_name,
+    )
+    .await?;
+
+    let mut last_block_number = target_block_number;
+    // Block with state is imported without execution, the rest of the blocks is imported on top
+    // of it as usual
+    let mut blocks_to_import = vec![create_incoming_block(target_block, Some(state))];
+    for maybe_block in blocks {
+        let (block_number, signed_block) = maybe_block?;
+        last_block_number = block_number;
+        blocks_to_import.push(create_incoming_block(signed_block, None));
+    }
+
+    debug!(
+        %target_block_number,
+        %last_block_number,
+        "Importing snap synced blocks"
+    );
+
+    let last_block = blocks_to_import
+        .pop()
+        .expect("Contains at least target block; qed");
+    import_queue_service.import_blocks(BlockOrigin::NetworkInitialSync, blocks_to_import);
+    // This will notify Substrate's sync mechanism and allow regular Substrate sync to continue
+    // gracefully
+    import_queue_service.import_blocks(BlockOrigin::NetworkBroadcast, vec![last_block]);
+
+    let last_block_number = NumberFor::<Block>::from(last_block_number);
+    let started_at = Instant::now();
+    while client.info().best_number < last_block_number {
+        if started_at.elapsed() > IMPORT_BLOCKS_TIMEOUT {
+            return Err(sc_service::Error::Other(format!(
+                "Snap synced blocks were not imported within {IMPORT_BLOCKS_TIMEOUT:?}"
+            )));
+        }
+
+        tokio::time::sleep(CHECK_IMPORTED_BLOCKS_INTERVAL).await;
+    }
+
+    info!(
+        %target_block_number,
+        %last_block_number,
+        "Snap sync finished successfully"
+    );
+
+    Ok(())
+}
+
+fn create_incoming_block<Block>(
+    signed_block: SignedBlock<Block>,
+    state: Option<ImportedState<Block>>,
+) -> IncomingBlock<Block>
+where
+    Block: BlockT,
+{
+    let SignedBlock {
+        block,
+        justifications,
+    } = signed_block;
+    let (header, extrinsics) = block.deconstruct();
+    let with_state = state.is_some();
+
+    IncomingBlock {
+        hash: header.hash(),
+        header: Some(header),
+        body: Some(extrinsics),
+        indexed_body: None,
+        justifications,
+        origin: None,
+        allow_missing_state: with_state,
+        import_existing: with_state,
+        state,
+        skip_execution: with_state,
+    }
+}
+
+async fn download_state_with_retries<Block, Client, NR>(
+    header: &Block::Header,
+    client: &Client,
+    network_request: &NR,
+    sync_service: &SyncingService<Block>,
+    state_protocol_name: &ProtocolName,
+) -> Result<ImportedState<Block>, sc_service::Error>
+where
+    Block: BlockT,
+    Client: ProofProvider<Block>,
+    NR: NetworkRequest + ?Sized,
+{
+    let block_number = *header.number();
+    let mut tried_peers = HashSet::<PeerId>::new();
+
+    for attempt in 1..=STATE_DOWNLOAD_ATTEMPTS {
+        let peer_id = wait_for_peer(sync_service, block_number, &tried_peers).await?;
+        tried_peers.insert(peer_id);
+
+        debug!(%attempt, %peer_id, %block_number, "Downloading state");
+
+        match download_state(
+            header,
+            client,
+            network_request,
+            state_protocol_name,
+            peer_id,
+        )
+        .await
+        {
+            Ok(state) => {
+                return Ok(state);
+            }
+            Err(error) => {
+                warn!(%attempt, %peer_id, %error, "Failed to download state from peer");
+            }
+        }
+    }
+
+    Err(sc_service::Error::Other(format!(
+        "Failed to download state of block {block_number} after {STATE_DOWNLOAD_ATTEMPTS} attempts"
+    )))
+}
+
+/// Wait for a full node peer that was not tried before and has block with specified number
+async fn wait_for_peer<Block>(
+    sync_service: &SyncingService<Block>,
+    block_number: NumberFor<Block>,
+    tried_peers: &HashSet<PeerId>,
+) -> Result<PeerId, sc_service::Error>
+where
+    Block: BlockT,
+{
+    let started_at = Instant::now();
+
+    loop {
+        let maybe_peer_id = sync_service
+            .peers_info()
+            .await
+            .map_err(|_cancelled| {
+                sc_service::Error::Other("Syncing service was shut down".to_string())
+            })?
+            .into_iter()
+            .find_map(|(peer_id, peer_info)| {
+                (peer_info.roles.is_full()
+                    && peer_info.best_number >= block_number
+                    && !tried_peers.contains(&peer_id))
+                .then_some(peer_id)
+            });
+
+        if let Some(peer_id) = maybe_peer_id {
+            return Ok(peer_id);
+        }
+
+        if started_at.elapsed() > WAIT_FOR_PEERS_TIMEOUT {
+            return Err(sc_service::Error::Other(format!(
+                "No peers to download state of block {block_number} from within \
+                {WAIT_FOR_PEERS_TIMEOUT:?}"
+            )));
+        }
+
+        debug!(%block_number, "Waiting for peers to download state from");
+        tokio::time::sleep(CHECK_PEERS_INTERVAL).await;
+    }
+}
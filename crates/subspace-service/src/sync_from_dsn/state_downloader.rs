@@ -0,0 +1,236 @@
+//! Download of the state at a specific block from Substrate peers.
+//!
+//! State is requested in chunks over Substrate's state request protocol, every chunk comes with a
+//! storage proof that is verified against state root in the header of the block before being
+//! accepted.
+
+#[cfg(test)]
+mod tests;
+
+use parity_scale_codec::Decode;
+use prost::Message;
+use sc_client_api::ProofProvider;
+use sc_consensus::block_import::ImportedState;
+use sc_network::request_responses::{IfDisconnected, RequestFailure};
+use sc_network::types::ProtocolName;
+use sc_network::{NetworkRequest, PeerId};
+use smallvec::SmallVec;
+use sp_core::storage::well_known_keys;
+use sp_runtime::traits::{Block as BlockT, Header};
+use sp_state_machine::{CompactProof, KeyValueStates, KeyValueStorageLevel};
+use std::collections::HashMap;
+use tracing::{debug, trace};
+
+/// State request, must match `StateRequest` of Substrate's state request protocol
+#[derive(Clone, PartialEq, Message)]
+struct StateRequest {
+    /// Block header hash
+    #[prost(bytes = "vec", tag = "1")]
+    block: Vec<u8>,
+    /// Start from this key, multiple keys are used for nested state start
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    start: Vec<Vec<u8>>,
+    /// If `true` response will contain raw key-values rather than proof
+    #[prost(bool, tag = "3")]
+    no_proof: bool,
+}
+
+/// State response, must match `StateResponse` of Substrate's state request protocol.
+///
+/// Raw key-values are only included when no proof was requested, so they are not decoded here.
+#[derive(Clone, PartialEq, Message)]
+struct StateResponse {
+    /// Compact proof that contains requested state
+    #[prost(bytes = "vec", tag = "2")]
+    proof: Vec<u8>,
+}
+
+/// Error happened during state download
+#[derive(Debug, thiserror::Error)]
+pub(super) enum StateDownloadError {
+    /// Request to peer failed
+    #[error("Request to peer failed: {0}")]
+    Request(#[from] RequestFailure),
+    /// Failed to decode state response
+    #[error("Failed to decode state response: {0}")]
+    DecodeResponse(#[from] prost::DecodeError),
+    /// Peer returned response without proof
+    #[error("Peer returned response without proof")]
+    MissingProof,
+    /// Failed to decode proof
+    #[error("Failed to decode proof: {0}")]
+    DecodeProof(#[from] parity_scale_codec::Error),
+    /// Proof verification failed
+    #[error("Proof verification failed: {0}")]
+    InvalidProof(#[from] sp_blockchain::Error),
+    /// Peer returned response that doesn't advance state download
+    #[error("Peer returned response that doesn't advance state download at depth {depth}")]
+    NoProgress {
+        /// Depth of the trie at which download stopped
+        depth: usize,
+    },
+}
+
+/// State download in progress.
+///
+/// Accumulates the key-values of the verified state responses, the download is complete once
+/// [`StateDownload::is_complete()`] returns `true`.
+#[derive(Debug, Default)]
+struct StateDownload {
+    /// Last key that was received for top trie and child trie (if any)
+    last_key: SmallVec<[Vec<u8>; 2]>,
+    /// Child trie root (empty for top trie) -> (key-values, parent storage keys)
+    state: HashMap<Vec<u8>, (Vec<(Vec<u8>, Vec<u8>)>, Vec<Vec<u8>>)>,
+    downloaded_bytes: usize,
+    complete: bool,
+}
+
+impl StateDownload {
+    /// Request for the next chunk of the state of the block
+    fn next_request(&self, block_hash: &[u8]) -> StateRequest {
+        StateRequest {
+            block: block_hash.to_vec(),
+            start: self.last_key.to_vec(),
+            no_proof: false,
+        }
+    }
+
+    /// Verifies the proof of the state response with `verify_range_proof` and adds the proven
+    /// key-values to the downloaded state.
+    fn on_response<VerifyRangeProof>(
+        &mut self,
+        response_bytes: &[u8],
+        verify_range_proof: VerifyRangeProof,
+    ) -> Result<(), StateDownloadError>
+    where
+        VerifyRangeProof:
+            FnOnce(CompactProof, &[Vec<u8>]) -> sp_blockchain::Result<(KeyValueStates, usize)>,
+    {
+        let response = StateResponse::decode(response_bytes)?;
+
+        if response.proof.is_empty() {
+            return Err(StateDownloadError::MissingProof);
+        }
+
+        self.downloaded_bytes += response.proof.len();
+        let proof = CompactProof::decode(&mut response.proof.as_slice())?;
+        let (values, completed) = verify_range_proof(proof, self.last_key.as_slice())?;
+
+        trace!(
+            levels = values.0.len(),
+            %completed,
+            "Received state response"
+        );
+
+        let complete = completed == 0;
+        if !complete && !values.update_last_key(completed, &mut self.last_key) {
+            return Err(StateDownloadError::NoProgress { depth: completed });
+        }
+
+        for values in values.0 {
+            let key_values = if values.state_root.is_empty() {
+                // Top trie contains roots of child tries, those are stored as parent storage keys
+                // of corresponding child tries instead of regular key-values
+                values
+                    .key_values
+                    .into_iter()
+                    .filter(|(key, value)| {
+                        if well_known_keys::is_child_storage_key(key) {
+                            self.state
+                                .entry(value.clone())
+                                .or_default()
+                                .1
+                                .push(key.clone());
+                            false
+                        } else {
+                            true
+                        }
+                    })
+                    .collect()
+            } else {
+                values.key_values
+            };
+
+            let entry = self.state.entry(values.state_root).or_default();
+            if !entry.0.is_empty() && entry.1.len() > 1 {
+                // Child trie with the same root was already downloaded
+            } else {
+                entry.0.extend(key_values);
+            }
+        }
+
+        self.complete = complete;
+
+        Ok(())
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Downloaded state, ready to be imported
+    fn into_state(self) -> KeyValueStates {
+        KeyValueStates(
+            self.state
+                .into_iter()
+                .map(
+                    |(state_root, (key_values, parent_storage_keys))| KeyValueStorageLevel {
+                        state_root,
+                        parent_storage_keys,
+                        key_values,
+                    },
+                )
+                .collect(),
+        )
+    }
+}
+
+/// Downloads state of the block from the peer.
+///
+/// Returns state that can be imported together with the block once it was fully downloaded and
+/// verified.
+pub(super) async fn download_state<Block, Client, NR>(
+    header: &Block::Header,
+    client: &Client,
+    network_request: &NR,
+    protocol_name: &ProtocolName,
+    peer_id: PeerId,
+) -> Result<ImportedState<Block>, StateDownloadError>
+where
+    Block: BlockT,
+    Client: ProofProvider<Block>,
+    NR: NetworkRequest + ?Sized,
+{
+    let block_hash = header.hash();
+    let state_root = *header.state_root();
+    let mut state_download = StateDownload::default();
+
+    while !state_download.is_complete() {
+        let request = state_download.next_request(block_hash.as_ref());
+
+        let (response_bytes, _protocol_name) = network_request
+            .request(
+                peer_id,
+                protocol_name.clone(),
+                request.encode_to_vec(),
+                None,
+                IfDisconnected::ImmediateError,
+            )
+            .await?;
+
+        state_download.on_response(&response_bytes, |proof, start_key| {
+            client.verify_range_proof(state_root, proof, start_key)
+        })?;
+    }
+
+    debug!(
+        %peer_id,
+        downloaded_bytes = %state_download.downloaded_bytes,
+        "State download complete"
+    );
+
+    Ok(ImportedState {
+        block: block_hash,
+        state: state_download.into_state(),
+    })
+}
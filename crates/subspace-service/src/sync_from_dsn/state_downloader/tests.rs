@@ -0,0 +1,157 @@
+use crate::sync_from_dsn::state_downloader::{StateDownload, StateDownloadError, StateResponse};
+use parity_scale_codec::Encode;
+use prost::Message;
+use sp_core::storage::{ChildInfo, StateVersion, Storage, StorageChild};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::{
+    prove_range_read_with_child_with_size, read_range_proof_check_with_child, CompactProof,
+    InMemoryBackend, KeyValueStates,
+};
+
+/// Size limit of the response proof, small enough for the state to be downloaded in multiple
+/// chunks
+const SIZE_LIMIT: usize = 512;
+
+fn child_info() -> ChildInfo {
+    ChildInfo::new_default(b"child")
+}
+
+fn storage(value_prefix: u8) -> Storage {
+    Storage {
+        top: (0..32u8)
+            .map(|i| (vec![i; 8], vec![value_prefix, i]))
+            .collect(),
+        children_default: [(
+            child_info().storage_key().to_vec(),
+            StorageChild {
+                data: (0..16u8)
+                    .map(|i| (vec![i; 4], vec![value_prefix; 24]))
+                    .collect(),
+                child_info: child_info(),
+            },
+        )]
+        .into_iter()
+        .collect(),
+    }
+}
+
+fn backend(storage: &Storage) -> InMemoryBackend<BlakeTwo256> {
+    (storage.clone(), StateVersion::V1).into()
+}
+
+fn state_root(storage: &Storage) -> H256 {
+    *backend(storage).root()
+}
+
+/// Builds the response the same way as Substrate's state request handler does
+fn state_response(storage: &Storage, start: &[Vec<u8>]) -> Vec<u8> {
+    let (proof, _count) = prove_range_read_with_child_with_size::<_, BlakeTwo256>(
+        backend(storage),
+        SIZE_LIMIT,
+        start,
+    )
+    .unwrap();
+    let proof = proof
+        .into_compact_proof::<BlakeTwo256>(state_root(storage))
+        .unwrap();
+
+    StateResponse {
+        proof: proof.encode(),
+    }
+    .encode_to_vec()
+}
+
+/// Verifies the range proof against the state root the same way as the client does
+fn verify_range_proof(
+    state_root: H256,
+) -> impl FnOnce(CompactProof, &[Vec<u8>]) -> sp_blockchain::Result<(KeyValueStates, usize)> {
+    move |proof, start_key| {
+        let (proof, _root) = proof
+            .to_storage_proof::<BlakeTwo256>(Some(&state_root))
+            .map_err(|error| sp_blockchain::Error::Backend(format!("{error:?}")))?;
+        read_range_proof_check_with_child::<BlakeTwo256>(state_root, proof, start_key)
+            .map_err(|error| sp_blockchain::Error::Backend(error.to_string()))
+    }
+}
+
+#[test]
+fn state_download_in_chunks() {
+    let storage = storage(1);
+    let mut state_download = StateDownload::default();
+
+    let mut responses = 0;
+    while !state_download.is_complete() {
+        let request = state_download.next_request(&[]);
+        state_download
+            .on_response(
+                &state_response(&storage, &request.start),
+                verify_range_proof(state_root(&storage)),
+            )
+            .unwrap();
+        responses += 1;
+    }
+    assert!(responses > 1, "State must be downloaded in multiple chunks");
+
+    let state = state_download.into_state();
+    assert_eq!(state.0.len(), 2);
+
+    // Child trie root is not a regular key-value of the top trie
+    let top = state
+        .0
+        .iter()
+        .find(|level| level.state_root.is_empty())
+        .unwrap();
+    assert!(top.parent_storage_keys.is_empty());
+    assert_eq!(
+        top.key_values,
+        storage.top.clone().into_iter().collect::<Vec<_>>()
+    );
+
+    let child = state
+        .0
+        .iter()
+        .find(|level| !level.state_root.is_empty())
+        .unwrap();
+    assert_eq!(
+        child.parent_storage_keys,
+        vec![child_info().prefixed_storage_key().into_inner()]
+    );
+    assert_eq!(
+        child.key_values,
+        storage.children_default[child_info().storage_key()]
+            .data
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn state_download_rejects_proof_of_other_state() {
+    let mut state_download = StateDownload::default();
+
+    // The proof is for the state of a different block
+    let result = state_download.on_response(
+        &state_response(&storage(2), &[]),
+        verify_range_proof(state_root(&storage(1))),
+    );
+    assert!(
+        matches!(result, Err(StateDownloadError::InvalidProof(_))),
+        "{result:?}"
+    );
+    assert!(!state_download.is_complete());
+    assert!(state_download.into_state().0.is_empty());
+}
+
+#[test]
+fn state_download_rejects_response_without_proof() {
+    let mut state_download = StateDownload::default();
+
+    let response = StateResponse { proof: Vec::new() }.encode_to_vec();
+    let result = state_download.on_response(&response, verify_range_proof(H256::zero()));
+    assert!(
+        matches!(result, Err(StateDownloadError::MissingProof)),
+        "{result:?}"
+    );
+}
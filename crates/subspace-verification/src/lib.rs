@@ -20,11 +20,23 @@
 #![feature(array_chunks, portable_simd)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use codec::{Decode, Encode, MaxEncodedLen};
 use core::mem;
 use core::simd::Simd;
 use schnorrkel::context::SigningContext;
 use schnorrkel::SignatureError;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
 use subspace_archiving::archiver;
 use subspace_core_primitives::crypto::kzg::{Commitment, Kzg, Witness};
 use subspace_core_primitives::crypto::{
@@ -32,8 +44,8 @@ use subspace_core_primitives::crypto::{
 };
 use subspace_core_primitives::{
     Blake3Hash, BlockNumber, BlockWeight, HistorySize, PotOutput, PublicKey, Record,
-    RewardSignature, SectorId, SectorSlotChallenge, SegmentCommitment, SlotNumber, Solution,
-    SolutionRange,
+    RewardSignature, SBucket, SectorId, SectorSlotChallenge, SegmentCommitment, SlotNumber,
+    Solution, SolutionRange,
 };
 use subspace_proof_of_space::Table;
 
@@ -176,8 +188,34 @@ pub fn calculate_block_weight(solution_range: SolutionRange) -> BlockWeight {
     BlockWeight::from(SolutionRange::MAX - solution_range)
 }
 
+/// Values derived from solution and slot that are needed for solution verification
+struct SolutionChallenges {
+    sector_id: SectorId,
+    global_challenge: Blake3Hash,
+    sector_slot_challenge: SectorSlotChallenge,
+    s_bucket_audit_index: SBucket,
+}
+
+impl SolutionChallenges {
+    fn derive(sector_id: SectorId, slot: SlotNumber, proof_of_time: &PotOutput) -> Self {
+        let global_randomness = proof_of_time.derive_global_randomness();
+        let global_challenge = global_randomness.derive_global_challenge(slot);
+        let sector_slot_challenge = sector_id.derive_sector_slot_challenge(&global_challenge);
+        let s_bucket_audit_index = sector_slot_challenge.s_bucket_audit_index();
+
+        Self {
+            sector_id,
+            global_challenge,
+            sector_slot_challenge,
+            s_bucket_audit_index,
+        }
+    }
+}
+
 /// Verify whether solution is valid, returns solution distance that is `<= solution_range/2` on
 /// success.
+///
+/// Prefer [`verify_solutions()`] when verifying multiple solutions at once.
 pub fn verify_solution<'a, PosTable, FarmerPublicKey, RewardAddress>(
     solution: &'a Solution<FarmerPublicKey, RewardAddress>,
     slot: SlotNumber,
@@ -188,37 +226,116 @@ where
     PosTable: Table,
     PublicKey: From<&'a FarmerPublicKey>,
 {
-    let VerifySolutionParams {
-        proof_of_time,
-        solution_range,
-        piece_check_params,
-    } = params;
-
     let sector_id = SectorId::new(
         PublicKey::from(&solution.public_key).hash(),
         solution.sector_index,
     );
-
-    let global_randomness = proof_of_time.derive_global_randomness();
-    let global_challenge = global_randomness.derive_global_challenge(slot);
-    let sector_slot_challenge = sector_id.derive_sector_slot_challenge(&global_challenge);
-    let s_bucket_audit_index = sector_slot_challenge.s_bucket_audit_index();
+    let challenges = SolutionChallenges::derive(sector_id, slot, &params.proof_of_time);
 
     // Check that proof of space is valid
     if !PosTable::is_proof_valid(
         &sector_id.derive_evaluation_seed(solution.piece_offset, solution.history_size),
-        s_bucket_audit_index.into(),
+        challenges.s_bucket_audit_index.into(),
         &solution.proof_of_space,
     ) {
         return Err(Error::InvalidProofOfSpace);
     };
 
+    verify_solution_with_valid_proof_of_space(solution, params, kzg, &challenges)
+}
+
+/// Verify whether multiple solutions are valid, returns solution distance that is
+/// `<= solution_range/2` or error for each solution in the same order as solutions were provided.
+///
+/// More efficient than calling [`verify_solution()`] for each solution individually: public key
+/// hashes are derived once per farmer and proofs of space are verified as a batch with
+/// [`Table::are_proofs_valid()`].
+pub fn verify_solutions<'a, PosTable, FarmerPublicKey, RewardAddress, Solutions>(
+    solutions: Solutions,
+    kzg: &'a Kzg,
+) -> Vec<Result<SolutionRange, Error>>
+where
+    PosTable: Table,
+    PublicKey: From<&'a FarmerPublicKey>,
+    FarmerPublicKey: 'a,
+    RewardAddress: 'a,
+    Solutions: IntoIterator<
+        Item = (
+            &'a Solution<FarmerPublicKey, RewardAddress>,
+            SlotNumber,
+            &'a VerifySolutionParams,
+        ),
+    >,
+{
+    let mut public_key_hashes = BTreeMap::<PublicKey, Blake3Hash>::new();
+
+    let solutions = solutions
+        .into_iter()
+        .map(|(solution, slot, params)| {
+            let public_key = PublicKey::from(&solution.public_key);
+            let public_key_hash = *public_key_hashes
+                .entry(public_key)
+                .or_insert_with(|| public_key.hash());
+            let sector_id = SectorId::new(public_key_hash, solution.sector_index);
+            let evaluation_seed =
+                sector_id.derive_evaluation_seed(solution.piece_offset, solution.history_size);
+            let challenges = SolutionChallenges::derive(sector_id, slot, &params.proof_of_time);
+
+            (solution, params, evaluation_seed, challenges)
+        })
+        .collect::<Vec<_>>();
+
+    let proofs_of_space_valid = PosTable::are_proofs_valid(solutions.iter().map(
+        |(solution, _params, evaluation_seed, challenges)| {
+            (
+                evaluation_seed,
+                u32::from(challenges.s_bucket_audit_index),
+                &solution.proof_of_space,
+            )
+        },
+    ));
+
+    solutions
+        .iter()
+        .zip(proofs_of_space_valid)
+        .map(
+            |((solution, params, _evaluation_seed, challenges), proof_of_space_valid)| {
+                // Check that proof of space is valid
+                if !proof_of_space_valid {
+                    return Err(Error::InvalidProofOfSpace);
+                }
+
+                verify_solution_with_valid_proof_of_space(solution, params, kzg, challenges)
+            },
+        )
+        .collect()
+}
+
+/// The rest of solution verification once proof of space was already checked
+fn verify_solution_with_valid_proof_of_space<FarmerPublicKey, RewardAddress>(
+    solution: &Solution<FarmerPublicKey, RewardAddress>,
+    params: &VerifySolutionParams,
+    kzg: &Kzg,
+    challenges: &SolutionChallenges,
+) -> Result<SolutionRange, Error> {
+    let VerifySolutionParams {
+        proof_of_time: _,
+        solution_range,
+        piece_check_params,
+    } = params;
+    let SolutionChallenges {
+        sector_id,
+        global_challenge,
+        sector_slot_challenge,
+        s_bucket_audit_index,
+    } = challenges;
+
     let masked_chunk = (Simd::from(solution.chunk.to_bytes())
         ^ Simd::from(solution.proof_of_space.hash()))
     .to_array();
 
     let solution_distance =
-        calculate_solution_distance(&global_challenge, &masked_chunk, &sector_slot_challenge);
+        calculate_solution_distance(global_challenge, &masked_chunk, sector_slot_challenge);
 
     // Check that solution is within solution range
     if solution_distance > solution_range / 2 {
@@ -233,7 +350,7 @@ where
         &Commitment::try_from(solution.record_commitment)
             .map_err(|_error| Error::InvalidChunkWitness)?,
         Record::NUM_S_BUCKETS,
-        s_bucket_audit_index.into(),
+        u32::from(*s_bucket_audit_index),
         &solution.chunk,
         &Witness::try_from(solution.chunk_witness).map_err(|_error| Error::InvalidChunkWitness)?,
     ) {
@@ -0,0 +1,92 @@
+use crate::{verify_solution, verify_solutions, Error, VerifySolutionParams};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{
+    PosProof, PotOutput, PublicKey, SectorId, SlotNumber, Solution, SolutionRange,
+};
+use subspace_proof_of_space::shim::ShimTable;
+use subspace_proof_of_space::Table;
+
+#[derive(Debug, Clone)]
+struct TestPublicKey(PublicKey);
+
+impl From<&TestPublicKey> for PublicKey {
+    fn from(public_key: &TestPublicKey) -> Self {
+        public_key.0
+    }
+}
+
+/// Create solution with valid proof of space (but not the rest of the solution) at the first slot
+/// where proof of space exists
+fn solution_with_valid_proof_of_space(
+    public_key: PublicKey,
+    proof_of_time: &PotOutput,
+) -> (Solution<TestPublicKey, ()>, SlotNumber) {
+    let mut solution = Solution::genesis_solution(TestPublicKey(public_key), ());
+    let sector_id = SectorId::new(public_key.hash(), solution.sector_index);
+    let evaluation_seed =
+        sector_id.derive_evaluation_seed(solution.piece_offset, solution.history_size);
+    let table = ShimTable::generate(&evaluation_seed);
+
+    for slot in 0.. {
+        let global_challenge = proof_of_time
+            .derive_global_randomness()
+            .derive_global_challenge(slot);
+        let s_bucket_audit_index = sector_id
+            .derive_sector_slot_challenge(&global_challenge)
+            .s_bucket_audit_index();
+
+        if let Some(proof_of_space) = table.find_proof(u32::from(s_bucket_audit_index)) {
+            solution.proof_of_space = proof_of_space;
+            return (solution, slot);
+        }
+    }
+
+    unreachable!("Will find proof of space before exhausting slots");
+}
+
+#[test]
+fn verify_solutions_matches_verify_solution() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let params = VerifySolutionParams {
+        proof_of_time: PotOutput::default(),
+        solution_range: SolutionRange::MAX,
+        piece_check_params: None,
+    };
+
+    let (solution_1, slot_1) =
+        solution_with_valid_proof_of_space(PublicKey::from([1; 32]), &params.proof_of_time);
+    let (solution_2, slot_2) =
+        solution_with_valid_proof_of_space(PublicKey::from([2; 32]), &params.proof_of_time);
+    let mut invalid_solution = solution_1.clone();
+    invalid_solution.proof_of_space = PosProof::default();
+
+    let solutions = [
+        (&solution_1, slot_1),
+        (&invalid_solution, slot_1),
+        (&solution_2, slot_2),
+        // Same farmer as the first solution, but different slot
+        (&solution_1, slot_1 + 1),
+    ];
+
+    let results = verify_solutions::<ShimTable, _, _, _>(
+        solutions
+            .iter()
+            .map(|&(solution, slot)| (solution, slot, &params)),
+        &kzg,
+    );
+
+    assert_eq!(
+        results,
+        solutions
+            .iter()
+            .map(|&(solution, slot)| verify_solution::<ShimTable, _, _>(
+                solution, slot, &params, &kzg
+            ))
+            .collect::<Vec<_>>()
+    );
+    // Proofs of space are valid, but chunks are not
+    assert_ne!(results[0], Err(Error::InvalidProofOfSpace));
+    assert_eq!(results[1], Err(Error::InvalidProofOfSpace));
+    assert_ne!(results[2], Err(Error::InvalidProofOfSpace));
+    assert!(verify_solutions::<ShimTable, TestPublicKey, (), _>([], &kzg).is_empty());
+}
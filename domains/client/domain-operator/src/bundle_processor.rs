@@ -142,6 +142,7 @@ where
     CClient: HeaderBackend<CBlock>
         + HeaderMetadata<CBlock, Error = sp_blockchain::Error>
         + BlockBackend<CBlock>
+        + AuxStore
         + ProofProvider<CBlock>
         + ProvideRuntimeApi<CBlock>
         + 'static,
@@ -184,6 +185,18 @@ where
     ) -> sp_blockchain::Result<()> {
         let (consensus_block_hash, consensus_block_number, is_new_best) = consensus_block_info;
 
+        // Check the bundles of every imported consensus block for bundle equivocation, including
+        // the blocks of the non-canonical chain
+        if let Err(err) = self
+            .domain_receipts_checker
+            .maybe_submit_bundle_equivocation_proof(consensus_block_hash)
+        {
+            tracing::error!(
+                ?err,
+                "Failed to check bundle equivocation at consensus block #{consensus_block_number},{consensus_block_hash}"
+            );
+        }
+
         // Skip processing the blocks of the non-canonical chain, these blocks will be processed if
         // the chain becomes canonical later
         if !is_new_best {
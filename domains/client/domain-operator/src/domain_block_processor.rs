@@ -741,6 +741,7 @@ where
         DomainCoreApi<Block> + sp_block_builder::BlockBuilder<Block> + sp_api::ApiExt<Block>,
     CClient: HeaderBackend<CBlock>
         + BlockBackend<CBlock>
+        + AuxStore
         + ProofProvider<CBlock>
         + ProvideRuntimeApi<CBlock>
         + 'static,
@@ -761,17 +762,73 @@ where
 
         if let Some(mismatched_receipts) = self.find_mismatch_receipt(consensus_block_hash)? {
             let fraud_proof = self.generate_fraud_proof(mismatched_receipts)?;
+            self.submit_fraud_proof(fraud_proof)?;
+        }
+
+        Ok(())
+    }
 
-            tracing::info!("Submit fraud proof: {fraud_proof:?}");
-            let consensus_best_hash = self.consensus_client.info().best_hash;
-            let mut runtime_api = self.consensus_client.runtime_api();
-            runtime_api.register_extension(
-                self.consensus_offchain_tx_pool_factory
-                    .offchain_transaction_pool(consensus_best_hash),
+    /// Check the bundles of the domain included in the consensus block against the bundles seen
+    /// before, and submit the bundle equivocation proof if any operator signed two different
+    /// bundles in the same slot.
+    ///
+    /// NOTE: this should be done for every imported consensus block including the blocks of
+    /// the non-canonical chain, since the equivocated bundles may be included in different forks.
+    pub(crate) fn maybe_submit_bundle_equivocation_proof(
+        &self,
+        consensus_block_hash: CBlock::Hash,
+    ) -> sp_blockchain::Result<()> {
+        if self.consensus_network_sync_oracle.is_major_syncing() {
+            tracing::debug!(
+                "Skip checking bundle equivocation as the consensus node is still major syncing..."
             );
-            runtime_api.submit_fraud_proof_unsigned(consensus_best_hash, fraud_proof)?;
+            return Ok(());
         }
 
+        let extrinsics = self
+            .consensus_client
+            .block_body(consensus_block_hash)?
+            .ok_or_else(|| {
+                sp_blockchain::Error::Backend(format!(
+                    "Consensus block body for {consensus_block_hash} not found"
+                ))
+            })?;
+        let bundles = self
+            .consensus_client
+            .runtime_api()
+            .extract_successful_bundles(consensus_block_hash, self.domain_id, extrinsics)?;
+
+        for bundle in bundles {
+            let maybe_fraud_proof = self
+                .fraud_proof_generator
+                .generate_bundle_equivocation_proof(bundle.sealed_header)
+                .map_err(|err| {
+                    sp_blockchain::Error::Application(Box::from(format!(
+                        "Failed to generate bundle equivocation fraud proof: {err}"
+                    )))
+                })?;
+
+            if let Some(fraud_proof) = maybe_fraud_proof {
+                self.submit_fraud_proof(fraud_proof)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn submit_fraud_proof(
+        &self,
+        fraud_proof: FraudProof<NumberFor<CBlock>, CBlock::Hash, Block::Header>,
+    ) -> sp_blockchain::Result<()> {
+        tracing::info!("Submit fraud proof: {fraud_proof:?}");
+        let consensus_best_hash = self.consensus_client.info().best_hash;
+        let mut runtime_api = self.consensus_client.runtime_api();
+        runtime_api.register_extension(
+            self.consensus_offchain_tx_pool_factory
+                .offchain_transaction_pool(consensus_best_hash),
+        );
+        runtime_api.submit_fraud_proof_unsigned(consensus_best_hash, fraud_proof)?;
+
         Ok(())
     }
 
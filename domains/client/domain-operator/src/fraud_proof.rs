@@ -7,12 +7,16 @@ use domain_runtime_primitives::CheckExtrinsicsValidityError;
 use sc_client_api::{AuxStore, BlockBackend, ProofProvider};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
+use sp_consensus_slots::Slot;
 use sp_core::traits::CodeExecutor;
 use sp_core::H256;
 use sp_domain_digests::AsPredigest;
 use sp_domains::core_api::DomainCoreApi;
 use sp_domains::proof_provider_and_verifier::StorageProofProvider;
-use sp_domains::{DomainId, DomainsApi, ExtrinsicDigest, HeaderHashingFor, InvalidBundleType};
+use sp_domains::{
+    DomainId, DomainsApi, ExtrinsicDigest, HeaderHashingFor, InvalidBundleType, SealedBundleHeader,
+};
+use sp_domains_fraud_proof::bundle_equivocation::check_equivocation;
 use sp_domains_fraud_proof::execution_prover::ExecutionProver;
 use sp_domains_fraud_proof::fraud_proof::{
     ApplyExtrinsicMismatch, ExecutionPhase, FinalizeBlockMismatch, FraudProof,
@@ -26,6 +30,7 @@ use sp_runtime::{Digest, DigestItem};
 use sp_trie::LayoutV1;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use subspace_runtime_primitives::Balance;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum TraceDiffType {
@@ -111,6 +116,7 @@ where
         sp_block_builder::BlockBuilder<Block> + sp_api::ApiExt<Block> + DomainCoreApi<Block>,
     CClient: HeaderBackend<CBlock>
         + BlockBackend<CBlock>
+        + AuxStore
         + ProvideRuntimeApi<CBlock>
         + ProofProvider<CBlock>
         + 'static,
@@ -133,6 +139,23 @@ where
         }
     }
 
+    /// Generate the bundle equivocation proof if the operator of the given bundle has signed a
+    /// different bundle in the same slot before.
+    ///
+    /// The bundle headers seen before are tracked in the aux storage of the consensus client,
+    /// which is shared with the consensus transaction pool, thus both the gossiped bundles and
+    /// the bundles included in the consensus blocks are checked against each other.
+    pub(crate) fn generate_bundle_equivocation_proof(
+        &self,
+        sealed_header: SealedBundleHeader<NumberFor<CBlock>, CBlock::Hash, Block::Header, Balance>,
+    ) -> Result<Option<FraudProofFor<CBlock, Block::Header>>, FraudProofError> {
+        let slot = Slot::from(sealed_header.slot_number());
+        let maybe_fraud_proof =
+            check_equivocation::<_, CBlock, _>(&self.consensus_client, slot, sealed_header)?;
+
+        Ok(maybe_fraud_proof)
+    }
+
     pub(crate) fn generate_invalid_block_fees_proof(
         &self,
         domain_id: DomainId,
@@ -83,6 +83,7 @@ where
     CClient: HeaderBackend<CBlock>
         + HeaderMetadata<CBlock, Error = sp_blockchain::Error>
         + BlockBackend<CBlock>
+        + AuxStore
         + ProvideRuntimeApi<CBlock>
         + ProofProvider<CBlock>
         + BlockchainEvents<CBlock>
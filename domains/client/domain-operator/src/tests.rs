@@ -25,7 +25,7 @@ use sp_api::{ProvideRuntimeApi, StorageProof};
 use sp_consensus::SyncOracle;
 use sp_core::storage::StateVersion;
 use sp_core::traits::FetchRuntimeCode;
-use sp_core::{Pair, H256};
+use sp_core::{Get, Pair, H160, H256, U256};
 use sp_domain_digests::AsPredigest;
 use sp_domains::core_api::DomainCoreApi;
 use sp_domains::merkle_tree::MerkleTree;
@@ -39,7 +39,11 @@ use sp_domains_fraud_proof::fraud_proof::{
     InvalidTransfersProof,
 };
 use sp_domains_fraud_proof::InvalidTransactionCode;
-use sp_messenger::messages::{CrossDomainMessage, FeeModel, InitiateChannelParams, Proof};
+use sp_messenger::messages::{
+    CrossDomainMessage, FeeModel, InboxMessageStatus, InitiateChannelParams, OutboxMessageStatus,
+    Proof,
+};
+use sp_messenger::RelayerApi;
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof as MmrProof};
 use sp_runtime::generic::{BlockId, DigestItem};
 use sp_runtime::traits::{
@@ -2555,6 +2559,85 @@ async fn test_bundle_equivocation_fraud_proof() {
     ferdie.produce_blocks(1).await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_operator_submit_bundle_equivocation_fraud_proof() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Ferdie,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (a evm domain authority node)
+    let alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    produce_blocks!(ferdie, alice, 3).await.unwrap();
+
+    let bundle_to_tx = |opaque_bundle| {
+        subspace_test_runtime::UncheckedExtrinsic::new_unsigned(
+            pallet_domains::Call::submit_bundle { opaque_bundle }.into(),
+        )
+        .into()
+    };
+
+    let common_consensus_hash = ferdie.client.info().best_hash;
+    let (slot, mut opaque_bundle) = ferdie.produce_slot_and_wait_for_bundle_submission().await;
+
+    // Fork A includes the original bundle, the bundle is included in the consensus block directly
+    // thus it is not checked by the consensus transaction pool
+    let fork_a_block_hash = ferdie
+        .produce_block_with_slot_at(
+            slot,
+            common_consensus_hash,
+            Some(vec![bundle_to_tx(opaque_bundle.clone())]),
+        )
+        .await
+        .unwrap();
+
+    // Fork B includes a different bundle signed by the same operator in the same slot
+    let equivocated_bundle_tx = {
+        let receipt = &mut opaque_bundle.sealed_header.header.receipt;
+        receipt.domain_block_extrinsic_root = Default::default();
+        opaque_bundle.sealed_header.signature = Sr25519Keyring::Alice
+            .pair()
+            .sign(opaque_bundle.sealed_header.pre_hash().as_ref())
+            .into();
+        bundle_to_tx(opaque_bundle)
+    };
+    let wait_for_fraud_proof_fut =
+        ferdie.wait_for_fraud_proof(move |fp| matches!(fp, FraudProof::BundleEquivocation(_)));
+    ferdie
+        .produce_block_with_slot_at(
+            slot,
+            common_consensus_hash,
+            Some(vec![equivocated_bundle_tx]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ferdie.client.info().best_hash, fork_a_block_hash);
+
+    // The operator should detect the equivocation and submit the fraud proof
+    let _ = wait_for_fraud_proof_fut.await;
+
+    // Produce a consensus block that contains the fraud proof, the fraud proof wil be verified on
+    // on the runtime itself
+    ferdie.produce_blocks(1).await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_domain_block_builder_include_ext_with_failed_execution() {
     let directory = TempDir::new().expect("Must be able to create temporary directory");
@@ -3481,6 +3564,299 @@ async fn test_cross_domains_messages_should_work() {
     .unwrap();
 }
 
+// TODO: this test relies on the same cross domain message flow as
+// `test_cross_domains_messages_should_work`, enable it after the root cause is fixed.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_xdm_message_status_and_relay_request() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie with Alice Key since that is the sudo key
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Sr25519Alice,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (an evm domain)
+    let mut alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    // Run the cross domain gossip message worker
+    ferdie.start_cross_domain_gossip_message_worker();
+
+    produce_blocks!(ferdie, alice, 3).await.unwrap();
+
+    // add domain to consensus chain allowlist
+    ferdie
+        .construct_and_send_extrinsic_with(pallet_sudo::Call::sudo {
+            call: Box::new(subspace_test_runtime::RuntimeCall::Messenger(
+                pallet_messenger::Call::update_consensus_chain_allowlist {
+                    update: ChainAllowlistUpdate::Add(ChainId::Domain(GENESIS_DOMAIN_ID)),
+                },
+            )),
+        })
+        .await
+        .expect("Failed to construct and send consensus chain allowlist update");
+    produce_blocks!(ferdie, alice, 1).await.unwrap();
+
+    // add consensus chain to domain chain allow list
+    ferdie
+        .construct_and_send_extrinsic_with(subspace_test_runtime::RuntimeCall::Messenger(
+            pallet_messenger::Call::initiate_domain_update_chain_allowlist {
+                domain_id: GENESIS_DOMAIN_ID,
+                update: ChainAllowlistUpdate::Add(ChainId::Consensus),
+            },
+        ))
+        .await
+        .expect("Failed to construct and send domain chain allowlist update");
+    produce_blocks!(ferdie, alice, 1).await.unwrap();
+
+    // Open channel between the Consensus chain and EVM domains
+    alice
+        .construct_and_send_extrinsic(evm_domain_test_runtime::RuntimeCall::Messenger(
+            pallet_messenger::Call::initiate_channel {
+                dst_chain_id: ChainId::Consensus,
+                params: InitiateChannelParams {
+                    max_outgoing_messages: 100,
+                    fee_model: FeeModel { relay_fee: 1 },
+                },
+            },
+        ))
+        .await
+        .expect("Failed to construct and send extrinsic");
+    produce_blocks_until!(ferdie, alice, {
+        alice
+            .get_open_channel_for_chain(ChainId::Consensus)
+            .is_some()
+    })
+    .await
+    .unwrap();
+
+    // The channel open request is the first message of the channel, so the transfer is the second
+    let channel_id = alice
+        .get_open_channel_for_chain(ChainId::Consensus)
+        .unwrap();
+    let message_id = (channel_id, U256::one());
+    let outbox_message_status = |alice: &domain_test_service::EvmDomainNode| {
+        alice
+            .client
+            .runtime_api()
+            .outbox_message_status(
+                alice.client.info().best_hash,
+                ChainId::Consensus,
+                message_id,
+            )
+            .unwrap()
+    };
+    assert_eq!(outbox_message_status(&alice), OutboxMessageStatus::Unknown);
+
+    alice
+        .construct_and_send_extrinsic(pallet_transporter::Call::transfer {
+            dst_location: pallet_transporter::Location {
+                chain_id: ChainId::Consensus,
+                account_id: AccountIdConverter::convert(Sr25519Alice.into()),
+            },
+            amount: 10,
+        })
+        .await
+        .expect("Failed to construct and send extrinsic");
+    produce_blocks_until!(ferdie, alice, {
+        outbox_message_status(&alice) != OutboxMessageStatus::Unknown
+    })
+    .await
+    .unwrap();
+
+    // Wait until the message response is received by the domain
+    produce_blocks_until!(ferdie, alice, {
+        outbox_message_status(&alice) == OutboxMessageStatus::ResponseReceived
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        ferdie
+            .client
+            .runtime_api()
+            .inbox_message_status(
+                ferdie.client.info().best_hash,
+                ChainId::Domain(GENESIS_DOMAIN_ID),
+                message_id
+            )
+            .unwrap(),
+        InboxMessageStatus::ResponseDelivered
+    );
+
+    // The message is tracked end to end through the domain RPC
+    let (response, _) = alice
+        .rpc_handlers
+        .rpc_query(&format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"messenger_messageStatus","params":[{{"Domain":{}}},"Consensus",["{:#x}","{:#x}"]]}}"#,
+            u32::from(GENESIS_DOMAIN_ID),
+            message_id.0,
+            message_id.1
+        ))
+        .await
+        .unwrap();
+    assert!(
+        response.contains(r#""stage":"responseReceived""#),
+        "{response}"
+    );
+
+    // The relayer accepts requests to relay the message again, a message that is already
+    // processed is skipped by the relayer
+    let (response, _) = alice
+        .rpc_handlers
+        .rpc_query(&format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"messenger_relayMessage","params":[{{"Domain":{}}},"Consensus",["{:#x}","{:#x}"]]}}"#,
+            u32::from(GENESIS_DOMAIN_ID),
+            message_id.0,
+            message_id.1
+        ))
+        .await
+        .unwrap();
+    assert!(response.contains(r#""result":null"#), "{response}");
+    produce_blocks!(ferdie, alice, 3).await.unwrap();
+    assert_eq!(
+        outbox_message_status(&alice),
+        OutboxMessageStatus::ResponseReceived
+    );
+}
+
+// TODO: this test relies on the same cross domain message flow as
+// `test_cross_domains_messages_should_work`, enable it after the root cause is fixed.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn test_xdm_precompile_transfer_to_consensus() {
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie with Alice Key since that is the sudo key
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Sr25519Alice,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (an evm domain)
+    let mut alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    // Run the cross domain gossip message worker
+    ferdie.start_cross_domain_gossip_message_worker();
+
+    produce_blocks!(ferdie, alice, 3).await.unwrap();
+
+    // add domain to consensus chain allowlist
+    ferdie
+        .construct_and_send_extrinsic_with(pallet_sudo::Call::sudo {
+            call: Box::new(subspace_test_runtime::RuntimeCall::Messenger(
+                pallet_messenger::Call::update_consensus_chain_allowlist {
+                    update: ChainAllowlistUpdate::Add(ChainId::Domain(GENESIS_DOMAIN_ID)),
+                },
+            )),
+        })
+        .await
+        .expect("Failed to construct and send consensus chain allowlist update");
+    produce_blocks!(ferdie, alice, 1).await.unwrap();
+
+    // add consensus chain to domain chain allow list
+    ferdie
+        .construct_and_send_extrinsic_with(subspace_test_runtime::RuntimeCall::Messenger(
+            pallet_messenger::Call::initiate_domain_update_chain_allowlist {
+                domain_id: GENESIS_DOMAIN_ID,
+                update: ChainAllowlistUpdate::Add(ChainId::Consensus),
+            },
+        ))
+        .await
+        .expect("Failed to construct and send domain chain allowlist update");
+    produce_blocks!(ferdie, alice, 1).await.unwrap();
+
+    // Open channel between the Consensus chain and EVM domains
+    alice
+        .construct_and_send_extrinsic(evm_domain_test_runtime::RuntimeCall::Messenger(
+            pallet_messenger::Call::initiate_channel {
+                dst_chain_id: ChainId::Consensus,
+                params: InitiateChannelParams {
+                    max_outgoing_messages: 100,
+                    fee_model: FeeModel { relay_fee: 1 },
+                },
+            },
+        ))
+        .await
+        .expect("Failed to construct and send extrinsic");
+    produce_blocks_until!(ferdie, alice, {
+        alice
+            .get_open_channel_for_chain(ChainId::Consensus)
+            .is_some()
+    })
+    .await
+    .unwrap();
+
+    // Call `transferToConsensus(bytes32,uint256)` of the XDM precompile from Alice
+    let pre_alice_free_balance = alice.free_balance(alice.key.to_account_id());
+    let pre_ferdie_free_balance = ferdie.free_balance(ferdie.key.to_account_id());
+    let transfer_amount: Balance = 10;
+    let mut input = vec![0x84, 0xfb, 0x8a, 0x3b];
+    input.extend_from_slice(&Sr25519Alice.to_raw_public());
+    input.extend_from_slice(&{
+        let mut amount = [0u8; 32];
+        U256::from(transfer_amount).to_big_endian(&mut amount);
+        amount
+    });
+    alice
+        .construct_and_send_extrinsic(evm_domain_test_runtime::RuntimeCall::Sudo(
+            pallet_sudo::Call::sudo {
+                call: Box::new(evm_domain_test_runtime::RuntimeCall::EVM(
+                    pallet_evm::Call::call {
+                        source: H160::from(alice.key.to_account_id()),
+                        target: H160::from_low_u64_be(2048),
+                        input,
+                        value: U256::zero(),
+                        gas_limit: 1_000_000,
+                        max_fee_per_gas: U256::from(2_000_000_000u64),
+                        max_priority_fee_per_gas: None,
+                        nonce: None,
+                        access_list: vec![],
+                    },
+                )),
+            },
+        ))
+        .await
+        .expect("Failed to construct and send extrinsic");
+
+    // Wait until transfer succeed
+    produce_blocks_until!(ferdie, alice, {
+        let post_alice_free_balance = alice.free_balance(alice.key.to_account_id());
+        let post_ferdie_free_balance = ferdie.free_balance(ferdie.key.to_account_id());
+
+        post_alice_free_balance < pre_alice_free_balance - transfer_amount
+            && post_ferdie_free_balance == pre_ferdie_free_balance + transfer_amount
+    })
+    .await
+    .unwrap();
+}
+
 // TODO: Unlock test when multiple domains are supported in DecEx v2.
 // #[tokio::test(flavor = "multi_thread")]
 // async fn test_cross_domains_message_should_work() {
@@ -4653,3 +5029,126 @@ async fn test_verify_mmr_proof_stateless() {
         produce_blocks!(ferdie, alice, 1).await.unwrap();
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_domain_sunset() {
+    use sp_domains::BundleProducerElectionApi as _;
+    let directory = TempDir::new().expect("Must be able to create temporary directory");
+
+    let mut builder = sc_cli::LoggerBuilder::new("");
+    builder.with_colors(false);
+    let _ = builder.init();
+
+    let tokio_handle = tokio::runtime::Handle::current();
+
+    // Start Ferdie with Alice Key since that is the domain owner and the owner of the genesis operator
+    let mut ferdie = MockConsensusNode::run(
+        tokio_handle.clone(),
+        Sr25519Alice,
+        BasePath::new(directory.path().join("ferdie")),
+    );
+
+    // Run Alice (an evm domain)
+    let mut alice = domain_test_service::DomainNodeBuilder::new(
+        tokio_handle.clone(),
+        Alice,
+        BasePath::new(directory.path().join("alice")),
+    )
+    .build_evm_node(Role::Authority, GENESIS_DOMAIN_ID, &mut ferdie)
+    .await;
+
+    produce_blocks!(ferdie, alice, 5).await.unwrap();
+
+    // Deactivate the domain
+    ferdie
+        .construct_and_send_extrinsic_with(pallet_domains::Call::deactivate_domain {
+            domain_id: GENESIS_DOMAIN_ID,
+        })
+        .await
+        .expect("Failed to send extrinsic");
+    ferdie.produce_blocks(1).await.unwrap();
+    let head_receipt_number = ferdie
+        .client
+        .runtime_api()
+        .head_receipt_number(ferdie.client.info().best_hash, GENESIS_DOMAIN_ID)
+        .unwrap();
+
+    // The sunset can't be advanced during the challenge period
+    ferdie
+        .construct_and_send_extrinsic_with(pallet_domains::Call::advance_domain_sunset {
+            domain_id: GENESIS_DOMAIN_ID,
+        })
+        .await
+        .expect("Failed to send extrinsic");
+    ferdie.produce_blocks(1).await.unwrap();
+    assert!(ferdie
+        .client
+        .runtime_api()
+        .runtime_id(ferdie.client.info().best_hash, GENESIS_DOMAIN_ID)
+        .unwrap()
+        .is_some());
+
+    // Wait for the challenge period, no more bundle is accepted by the deactivated domain
+    let challenge_period = subspace_test_runtime::BlockTreePruningDepth::get();
+    ferdie
+        .produce_blocks(challenge_period as u64)
+        .await
+        .unwrap();
+    assert_eq!(
+        ferdie
+            .client
+            .runtime_api()
+            .head_receipt_number(ferdie.client.info().best_hash, GENESIS_DOMAIN_ID)
+            .unwrap(),
+        head_receipt_number
+    );
+
+    // Advance the sunset until all the receipts are confirmed and the domain is pruned
+    let mut steps = 0;
+    while ferdie
+        .client
+        .runtime_api()
+        .runtime_id(ferdie.client.info().best_hash, GENESIS_DOMAIN_ID)
+        .unwrap()
+        .is_some()
+    {
+        // One step per receipt plus the last step that sunsets the domain
+        assert!(steps <= head_receipt_number + 1);
+        ferdie
+            .construct_and_send_extrinsic_with(pallet_domains::Call::advance_domain_sunset {
+                domain_id: GENESIS_DOMAIN_ID,
+            })
+            .await
+            .expect("Failed to send extrinsic");
+        ferdie.produce_blocks(1).await.unwrap();
+        steps += 1;
+    }
+    assert!(ferdie
+        .client
+        .runtime_api()
+        .latest_confirmed_domain_block(ferdie.client.info().best_hash, GENESIS_DOMAIN_ID)
+        .unwrap()
+        .is_none());
+
+    // The stake of the genesis operator can be unlocked right away
+    let operator_id = 0;
+    assert!(ferdie
+        .client
+        .runtime_api()
+        .operator(ferdie.client.info().best_hash, operator_id)
+        .unwrap()
+        .is_some());
+    let pre_ferdie_free_balance = ferdie.free_balance(ferdie.key.to_account_id());
+    ferdie
+        .construct_and_send_extrinsic_with(pallet_domains::Call::unlock_operator { operator_id })
+        .await
+        .expect("Failed to send extrinsic");
+    ferdie.produce_blocks(1).await.unwrap();
+    assert!(ferdie
+        .client
+        .runtime_api()
+        .operator(ferdie.client.info().best_hash, operator_id)
+        .unwrap()
+        .is_none());
+    assert!(ferdie.free_balance(ferdie.key.to_account_id()) > pre_ferdie_free_balance);
+}
@@ -2,6 +2,7 @@
 // TODO: Restore once https://github.com/rust-lang/rust/issues/122105 is resolved
 // #![deny(unused_crate_dependencies)]
 
+pub mod rpc;
 pub mod worker;
 
 use async_channel::TrySendError;
@@ -13,7 +14,8 @@ use sp_api::ProvideRuntimeApi;
 use sp_core::H256;
 use sp_domains::{DomainId, DomainsApi};
 use sp_messenger::messages::{
-    BlockMessageWithStorageKey, BlockMessagesWithStorageKey, ChainId, CrossDomainMessage, Proof,
+    BlockMessageWithStorageKey, BlockMessagesWithStorageKey, ChainId, CrossDomainMessage,
+    MessageId, Proof,
 };
 use sp_messenger::{MessengerApi, RelayerApi};
 use sp_mmr_primitives::MmrApi;
@@ -32,6 +34,38 @@ struct Relayer<Client, Block>(PhantomData<(Client, Block)>);
 /// Sink used to submit all the gossip messages.
 pub type GossipMessageSink = TracingUnboundedSender<GossipMessage>;
 
+/// Maximum number of pending relay requests, further requests are rejected until the relayer
+/// catches up.
+pub const RELAY_REQUEST_QUEUE_SIZE: usize = 32;
+
+/// Maximum number of blocks the relayer looks back, from the block the messages are relayed at,
+/// to find the message of a relay request.
+pub const MAX_RELAY_REQUEST_LOOKBACK: u32 = 256;
+
+/// Request to relay a message, or a message response, from `src_chain_id` again.
+///
+/// Used to recover messages that are stuck, e.g. when the gossip of the original relay is lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayRequest {
+    /// Chain the message, or the message response, is sent from.
+    pub src_chain_id: ChainId,
+    /// Chain the message, or the message response, is sent to.
+    pub dst_chain_id: ChainId,
+    /// Id of the message.
+    pub message_id: MessageId,
+}
+
+/// Sink used to submit the relay requests to the relayer.
+pub type RelayRequestSink = async_channel::Sender<RelayRequest>;
+
+/// Stream of the relay requests consumed by the relayer.
+pub type RelayRequestStream = async_channel::Receiver<RelayRequest>;
+
+/// Creates the channel of relay requests.
+pub fn relay_request_channel() -> (RelayRequestSink, RelayRequestStream) {
+    async_channel::bounded(RELAY_REQUEST_QUEUE_SIZE)
+}
+
 /// Relayer error types.
 #[derive(Debug)]
 pub enum Error {
@@ -65,6 +99,10 @@ pub enum Error {
     MissingBlockHeader,
     /// Missing block hash
     MissingBlockHash,
+    /// Missing confirmed domain block
+    MissingConfirmedDomainBlock,
+    /// Relay request for a message that is not sent by the chains of this relayer
+    UnsupportedRelayRequest,
 }
 
 impl From<sp_blockchain::Error> for Error {
@@ -325,6 +363,71 @@ where
         let block_messages: BlockMessagesWithStorageKey = api
             .block_messages(block_hash_to_process)
             .map_err(|_| Error::FetchAssignedMessages)?;
+
+        Self::submit_block_messages_from_consensus_chain(
+            consensus_chain_client,
+            finalized_block,
+            block_hash_to_process,
+            block_messages,
+            gossip_message_sink,
+        )
+    }
+
+    /// Relays the message of the relay request again if the message is still pending.
+    ///
+    /// The message is searched in the block messages of the recent consensus blocks, starting
+    /// from the parent of the finalized block, and the proof is generated at that parent block.
+    pub(crate) fn submit_requested_message_from_consensus_chain(
+        consensus_chain_client: &Arc<Client>,
+        finalized_block: (NumberFor<Block>, Block::Hash),
+        relay_request: RelayRequest,
+        gossip_message_sink: &GossipMessageSink,
+    ) -> Result<(), Error>
+    where
+        Client::Api: MmrApi<Block, H256, NumberFor<Block>>
+            + RelayerApi<Block, NumberFor<Block>, NumberFor<Block>, Block::Hash>,
+    {
+        let block_number_to_process = match finalized_block.0.checked_sub(&One::one()) {
+            None => return Ok(()),
+            Some(number) => number,
+        };
+        let block_hash_to_process = consensus_chain_client
+            .hash(block_number_to_process)?
+            .ok_or(Error::MissingBlockHeader)?;
+
+        let block_messages = Self::find_requested_messages(
+            consensus_chain_client,
+            block_hash_to_process,
+            relay_request,
+        )?;
+        if block_messages.outbox.is_empty() && block_messages.inbox_responses.is_empty() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                "No message found for relay request {relay_request:?}",
+            );
+            return Ok(());
+        }
+
+        Self::submit_block_messages_from_consensus_chain(
+            consensus_chain_client,
+            finalized_block,
+            block_hash_to_process,
+            block_messages,
+            gossip_message_sink,
+        )
+    }
+
+    fn submit_block_messages_from_consensus_chain(
+        consensus_chain_client: &Arc<Client>,
+        finalized_block: (NumberFor<Block>, Block::Hash),
+        block_hash_to_process: Block::Hash,
+        block_messages: BlockMessagesWithStorageKey,
+        gossip_message_sink: &GossipMessageSink,
+    ) -> Result<(), Error>
+    where
+        Client::Api: MmrApi<Block, H256, NumberFor<Block>>
+            + RelayerApi<Block, NumberFor<Block>, NumberFor<Block>, Block::Hash>,
+    {
         let filtered_messages = Self::filter_messages(consensus_chain_client, block_messages)?;
 
         // short circuit if the there are no messages to relay
@@ -385,6 +488,139 @@ where
             .block_messages(confirmed_domain_block_hash)
             .map_err(|_| Error::FetchAssignedMessages)?;
 
+        Self::submit_block_messages_from_domain(
+            domain_id,
+            domain_client,
+            consensus_chain_client,
+            finalized_consensus_block,
+            confirmed_domain_block_hash,
+            block_messages,
+            gossip_message_sink,
+        )
+    }
+
+    /// Relays the message of the relay request again if the message is still pending.
+    ///
+    /// The message is searched in the block messages of the recent domain blocks, starting from
+    /// the confirmed domain block, and the proof is generated at the confirmed domain block.
+    pub(crate) fn submit_requested_message_from_domain<CClient, CBlock>(
+        domain_id: DomainId,
+        domain_client: &Arc<Client>,
+        consensus_chain_client: &Arc<CClient>,
+        finalized_consensus_block: (NumberFor<CBlock>, CBlock::Hash),
+        confirmed_domain_block_hash: Block::Hash,
+        relay_request: RelayRequest,
+        gossip_message_sink: &GossipMessageSink,
+    ) -> Result<(), Error>
+    where
+        CBlock: BlockT,
+        CClient: HeaderBackend<CBlock> + ProvideRuntimeApi<CBlock> + ProofProvider<CBlock>,
+        CClient::Api: DomainsApi<CBlock, Block::Header>
+            + MessengerApi<CBlock>
+            + MmrApi<CBlock, H256, NumberFor<CBlock>>,
+        Client::Api: RelayerApi<Block, NumberFor<Block>, NumberFor<CBlock>, CBlock::Hash>,
+    {
+        let block_messages = Self::find_requested_messages(
+            domain_client,
+            confirmed_domain_block_hash,
+            relay_request,
+        )?;
+        if block_messages.outbox.is_empty() && block_messages.inbox_responses.is_empty() {
+            tracing::debug!(
+                target: LOG_TARGET,
+                "No message found for relay request {relay_request:?}",
+            );
+            return Ok(());
+        }
+
+        Self::submit_block_messages_from_domain(
+            domain_id,
+            domain_client,
+            consensus_chain_client,
+            finalized_consensus_block,
+            confirmed_domain_block_hash,
+            block_messages,
+            gossip_message_sink,
+        )
+    }
+
+    /// Finds the outbox message and the inbox response of the relay request in the block
+    /// messages of the blocks up to [`MAX_RELAY_REQUEST_LOOKBACK`] from the given block.
+    fn find_requested_messages<CNumber, CHash>(
+        client: &Arc<Client>,
+        from_block_hash: Block::Hash,
+        relay_request: RelayRequest,
+    ) -> Result<BlockMessagesWithStorageKey, Error>
+    where
+        CNumber: Codec,
+        CHash: Codec,
+        Client::Api: RelayerApi<Block, NumberFor<Block>, CNumber, CHash>,
+    {
+        let is_requested = |msg: &BlockMessageWithStorageKey| {
+            msg.src_chain_id == relay_request.src_chain_id
+                && msg.dst_chain_id == relay_request.dst_chain_id
+                && (msg.channel_id, msg.nonce) == relay_request.message_id
+        };
+
+        let api = client.runtime_api();
+        let mut requested_messages = BlockMessagesWithStorageKey::default();
+        let mut block_hash = from_block_hash;
+        for _ in 0..MAX_RELAY_REQUEST_LOOKBACK {
+            // the state of the older blocks may be pruned already
+            let block_messages = match api.block_messages(block_hash) {
+                Ok(block_messages) => block_messages,
+                Err(_) => break,
+            };
+
+            if requested_messages.outbox.is_empty() {
+                requested_messages
+                    .outbox
+                    .extend(block_messages.outbox.into_iter().filter(is_requested));
+            }
+            if requested_messages.inbox_responses.is_empty() {
+                requested_messages.inbox_responses.extend(
+                    block_messages
+                        .inbox_responses
+                        .into_iter()
+                        .filter(is_requested),
+                );
+            }
+            if !requested_messages.outbox.is_empty()
+                && !requested_messages.inbox_responses.is_empty()
+            {
+                break;
+            }
+
+            let header = client
+                .header(block_hash)?
+                .ok_or(Error::MissingBlockHeader)?;
+            if header.number().is_zero() {
+                break;
+            }
+            block_hash = *header.parent_hash();
+        }
+
+        Ok(requested_messages)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn submit_block_messages_from_domain<CClient, CBlock>(
+        domain_id: DomainId,
+        domain_client: &Arc<Client>,
+        consensus_chain_client: &Arc<CClient>,
+        finalized_consensus_block: (NumberFor<CBlock>, CBlock::Hash),
+        confirmed_domain_block_hash: Block::Hash,
+        block_messages: BlockMessagesWithStorageKey,
+        gossip_message_sink: &GossipMessageSink,
+    ) -> Result<(), Error>
+    where
+        CBlock: BlockT,
+        CClient: HeaderBackend<CBlock> + ProvideRuntimeApi<CBlock> + ProofProvider<CBlock>,
+        CClient::Api: DomainsApi<CBlock, Block::Header>
+            + MessengerApi<CBlock>
+            + MmrApi<CBlock, H256, NumberFor<CBlock>>,
+        Client::Api: RelayerApi<Block, NumberFor<Block>, NumberFor<CBlock>, CBlock::Hash>,
+    {
         // filter out already relayed messages
         let filtered_messages = Self::filter_messages(domain_client, block_messages)?;
 
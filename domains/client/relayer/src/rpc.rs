@@ -0,0 +1,339 @@
+//! RPC api for tracking the cross domain messages and relaying the stuck messages again.
+
+use crate::{RelayRequest, RelayRequestSink};
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use parity_scale_codec::Codec;
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_domains::DomainId;
+use sp_messenger::messages::{ChainId, InboxMessageStatus, MessageId, OutboxMessageStatus};
+use sp_messenger::RelayerApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const MESSENGER_ERROR: i32 = 9300;
+
+fn runtime_error(message: &str, error: ApiError) -> ErrorObjectOwned {
+    ErrorObject::owned(MESSENGER_ERROR + 1, message, Some(error.to_string()))
+}
+
+fn unsupported_error() -> ErrorObjectOwned {
+    ErrorObject::owned(
+        MESSENGER_ERROR + 2,
+        "Message status is not supported by the runtime",
+        None::<()>,
+    )
+}
+
+fn relayer_error(message: &str) -> ErrorObjectOwned {
+    ErrorObject::owned(MESSENGER_ERROR + 3, message, None::<()>)
+}
+
+/// Stage of a cross domain message, derived from its status on the src_chain and dst_chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageStage {
+    /// Message is unknown to the chains tracked by this node.
+    Unknown,
+    /// Message is in the outbox of the src_chain and not processed by the dst_chain yet.
+    Outbox,
+    /// Message is processed by the dst_chain and the response is not processed by the
+    /// src_chain yet.
+    InboxProcessed,
+    /// Message response is processed by the src_chain.
+    ResponseReceived,
+}
+
+/// Status of a cross domain message tracked across its src_chain and dst_chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageStatus {
+    /// Stage of the message.
+    pub stage: MessageStage,
+    /// Status of the message on the src_chain, `None` if the src_chain is not tracked by this
+    /// node.
+    pub src_chain_status: Option<OutboxMessageStatus>,
+    /// Status of the message on the dst_chain, `None` if the dst_chain is not tracked by this
+    /// node.
+    pub dst_chain_status: Option<InboxMessageStatus>,
+}
+
+impl MessageStatus {
+    fn new(
+        src_chain_status: Option<OutboxMessageStatus>,
+        dst_chain_status: Option<InboxMessageStatus>,
+    ) -> Self {
+        let stage = match (src_chain_status, dst_chain_status) {
+            (Some(OutboxMessageStatus::ResponseReceived), _)
+            | (None, Some(InboxMessageStatus::ResponseDelivered)) => MessageStage::ResponseReceived,
+            (
+                Some(OutboxMessageStatus::AwaitingResponse) | None,
+                Some(InboxMessageStatus::ResponsePending | InboxMessageStatus::ResponseDelivered),
+            ) => MessageStage::InboxProcessed,
+            (Some(OutboxMessageStatus::AwaitingResponse), _) => MessageStage::Outbox,
+            (Some(OutboxMessageStatus::Unknown), _)
+            | (None, Some(InboxMessageStatus::NotReceived) | None) => MessageStage::Unknown,
+        };
+
+        Self {
+            stage,
+            src_chain_status,
+            dst_chain_status,
+        }
+    }
+}
+
+/// Message relayer RPC methods.
+#[rpc(client, server)]
+pub trait MessageRelayerApi {
+    /// Returns the status of the message sent from `src_chain_id` to `dst_chain_id`, using the
+    /// best block of the consensus chain and this domain.
+    #[method(name = "messenger_messageStatus")]
+    fn message_status(
+        &self,
+        src_chain_id: ChainId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> RpcResult<MessageStatus>;
+
+    /// Requests the relayer of this domain to relay the message, or the message response, sent
+    /// from `src_chain_id` to `dst_chain_id` again.
+    ///
+    /// `src_chain_id` must be either this domain or the consensus chain.
+    #[method(name = "messenger_relayMessage")]
+    fn relay_message(
+        &self,
+        src_chain_id: ChainId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> RpcResult<()>;
+}
+
+/// Provides RPC methods for tracking the messages of a domain and the consensus chain.
+pub struct MessageRelayerRpc<CClient, Client, CBlock, Block> {
+    domain_id: DomainId,
+    consensus_client: Arc<CClient>,
+    domain_client: Arc<Client>,
+    relay_request_sink: Option<RelayRequestSink>,
+    _phantom: PhantomData<(CBlock, Block)>,
+}
+
+impl<CClient, Client, CBlock, Block> MessageRelayerRpc<CClient, Client, CBlock, Block> {
+    /// Creates a new instance of the message relayer RPC handler.
+    ///
+    /// `relay_request_sink` is `None` if the domain relayer is not running on this node.
+    pub fn new(
+        domain_id: DomainId,
+        consensus_client: Arc<CClient>,
+        domain_client: Arc<Client>,
+        relay_request_sink: Option<RelayRequestSink>,
+    ) -> Self {
+        Self {
+            domain_id,
+            consensus_client,
+            domain_client,
+            relay_request_sink,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+fn ensure_message_status_supported<Client, Block, BlockNumber, CNumber, CHash>(
+    client: &Arc<Client>,
+) -> RpcResult<Block::Hash>
+where
+    Block: BlockT,
+    BlockNumber: Codec,
+    CNumber: Codec,
+    CHash: Codec,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client::Api: RelayerApi<Block, BlockNumber, CNumber, CHash>,
+{
+    let best_hash = client.info().best_hash;
+    let api_version = client
+        .runtime_api()
+        .api_version::<dyn RelayerApi<Block, BlockNumber, CNumber, CHash>>(best_hash)
+        .map_err(|error| runtime_error("Unable to query relayer api version", error))?
+        .unwrap_or_default();
+    if api_version < 2 {
+        return Err(unsupported_error());
+    }
+
+    Ok(best_hash)
+}
+
+fn outbox_message_status<Client, Block, BlockNumber, CNumber, CHash>(
+    client: &Arc<Client>,
+    dst_chain_id: ChainId,
+    message_id: MessageId,
+) -> RpcResult<OutboxMessageStatus>
+where
+    Block: BlockT,
+    BlockNumber: Codec,
+    CNumber: Codec,
+    CHash: Codec,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client::Api: RelayerApi<Block, BlockNumber, CNumber, CHash>,
+{
+    let best_hash = ensure_message_status_supported(client)?;
+    client
+        .runtime_api()
+        .outbox_message_status(best_hash, dst_chain_id, message_id)
+        .map_err(|error| runtime_error("Unable to query outbox message status", error))
+}
+
+fn inbox_message_status<Client, Block, BlockNumber, CNumber, CHash>(
+    client: &Arc<Client>,
+    src_chain_id: ChainId,
+    message_id: MessageId,
+) -> RpcResult<InboxMessageStatus>
+where
+    Block: BlockT,
+    BlockNumber: Codec,
+    CNumber: Codec,
+    CHash: Codec,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client::Api: RelayerApi<Block, BlockNumber, CNumber, CHash>,
+{
+    let best_hash = ensure_message_status_supported(client)?;
+    client
+        .runtime_api()
+        .inbox_message_status(best_hash, src_chain_id, message_id)
+        .map_err(|error| runtime_error("Unable to query inbox message status", error))
+}
+
+impl<CClient, Client, CBlock, Block> MessageRelayerApiServer
+    for MessageRelayerRpc<CClient, Client, CBlock, Block>
+where
+    Block: BlockT,
+    CBlock: BlockT,
+    CClient: ProvideRuntimeApi<CBlock> + HeaderBackend<CBlock> + Send + Sync + 'static,
+    CClient::Api: RelayerApi<CBlock, NumberFor<CBlock>, NumberFor<CBlock>, CBlock::Hash>,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    Client::Api: RelayerApi<Block, NumberFor<Block>, NumberFor<CBlock>, CBlock::Hash>,
+{
+    fn message_status(
+        &self,
+        src_chain_id: ChainId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> RpcResult<MessageStatus> {
+        let self_chain_id = ChainId::Domain(self.domain_id);
+
+        let src_chain_status = match src_chain_id {
+            ChainId::Consensus => Some(outbox_message_status(
+                &self.consensus_client,
+                dst_chain_id,
+                message_id,
+            )?),
+            chain_id if chain_id == self_chain_id => Some(outbox_message_status(
+                &self.domain_client,
+                dst_chain_id,
+                message_id,
+            )?),
+            _ => None,
+        };
+
+        let dst_chain_status = match dst_chain_id {
+            ChainId::Consensus => Some(inbox_message_status(
+                &self.consensus_client,
+                src_chain_id,
+                message_id,
+            )?),
+            chain_id if chain_id == self_chain_id => Some(inbox_message_status(
+                &self.domain_client,
+                src_chain_id,
+                message_id,
+            )?),
+            _ => None,
+        };
+
+        Ok(MessageStatus::new(src_chain_status, dst_chain_status))
+    }
+
+    fn relay_message(
+        &self,
+        src_chain_id: ChainId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> RpcResult<()> {
+        let relay_request_sink = self
+            .relay_request_sink
+            .as_ref()
+            .ok_or_else(|| relayer_error("Relayer is not running on this node"))?;
+
+        if src_chain_id != ChainId::Consensus && src_chain_id != ChainId::Domain(self.domain_id) {
+            return Err(relayer_error(
+                "Messages of other domains are not relayed by this node",
+            ));
+        }
+
+        relay_request_sink
+            .try_send(RelayRequest {
+                src_chain_id,
+                dst_chain_id,
+                message_id,
+            })
+            .map_err(|_| relayer_error("Too many pending relay requests"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageStage, MessageStatus};
+    use sp_messenger::messages::{InboxMessageStatus, OutboxMessageStatus};
+
+    #[test]
+    fn message_stage() {
+        let cases = [
+            (
+                Some(OutboxMessageStatus::Unknown),
+                Some(InboxMessageStatus::NotReceived),
+                MessageStage::Unknown,
+            ),
+            (
+                Some(OutboxMessageStatus::AwaitingResponse),
+                Some(InboxMessageStatus::NotReceived),
+                MessageStage::Outbox,
+            ),
+            (
+                Some(OutboxMessageStatus::AwaitingResponse),
+                None,
+                MessageStage::Outbox,
+            ),
+            (
+                Some(OutboxMessageStatus::AwaitingResponse),
+                Some(InboxMessageStatus::ResponsePending),
+                MessageStage::InboxProcessed,
+            ),
+            (
+                None,
+                Some(InboxMessageStatus::ResponsePending),
+                MessageStage::InboxProcessed,
+            ),
+            (
+                Some(OutboxMessageStatus::ResponseReceived),
+                Some(InboxMessageStatus::ResponsePending),
+                MessageStage::ResponseReceived,
+            ),
+            (
+                None,
+                Some(InboxMessageStatus::ResponseDelivered),
+                MessageStage::ResponseReceived,
+            ),
+            (None, None, MessageStage::Unknown),
+        ];
+
+        for (src_chain_status, dst_chain_status, stage) in cases {
+            assert_eq!(
+                MessageStatus::new(src_chain_status, dst_chain_status).stage,
+                stage,
+                "src_chain_status: {src_chain_status:?}, dst_chain_status: {dst_chain_status:?}"
+            );
+        }
+    }
+}
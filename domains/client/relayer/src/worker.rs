@@ -1,4 +1,7 @@
-use crate::{BlockT, Error, GossipMessageSink, HeaderBackend, HeaderT, Relayer, LOG_TARGET};
+use crate::{
+    BlockT, Error, GossipMessageSink, HeaderBackend, HeaderT, RelayRequest, RelayRequestStream,
+    Relayer, LOG_TARGET,
+};
 use futures::StreamExt;
 use sc_client_api::{AuxStore, BlockchainEvents, ProofProvider};
 use sc_state_db::PruningMode;
@@ -79,6 +82,7 @@ pub async fn relay_domain_messages<CClient, Client, CBlock, Block, SO>(
     domain_state_pruning: PruningMode,
     sync_oracle: SO,
     gossip_message_sink: GossipMessageSink,
+    relay_request_stream: RelayRequestStream,
 ) where
     Block: BlockT,
     CBlock: BlockT,
@@ -91,10 +95,20 @@ pub async fn relay_domain_messages<CClient, Client, CBlock, Block, SO>(
         + AuxStore,
     CClient::Api: DomainsApi<CBlock, Block::Header>
         + MessengerApi<CBlock>
+        + RelayerApi<CBlock, NumberFor<CBlock>, NumberFor<CBlock>, CBlock::Hash>
         + MmrApi<CBlock, sp_core::H256, NumberFor<CBlock>>,
     SO: SyncOracle + Send,
 {
-    let result = start_relaying_messages(
+    let relay_requests = handle_domain_relay_requests(
+        domain_id,
+        consensus_chain_client.clone(),
+        confirmation_depth_k,
+        domain_client.clone(),
+        relay_request_stream,
+        gossip_message_sink.clone(),
+    );
+
+    let relay_messages = start_relaying_messages(
         ChainId::Domain(domain_id),
         consensus_chain_client.clone(),
         confirmation_depth_k,
@@ -187,8 +201,9 @@ pub async fn relay_domain_messages<CClient, Client, CBlock, Block, SO>(
                 Ok(None)
             }
         },
-    )
-        .await;
+    );
+
+    let (result, ()) = futures::future::join(relay_messages, relay_requests).await;
     if let Err(err) = result {
         tracing::error!(
             target: LOG_TARGET,
@@ -198,6 +213,110 @@ pub async fn relay_domain_messages<CClient, Client, CBlock, Block, SO>(
     }
 }
 
+/// Relays the requested messages of this domain, or of the consensus chain, again, using the
+/// same finalized consensus block and confirmed domain block as the regular relaying of the
+/// messages.
+async fn handle_domain_relay_requests<CClient, Client, CBlock, Block>(
+    domain_id: DomainId,
+    consensus_chain_client: Arc<CClient>,
+    confirmation_depth_k: NumberFor<CBlock>,
+    domain_client: Arc<Client>,
+    relay_request_stream: RelayRequestStream,
+    gossip_message_sink: GossipMessageSink,
+) where
+    Block: BlockT,
+    CBlock: BlockT,
+    Client: HeaderBackend<Block> + AuxStore + ProofProvider<Block> + ProvideRuntimeApi<Block>,
+    Client::Api: RelayerApi<Block, NumberFor<Block>, NumberFor<CBlock>, CBlock::Hash>,
+    CClient: HeaderBackend<CBlock> + AuxStore + ProvideRuntimeApi<CBlock> + ProofProvider<CBlock>,
+    CClient::Api: DomainsApi<CBlock, Block::Header>
+        + MessengerApi<CBlock>
+        + RelayerApi<CBlock, NumberFor<CBlock>, NumberFor<CBlock>, CBlock::Hash>
+        + MmrApi<CBlock, sp_core::H256, NumberFor<CBlock>>,
+{
+    while let Ok(relay_request) = relay_request_stream.recv().await {
+        let result = relay_requested_message(
+            domain_id,
+            &consensus_chain_client,
+            confirmation_depth_k,
+            &domain_client,
+            relay_request,
+            &gossip_message_sink,
+        );
+
+        if let Err(err) = result {
+            tracing::error!(
+                target: LOG_TARGET,
+                ?err,
+                "Failed to relay the requested message {relay_request:?} of domain {domain_id:?}"
+            );
+        }
+    }
+}
+
+fn relay_requested_message<CClient, Client, CBlock, Block>(
+    domain_id: DomainId,
+    consensus_chain_client: &Arc<CClient>,
+    confirmation_depth_k: NumberFor<CBlock>,
+    domain_client: &Arc<Client>,
+    relay_request: RelayRequest,
+    gossip_message_sink: &GossipMessageSink,
+) -> Result<(), Error>
+where
+    Block: BlockT,
+    CBlock: BlockT,
+    Client: HeaderBackend<Block> + AuxStore + ProofProvider<Block> + ProvideRuntimeApi<Block>,
+    Client::Api: RelayerApi<Block, NumberFor<Block>, NumberFor<CBlock>, CBlock::Hash>,
+    CClient: HeaderBackend<CBlock> + AuxStore + ProvideRuntimeApi<CBlock> + ProofProvider<CBlock>,
+    CClient::Api: DomainsApi<CBlock, Block::Header>
+        + MessengerApi<CBlock>
+        + RelayerApi<CBlock, NumberFor<CBlock>, NumberFor<CBlock>, CBlock::Hash>
+        + MmrApi<CBlock, sp_core::H256, NumberFor<CBlock>>,
+{
+    let finalized_number = consensus_chain_client
+        .info()
+        .best_number
+        .checked_sub(&confirmation_depth_k)
+        .ok_or(Error::MissingBlockHash)?;
+    let finalized_hash = consensus_chain_client
+        .hash(finalized_number)?
+        .ok_or(Error::MissingBlockHash)?;
+
+    match relay_request.src_chain_id {
+        ChainId::Consensus => Relayer::submit_requested_message_from_consensus_chain(
+            consensus_chain_client,
+            (finalized_number, finalized_hash),
+            relay_request,
+            gossip_message_sink,
+        ),
+        ChainId::Domain(src_domain_id) if src_domain_id == domain_id => {
+            // the confirmed domain block is taken from the parent of the finalized consensus block
+            let consensus_number_to_process = finalized_number
+                .checked_sub(&One::one())
+                .ok_or(Error::MissingBlockHash)?;
+            let consensus_hash_to_process = consensus_chain_client
+                .hash(consensus_number_to_process)?
+                .ok_or(Error::MissingBlockHash)?;
+            let (_, confirmed_domain_block_hash) = consensus_chain_client
+                .runtime_api()
+                .latest_confirmed_domain_block(consensus_hash_to_process, domain_id)?
+                .ok_or(Error::MissingConfirmedDomainBlock)?;
+
+            Relayer::submit_requested_message_from_domain(
+                domain_id,
+                domain_client,
+                consensus_chain_client,
+                (finalized_number, finalized_hash),
+                confirmed_domain_block_hash,
+                relay_request,
+                gossip_message_sink,
+            )
+        }
+        // messages of the other domains are relayed by the relayers of those domains
+        ChainId::Domain(_) => Err(Error::UnsupportedRelayRequest),
+    }
+}
+
 fn is_state_available<Client, Block>(
     state_pruning_mode: &PruningMode,
     client: &Arc<Client>,
@@ -33,7 +33,7 @@ use frame_support::traits::Time;
 pub use pallet::*;
 use scale_info::TypeInfo;
 use sp_auto_id::auto_id_runtime_interface::{decode_tbs_certificate, verify_signature};
-use sp_auto_id::{DerVec, SignatureVerificationRequest, Validity};
+use sp_auto_id::{DerVec, SignatureVerificationRequest, TbsCertificate, Validity};
 use sp_core::{blake2_256, H256, U256};
 #[cfg(feature = "std")]
 use std::collections::BTreeSet;
@@ -75,7 +75,6 @@ pub enum Certificate {
 
 impl Certificate {
     /// Returns the subject distinguished name.
-    #[cfg(test)]
     fn subject_common_name(&self) -> DerVec {
         match self {
             Certificate::X509(cert) => cert.subject_common_name.clone(),
@@ -148,6 +147,22 @@ impl Certificate {
         }
     }
 
+    /// Replaces the certificate with the renewed certificate of the same subject.
+    ///
+    /// The issued serials are preserved and the nonce is incremented.
+    fn renew<T: Config>(&mut self, tbs_certificate: TbsCertificate, raw: DerVec) -> DispatchResult {
+        match self {
+            Certificate::X509(cert) => {
+                cert.serial = tbs_certificate.serial;
+                cert.subject_public_key_info = tbs_certificate.subject_public_key_info;
+                cert.validity = tbs_certificate.validity;
+                cert.raw = raw;
+            }
+        }
+
+        self.inc_nonce::<T>()
+    }
+
     fn inc_nonce<T: Config>(&mut self) -> DispatchResult {
         match self {
             Certificate::X509(cert) => {
@@ -184,6 +199,20 @@ pub enum RegisterAutoIdX509 {
     },
 }
 
+/// Type holds X509 certificate details used to renew an AutoId.
+#[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
+pub struct RenewAutoIdX509 {
+    pub certificate: DerVec,
+    pub signature_algorithm: DerVec,
+    pub signature: Vec<u8>,
+}
+
+/// Request to renew the certificate of an existing AutoId.
+#[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
+pub enum RenewAutoId {
+    X509(RenewAutoIdX509),
+}
+
 /// Signature holds algorithm used and the signature value.
 #[derive(Debug, Decode, Encode, TypeInfo, PartialEq, Eq, Clone)]
 pub struct Signature {
@@ -218,7 +247,7 @@ pub struct CertificateAction {
 #[frame_support::pallet]
 mod pallet {
     use super::*;
-    use crate::{AutoId, Identifier, RegisterAutoId, Serial, Signature};
+    use crate::{AutoId, Identifier, RegisterAutoId, RenewAutoId, Serial, Signature};
     use frame_support::pallet_prelude::*;
     use frame_support::traits::Time;
     use frame_system::pallet_prelude::*;
@@ -267,6 +296,8 @@ mod pallet {
         NonceOverflow,
         /// Identifier already exists.
         AutoIdIdentifierAlreadyExists,
+        /// Renewed certificate subject does not match the AutoId subject.
+        CertificateSubjectMismatch,
     }
 
     #[pallet::event]
@@ -278,6 +309,8 @@ mod pallet {
         CertificateRevoked(Identifier),
         /// Emits when an AutoId is deactivated.
         AutoIdDeactivated(Identifier),
+        /// Emits when the Certificate associated with AutoId is renewed.
+        AutoIdRenewed(Identifier),
     }
 
     #[pallet::call]
@@ -321,6 +354,23 @@ mod pallet {
             Self::do_deactivate_auto_id(auto_id_identifier, signature)?;
             Ok(())
         }
+
+        /// Renews the certificate associated with given AutoId.
+        ///
+        /// The renewed certificate must be issued to the same subject and signed by the same
+        /// issuer. For a root AutoId, the renewed certificate is signed with its current key.
+        #[pallet::call_index(3)]
+        // TODO: benchmark
+        #[pallet::weight({10_000})]
+        pub fn renew_auto_id(
+            origin: OriginFor<T>,
+            auto_id_identifier: Identifier,
+            req: RenewAutoId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::do_renew_auto_id(auto_id_identifier, req)?;
+            Ok(())
+        }
     }
 }
 
@@ -435,6 +485,83 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    fn do_renew_auto_id(auto_id_identifier: Identifier, req: RenewAutoId) -> DispatchResult {
+        let current_time = T::Time::now();
+        let mut auto_id = AutoIds::<T>::get(auto_id_identifier).ok_or(Error::<T>::UnknownAutoId)?;
+
+        let RenewAutoId::X509(RenewAutoIdX509 {
+            certificate,
+            signature_algorithm,
+            signature,
+        }) = req;
+
+        let (issuer_id, mut issuer_auto_id) = match auto_id.certificate.issuer_id() {
+            Some(issuer_id) => {
+                let issuer_auto_id =
+                    AutoIds::<T>::get(issuer_id).ok_or(Error::<T>::UnknownIssuer)?;
+                ensure!(
+                    issuer_auto_id.certificate.is_valid_at(current_time),
+                    Error::<T>::ExpiredCertificate
+                );
+                (issuer_id, issuer_auto_id)
+            }
+            // self renew, an expired root certificate is renewed using its current key
+            None => (auto_id_identifier, auto_id.clone()),
+        };
+
+        let tbs_certificate =
+            decode_tbs_certificate(certificate.clone()).ok_or(Error::<T>::InvalidCertificate)?;
+
+        let req = SignatureVerificationRequest {
+            public_key_info: issuer_auto_id.certificate.subject_public_key_info(),
+            signature_algorithm,
+            data: certificate.0.clone(),
+            signature,
+        };
+        verify_signature(req).ok_or(Error::<T>::InvalidSignature)?;
+
+        ensure!(
+            tbs_certificate.subject_common_name == auto_id.certificate.subject_common_name(),
+            Error::<T>::CertificateSubjectMismatch
+        );
+
+        ensure!(
+            tbs_certificate.validity.is_valid_at(current_time),
+            Error::<T>::ExpiredCertificate
+        );
+
+        ensure!(
+            !CertificateRevocationList::<T>::get(issuer_id).map_or(false, |serials| {
+                serials.iter().any(|s| {
+                    *s == issuer_auto_id.certificate.serial()
+                        || *s == auto_id.certificate.serial()
+                        || *s == tbs_certificate.serial
+                })
+            }),
+            Error::<T>::CertificateRevoked
+        );
+
+        match auto_id.certificate.issuer_id() {
+            Some(_) => {
+                issuer_auto_id
+                    .certificate
+                    .issue_certificate_serial::<T>(tbs_certificate.serial)?;
+                AutoIds::<T>::insert(issuer_id, issuer_auto_id);
+            }
+            None => auto_id
+                .certificate
+                .issue_certificate_serial::<T>(tbs_certificate.serial)?,
+        }
+
+        auto_id
+            .certificate
+            .renew::<T>(tbs_certificate, certificate)?;
+        AutoIds::<T>::insert(auto_id_identifier, auto_id);
+
+        Self::deposit_event(Event::<T>::AutoIdRenewed(auto_id_identifier));
+        Ok(())
+    }
+
     fn do_verify_signature(
         auto_id: &AutoId,
         signing_data: CertificateAction,
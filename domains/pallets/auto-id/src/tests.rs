@@ -2,7 +2,7 @@ use crate::pallet::AutoIds;
 use crate::{
     self as pallet_auto_id, Certificate, CertificateAction, CertificateActionType,
     CertificateRevocationList, Error, Identifier, Pallet, RegisterAutoId, RegisterAutoIdX509,
-    Signature, X509Certificate,
+    RenewAutoId, RenewAutoIdX509, Signature, X509Certificate,
 };
 use alloc::collections::BTreeSet;
 use codec::Encode;
@@ -215,6 +215,18 @@ fn register_leaf_auto_id(issuer_auto_id: Identifier) -> Identifier {
     auto_id_identifier
 }
 
+fn renew_auto_id_request(cert: &[u8]) -> (U256, RenewAutoId) {
+    let (_, cert) = x509_parser::certificate::X509Certificate::from_der(cert).unwrap();
+    let serial = U256::from_big_endian(cert.raw_serial());
+    let req = RenewAutoId::X509(RenewAutoIdX509 {
+        certificate: cert.tbs_certificate.as_ref().to_vec().into(),
+        signature_algorithm: algorithm_to_der(cert.signature_algorithm.clone()),
+        signature: cert.signature_value.as_ref().to_vec(),
+    });
+
+    (serial, req)
+}
+
 fn sign_preimage(data: Vec<u8>, issuer: bool) -> Signature {
     let priv_key_pem = if issuer {
         include_str!("../res/private.issuer.pem")
@@ -464,3 +476,81 @@ fn test_auto_id_identifier_is_deterministic() {
         );
     })
 }
+
+#[test]
+fn test_renew_leaf_auto_id() {
+    new_test_ext().execute_with(|| {
+        let issuer_id = register_issuer_auto_id();
+        let leaf_id = register_leaf_auto_id(issuer_id);
+        let leaf_auto_id = AutoIds::<Test>::get(leaf_id).unwrap();
+
+        let (serial, req) = renew_auto_id_request(include_bytes!("../res/leaf.renewed.cert.der"));
+        assert_ne!(serial, leaf_auto_id.certificate.serial());
+        Pallet::<Test>::renew_auto_id(RawOrigin::Signed(1).into(), leaf_id, req.clone()).unwrap();
+
+        let renewed_auto_id = AutoIds::<Test>::get(leaf_id).unwrap();
+        assert_eq!(renewed_auto_id.certificate.serial(), serial);
+        assert_eq!(renewed_auto_id.certificate.derive_identifier(), leaf_id);
+        assert_eq!(renewed_auto_id.certificate.nonce(), U256::one());
+        match (renewed_auto_id.certificate, leaf_auto_id.certificate) {
+            (Certificate::X509(renewed), Certificate::X509(leaf)) => {
+                assert_eq!(renewed.issued_serials, leaf.issued_serials);
+                assert_eq!(
+                    renewed.subject_public_key_info,
+                    leaf.subject_public_key_info
+                );
+                assert_ne!(renewed.validity, leaf.validity);
+            }
+        }
+
+        let issuer_auto_id = AutoIds::<Test>::get(issuer_id).unwrap();
+        match issuer_auto_id.certificate {
+            Certificate::X509(issuer) => assert!(issuer.issued_serials.contains(&serial)),
+        }
+
+        // the same certificate cannot be used to renew again
+        assert_noop!(
+            Pallet::<Test>::renew_auto_id(RawOrigin::Signed(1).into(), leaf_id, req),
+            Error::<Test>::CertificateSerialAlreadyIssued
+        );
+    })
+}
+
+#[test]
+fn test_renew_auto_id_with_different_subject() {
+    new_test_ext().execute_with(|| {
+        let issuer_id = register_issuer_auto_id();
+        let leaf_id = register_leaf_auto_id(issuer_id);
+
+        // issuer certificate is signed by the issuer but issued to a different subject
+        let (_, req) = renew_auto_id_request(include_bytes!("../res/issuer.cert.der"));
+        assert_noop!(
+            Pallet::<Test>::renew_auto_id(RawOrigin::Signed(1).into(), leaf_id, req),
+            Error::<Test>::CertificateSubjectMismatch
+        );
+    })
+}
+
+#[test]
+fn test_renew_revoked_auto_id() {
+    new_test_ext().execute_with(|| {
+        let issuer_id = register_issuer_auto_id();
+        let leaf_id = register_leaf_auto_id(issuer_id);
+        let issuer_auto_id = AutoIds::<Test>::get(issuer_id).unwrap();
+
+        let signing_data = CertificateAction {
+            id: leaf_id,
+            nonce: issuer_auto_id.certificate.nonce(),
+            action_type: CertificateActionType::RevokeCertificate,
+        };
+        let signature = sign_preimage(signing_data.encode(), true);
+        Pallet::<Test>::revoke_certificate(RawOrigin::Signed(1).into(), leaf_id, signature)
+            .unwrap();
+
+        let (_, req) = renew_auto_id_request(include_bytes!("../res/leaf.renewed.cert.der"));
+        assert_noop!(
+            Pallet::<Test>::renew_auto_id(RawOrigin::Signed(1).into(), leaf_id, req),
+            Error::<Test>::CertificateRevoked
+        );
+    })
+}
@@ -41,7 +41,8 @@ use scale_info::TypeInfo;
 use sp_core::U256;
 use sp_domains::DomainId;
 use sp_messenger::messages::{
-    ChainId, ChannelId, CrossDomainMessage, FeeModel, Message, MessageId, Nonce,
+    ChainId, ChannelId, CrossDomainMessage, FeeModel, InboxMessageStatus, Message, MessageId,
+    Nonce, OutboxMessageStatus,
 };
 use sp_runtime::traits::{Extrinsic, Hash};
 use sp_runtime::DispatchError;
@@ -152,7 +153,7 @@ mod pallet {
     use frame_system::pallet_prelude::*;
     use sp_core::storage::StorageKey;
     use sp_domains::proof_provider_and_verifier::{StorageProofVerifier, VerificationError};
-    use sp_domains::{DomainAllowlistUpdates, DomainId, DomainOwner};
+    use sp_domains::{DeactivatedDomains, DomainAllowlistUpdates, DomainId, DomainOwner};
     use sp_messenger::endpoint::{Endpoint, EndpointHandler, EndpointRequest, Sender};
     use sp_messenger::messages::{
         ChainId, CrossDomainMessage, InitiateChannelParams, Message, MessageId, MessageKey,
@@ -196,6 +197,8 @@ mod pallet {
         type StorageKeys: StorageKeys;
         /// Domain owner provider.
         type DomainOwner: DomainOwner<Self::AccountId>;
+        /// Deactivated domains provider.
+        type DeactivatedDomains: DeactivatedDomains;
         /// A variation of the Identifier used for holding the funds used for Messenger
         type HoldIdentifier: HoldIdentifier<Self>;
         /// Channel reserve fee to open a channel.
@@ -536,6 +539,9 @@ mod pallet {
 
         /// Failed to unlock the balance
         BalanceUnlock,
+
+        /// Emits when the destination domain is deactivated and doesn't process new messages.
+        DomainDeactivated,
     }
 
     #[pallet::hooks]
@@ -560,6 +566,7 @@ mod pallet {
             params: InitiateChannelParams<BalanceOf<T>>,
         ) -> DispatchResult {
             let owner = ensure_signed(origin)?;
+            Self::ensure_active_dst_chain(dst_chain_id)?;
 
             // initiate the channel config
             let channel_id = Self::do_init_channel(dst_chain_id, params, Some(owner.clone()))?;
@@ -807,6 +814,7 @@ mod pallet {
             dst_chain_id: ChainId,
             req: EndpointRequest,
         ) -> Result<Self::MessageId, DispatchError> {
+            Self::ensure_active_dst_chain(dst_chain_id)?;
             let (channel_id, fee_model) =
                 Self::get_open_channel_for_chain(dst_chain_id).ok_or(Error::<T>::NoOpenChannel)?;
 
@@ -868,6 +876,19 @@ mod pallet {
             }
         }
 
+        /// Ensures the destination chain is not a deactivated domain, which never processes the
+        /// new messages.
+        fn ensure_active_dst_chain(dst_chain_id: ChainId) -> DispatchResult {
+            if let Some(domain_id) = dst_chain_id.maybe_domain_chain() {
+                ensure!(
+                    !T::DeactivatedDomains::is_domain_deactivated(domain_id),
+                    Error::<T>::DomainDeactivated
+                );
+            }
+
+            Ok(())
+        }
+
         /// Returns the last open channel for a given chain.
         pub fn get_open_channel_for_chain(
             dst_chain_id: ChainId,
@@ -1243,6 +1264,39 @@ where
     pub fn should_relay_inbox_message_response(dst_chain_id: ChainId, msg_id: MessageId) -> bool {
         InboxResponses::<T>::contains_key((dst_chain_id, msg_id.0, msg_id.1))
     }
+
+    /// Returns the status of the message sent from this chain to dst_chain.
+    pub fn outbox_message_status(dst_chain_id: ChainId, msg_id: MessageId) -> OutboxMessageStatus {
+        let (channel_id, nonce) = msg_id;
+        if Outbox::<T>::contains_key((dst_chain_id, channel_id, nonce)) {
+            return OutboxMessageStatus::AwaitingResponse;
+        }
+
+        // responses are processed in the nonce order so any message with nonce less than or
+        // equal to the latest response nonce has received its response.
+        match Channels::<T>::get(dst_chain_id, channel_id)
+            .and_then(|channel| channel.latest_response_received_message_nonce)
+        {
+            Some(latest_nonce) if nonce <= latest_nonce => OutboxMessageStatus::ResponseReceived,
+            _ => OutboxMessageStatus::Unknown,
+        }
+    }
+
+    /// Returns the status of the message sent from src_chain to this chain.
+    pub fn inbox_message_status(src_chain_id: ChainId, msg_id: MessageId) -> InboxMessageStatus {
+        let (channel_id, nonce) = msg_id;
+        if InboxResponses::<T>::contains_key((src_chain_id, channel_id, nonce)) {
+            return InboxMessageStatus::ResponsePending;
+        }
+
+        // inbox responses are removed once the src_chain acknowledged the delivery.
+        match Channels::<T>::get(src_chain_id, channel_id) {
+            Some(channel) if nonce < channel.next_inbox_nonce => {
+                InboxMessageStatus::ResponseDelivered
+            }
+            _ => InboxMessageStatus::NotReceived,
+        }
+    }
 }
 
 impl<T: Config> sp_domains::DomainBundleSubmitted for Pallet<T> {
@@ -32,7 +32,8 @@ macro_rules! impl_runtime {
         use sp_runtime::traits::Convert;
         use sp_runtime::BuildStorage;
         use crate::HoldIdentifier;
-        use sp_domains::ChannelId;
+        use sp_domains::{ChannelId, DeactivatedDomains, DomainId};
+        use std::collections::BTreeSet;
         use scale_info::TypeInfo;
         use codec::MaxEncodedLen;
         use sp_domains::MessengerHoldIdentifier;
@@ -59,6 +60,15 @@ macro_rules! impl_runtime {
         parameter_types! {
             pub SelfChainId: ChainId = $chain_id.into();
             pub const ChannelReserveFee: Balance = 10;
+            pub static DeactivatedDomainIds: BTreeSet<DomainId> = BTreeSet::new();
+        }
+
+        pub struct MockDeactivatedDomains;
+
+        impl DeactivatedDomains for MockDeactivatedDomains {
+            fn is_domain_deactivated(domain_id: DomainId) -> bool {
+                DeactivatedDomainIds::get().contains(&domain_id)
+            }
         }
 
         #[derive(
@@ -89,6 +99,7 @@ macro_rules! impl_runtime {
             type MmrProofVerifier = ();
             type StorageKeys = ();
             type DomainOwner = ();
+            type DeactivatedDomains = MockDeactivatedDomains;
             type ChannelReserveFee = ChannelReserveFee;
             type HoldIdentifier = MockHoldIdentifer;
             /// function to fetch endpoint response handler by Endpoint.
@@ -17,8 +17,8 @@ use sp_core::{Blake2Hasher, H256};
 use sp_domains::proof_provider_and_verifier::{StorageProofVerifier, VerificationError};
 use sp_messenger::endpoint::{Endpoint, EndpointPayload, EndpointRequest, Sender};
 use sp_messenger::messages::{
-    ChainId, CrossDomainMessage, InitiateChannelParams, MessageWeightTag, Payload, Proof,
-    ProtocolMessageRequest, RequestResponse, VersionedPayload,
+    ChainId, CrossDomainMessage, InboxMessageStatus, InitiateChannelParams, MessageWeightTag,
+    OutboxMessageStatus, Payload, Proof, ProtocolMessageRequest, RequestResponse, VersionedPayload,
 };
 use sp_mmr_primitives::{EncodableOpaqueLeaf, Proof as MmrProof};
 use sp_runtime::traits::Convert;
@@ -685,6 +685,86 @@ fn test_send_message_between_chains() {
     )
 }
 
+#[test]
+fn test_message_status_between_chains() {
+    let mut chain_a_test_ext = chain_a::new_test_ext();
+    let mut chain_b_test_ext = chain_b::new_test_ext();
+    let chain_a_id = chain_a::SelfChainId::get();
+    let chain_b_id = chain_b::SelfChainId::get();
+    let channel_id = open_channel_between_chains(
+        &mut chain_a_test_ext,
+        &mut chain_b_test_ext,
+        Default::default(),
+    );
+
+    chain_a_test_ext.execute_with(|| {
+        assert_eq!(
+            chain_a::Messenger::outbox_message_status(chain_b_id, (channel_id, Nonce::zero())),
+            OutboxMessageStatus::ResponseReceived
+        );
+        assert_eq!(
+            chain_a::Messenger::outbox_message_status(chain_b_id, (channel_id, Nonce::one())),
+            OutboxMessageStatus::Unknown
+        );
+    });
+    chain_b_test_ext.execute_with(|| {
+        assert_eq!(
+            chain_b::Messenger::inbox_message_status(chain_a_id, (channel_id, Nonce::zero())),
+            InboxMessageStatus::ResponsePending
+        );
+        assert_eq!(
+            chain_b::Messenger::inbox_message_status(chain_a_id, (channel_id, Nonce::one())),
+            InboxMessageStatus::NotReceived
+        );
+    });
+
+    send_message_between_chains(
+        &0,
+        &mut chain_a_test_ext,
+        &mut chain_b_test_ext,
+        vec![1, 2, 3, 4],
+        channel_id,
+    );
+
+    // send another message without relaying it
+    let next_nonce = Nonce::from(2u32);
+    chain_a_test_ext.execute_with(|| {
+        assert_ok!(<chain_a::Messenger as Sender<AccountId>>::send_message(
+            &0,
+            chain_b_id,
+            EndpointRequest {
+                src_endpoint: Endpoint::Id(0),
+                dst_endpoint: Endpoint::Id(0),
+                payload: vec![1, 2, 3, 4],
+            },
+        ));
+
+        assert_eq!(
+            chain_a::Messenger::outbox_message_status(chain_b_id, (channel_id, Nonce::one())),
+            OutboxMessageStatus::ResponseReceived
+        );
+        assert_eq!(
+            chain_a::Messenger::outbox_message_status(chain_b_id, (channel_id, next_nonce)),
+            OutboxMessageStatus::AwaitingResponse
+        );
+    });
+    chain_b_test_ext.execute_with(|| {
+        // the response of the channel open message is acknowledged by the next message
+        assert_eq!(
+            chain_b::Messenger::inbox_message_status(chain_a_id, (channel_id, Nonce::zero())),
+            InboxMessageStatus::ResponseDelivered
+        );
+        assert_eq!(
+            chain_b::Messenger::inbox_message_status(chain_a_id, (channel_id, Nonce::one())),
+            InboxMessageStatus::ResponsePending
+        );
+        assert_eq!(
+            chain_b::Messenger::inbox_message_status(chain_a_id, (channel_id, next_nonce)),
+            InboxMessageStatus::NotReceived
+        );
+    });
+}
+
 fn initiate_transfer_on_chain(chain_a_ext: &mut TestExternalities) {
     // this account should have 1000 balance on each chain
     let account_id = 1;
@@ -825,6 +905,55 @@ fn test_transport_funds_between_chains_failed_low_balance() {
     });
 }
 
+#[test]
+fn test_transport_funds_to_deactivated_domain() {
+    let mut chain_a_test_ext = chain_a::new_test_ext();
+    let mut chain_b_test_ext = chain_b::new_test_ext();
+    // open channel between chain_a and chain_b
+    // chain_a initiates the channel open
+    open_channel_between_chains(
+        &mut chain_a_test_ext,
+        &mut chain_b_test_ext,
+        Default::default(),
+    );
+
+    // chain_b is deactivated after the channel is open
+    let account_id = 1;
+    chain_a_test_ext.execute_with(|| {
+        let chain_b_id = chain_b::SelfChainId::get();
+        chain_a::DeactivatedDomainIds::set(BTreeSet::from([chain_b_id
+            .maybe_domain_chain()
+            .unwrap()]));
+
+        let res = chain_a::Transporter::transfer(
+            chain_a::RuntimeOrigin::signed(account_id),
+            Location {
+                chain_id: chain_b_id,
+                account_id: chain_b::MockAccountIdConverter::convert(account_id),
+            },
+            500,
+        );
+        assert_err!(res, crate::Error::<chain_a::Runtime>::DomainDeactivated);
+        assert!(
+            chain_a::Transporter::outgoing_transfers(chain_b_id, (U256::zero(), U256::one()),)
+                .is_none()
+        );
+
+        // New channels can't be opened either
+        assert_err!(
+            Messenger::initiate_channel(
+                RuntimeOrigin::signed(USER_ACCOUNT),
+                chain_b_id,
+                InitiateChannelParams {
+                    max_outgoing_messages: 100,
+                    fee_model: Default::default(),
+                },
+            ),
+            crate::Error::<chain_a::Runtime>::DomainDeactivated
+        );
+    });
+}
+
 #[test]
 fn test_transport_funds_between_chains_failed_no_open_channel() {
     let mut chain_a_test_ext = chain_a::new_test_ext();
@@ -30,6 +30,8 @@ pub mod weights;
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use domain_runtime_primitives::{MultiAccountId, TryConvertBack};
 use frame_support::dispatch::DispatchResult;
@@ -40,7 +42,7 @@ use scale_info::TypeInfo;
 use sp_domains::{DomainId, DomainsTransfersTracker, Transfers};
 use sp_messenger::endpoint::EndpointResponse;
 use sp_messenger::messages::ChainId;
-use sp_runtime::traits::{CheckedAdd, CheckedSub, Get};
+use sp_runtime::traits::{CheckedAdd, CheckedSub, Get, Zero};
 use sp_std::vec;
 
 /// Location that either sends or receives transfers between chains.
@@ -64,7 +66,7 @@ pub struct Transfer<Balance> {
 }
 
 /// Balance type used by the pallet.
-pub(crate) type BalanceOf<T> =
+pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 type MessageIdOf<T> = <<T as Config>::Sender as sp_messenger::endpoint::Sender<
@@ -222,6 +224,8 @@ mod pallet {
         BalanceUnderflow,
         /// Emits when domain balance is already initialized
         DomainBalanceAlreadyInitialized,
+        /// Emits when the domain balance is closed before the transfers from the domain are settled
+        UnsettledDomainTransfers,
     }
 
     #[pallet::call]
@@ -236,7 +240,32 @@ mod pallet {
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            Self::do_transfer(sender, dst_location, amount)?;
+            Ok(())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            ChainTransfers::<T>::set(Default::default());
+            T::DbWeight::get().writes(1)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn transfers_storage_key() -> Vec<u8> {
+            use frame_support::storage::generator::StorageValue;
+            ChainTransfers::<T>::storage_value_final_key().to_vec()
+        }
 
+        /// Burns `amount` from `sender` and initiates the transfer to `dst_location`, returns
+        /// the message id of the transfer.
+        pub fn do_transfer(
+            sender: T::AccountId,
+            dst_location: Location,
+            amount: BalanceOf<T>,
+        ) -> Result<MessageIdOf<T>, DispatchError> {
             // burn transfer amount
             let _imbalance = T::Currency::withdraw(
                 &sender,
@@ -285,22 +314,7 @@ mod pallet {
                 })?;
             }
 
-            Ok(())
-        }
-    }
-
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            ChainTransfers::<T>::set(Default::default());
-            T::DbWeight::get().writes(1)
-        }
-    }
-
-    impl<T: Config> Pallet<T> {
-        pub fn transfers_storage_key() -> Vec<u8> {
-            use frame_support::storage::generator::StorageValue;
-            ChainTransfers::<T>::storage_value_final_key().to_vec()
+            Ok(message_id)
         }
     }
 
@@ -552,6 +566,49 @@ impl<T: Config> sp_domains::DomainsTransfersTracker<BalanceOf<T>> for Pallet<T>
             Ok(())
         })
     }
+
+    fn is_domain_transfers_settled(domain_id: DomainId) -> bool {
+        UnconfirmedTransfers::<T>::iter_prefix_values(ChainId::Domain(domain_id))
+            .all(|amount| amount.is_zero())
+    }
+
+    fn close_domain_balance(domain_id: DomainId) -> Result<BalanceOf<T>, Self::Error> {
+        Self::ensure_consensus_chain()?;
+
+        let domain_chain_id = ChainId::Domain(domain_id);
+        ensure!(
+            Self::is_domain_transfers_settled(domain_id),
+            Error::UnsettledDomainTransfers
+        );
+
+        // Transfers from the domain are all settled at this point so the unconfirmed entries are
+        // all zero. The rejected transfers from the domain can only be claimed by the domain, add
+        // them to the remaining balance.
+        let _ = UnconfirmedTransfers::<T>::clear_prefix(domain_chain_id, u32::MAX, None);
+        let mut remaining_balance = DomainBalances::<T>::take(domain_id);
+        for (_, amount) in CancelledTransfers::<T>::drain_prefix(domain_chain_id) {
+            remaining_balance = remaining_balance
+                .checked_add(&amount)
+                .ok_or(Error::BalanceOverflow)?;
+        }
+
+        // The domain will never process the transfers to it that are still unconfirmed, cancel
+        // them as if they were rejected by the domain.
+        let incoming_transfers = UnconfirmedTransfers::<T>::iter()
+            .filter(|(_, to_chain_id, _)| *to_chain_id == domain_chain_id)
+            .collect::<Vec<_>>();
+        for (from_chain_id, to_chain_id, amount) in incoming_transfers {
+            UnconfirmedTransfers::<T>::remove(from_chain_id, to_chain_id);
+            CancelledTransfers::<T>::try_mutate(from_chain_id, to_chain_id, |total_amount| {
+                *total_amount = total_amount
+                    .checked_add(&amount)
+                    .ok_or(Error::BalanceOverflow)?;
+                Ok(())
+            })?;
+        }
+
+        Ok(remaining_balance)
+    }
 }
 
 impl<T: Config> Pallet<T> {
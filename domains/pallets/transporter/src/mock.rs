@@ -42,7 +42,7 @@ impl pallet_balances::Config for MockRuntime {
 }
 
 parameter_types! {
-    pub SelfChainId: ChainId = 1.into();
+    pub static SelfChainId: ChainId = 1.into();
     pub const SelfEndpointId: EndpointId = 100;
 }
 
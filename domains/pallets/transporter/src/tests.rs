@@ -2,10 +2,14 @@ use crate::mock::{
     new_test_ext, AccountId, Balance, Balances, MockAccountIdConverter, MockRuntime, RuntimeEvent,
     RuntimeOrigin, SelfChainId, SelfEndpointId, System, Transporter, USER_ACCOUNT,
 };
-use crate::{EndpointHandler, Error, Location, Transfer};
+use crate::{
+    CancelledTransfers, DomainBalances, EndpointHandler, Error, Location, Transfer,
+    UnconfirmedTransfers,
+};
 use codec::Encode;
 use frame_support::dispatch::DispatchResult;
 use frame_support::{assert_err, assert_ok};
+use sp_domains::{DomainId, DomainsTransfersTracker};
 use sp_messenger::endpoint::{
     Endpoint, EndpointHandler as EndpointHandlerT, EndpointRequest, EndpointResponse,
 };
@@ -319,3 +323,104 @@ fn test_receive_incoming_transfer() {
         assert_eq!(total_balance, 1500);
     })
 }
+
+#[test]
+fn test_domain_transfers_settled() {
+    new_test_ext().execute_with(|| {
+        let domain_id = DomainId::new(2);
+        let domain_chain_id = ChainId::Domain(domain_id);
+        assert!(Transporter::is_domain_transfers_settled(domain_id));
+
+        // Unrelated transfers don't affect the domain
+        UnconfirmedTransfers::<MockRuntime>::insert(
+            ChainId::Consensus,
+            ChainId::Domain(DomainId::new(3)),
+            100,
+        );
+        assert!(Transporter::is_domain_transfers_settled(domain_id));
+
+        // Transfers from the domain are not settled
+        UnconfirmedTransfers::<MockRuntime>::insert(domain_chain_id, ChainId::Consensus, 100);
+        assert!(!Transporter::is_domain_transfers_settled(domain_id));
+        UnconfirmedTransfers::<MockRuntime>::insert(domain_chain_id, ChainId::Consensus, 0);
+        assert!(Transporter::is_domain_transfers_settled(domain_id));
+
+        // Transfers to the domain are cancelled when the domain balance is closed, they don't
+        // need to be settled
+        UnconfirmedTransfers::<MockRuntime>::insert(ChainId::Consensus, domain_chain_id, 100);
+        assert!(Transporter::is_domain_transfers_settled(domain_id));
+    })
+}
+
+#[test]
+fn test_close_domain_balance() {
+    new_test_ext().execute_with(|| {
+        SelfChainId::set(ChainId::Consensus);
+        let domain_id = DomainId::new(2);
+        let domain_chain_id = ChainId::Domain(domain_id);
+        let other_domain_id = DomainId::new(3);
+        let other_domain_chain_id = ChainId::Domain(other_domain_id);
+        assert_ok!(Transporter::initialize_domain_balance(domain_id, 1000));
+        assert_ok!(Transporter::initialize_domain_balance(
+            other_domain_id,
+            1000
+        ));
+
+        // Transfer from the domain that is not settled yet
+        assert_ok!(Transporter::note_transfer(
+            domain_chain_id,
+            ChainId::Consensus,
+            100
+        ));
+        assert_err!(
+            Transporter::close_domain_balance(domain_id),
+            Error::<MockRuntime>::UnsettledDomainTransfers
+        );
+        assert_ok!(Transporter::reject_transfer(
+            domain_chain_id,
+            ChainId::Consensus,
+            100
+        ));
+
+        // Transfers to the domain that the domain will never process
+        assert_ok!(Transporter::note_transfer(
+            ChainId::Consensus,
+            domain_chain_id,
+            200
+        ));
+        assert_ok!(Transporter::note_transfer(
+            other_domain_chain_id,
+            domain_chain_id,
+            300
+        ));
+
+        // The rejected transfer from the domain is added to the remaining balance since the
+        // domain will never claim it
+        assert_eq!(Transporter::close_domain_balance(domain_id), Ok(1000));
+        assert!(!DomainBalances::<MockRuntime>::contains_key(domain_id));
+        assert_eq!(
+            CancelledTransfers::<MockRuntime>::get(domain_chain_id, ChainId::Consensus),
+            0
+        );
+
+        // The transfers to the domain are cancelled, the source chains can claim them back
+        assert_eq!(
+            UnconfirmedTransfers::<MockRuntime>::get(ChainId::Consensus, domain_chain_id),
+            0
+        );
+        assert_eq!(
+            CancelledTransfers::<MockRuntime>::get(ChainId::Consensus, domain_chain_id),
+            200
+        );
+        assert_eq!(
+            CancelledTransfers::<MockRuntime>::get(other_domain_chain_id, domain_chain_id),
+            300
+        );
+        assert_ok!(Transporter::claim_rejected_transfer(
+            other_domain_chain_id,
+            domain_chain_id,
+            300
+        ));
+        assert_eq!(Transporter::domain_balances(other_domain_id), 1000);
+    })
+}
@@ -0,0 +1,155 @@
+//! Benchmarking for `pallet-xdm-call`.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use super::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+use frame_benchmarking::v2::*;
+use frame_support::assert_ok;
+use frame_support::traits::fungible::{Inspect, Mutate, MutateHold};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+use sp_messenger::endpoint::{
+    Endpoint, EndpointHandler as EndpointHandlerT, EndpointRequest, Sender,
+};
+use sp_runtime::traits::{Convert, Hash};
+use sp_runtime::DispatchError;
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+use crate::Pallet as XdmCall;
+
+const SEED: u32 = 0;
+
+#[benchmarks(where <T as Config>::RuntimeCall: From<frame_system::Call<T>>)]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn send_call() {
+        let sender: T::AccountId = account("sender", 1, SEED);
+        let dst_chain_id: ChainId = u32::MAX.into();
+        assert_ne!(T::SelfChainId::get(), dst_chain_id);
+        let call = vec![0u8; T::MaxCallLength::get() as usize];
+        T::Currency::set_balance(
+            &sender,
+            T::CallDeposit::get() + T::Currency::minimum_balance(),
+        );
+
+        assert_ok!(T::Sender::unchecked_open_channel(dst_chain_id));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(sender), dst_chain_id, call);
+
+        assert!(OutgoingCalls::<T>::contains_key(
+            dst_chain_id,
+            MessageIdOf::<T>::default()
+        ));
+    }
+
+    /// Benchmark `message` without the weight of the dispatched call, which is bounded by
+    /// `MaxCallWeight` separately.
+    #[benchmark]
+    fn message() {
+        let sender: T::AccountId = account("sender", 1, SEED);
+        let src_chain_id: ChainId = u32::MAX.into();
+        assert_ne!(T::SelfChainId::get(), src_chain_id);
+        let call: <T as Config>::RuntimeCall =
+            frame_system::Call::<T>::remark_with_event { remark: vec![] }.into();
+        let endpoint_req = EndpointRequest {
+            src_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+            dst_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+            payload: CrossDomainCall {
+                sender: T::AccountIdConverter::convert(sender),
+                call: call.encode(),
+            }
+            .encode(),
+        };
+        let message_id = MessageIdOf::<T>::default();
+
+        #[block]
+        {
+            // The call may be rejected by the `CallFilter` of the runtime, the decoding and the
+            // event are still measured.
+            let _ =
+                EndpointHandler(PhantomData::<T>).message(src_chain_id, message_id, endpoint_req);
+        }
+    }
+
+    /// Benchmark `message_response` without the weight of the `OnCallResponse` callback.
+    #[benchmark]
+    fn message_response() {
+        let sender: T::AccountId = account("sender", 1, SEED);
+        let dst_chain_id: ChainId = u32::MAX.into();
+        assert_ne!(T::SelfChainId::get(), dst_chain_id);
+        let payload = CrossDomainCall {
+            sender: T::AccountIdConverter::convert(sender.clone()),
+            call: vec![0u8; T::MaxCallLength::get() as usize],
+        }
+        .encode();
+        let endpoint_req = EndpointRequest {
+            src_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+            dst_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+            payload: payload.clone(),
+        };
+        let endpoint_resp = Err(DispatchError::Exhausted);
+        let message_id = MessageIdOf::<T>::default();
+        OutgoingCalls::<T>::insert(
+            dst_chain_id,
+            message_id,
+            OutgoingCall {
+                sender,
+                payload_hash: T::Hashing::hash(&payload),
+                deposit: T::CallDeposit::get(),
+            },
+        );
+
+        #[block]
+        {
+            assert_ok!(EndpointHandler(PhantomData::<T>).message_response(
+                dst_chain_id,
+                message_id,
+                endpoint_req,
+                endpoint_resp,
+            ));
+        }
+
+        assert!(CallResponses::<T>::contains_key(dst_chain_id, message_id));
+    }
+
+    #[benchmark]
+    fn clear_call_response() {
+        let sender: T::AccountId = account("sender", 1, SEED);
+        let dst_chain_id: ChainId = u32::MAX.into();
+        let message_id = MessageIdOf::<T>::default();
+        let deposit = T::CallDeposit::get();
+        T::Currency::set_balance(&sender, deposit + T::Currency::minimum_balance());
+        assert_ok!(T::Currency::hold(
+            &T::HoldIdentifier::xdm_call(),
+            &sender,
+            deposit
+        ));
+        CallResponses::<T>::insert(
+            dst_chain_id,
+            message_id,
+            CallResponse {
+                sender: sender.clone(),
+                response: Err(DispatchError::Exhausted),
+                deposit,
+            },
+        );
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(sender), dst_chain_id, message_id);
+
+        assert!(!CallResponses::<T>::contains_key(dst_chain_id, message_id));
+    }
+
+    impl_benchmark_test_suite!(
+        XdmCall,
+        crate::mock::new_test_ext(),
+        crate::mock::MockRuntime,
+    );
+}
@@ -0,0 +1,534 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pallet XDM call used to dispatch calls on other chains.
+//!
+//! A signed origin on the src_chain sends an encoded call of the dst_chain runtime, the call is
+//! dispatched on the dst_chain under the sovereign account derived from the src_chain and the
+//! sender, if the call is allowed by the `CallFilter` of the dst_chain. The result of the call is
+//! sent back to the src_chain, where it is emitted as an event and passed to the
+//! `OnCallResponse` callback.
+//!
+//! A `CallDeposit` is held from the sender for every outgoing call, it covers the storage of the
+//! call and its response and is released once the response is cleared by the sender.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms, missing_debug_implementations)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use domain_runtime_primitives::MultiAccountId;
+use frame_support::traits::fungible::{Inspect, InspectHold};
+use frame_support::weights::Weight;
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_core::hashing::blake2_256;
+use sp_messenger::endpoint::EndpointResponse;
+use sp_messenger::messages::ChainId;
+use sp_runtime::traits::TrailingZeroInput;
+
+/// Call sent to the dst_chain to be dispatched under the sovereign account of the sender.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub struct CrossDomainCall {
+    /// Account that sent the call on the src_chain.
+    pub sender: MultiAccountId,
+    /// Encoded call of the dst_chain runtime.
+    pub call: Vec<u8>,
+}
+
+/// Outgoing call that is waiting for the response from the dst_chain.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub struct OutgoingCall<AccountId, Hash, Balance> {
+    /// Account that sent the call.
+    pub sender: AccountId,
+    /// Hash of the `CrossDomainCall` sent to the dst_chain.
+    pub payload_hash: Hash,
+    /// Deposit held from the sender.
+    pub deposit: Balance,
+}
+
+/// Response of an outgoing call, kept until it is cleared by the sender.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+pub struct CallResponse<AccountId, Balance> {
+    /// Account that sent the call.
+    pub sender: AccountId,
+    /// Response from the dst_chain endpoint.
+    pub response: EndpointResponse,
+    /// Deposit held from the sender, released once the response is cleared.
+    pub deposit: Balance,
+}
+
+/// Hold identifier trait for xdm-call specific balance holds
+pub trait HoldIdentifier<T: Config> {
+    fn xdm_call() -> FungibleHoldId<T>;
+}
+
+/// Callback triggered when the response of an outgoing call is received from the dst_chain.
+pub trait OnCallResponse<AccountId, MessageId> {
+    /// Handles the response of the call sent by `sender`.
+    fn on_call_response(
+        sender: &AccountId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+        response: &EndpointResponse,
+    );
+
+    /// Return the maximal possible consume weight of `on_call_response`
+    fn on_call_response_weight() -> Weight;
+}
+
+impl<AccountId, MessageId> OnCallResponse<AccountId, MessageId> for () {
+    fn on_call_response(
+        _sender: &AccountId,
+        _dst_chain_id: ChainId,
+        _message_id: MessageId,
+        _response: &EndpointResponse,
+    ) {
+    }
+
+    fn on_call_response_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Maximum nesting depth allowed when decoding the incoming calls, same as the one used for
+/// decoding extrinsics.
+const MAX_CALL_DECODE_DEPTH: u32 = 256;
+
+pub(crate) type BalanceOf<T> =
+    <<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub(crate) type FungibleHoldId<T> =
+    <<T as Config>::Currency as InspectHold<<T as frame_system::Config>::AccountId>>::Reason;
+
+type MessageIdOf<T> = <<T as Config>::Sender as sp_messenger::endpoint::Sender<
+    <T as frame_system::Config>::AccountId,
+>>::MessageId;
+
+#[frame_support::pallet]
+mod pallet {
+    use crate::weights::WeightInfo;
+    use crate::{
+        BalanceOf, CallResponse, CrossDomainCall, HoldIdentifier, MessageIdOf, OnCallResponse,
+        OutgoingCall, MAX_CALL_DECODE_DEPTH,
+    };
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+    use codec::{Decode, DecodeLimit, Encode};
+    use domain_runtime_primitives::{MultiAccountId, TryConvertBack};
+    use frame_support::dispatch::{GetDispatchInfo, PostDispatchInfo};
+    use frame_support::pallet_prelude::*;
+    use frame_support::traits::fungible::{Mutate, MutateHold};
+    use frame_support::traits::tokens::Precision;
+    use frame_support::traits::Contains;
+    use frame_support::weights::Weight;
+    use frame_system::pallet_prelude::*;
+    use sp_messenger::endpoint::{
+        Endpoint, EndpointHandler as EndpointHandlerT, EndpointId, EndpointRequest,
+        EndpointResponse, Sender,
+    };
+    use sp_messenger::messages::ChainId;
+    use sp_runtime::traits::{Convert, Dispatchable, Hash};
+    use sp_std::vec;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// Event type for this pallet.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The overarching call type, calls received from other chains are decoded into it.
+        type RuntimeCall: Parameter
+            + Dispatchable<
+                RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+                PostInfo = PostDispatchInfo,
+            > + GetDispatchInfo;
+
+        /// Gets the chain_id of the current execution environment.
+        type SelfChainId: Get<ChainId>;
+
+        /// Gets the endpoint_id of this pallet in a given execution environment.
+        type SelfEndpointId: Get<EndpointId>;
+
+        /// Sender used to send calls to other chains.
+        type Sender: Sender<Self::AccountId>;
+
+        /// MultiAccountID <> T::AccountId converter.
+        type AccountIdConverter: TryConvertBack<Self::AccountId, MultiAccountId>;
+
+        /// Whitelist of the calls that other chains are allowed to dispatch on this chain.
+        type CallFilter: Contains<<Self as Config>::RuntimeCall>;
+
+        /// Maximum weight of a call received from other chains.
+        #[pallet::constant]
+        type MaxCallWeight: Get<Weight>;
+
+        /// Maximum length of the encoded call sent to other chains.
+        #[pallet::constant]
+        type MaxCallLength: Get<u32>;
+
+        /// Callback for the responses of the outgoing calls.
+        type OnCallResponse: OnCallResponse<Self::AccountId, MessageIdOf<Self>>;
+
+        /// Currency type pallet uses for deposits.
+        type Currency: Mutate<Self::AccountId> + MutateHold<Self::AccountId>;
+
+        /// Hold identifier used for the deposits.
+        type HoldIdentifier: HoldIdentifier<Self>;
+
+        /// Deposit held from the sender for each outgoing call until its response is cleared.
+        #[pallet::constant]
+        type CallDeposit: Get<BalanceOf<Self>>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    /// Pallet xdm-call to dispatch calls on other chains.
+    #[pallet::pallet]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    /// All the outgoing calls on this execution environment that are waiting for the response.
+    #[pallet::storage]
+    #[pallet::getter(fn outgoing_calls)]
+    pub(super) type OutgoingCalls<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        ChainId,
+        Identity,
+        MessageIdOf<T>,
+        OutgoingCall<T::AccountId, T::Hash, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Responses of the outgoing calls, kept until they are cleared by the sender.
+    #[pallet::storage]
+    #[pallet::getter(fn call_responses)]
+    pub(super) type CallResponses<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        ChainId,
+        Identity,
+        MessageIdOf<T>,
+        CallResponse<T::AccountId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Events emitted by pallet-xdm-call.
+    #[pallet::event]
+    #[pallet::generate_deposit(pub (super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Emits when there is a new outgoing call.
+        OutgoingCallInitiated {
+            /// Destination chain the call is bound to.
+            chain_id: ChainId,
+            /// Id of the call.
+            message_id: MessageIdOf<T>,
+            /// Account that sent the call.
+            sender: T::AccountId,
+        },
+
+        /// Emits when the response of an outgoing call is received.
+        OutgoingCallResponded {
+            /// Destination chain the call is bound to.
+            chain_id: ChainId,
+            /// Id of the call.
+            message_id: MessageIdOf<T>,
+            /// Account that sent the call.
+            sender: T::AccountId,
+            /// Response from dst_chain endpoint.
+            response: EndpointResponse,
+        },
+
+        /// Emits when an incoming call is dispatched.
+        IncomingCallDispatched {
+            /// Source chain the call is coming from.
+            chain_id: ChainId,
+            /// Id of the call.
+            message_id: MessageIdOf<T>,
+            /// Sovereign account the call is dispatched under.
+            sovereign_account: T::AccountId,
+            /// Result of the call.
+            result: DispatchResult,
+        },
+    }
+
+    /// Errors emitted by pallet-xdm-call.
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Emits when the call is sent to the current chain.
+        SelfChainCall,
+        /// Emits when the encoded call is longer than `MaxCallLength`.
+        CallTooLong,
+        /// Failed to decode the call payload.
+        InvalidPayload,
+        /// Failed to decode the call with the runtime call type.
+        UndecodableCall,
+        /// Emits when the call is not allowed by the `CallFilter`.
+        CallNotAllowed,
+        /// Emits when the weight of the call exceeds `MaxCallWeight`.
+        CallWeightTooHigh,
+        /// Emits when the request for a response received is missing.
+        MissingCallRequest,
+        /// Emits when the request doesn't match the expected one.
+        InvalidCallRequest,
+        /// Emits when the incoming message is not bound to this chain.
+        UnexpectedMessage,
+        /// Emits when the call response to clear is missing.
+        MissingCallResponse,
+        /// Emits when the call response is cleared by an account other than the sender.
+        NotCallSender,
+        /// Emits when the deposit of the call can't be held.
+        BalanceHold,
+        /// Emits when the deposit of the call can't be released.
+        BalanceRelease,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Sends the encoded `call` to `dst_chain_id`, where it is dispatched under the sovereign
+        /// account of the sender.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::send_call())]
+        pub fn send_call(
+            origin: OriginFor<T>,
+            dst_chain_id: ChainId,
+            call: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::do_send_call(sender, dst_chain_id, call)?;
+            Ok(())
+        }
+
+        /// Clears the response of a call sent by the origin.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::clear_call_response())]
+        pub fn clear_call_response(
+            origin: OriginFor<T>,
+            dst_chain_id: ChainId,
+            message_id: MessageIdOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+
+            let call_response = CallResponses::<T>::get(dst_chain_id, message_id)
+                .ok_or(Error::<T>::MissingCallResponse)?;
+            ensure!(call_response.sender == sender, Error::<T>::NotCallSender);
+            CallResponses::<T>::remove(dst_chain_id, message_id);
+
+            T::Currency::release(
+                &T::HoldIdentifier::xdm_call(),
+                &sender,
+                call_response.deposit,
+                Precision::Exact,
+            )
+            .map_err(|_| Error::<T>::BalanceRelease)?;
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Sends the encoded `call` of `sender` to `dst_chain_id`, returns the message id of
+        /// the call.
+        pub fn do_send_call(
+            sender: T::AccountId,
+            dst_chain_id: ChainId,
+            call: Vec<u8>,
+        ) -> Result<MessageIdOf<T>, DispatchError> {
+            ensure!(
+                dst_chain_id != T::SelfChainId::get(),
+                Error::<T>::SelfChainCall
+            );
+            ensure!(
+                call.len() <= T::MaxCallLength::get() as usize,
+                Error::<T>::CallTooLong
+            );
+
+            let payload = CrossDomainCall {
+                sender: T::AccountIdConverter::convert(sender.clone()),
+                call,
+            }
+            .encode();
+            let payload_hash = T::Hashing::hash(&payload);
+
+            // hold the deposit for the storage of the call and its response
+            let deposit = T::CallDeposit::get();
+            T::Currency::hold(&T::HoldIdentifier::xdm_call(), &sender, deposit)
+                .map_err(|_| Error::<T>::BalanceHold)?;
+
+            // send message
+            let message_id = T::Sender::send_message(
+                &sender,
+                dst_chain_id,
+                EndpointRequest {
+                    src_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+                    // destination endpoint must be xdm-call with same id
+                    dst_endpoint: Endpoint::Id(T::SelfEndpointId::get()),
+                    payload,
+                },
+            )?;
+
+            OutgoingCalls::<T>::insert(
+                dst_chain_id,
+                message_id,
+                OutgoingCall {
+                    sender: sender.clone(),
+                    payload_hash,
+                    deposit,
+                },
+            );
+            Self::deposit_event(Event::<T>::OutgoingCallInitiated {
+                chain_id: dst_chain_id,
+                message_id,
+                sender,
+            });
+
+            Ok(message_id)
+        }
+    }
+
+    /// Endpoint handler implementation for pallet xdm-call.
+    #[derive(Debug)]
+    pub struct EndpointHandler<T>(pub PhantomData<T>);
+
+    impl<T: Config> EndpointHandlerT<MessageIdOf<T>> for EndpointHandler<T> {
+        fn message(
+            &self,
+            src_chain_id: ChainId,
+            message_id: MessageIdOf<T>,
+            req: EndpointRequest,
+        ) -> EndpointResponse {
+            // ensure message is not from the self
+            ensure!(
+                T::SelfChainId::get() != src_chain_id,
+                Error::<T>::InvalidCallRequest
+            );
+
+            // check the endpoint id
+            ensure!(
+                req.dst_endpoint == Endpoint::Id(T::SelfEndpointId::get()),
+                Error::<T>::UnexpectedMessage
+            );
+
+            // decode payload and the call
+            let req = CrossDomainCall::decode(&mut req.payload.as_slice())
+                .map_err(|_| Error::<T>::InvalidPayload)?;
+            let call = <T as Config>::RuntimeCall::decode_with_depth_limit(
+                MAX_CALL_DECODE_DEPTH,
+                &mut req.call.as_slice(),
+            )
+            .map_err(|_| Error::<T>::UndecodableCall)?;
+
+            ensure!(T::CallFilter::contains(&call), Error::<T>::CallNotAllowed);
+            ensure!(
+                call.get_dispatch_info()
+                    .weight
+                    .all_lte(T::MaxCallWeight::get()),
+                Error::<T>::CallWeightTooHigh
+            );
+
+            let sovereign_account = Pallet::<T>::sovereign_account(src_chain_id, &req.sender);
+            let result = call
+                .dispatch(frame_system::RawOrigin::Signed(sovereign_account.clone()).into())
+                .map(|_| ())
+                .map_err(|err| err.error);
+
+            frame_system::Pallet::<T>::deposit_event(Into::<<T as Config>::RuntimeEvent>::into(
+                Event::<T>::IncomingCallDispatched {
+                    chain_id: src_chain_id,
+                    message_id,
+                    sovereign_account,
+                    result,
+                },
+            ));
+
+            result.map(|_| vec![])
+        }
+
+        fn message_weight(&self) -> Weight {
+            T::WeightInfo::message().saturating_add(T::MaxCallWeight::get())
+        }
+
+        fn message_response(
+            &self,
+            dst_chain_id: ChainId,
+            message_id: MessageIdOf<T>,
+            req: EndpointRequest,
+            resp: EndpointResponse,
+        ) -> DispatchResult {
+            // ensure request is valid
+            let outgoing_call = OutgoingCalls::<T>::take(dst_chain_id, message_id)
+                .ok_or(Error::<T>::MissingCallRequest)?;
+            ensure!(
+                T::Hashing::hash(&req.payload) == outgoing_call.payload_hash,
+                Error::<T>::InvalidCallRequest
+            );
+
+            T::OnCallResponse::on_call_response(
+                &outgoing_call.sender,
+                dst_chain_id,
+                message_id,
+                &resp,
+            );
+
+            frame_system::Pallet::<T>::deposit_event(Into::<<T as Config>::RuntimeEvent>::into(
+                Event::<T>::OutgoingCallResponded {
+                    chain_id: dst_chain_id,
+                    message_id,
+                    sender: outgoing_call.sender.clone(),
+                    response: resp.clone(),
+                },
+            ));
+            CallResponses::<T>::insert(
+                dst_chain_id,
+                message_id,
+                CallResponse {
+                    sender: outgoing_call.sender,
+                    response: resp,
+                    deposit: outgoing_call.deposit,
+                },
+            );
+
+            Ok(())
+        }
+
+        fn message_response_weight(&self) -> Weight {
+            T::WeightInfo::message_response()
+                .saturating_add(T::OnCallResponse::on_call_response_weight())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the sovereign account on this chain of the `sender` from `src_chain_id`, the
+    /// incoming calls of the sender are dispatched under this account.
+    pub fn sovereign_account(src_chain_id: ChainId, sender: &MultiAccountId) -> T::AccountId {
+        let entropy = (b"xdm-call/sovereign", src_chain_id, sender).using_encoded(blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+}
@@ -0,0 +1,196 @@
+use crate as pallet_xdm_call;
+use crate::{Config, HoldIdentifier, OnCallResponse};
+use codec::{Decode, Encode, MaxEncodedLen};
+use domain_runtime_primitives::{MultiAccountId, TryConvertBack};
+use frame_support::traits::{Contains, VariantCount};
+use frame_support::weights::Weight;
+use frame_support::{derive_impl, parameter_types};
+use scale_info::TypeInfo;
+use sp_messenger::endpoint::{EndpointId, EndpointRequest, EndpointResponse, Sender};
+use sp_messenger::messages::ChainId;
+use sp_runtime::traits::{Convert, IdentityLookup};
+use sp_runtime::{BuildStorage, DispatchError};
+use std::cell::RefCell;
+
+type Block = frame_system::mocking::MockBlock<MockRuntime>;
+pub(crate) type AccountId = u64;
+pub(crate) type MessageId = u64;
+pub(crate) type Balance = u128;
+
+frame_support::construct_runtime!(
+    pub struct MockRuntime {
+        System: frame_system,
+        Balances: pallet_balances,
+        XdmCall: pallet_xdm_call,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for MockRuntime {
+    type Block = Block;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive(
+    PartialEq, Eq, Clone, Encode, Decode, TypeInfo, MaxEncodedLen, Ord, PartialOrd, Copy, Debug,
+)]
+pub enum MockHoldIdentifier {
+    XdmCall,
+}
+
+impl VariantCount for MockHoldIdentifier {
+    const VARIANT_COUNT: u32 = 1;
+}
+
+impl HoldIdentifier<MockRuntime> for MockHoldIdentifier {
+    fn xdm_call() -> Self {
+        MockHoldIdentifier::XdmCall
+    }
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig)]
+impl pallet_balances::Config for MockRuntime {
+    type AccountStore = System;
+    type Balance = Balance;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type RuntimeHoldReason = MockHoldIdentifier;
+}
+
+parameter_types! {
+    pub SelfChainId: ChainId = 1.into();
+    pub const SelfEndpointId: EndpointId = 100;
+    pub const MaxCallWeight: Weight = Weight::from_parts(1_000_000_000, 0);
+    pub const MaxCallLength: u32 = 1024;
+    pub const CallDeposit: Balance = 10;
+}
+
+thread_local! {
+    pub static NEXT_MESSAGE_ID: RefCell<MessageId> = const { RefCell::new(0) };
+}
+
+#[derive(Debug)]
+pub struct MockMessenger {}
+
+impl Sender<AccountId> for MockMessenger {
+    type MessageId = MessageId;
+
+    fn send_message(
+        _sender: &AccountId,
+        _dst_chain_id: ChainId,
+        _req: EndpointRequest,
+    ) -> Result<Self::MessageId, DispatchError> {
+        Ok(NEXT_MESSAGE_ID.with(|next_message_id| {
+            let mut next_message_id = next_message_id.borrow_mut();
+            let message_id = *next_message_id;
+            *next_message_id += 1;
+            message_id
+        }))
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn unchecked_open_channel(_dst_chain_id: ChainId) -> Result<(), DispatchError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MockAccountIdConverter;
+
+impl Convert<AccountId, MultiAccountId> for MockAccountIdConverter {
+    fn convert(account_id: AccountId) -> MultiAccountId {
+        MultiAccountId::Raw(account_id.encode())
+    }
+}
+
+impl TryConvertBack<AccountId, MultiAccountId> for MockAccountIdConverter {
+    fn try_convert_back(multi_account_id: MultiAccountId) -> Option<AccountId> {
+        match multi_account_id {
+            MultiAccountId::Raw(data) => AccountId::decode(&mut data.as_slice()).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Only allow `System::remark_with_event` to be dispatched from other chains.
+#[derive(Debug)]
+pub struct MockCallFilter;
+
+impl Contains<RuntimeCall> for MockCallFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::System(frame_system::Call::remark_with_event { .. })
+        )
+    }
+}
+
+thread_local! {
+    pub static CALL_RESPONSES: RefCell<Vec<(AccountId, ChainId, MessageId, EndpointResponse)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Debug)]
+pub struct MockOnCallResponse;
+
+impl OnCallResponse<AccountId, MessageId> for MockOnCallResponse {
+    fn on_call_response(
+        sender: &AccountId,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+        response: &EndpointResponse,
+    ) {
+        CALL_RESPONSES.with(|responses| {
+            responses
+                .borrow_mut()
+                .push((*sender, dst_chain_id, message_id, response.clone()))
+        });
+    }
+
+    fn on_call_response_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+impl Config for MockRuntime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type SelfChainId = SelfChainId;
+    type SelfEndpointId = SelfEndpointId;
+    type Sender = MockMessenger;
+    type AccountIdConverter = MockAccountIdConverter;
+    type CallFilter = MockCallFilter;
+    type MaxCallWeight = MaxCallWeight;
+    type MaxCallLength = MaxCallLength;
+    type OnCallResponse = MockOnCallResponse;
+    type Currency = Balances;
+    type HoldIdentifier = MockHoldIdentifier;
+    type CallDeposit = CallDeposit;
+    type WeightInfo = ();
+}
+
+pub const USER_ACCOUNT: AccountId = 1;
+pub const USER_INITIAL_BALANCE: Balance = 1000;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<MockRuntime>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<MockRuntime> {
+        balances: vec![(USER_ACCOUNT, USER_INITIAL_BALANCE)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut t: sp_io::TestExternalities = t.into();
+    t.execute_with(|| System::set_block_number(1));
+    NEXT_MESSAGE_ID.with(|next_message_id| *next_message_id.borrow_mut() = 0);
+    t
+}
@@ -0,0 +1,273 @@
+use crate::mock::{
+    new_test_ext, AccountId, Balances, CallDeposit, MockAccountIdConverter, MockHoldIdentifier,
+    MockRuntime, RuntimeCall, RuntimeEvent, RuntimeOrigin, SelfChainId, SelfEndpointId, System,
+    XdmCall, CALL_RESPONSES, USER_ACCOUNT, USER_INITIAL_BALANCE,
+};
+use crate::{CallResponse, CrossDomainCall, EndpointHandler, Error, OutgoingCall};
+use codec::Encode;
+use frame_support::traits::fungible::{Inspect, InspectHold};
+use frame_support::{assert_err, assert_ok};
+use sp_messenger::endpoint::{
+    Endpoint, EndpointHandler as EndpointHandlerT, EndpointRequest, EndpointResponse,
+};
+use sp_messenger::messages::ChainId;
+use sp_runtime::traits::{BlakeTwo256, Convert, Hash};
+use sp_runtime::DispatchError;
+use std::marker::PhantomData;
+
+fn remark_call() -> RuntimeCall {
+    RuntimeCall::System(frame_system::Call::remark_with_event {
+        remark: b"xdm".to_vec(),
+    })
+}
+
+fn held_deposit(account: AccountId) -> u128 {
+    Balances::balance_on_hold(&MockHoldIdentifier::XdmCall, &account)
+}
+
+fn call_request(sender: AccountId, call: Vec<u8>) -> EndpointRequest {
+    EndpointRequest {
+        src_endpoint: Endpoint::Id(SelfEndpointId::get()),
+        dst_endpoint: Endpoint::Id(SelfEndpointId::get()),
+        payload: CrossDomainCall {
+            sender: MockAccountIdConverter::convert(sender),
+            call,
+        }
+        .encode(),
+    }
+}
+
+#[test]
+fn test_send_call() {
+    new_test_ext().execute_with(|| {
+        let dst_chain_id: ChainId = 2.into();
+        let call = remark_call().encode();
+
+        assert_ok!(XdmCall::send_call(
+            RuntimeOrigin::signed(USER_ACCOUNT),
+            dst_chain_id,
+            call.clone()
+        ));
+        System::assert_has_event(RuntimeEvent::XdmCall(
+            crate::Event::<MockRuntime>::OutgoingCallInitiated {
+                chain_id: dst_chain_id,
+                message_id: 0,
+                sender: USER_ACCOUNT,
+            },
+        ));
+        assert_eq!(
+            XdmCall::outgoing_calls(dst_chain_id, 0).unwrap(),
+            OutgoingCall {
+                sender: USER_ACCOUNT,
+                payload_hash: BlakeTwo256::hash(&call_request(USER_ACCOUNT, call).payload),
+                deposit: CallDeposit::get(),
+            }
+        );
+        assert_eq!(held_deposit(USER_ACCOUNT), CallDeposit::get());
+
+        // Can't send call to self chain
+        assert_err!(
+            XdmCall::send_call(
+                RuntimeOrigin::signed(USER_ACCOUNT),
+                SelfChainId::get(),
+                remark_call().encode()
+            ),
+            Error::<MockRuntime>::SelfChainCall
+        );
+
+        // Can't send call that is longer than `MaxCallLength`
+        assert_err!(
+            XdmCall::send_call(
+                RuntimeOrigin::signed(USER_ACCOUNT),
+                dst_chain_id,
+                vec![0u8; 1025]
+            ),
+            Error::<MockRuntime>::CallTooLong
+        );
+
+        // Can't send call without the balance for the deposit
+        assert_err!(
+            XdmCall::send_call(
+                RuntimeOrigin::signed(USER_ACCOUNT + 1),
+                dst_chain_id,
+                remark_call().encode()
+            ),
+            Error::<MockRuntime>::BalanceHold
+        );
+    })
+}
+
+#[test]
+fn test_incoming_call_dispatched_under_sovereign_account() {
+    new_test_ext().execute_with(|| {
+        let src_chain_id: ChainId = 2.into();
+        let sender = 100;
+        let sovereign_account =
+            XdmCall::sovereign_account(src_chain_id, &MockAccountIdConverter::convert(sender));
+        assert_ne!(sovereign_account, sender);
+        // The sovereign account is different for the same sender on different chains
+        assert_ne!(
+            sovereign_account,
+            XdmCall::sovereign_account(3.into(), &MockAccountIdConverter::convert(sender))
+        );
+
+        let resp = EndpointHandler(PhantomData::<MockRuntime>).message(
+            src_chain_id,
+            0,
+            call_request(sender, remark_call().encode()),
+        );
+        assert_ok!(resp);
+
+        System::assert_has_event(RuntimeEvent::System(frame_system::Event::Remarked {
+            sender: sovereign_account,
+            hash: BlakeTwo256::hash(b"xdm"),
+        }));
+        System::assert_has_event(RuntimeEvent::XdmCall(
+            crate::Event::<MockRuntime>::IncomingCallDispatched {
+                chain_id: src_chain_id,
+                message_id: 0,
+                sovereign_account,
+                result: Ok(()),
+            },
+        ));
+    })
+}
+
+#[test]
+fn test_invalid_incoming_call() {
+    new_test_ext().execute_with(|| {
+        let src_chain_id: ChainId = 2.into();
+        let handler = EndpointHandler(PhantomData::<MockRuntime>);
+
+        // Message from self chain
+        assert_err!(
+            handler.message(
+                SelfChainId::get(),
+                0,
+                call_request(USER_ACCOUNT, remark_call().encode())
+            ),
+            Error::<MockRuntime>::InvalidCallRequest
+        );
+
+        // Message to a different endpoint
+        let mut req = call_request(USER_ACCOUNT, remark_call().encode());
+        req.dst_endpoint = Endpoint::Id(0);
+        assert_err!(
+            handler.message(src_chain_id, 0, req),
+            Error::<MockRuntime>::UnexpectedMessage
+        );
+
+        // Undecodable call
+        assert_err!(
+            handler.message(src_chain_id, 0, call_request(USER_ACCOUNT, vec![0xff; 8])),
+            Error::<MockRuntime>::UndecodableCall
+        );
+
+        // Call that is not allowed by the call filter
+        let call = RuntimeCall::System(frame_system::Call::remark {
+            remark: b"xdm".to_vec(),
+        });
+        assert_err!(
+            handler.message(src_chain_id, 0, call_request(USER_ACCOUNT, call.encode())),
+            Error::<MockRuntime>::CallNotAllowed
+        );
+    })
+}
+
+#[test]
+fn test_call_response() {
+    new_test_ext().execute_with(|| {
+        let dst_chain_id: ChainId = 2.into();
+        let call = remark_call().encode();
+        let handler = EndpointHandler(PhantomData::<MockRuntime>);
+
+        // Response without request
+        assert_err!(
+            handler.message_response(
+                dst_chain_id,
+                0,
+                call_request(USER_ACCOUNT, call.clone()),
+                Ok(vec![])
+            ),
+            Error::<MockRuntime>::MissingCallRequest
+        );
+
+        assert_ok!(XdmCall::send_call(
+            RuntimeOrigin::signed(USER_ACCOUNT),
+            dst_chain_id,
+            call.clone()
+        ));
+
+        // Response of a different request
+        assert_err!(
+            handler.message_response(
+                dst_chain_id,
+                0,
+                call_request(USER_ACCOUNT, vec![]),
+                Ok(vec![])
+            ),
+            Error::<MockRuntime>::InvalidCallRequest
+        );
+
+        assert_ok!(XdmCall::send_call(
+            RuntimeOrigin::signed(USER_ACCOUNT),
+            dst_chain_id,
+            call.clone()
+        ));
+        let resp: EndpointResponse = Err(DispatchError::BadOrigin);
+        assert_ok!(handler.message_response(
+            dst_chain_id,
+            1,
+            call_request(USER_ACCOUNT, call),
+            resp.clone()
+        ));
+        assert!(XdmCall::outgoing_calls(dst_chain_id, 1).is_none());
+        System::assert_has_event(RuntimeEvent::XdmCall(
+            crate::Event::<MockRuntime>::OutgoingCallResponded {
+                chain_id: dst_chain_id,
+                message_id: 1,
+                sender: USER_ACCOUNT,
+                response: resp.clone(),
+            },
+        ));
+        CALL_RESPONSES.with(|responses| {
+            assert_eq!(
+                *responses.borrow(),
+                vec![(USER_ACCOUNT, dst_chain_id, 1, resp.clone())]
+            )
+        });
+        assert_eq!(
+            XdmCall::call_responses(dst_chain_id, 1),
+            Some(CallResponse {
+                sender: USER_ACCOUNT,
+                response: resp,
+                deposit: CallDeposit::get(),
+            })
+        );
+        // Deposits of both calls are still held, the first call is never responded
+        assert_eq!(held_deposit(USER_ACCOUNT), 2 * CallDeposit::get());
+
+        // Only the sender can clear the response
+        assert_err!(
+            XdmCall::clear_call_response(RuntimeOrigin::signed(USER_ACCOUNT + 1), dst_chain_id, 1),
+            Error::<MockRuntime>::NotCallSender
+        );
+        assert_ok!(XdmCall::clear_call_response(
+            RuntimeOrigin::signed(USER_ACCOUNT),
+            dst_chain_id,
+            1
+        ));
+        assert!(XdmCall::call_responses(dst_chain_id, 1).is_none());
+        assert_err!(
+            XdmCall::clear_call_response(RuntimeOrigin::signed(USER_ACCOUNT), dst_chain_id, 1),
+            Error::<MockRuntime>::MissingCallResponse
+        );
+
+        // Deposit is released once the response is cleared
+        assert_eq!(held_deposit(USER_ACCOUNT), CallDeposit::get());
+        assert_eq!(
+            Balances::balance(&USER_ACCOUNT),
+            USER_INITIAL_BALANCE - CallDeposit::get()
+        );
+    })
+}
@@ -0,0 +1,93 @@
+//! Weights for pallet_xdm_call
+//!
+//! NOTE: THESE WEIGHTS ARE ESTIMATES, NOT BENCHMARK RESULTS. The message sending path is estimated
+//! the same as `pallet_transporter::transfer`, which sends messages through the same `Messenger`
+//! endpoint, with the storage accesses of this pallet and the deposit hold added on top.
+//!
+//! The benchmarks of `benchmarking.rs` are registered in the EVM and AutoId domain runtimes, this
+//! file must be replaced with the output of:
+//!
+//! ./target/release/subspace-node domain benchmark pallet --chain=dev --steps=50 --repeat=20
+//!     --pallet=pallet_xdm_call --extrinsic=* --wasm-execution=compiled --heap-pages=4096
+//!     --output=./domains/pallets/xdm-call/src/weights.rs --template ./frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::ParityDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_xdm_call.
+pub trait WeightInfo {
+	fn send_call() -> Weight;
+	fn message() -> Weight;
+	fn message_response() -> Weight;
+	fn clear_call_response() -> Weight;
+}
+
+/// Weights for pallet_xdm_call using the Substrate node and recommended hardware.
+#[derive(Debug)]
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `XdmCall::OutgoingCalls` (r:0 w:1)
+	/// Plus the storage accessed by `Messenger` to send the message (r:7 w:5), same as
+	/// `pallet_transporter::transfer`.
+	fn send_call() -> Weight {
+		Weight::from_parts(70_000_000, 29000)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	fn message() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	/// Storage: `XdmCall::OutgoingCalls` (r:1 w:1)
+	/// Storage: `XdmCall::CallResponses` (r:0 w:1)
+	fn message_response() -> Weight {
+		Weight::from_parts(15_000_000, 3700)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XdmCall::CallResponses` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn clear_call_response() -> Weight {
+		Weight::from_parts(25_000_000, 7300)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `XdmCall::OutgoingCalls` (r:0 w:1)
+	/// Plus the storage accessed by `Messenger` to send the message (r:7 w:5), same as
+	/// `pallet_transporter::transfer`.
+	fn send_call() -> Weight {
+		Weight::from_parts(70_000_000, 29000)
+			.saturating_add(ParityDbWeight::get().reads(9_u64))
+			.saturating_add(ParityDbWeight::get().writes(8_u64))
+	}
+	fn message() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	/// Storage: `XdmCall::OutgoingCalls` (r:1 w:1)
+	/// Storage: `XdmCall::CallResponses` (r:0 w:1)
+	fn message_response() -> Weight {
+		Weight::from_parts(15_000_000, 3700)
+			.saturating_add(ParityDbWeight::get().reads(1_u64))
+			.saturating_add(ParityDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XdmCall::CallResponses` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn clear_call_response() -> Weight {
+		Weight::from_parts(25_000_000, 7300)
+			.saturating_add(ParityDbWeight::get().reads(3_u64))
+			.saturating_add(ParityDbWeight::get().writes(3_u64))
+	}
+}
@@ -30,7 +30,10 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use frame_support::inherent::InherentData;
 use frame_support::inherent::{InherentIdentifier, IsFatalError};
-use messages::{BlockMessagesWithStorageKey, CrossDomainMessage, MessageId};
+use messages::{
+    BlockMessagesWithStorageKey, CrossDomainMessage, InboxMessageStatus, MessageId,
+    OutboxMessageStatus,
+};
 use sp_domains::{ChainId, DomainAllowlistUpdates, DomainId};
 
 /// Messenger inherent identifier.
@@ -142,6 +145,7 @@ impl sp_inherents::InherentDataProvider for InherentDataProvider {
 
 sp_api::decl_runtime_apis! {
     /// Api useful for relayers to fetch messages and submit transactions.
+    #[api_version(2)]
     pub trait RelayerApi<BlockNumber, CNumber, CHash>
     where
         BlockNumber: Encode + Decode,
@@ -167,6 +171,14 @@ sp_api::decl_runtime_apis! {
 
         /// Returns true if the inbox message response is ready to be relayed to dst_chain.
         fn should_relay_inbox_message_response(dst_chain_id: ChainId, msg_id: MessageId) -> bool;
+
+        /// Returns the status of the message sent from this chain to dst_chain.
+        #[api_version(2)]
+        fn outbox_message_status(dst_chain_id: ChainId, msg_id: MessageId) -> OutboxMessageStatus;
+
+        /// Returns the status of the message sent from src_chain to this chain.
+        #[api_version(2)]
+        fn inbox_message_status(src_chain_id: ChainId, msg_id: MessageId) -> InboxMessageStatus;
     }
 
     /// Api to provide XDM extraction from Runtime Calls.
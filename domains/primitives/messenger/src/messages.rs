@@ -6,6 +6,7 @@ use crate::endpoint::{Endpoint, EndpointRequest, EndpointResponse};
 use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
 pub use sp_domains::{ChainId, ChannelId};
 use sp_runtime::app_crypto::sp_core::U256;
 use sp_runtime::DispatchError;
@@ -223,6 +224,28 @@ pub struct BlockMessageWithStorageKey {
     pub weight_tag: MessageWeightTag,
 }
 
+/// Status of an outgoing message on its src_chain.
+#[derive(Debug, Encode, Decode, TypeInfo, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum OutboxMessageStatus {
+    /// Message is not sent through the channel yet, or the channel does not exist.
+    Unknown,
+    /// Message is in the outbox and awaiting the response from dst_chain.
+    AwaitingResponse,
+    /// Message response from dst_chain is received and processed.
+    ResponseReceived,
+}
+
+/// Status of an incoming message on its dst_chain.
+#[derive(Debug, Encode, Decode, TypeInfo, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum InboxMessageStatus {
+    /// Message is not received from src_chain yet, or the channel does not exist.
+    NotReceived,
+    /// Message is processed and the response is awaiting to be delivered to src_chain.
+    ResponsePending,
+    /// Message is processed and src_chain acknowledged the delivery of the response.
+    ResponseDelivered,
+}
+
 /// Set of messages with storage keys to be relayed in a given block..
 #[derive(Default, Debug, Encode, Decode, TypeInfo, Clone, Eq, PartialEq)]
 pub struct BlockMessagesWithStorageKey {
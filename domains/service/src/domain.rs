@@ -3,7 +3,8 @@ use crate::transaction_pool::FullChainApiWrapper;
 use crate::{FullBackend, FullClient};
 use cross_domain_message_gossip::ChainTxPoolMsg;
 use domain_client_block_preprocessor::inherents::CreateInherentDataProvider;
-use domain_client_message_relayer::GossipMessageSink;
+use domain_client_message_relayer::rpc::{MessageRelayerApiServer, MessageRelayerRpc};
+use domain_client_message_relayer::{relay_request_channel, GossipMessageSink};
 use domain_client_operator::{Operator, OperatorParams, OperatorStreams};
 use domain_runtime_primitives::opaque::{Block, Header};
 use domain_runtime_primitives::{Balance, Hash};
@@ -22,6 +23,7 @@ use sc_service::{
     BuildNetworkParams, Configuration as ServiceConfiguration, NetworkStarter, PartialComponents,
     PruningMode, SpawnTasksParams, TFullBackend, TaskManager,
 };
+use sc_subspace_block_relay::{build_domain_relay, NetworkWrapper};
 use sc_telemetry::{Telemetry, TelemetryWorker, TelemetryWorkerHandle};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver};
@@ -350,6 +352,15 @@ where
     let mut task_manager = params.task_manager;
     let net_config = sc_network::config::FullNetworkConfiguration::new(&domain_config.network);
 
+    let network_wrapper = Arc::new(NetworkWrapper::default());
+    let block_relay = build_domain_relay(
+        network_wrapper.clone(),
+        client.clone(),
+        transaction_pool.clone(),
+        domain_config.prometheus_registry(),
+    )
+    .map_err(|error| sc_service::Error::Other(error.to_string()))?;
+
     let (network_service, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
         crate::build_network(BuildNetworkParams {
             config: &domain_config,
@@ -361,11 +372,13 @@ where
             // TODO: we might want to re-enable this some day.
             block_announce_validator_builder: None,
             warp_sync_params: None,
-            block_relay: None,
+            block_relay: Some(block_relay),
         })?;
+    network_wrapper.set(network_service.clone());
 
     let is_authority = domain_config.role.is_authority();
     let domain_state_pruning = domain_config.state_pruning.clone().unwrap_or_default();
+    let (relay_request_sink, relay_request_stream) = relay_request_channel();
     domain_config.rpc_id_provider = provider.rpc_id();
     let rpc_builder = {
         let deps = crate::rpc::FullDeps {
@@ -395,15 +408,31 @@ where
 
         let spawn_essential = task_manager.spawn_essential_handle();
         let rpc_deps = provider.deps(deps)?;
+        let consensus_client = consensus_client.clone();
+        let client = client.clone();
+        // Relay requests are only handled by the relayer, which only runs on the authority node.
+        let relay_request_sink = is_authority.then_some(relay_request_sink);
         Box::new(move |_, subscription_task_executor| {
             let spawn_essential = spawn_essential.clone();
-            provider
-                .rpc_builder(
-                    rpc_deps.clone(),
-                    subscription_task_executor,
-                    spawn_essential,
+            let mut module = provider.rpc_builder(
+                rpc_deps.clone(),
+                subscription_task_executor,
+                spawn_essential,
+            )?;
+
+            module
+                .merge(
+                    MessageRelayerRpc::new(
+                        domain_id,
+                        consensus_client.clone(),
+                        client.clone(),
+                        relay_request_sink.clone(),
+                    )
+                    .into_rpc(),
                 )
-                .map_err(Into::into)
+                .map_err(|error| sc_service::Error::Other(error.to_string()))?;
+
+            Ok(module)
         })
     };
 
@@ -469,6 +498,7 @@ where
             // since domain sync oracle will always return `synced` due to force sync being set.
             consensus_network_sync_oracle,
             gossip_message_sink,
+            relay_request_stream,
         );
 
         spawn_essential.spawn_essential_blocking("domain-relayer", None, Box::pin(relayer_worker));
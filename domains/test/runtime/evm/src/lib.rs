@@ -53,7 +53,8 @@ use sp_core::{Get, OpaqueMetadata, H160, H256, U256};
 use sp_domains::{DomainAllowlistUpdates, DomainId, MessengerHoldIdentifier, Transfers};
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
 use sp_messenger::messages::{
-    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, MessageId, MessageKey,
+    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, InboxMessageStatus,
+    MessageId, MessageKey, OutboxMessageStatus,
 };
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::EncodableOpaqueLeaf;
@@ -459,6 +460,7 @@ impl sp_subspace_mmr::MmrProofVerifier<MmrHash, NumberFor<Block>, Hash> for MmrP
 )]
 pub enum HoldIdentifier {
     Messenger(MessengerHoldIdentifier),
+    XdmCall,
 }
 
 impl VariantCount for HoldIdentifier {
@@ -474,6 +476,12 @@ impl pallet_messenger::HoldIdentifier<Runtime> for HoldIdentifier {
     }
 }
 
+impl pallet_xdm_call::HoldIdentifier<Runtime> for HoldIdentifier {
+    fn xdm_call() -> Self {
+        Self::XdmCall
+    }
+}
+
 parameter_types! {
     pub const ChannelReserveFee: Balance = SSC;
 }
@@ -507,6 +515,10 @@ impl pallet_messenger::Config for Runtime {
     fn get_endpoint_handler(endpoint: &Endpoint) -> Option<Box<dyn EndpointHandlerT<MessageId>>> {
         if endpoint == &Endpoint::Id(TransporterEndpointId::get()) {
             Some(Box::new(EndpointHandler(PhantomData::<Runtime>)))
+        } else if endpoint == &Endpoint::Id(XdmCallEndpointId::get()) {
+            Some(Box::new(pallet_xdm_call::EndpointHandler(
+                PhantomData::<Runtime>,
+            )))
         } else {
             None
         }
@@ -520,6 +532,7 @@ impl pallet_messenger::Config for Runtime {
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
     type DomainOwner = ();
+    type DeactivatedDomains = ();
     type HoldIdentifier = HoldIdentifier;
     type ChannelReserveFee = ChannelReserveFee;
 }
@@ -546,6 +559,42 @@ impl pallet_transporter::Config for Runtime {
     type WeightInfo = pallet_transporter::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    pub const XdmCallEndpointId: EndpointId = 2;
+    pub MaxXdmCallWeight: Weight = Perbill::from_percent(10) * RuntimeBlockWeights::get().max_block;
+    pub const MaxXdmCallLength: u32 = 4 * 1024;
+    pub const XdmCallDeposit: Balance = SSC;
+}
+
+/// Calls that other chains are allowed to dispatch on this chain through `pallet-xdm-call`.
+pub struct XdmCallFilter;
+
+impl frame_support::traits::Contains<RuntimeCall> for XdmCallFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::System(frame_system::Call::remark_with_event { .. })
+        )
+    }
+}
+
+impl pallet_xdm_call::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type SelfChainId = SelfChainId;
+    type SelfEndpointId = XdmCallEndpointId;
+    type Sender = Messenger;
+    type AccountIdConverter = domain_runtime_primitives::AccountId20Converter;
+    type CallFilter = XdmCallFilter;
+    type MaxCallWeight = MaxXdmCallWeight;
+    type MaxCallLength = MaxXdmCallLength;
+    type OnCallResponse = ();
+    type Currency = Balances;
+    type HoldIdentifier = HoldIdentifier;
+    type CallDeposit = XdmCallDeposit;
+    type WeightInfo = pallet_xdm_call::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_evm_chain_id::Config for Runtime {}
 
 pub struct FindAuthorTruncated;
@@ -708,6 +757,7 @@ construct_runtime!(
         // Note: Indexes should match the indexes of the System domain runtime
         Messenger: pallet_messenger = 60,
         Transporter: pallet_transporter = 61,
+        XdmCall: pallet_xdm_call = 62,
 
         // evm stuff
         Ethereum: pallet_ethereum = 80,
@@ -1214,6 +1264,14 @@ impl_runtime_apis! {
         fn should_relay_inbox_message_response(dst_chain_id: ChainId, msg_id: MessageId) -> bool {
             Messenger::should_relay_inbox_message_response(dst_chain_id, msg_id)
         }
+
+        fn outbox_message_status(dst_chain_id: ChainId, msg_id: MessageId) -> OutboxMessageStatus {
+            Messenger::outbox_message_status(dst_chain_id, msg_id)
+        }
+
+        fn inbox_message_status(src_chain_id: ChainId, msg_id: MessageId) -> InboxMessageStatus {
+            Messenger::inbox_message_status(src_chain_id, msg_id)
+        }
     }
 
     impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
@@ -1,20 +1,36 @@
+use alloc::format;
+use alloc::vec::Vec;
+use codec::Encode;
+use domain_runtime_primitives::MultiAccountId;
+use fp_evm::{ExitError, ExitSucceed, PrecompileFailure, PrecompileOutput};
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
 use pallet_evm::{
-    IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+    AddressMapping, GasWeightMapping, IsPrecompileResult, Precompile, PrecompileHandle,
+    PrecompileResult, PrecompileSet,
 };
-use sp_core::H160;
+use pallet_transporter::weights::WeightInfo as TransporterWeightInfo;
+use pallet_xdm_call::weights::WeightInfo as XdmCallWeightInfo;
+use sp_core::{H160, U256};
+use sp_domains::DomainId;
+use sp_messenger::endpoint::Sender;
+use sp_messenger::messages::{ChainId, MessageId};
 use sp_std::marker::PhantomData;
 
 use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 
+/// Address of the XDM precompile.
+pub const XDM_PRECOMPILE_ADDRESS: u64 = 2048;
+
 pub struct Precompiles<R>(PhantomData<R>);
 
 impl<R> Precompiles<R>
 where
     R: pallet_evm::Config,
 {
-    pub fn used_addresses() -> [H160; 7] {
+    pub fn used_addresses() -> [H160; 8] {
         [
             hash(1),
             hash(2),
@@ -23,6 +39,7 @@ where
             hash(5),
             hash(1024),
             hash(1025),
+            hash(XDM_PRECOMPILE_ADDRESS),
         ]
     }
 }
@@ -36,7 +53,12 @@ impl<R> Default for Precompiles<R> {
 
 impl<R> PrecompileSet for Precompiles<R>
 where
-    R: pallet_evm::Config,
+    R: pallet_evm::Config + pallet_transporter::Config + pallet_xdm_call::Config,
+    <R as pallet_transporter::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    <R as pallet_xdm_call::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    pallet_transporter::BalanceOf<R>: TryFrom<U256>,
 {
     fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
         match handle.code_address() {
@@ -49,6 +71,8 @@ where
             // Non-Frontier specific nor Ethereum precompiles :
             a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
             a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
+            // Subspace specific precompiles :
+            a if a == hash(XDM_PRECOMPILE_ADDRESS) => Some(XdmPrecompile::<R>::execute(handle)),
             _ => None,
         }
     }
@@ -64,3 +88,389 @@ where
 fn hash(a: u64) -> H160 {
     H160::from_low_u64_be(a)
 }
+
+/// Function selectors of the XDM precompile.
+///
+/// Selectors are the first 4 bytes of the keccak256 hash of the function signature.
+mod selector {
+    /// `transferToConsensus(bytes32 receiver, uint256 amount) returns (uint256 channelId, uint256 nonce)`
+    pub(super) const TRANSFER_TO_CONSENSUS: [u8; 4] = [0x84, 0xfb, 0x8a, 0x3b];
+    /// `transferToDomain(uint32 domainId, address receiver, uint256 amount) returns (uint256 channelId, uint256 nonce)`
+    pub(super) const TRANSFER_TO_DOMAIN: [u8; 4] = [0xce, 0xd7, 0x77, 0xb0];
+    /// `sendCallToConsensus(bytes call) returns (uint256 channelId, uint256 nonce)`
+    pub(super) const SEND_CALL_TO_CONSENSUS: [u8; 4] = [0x0b, 0x78, 0xb5, 0x62];
+    /// `sendCallToDomain(uint32 domainId, bytes call) returns (uint256 channelId, uint256 nonce)`
+    pub(super) const SEND_CALL_TO_DOMAIN: [u8; 4] = [0x2b, 0x14, 0x32, 0xa6];
+    /// `consensusCallResponse(uint256 channelId, uint256 nonce) returns (uint8 status, bytes data)`
+    pub(super) const CONSENSUS_CALL_RESPONSE: [u8; 4] = [0xa5, 0xd9, 0x26, 0xa0];
+    /// `domainCallResponse(uint32 domainId, uint256 channelId, uint256 nonce) returns (uint8 status, bytes data)`
+    pub(super) const DOMAIN_CALL_RESPONSE: [u8; 4] = [0x06, 0x11, 0x12, 0xa1];
+}
+
+/// Status of a call sent through the XDM precompile, as returned by the call response functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum CallStatus {
+    /// No call with the given message id, or its response is already cleared.
+    Unknown = 0,
+    /// The call is sent and waiting for the response from the dst_chain.
+    Pending = 1,
+    /// The call is dispatched successfully on the dst_chain, `data` is the response payload.
+    Succeeded = 2,
+    /// The call is failed on the dst_chain, `data` is the SCALE encoded `DispatchError`.
+    Failed = 3,
+}
+
+/// Precompile that exposes cross domain transfers of `pallet-transporter` and cross domain
+/// calls of `pallet-xdm-call` to EVM contracts.
+///
+/// The caller of the precompile is the sender of the transfers and calls, the responses of the
+/// calls are stored by `pallet-xdm-call` and can be queried by the call response functions.
+pub struct XdmPrecompile<R>(PhantomData<R>);
+
+impl<R> Precompile for XdmPrecompile<R>
+where
+    R: pallet_evm::Config + pallet_transporter::Config + pallet_xdm_call::Config,
+    <R as pallet_transporter::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    <R as pallet_xdm_call::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    pallet_transporter::BalanceOf<R>: TryFrom<U256>,
+{
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        let input = handle.input().to_vec();
+        if input.len() < 4 {
+            return Err(error("input is too short"));
+        }
+        let (selector, args) = input.split_at(4);
+
+        let output = match selector {
+            s if s == selector::TRANSFER_TO_CONSENSUS => {
+                let receiver = MultiAccountId::AccountId32(read_word(args, 0)?);
+                let amount = read_u256(args, 1)?;
+                Self::transfer(handle, ChainId::Consensus, receiver, amount)?
+            }
+            s if s == selector::TRANSFER_TO_DOMAIN => {
+                let domain_id = read_domain_id(args, 0)?;
+                let receiver = MultiAccountId::AccountId20(read_address(args, 1)?.0);
+                let amount = read_u256(args, 2)?;
+                Self::transfer(handle, ChainId::Domain(domain_id), receiver, amount)?
+            }
+            s if s == selector::SEND_CALL_TO_CONSENSUS => {
+                let call = read_bytes(args, 0)?;
+                Self::send_call(handle, ChainId::Consensus, call)?
+            }
+            s if s == selector::SEND_CALL_TO_DOMAIN => {
+                let domain_id = read_domain_id(args, 0)?;
+                let call = read_bytes(args, 1)?;
+                Self::send_call(handle, ChainId::Domain(domain_id), call)?
+            }
+            s if s == selector::CONSENSUS_CALL_RESPONSE => {
+                let message_id = (read_u256(args, 0)?, read_u256(args, 1)?);
+                Self::call_response(handle, ChainId::Consensus, message_id)?
+            }
+            s if s == selector::DOMAIN_CALL_RESPONSE => {
+                let domain_id = read_domain_id(args, 0)?;
+                let message_id = (read_u256(args, 1)?, read_u256(args, 2)?);
+                Self::call_response(handle, ChainId::Domain(domain_id), message_id)?
+            }
+            _ => return Err(error("unknown function selector")),
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output,
+        })
+    }
+}
+
+impl<R> XdmPrecompile<R>
+where
+    R: pallet_evm::Config + pallet_transporter::Config + pallet_xdm_call::Config,
+    <R as pallet_transporter::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    <R as pallet_xdm_call::Config>::Sender:
+        Sender<<R as frame_system::Config>::AccountId, MessageId = MessageId>,
+    pallet_transporter::BalanceOf<R>: TryFrom<U256>,
+{
+    fn transfer(
+        handle: &mut impl PrecompileHandle,
+        dst_chain_id: ChainId,
+        receiver: MultiAccountId,
+        amount: U256,
+    ) -> Result<Vec<u8>, PrecompileFailure> {
+        ensure_mutable(handle)?;
+        record_weight::<R>(
+            handle,
+            <R as pallet_transporter::Config>::WeightInfo::transfer(),
+        )?;
+
+        let amount = amount
+            .try_into()
+            .map_err(|_| error("transfer amount overflow"))?;
+        let sender = R::AddressMapping::into_account_id(handle.context().caller);
+        let message_id = pallet_transporter::Pallet::<R>::do_transfer(
+            sender,
+            pallet_transporter::Location {
+                chain_id: dst_chain_id,
+                account_id: receiver,
+            },
+            amount,
+        )
+        .map_err(|err| dispatch_error("transfer", err))?;
+
+        Ok(encode_message_id(message_id))
+    }
+
+    fn send_call(
+        handle: &mut impl PrecompileHandle,
+        dst_chain_id: ChainId,
+        call: Vec<u8>,
+    ) -> Result<Vec<u8>, PrecompileFailure> {
+        ensure_mutable(handle)?;
+        record_weight::<R>(
+            handle,
+            <R as pallet_xdm_call::Config>::WeightInfo::send_call(),
+        )?;
+
+        let sender = R::AddressMapping::into_account_id(handle.context().caller);
+        let message_id = pallet_xdm_call::Pallet::<R>::do_send_call(sender, dst_chain_id, call)
+            .map_err(|err| dispatch_error("send call", err))?;
+
+        Ok(encode_message_id(message_id))
+    }
+
+    fn call_response(
+        handle: &mut impl PrecompileHandle,
+        dst_chain_id: ChainId,
+        message_id: MessageId,
+    ) -> Result<Vec<u8>, PrecompileFailure> {
+        record_weight::<R>(
+            handle,
+            <R as frame_system::Config>::DbWeight::get().reads(2),
+        )?;
+
+        if let Some(call_response) =
+            pallet_xdm_call::Pallet::<R>::call_responses(dst_chain_id, message_id)
+        {
+            return Ok(match call_response.response {
+                Ok(data) => encode_call_response(CallStatus::Succeeded, &data),
+                Err(err) => encode_call_response(CallStatus::Failed, &err.encode()),
+            });
+        }
+
+        let status =
+            if pallet_xdm_call::Pallet::<R>::outgoing_calls(dst_chain_id, message_id).is_some() {
+                CallStatus::Pending
+            } else {
+                CallStatus::Unknown
+            };
+        Ok(encode_call_response(status, &[]))
+    }
+}
+
+fn error(reason: &'static str) -> PrecompileFailure {
+    PrecompileFailure::Error {
+        exit_status: ExitError::Other(reason.into()),
+    }
+}
+
+fn dispatch_error(action: &str, err: sp_runtime::DispatchError) -> PrecompileFailure {
+    PrecompileFailure::Error {
+        exit_status: ExitError::Other(
+            format!("{action} failed: {}", <&'static str>::from(err)).into(),
+        ),
+    }
+}
+
+fn ensure_mutable(handle: &impl PrecompileHandle) -> Result<(), PrecompileFailure> {
+    if handle.is_static() {
+        return Err(error("cannot be called in static context"));
+    }
+    // Value sent to the precompile would be locked in the precompile account.
+    if !handle.context().apparent_value.is_zero() {
+        return Err(error("function is not payable"));
+    }
+    Ok(())
+}
+
+fn record_weight<R: pallet_evm::Config>(
+    handle: &mut impl PrecompileHandle,
+    weight: Weight,
+) -> Result<(), PrecompileFailure> {
+    handle.record_cost(R::GasWeightMapping::weight_to_gas(weight))?;
+    Ok(())
+}
+
+/// Reads the `index`th 32 bytes word of the ABI encoded arguments.
+fn read_word(args: &[u8], index: usize) -> Result<[u8; 32], PrecompileFailure> {
+    let start = index * 32;
+    args.get(start..start + 32)
+        .map(|word| word.try_into().expect("Slice is exactly 32 bytes; qed"))
+        .ok_or_else(|| error("input is too short"))
+}
+
+fn read_u256(args: &[u8], index: usize) -> Result<U256, PrecompileFailure> {
+    Ok(U256::from_big_endian(&read_word(args, index)?))
+}
+
+fn read_domain_id(args: &[u8], index: usize) -> Result<DomainId, PrecompileFailure> {
+    let domain_id = read_u256(args, index)?;
+    if domain_id > U256::from(u32::MAX) {
+        return Err(error("invalid domain id"));
+    }
+    Ok(DomainId::new(domain_id.low_u32()))
+}
+
+fn read_address(args: &[u8], index: usize) -> Result<H160, PrecompileFailure> {
+    let word = read_word(args, index)?;
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(error("invalid address"));
+    }
+    Ok(H160::from_slice(&word[12..]))
+}
+
+/// Reads the dynamic `bytes` argument whose offset is at the `index`th word.
+fn read_bytes(args: &[u8], index: usize) -> Result<Vec<u8>, PrecompileFailure> {
+    let to_usize = |value: U256| -> Result<usize, PrecompileFailure> {
+        if value > U256::from(u32::MAX) {
+            return Err(error("invalid bytes argument"));
+        }
+        Ok(value.low_u32() as usize)
+    };
+
+    let offset = to_usize(read_u256(args, index)?)?;
+    let data = args
+        .get(offset..)
+        .ok_or_else(|| error("invalid bytes argument"))?;
+    let len = to_usize(read_u256(data, 0)?)?;
+    data.get(32..32 + len)
+        .map(|bytes| bytes.to_vec())
+        .ok_or_else(|| error("input is too short"))
+}
+
+fn encode_u256(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// Encodes the message id as `(uint256 channelId, uint256 nonce)`.
+fn encode_message_id((channel_id, nonce): MessageId) -> Vec<u8> {
+    let mut output = Vec::with_capacity(64);
+    output.extend_from_slice(&encode_u256(channel_id));
+    output.extend_from_slice(&encode_u256(nonce));
+    output
+}
+
+/// Encodes the call response as `(uint8 status, bytes data)`.
+fn encode_call_response(status: CallStatus, data: &[u8]) -> Vec<u8> {
+    let padded_len = data.len().div_ceil(32) * 32;
+    let mut output = Vec::with_capacity(96 + padded_len);
+    output.extend_from_slice(&encode_u256(U256::from(status as u8)));
+    // offset of `data`
+    output.extend_from_slice(&encode_u256(U256::from(64)));
+    output.extend_from_slice(&encode_u256(U256::from(data.len())));
+    output.extend_from_slice(data);
+    output.resize(96 + padded_len, 0);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encode_call_response, encode_message_id, encode_u256, read_address, read_bytes,
+        read_domain_id, read_u256, selector, CallStatus,
+    };
+    use sp_core::hashing::keccak_256;
+    use sp_core::{H160, U256};
+    use sp_domains::DomainId;
+
+    fn function_selector(signature: &str) -> [u8; 4] {
+        keccak_256(signature.as_bytes())[..4]
+            .try_into()
+            .expect("Selector is 4 bytes; qed")
+    }
+
+    #[test]
+    fn xdm_precompile_selectors() {
+        assert_eq!(
+            selector::TRANSFER_TO_CONSENSUS,
+            function_selector("transferToConsensus(bytes32,uint256)")
+        );
+        assert_eq!(
+            selector::TRANSFER_TO_DOMAIN,
+            function_selector("transferToDomain(uint32,address,uint256)")
+        );
+        assert_eq!(
+            selector::SEND_CALL_TO_CONSENSUS,
+            function_selector("sendCallToConsensus(bytes)")
+        );
+        assert_eq!(
+            selector::SEND_CALL_TO_DOMAIN,
+            function_selector("sendCallToDomain(uint32,bytes)")
+        );
+        assert_eq!(
+            selector::CONSENSUS_CALL_RESPONSE,
+            function_selector("consensusCallResponse(uint256,uint256)")
+        );
+        assert_eq!(
+            selector::DOMAIN_CALL_RESPONSE,
+            function_selector("domainCallResponse(uint32,uint256,uint256)")
+        );
+    }
+
+    #[test]
+    fn xdm_precompile_read_arguments() {
+        // sendCallToDomain(3, 0x0102..) arguments
+        let mut args = vec![];
+        args.extend_from_slice(&encode_u256(U256::from(3)));
+        args.extend_from_slice(&encode_u256(U256::from(64)));
+        args.extend_from_slice(&encode_u256(U256::from(33)));
+        args.extend_from_slice(&[1u8; 33]);
+        args.resize(args.len() + 31, 0);
+
+        assert_eq!(read_domain_id(&args, 0).unwrap(), DomainId::new(3));
+        assert_eq!(read_bytes(&args, 1).unwrap(), vec![1u8; 33]);
+        assert!(read_u256(&args, 5).is_err());
+
+        // domain id out of the `uint32` range
+        assert!(read_domain_id(&encode_u256(U256::from(u32::MAX) + 1), 0).is_err());
+
+        // bytes length out of the input
+        let mut args = vec![];
+        args.extend_from_slice(&encode_u256(U256::from(32)));
+        args.extend_from_slice(&encode_u256(U256::from(64)));
+        args.extend_from_slice(&[1u8; 32]);
+        assert!(read_bytes(&args, 0).is_err());
+
+        // address with dirty high bytes
+        let address = H160::repeat_byte(0xab);
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(address.as_bytes());
+        assert_eq!(read_address(&word, 0).unwrap(), address);
+        word[0] = 1;
+        assert!(read_address(&word, 0).is_err());
+    }
+
+    #[test]
+    fn xdm_precompile_encode_outputs() {
+        let output = encode_message_id((U256::from(1), U256::from(2)));
+        assert_eq!(output.len(), 64);
+        assert_eq!(U256::from_big_endian(&output[..32]), U256::from(1));
+        assert_eq!(U256::from_big_endian(&output[32..]), U256::from(2));
+
+        let output = encode_call_response(CallStatus::Pending, &[]);
+        assert_eq!(output.len(), 96);
+        assert_eq!(U256::from_big_endian(&output[..32]), U256::from(1));
+        assert_eq!(U256::from_big_endian(&output[32..64]), U256::from(64));
+        assert_eq!(U256::from_big_endian(&output[64..96]), U256::zero());
+
+        let output = encode_call_response(CallStatus::Failed, &[7u8; 33]);
+        assert_eq!(output.len(), 96 + 64);
+        assert_eq!(U256::from_big_endian(&output[..32]), U256::from(3));
+        assert_eq!(U256::from_big_endian(&output[64..96]), U256::from(33));
+        assert_eq!(&output[96..129], &[7u8; 33]);
+        assert!(output[129..].iter().all(|byte| *byte == 0));
+    }
+}
@@ -23,6 +23,13 @@
 //! - `claim` - Claim unlocked balances.
 //! - `update_vesting_schedules` - Update all vesting schedules under an
 //!   account, `root` origin required.
+//!
+//! ### Public Functions
+//!
+//! - `add_vesting_schedule` - Add a new vesting schedule for the balance an
+//!   account already has, used by other pallets.
+//! - `vesting_locked_balance` - Balance still locked by vesting schedules of an
+//!   account.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -365,6 +372,56 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Adds vesting `schedule` for the balance `who` already has, the balance is
+	/// locked until vested.
+	///
+	/// Schedule with the same `start`, `period` and `period_count` as the
+	/// existing one is merged into it.
+	pub fn add_vesting_schedule(who: &T::AccountId, schedule: VestingScheduleOf<T>) -> DispatchResult {
+		let schedule_amount = ensure_valid_vesting_schedule::<T>(&schedule)?;
+
+		let total_amount = Self::locked_balance(who)
+			.checked_add(&schedule_amount)
+			.ok_or(ArithmeticError::Overflow)?;
+		ensure!(
+			T::Currency::free_balance(who) >= total_amount,
+			Error::<T>::InsufficientBalanceToLock,
+		);
+
+		<VestingSchedules<T>>::try_mutate(who, |schedules| -> DispatchResult {
+			match schedules.iter_mut().find(|existing| {
+				existing.start == schedule.start
+					&& existing.period == schedule.period
+					&& existing.period_count == schedule.period_count
+			}) {
+				Some(existing) => {
+					existing.per_period = existing
+						.per_period
+						.checked_add(&schedule.per_period)
+						.ok_or(ArithmeticError::Overflow)?;
+					existing.total_amount().ok_or(ArithmeticError::Overflow)?;
+					Ok(())
+				}
+				None => schedules
+					.try_push(schedule)
+					.map_err(|_| Error::<T>::MaxVestingSchedulesExceeded.into()),
+			}
+		})?;
+		T::Currency::set_lock(VESTING_LOCK_ID, who, total_amount, WithdrawReasons::all());
+		Ok(())
+	}
+
+	/// Returns balance of `who` that is still locked by vesting schedules at
+	/// the current block.
+	pub fn vesting_locked_balance(who: &T::AccountId) -> BalanceOf<T> {
+		let now = T::BlockNumberProvider::current_block_number();
+		Self::vesting_schedules(who)
+			.iter()
+			.fold(Zero::zero(), |total: BalanceOf<T>, schedule| {
+				total.saturating_add(schedule.locked_amount(now))
+			})
+	}
+
 	fn do_update_vesting_schedules(who: &T::AccountId, schedules: Vec<VestingScheduleOf<T>>) -> DispatchResult {
 		let bounded_schedules: BoundedVec<VestingScheduleOf<T>, T::MaxVestingSchedules> = schedules
 			.try_into()
@@ -601,3 +601,65 @@ fn cliff_vesting_works() {
         ));
     });
 }
+
+#[test]
+fn add_vesting_schedule_works() {
+    ExtBuilder::build().execute_with(|| {
+        let schedule = VestingSchedule {
+            start: 0u64,
+            period: 10u64,
+            period_count: 2u32,
+            per_period: 10u64,
+        };
+        assert_ok!(Vesting::add_vesting_schedule(&ALICE, schedule.clone()));
+        assert_ok!(Vesting::add_vesting_schedule(&ALICE, schedule.clone()));
+
+        // schedules with the same start, period and period count are merged
+        assert_eq!(
+            Vesting::vesting_schedules(ALICE),
+            vec![VestingSchedule {
+                per_period: 20u64,
+                ..schedule.clone()
+            }]
+        );
+        assert_eq!(Vesting::vesting_locked_balance(&ALICE), 40);
+        assert_eq!(
+            PalletBalances::locks(ALICE).first(),
+            Some(&BalanceLock {
+                id: VESTING_LOCK_ID,
+                amount: 40u64,
+                reasons: Reasons::All,
+            })
+        );
+
+        MockBlockNumberProvider::set(11);
+        assert_eq!(Vesting::vesting_locked_balance(&ALICE), 20);
+        assert_ok!(Vesting::add_vesting_schedule(
+            &ALICE,
+            VestingSchedule {
+                start: 10u64,
+                ..schedule
+            }
+        ));
+        assert_eq!(Vesting::vesting_schedules(ALICE).len(), 2);
+        assert_eq!(PalletBalances::locks(ALICE)[0].amount, 40u64);
+    });
+}
+
+#[test]
+fn add_vesting_schedule_fails_if_insufficient_balance() {
+    ExtBuilder::build().execute_with(|| {
+        assert_noop!(
+            Vesting::add_vesting_schedule(
+                &BOB,
+                VestingSchedule {
+                    start: 0u64,
+                    period: 10u64,
+                    period_count: 1u32,
+                    per_period: 10u64,
+                }
+            ),
+            Error::<Runtime>::InsufficientBalanceToLock
+        );
+    });
+}
@@ -105,6 +105,7 @@ fn create_genesis_config(
         system: SystemConfig::default(),
         balances: BalancesConfig { balances },
         transaction_payment: Default::default(),
+        runtime_configs: Default::default(),
         sudo: SudoConfig {
             // Assign network admin rights.
             key: Some(sudo_account.clone()),
@@ -19,10 +19,13 @@
 // `construct_runtime!` does a lot of recursion and requires us to increase the limit to 256.
 #![recursion_limit = "256"]
 
+mod signed_extensions;
+
 // Make the WASM binary available.
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+pub use crate::signed_extensions::CheckCallPermissions;
 use codec::{Compact, CompactLen, Decode, Encode, MaxEncodedLen};
 use core::num::NonZeroU64;
 use domain_runtime_primitives::opaque::Header as DomainHeader;
@@ -32,8 +35,8 @@ use domain_runtime_primitives::{
 use frame_support::genesis_builder_helper::{build_config, create_default_config};
 use frame_support::inherent::ProvideInherent;
 use frame_support::traits::{
-    ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, Currency, ExistenceRequirement, Get,
-    Imbalance, VariantCount, WithdrawReasons,
+    ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, Contains, Currency, ExistenceRequirement,
+    Get, Imbalance, PalletInfoAccess, VariantCount, WithdrawReasons,
 };
 use frame_support::weights::constants::{ParityDbWeight, WEIGHT_REF_TIME_PER_SECOND};
 use frame_support::weights::{ConstantMultiplier, IdentityFee, Weight};
@@ -54,18 +57,22 @@ use sp_consensus_subspace::{
 use sp_core::crypto::{ByteArray, KeyTypeId};
 use sp_core::{OpaqueMetadata, H256};
 use sp_domains::bundle_producer_election::BundleProducerElectionParams;
+use sp_domains::staking::{NominatorPosition, OperatorSummary};
 use sp_domains::{
     DomainAllowlistUpdates, DomainId, DomainInstanceData, DomainsHoldIdentifier,
     ExecutionReceiptFor, MessengerHoldIdentifier, OpaqueBundle, OpaqueBundles, OperatorId,
     OperatorPublicKey, StakingHoldIdentifier,
 };
 use sp_domains_fraud_proof::fraud_proof::FraudProof;
+use sp_domains_fraud_proof::FraudProofValidationError;
 use sp_messenger::endpoint::{Endpoint, EndpointHandler as EndpointHandlerT, EndpointId};
 use sp_messenger::messages::{
-    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, MessageId, MessageKey,
+    BlockMessagesWithStorageKey, ChainId, ChannelId, CrossDomainMessage, InboxMessageStatus,
+    MessageId, MessageKey, OutboxMessageStatus,
 };
 use sp_messenger_host_functions::{get_storage_key, StorageKeyRequest};
 use sp_mmr_primitives::EncodableOpaqueLeaf;
+use sp_rewards::VestedRewards;
 use sp_runtime::traits::{
     AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConstBool, DispatchInfoOf,
     Keccak256, NumberFor, PostDispatchInfoOf, Zero,
@@ -81,6 +88,7 @@ use sp_std::iter::Peekable;
 use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 use sp_subspace_mmr::ConsensusChainMmrLeafProof;
+use sp_transaction_fees::FeeEstimate;
 use sp_version::RuntimeVersion;
 use static_assertions::const_assert;
 use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping};
@@ -180,6 +188,15 @@ const_assert!(POT_ENTROPY_INJECTION_DELAY > BLOCK_AUTHORING_DELAY + 1);
 /// Era duration in blocks.
 const ERA_DURATION_IN_BLOCKS: BlockNumber = 2016;
 
+/// Number of eras farmers reported for equivocation stay in the block list.
+const EQUIVOCATION_BLOCK_LIST_ERAS: u32 = 7;
+
+/// Number of eras over which vested share of farmer rewards is released.
+const REWARD_VESTING_ERAS: u32 = 14;
+
+/// Vesting schedules from genesis.
+const GENESIS_VESTING_SCHEDULES: u32 = 2;
+
 const EQUIVOCATION_REPORT_LONGEVITY: BlockNumber = 256;
 
 /// Any solution range is valid in the test environment.
@@ -208,11 +225,47 @@ parameter_types! {
 
 pub type SS58Prefix = ConstU16<2254>;
 
+/// Sudo calls are never denied by the call permissions, such that they can always be changed.
+pub struct SudoCalls;
+
+impl Contains<RuntimeCall> for SudoCalls {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(call, RuntimeCall::Sudo(_))
+    }
+}
+
+/// Calls of the pallets that provide inherents can't be denied by the call permissions, otherwise
+/// blocks would be invalid.
+pub struct PalletsWithInherents;
+
+impl Contains<u8> for PalletsWithInherents {
+    fn contains(pallet_index: &u8) -> bool {
+        [Timestamp::index(), Subspace::index()].contains(&usize::from(*pallet_index))
+    }
+}
+
+pallet_runtime_configs::impl_get_call_indices!(
+    RuntimeCall,
+    System,
+    Timestamp,
+    Subspace,
+    Rewards,
+    Balances,
+    Utility,
+    Domains,
+    RuntimeConfigs,
+    Vesting,
+    Messenger,
+    Transporter,
+    XdmCall,
+    Sudo,
+);
+
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
     /// The basic call filter to use in dispatchable.
-    type BaseCallFilter = frame_support::traits::Everything;
+    type BaseCallFilter = pallet_runtime_configs::CallPermissionsFilter<Runtime, SudoCalls>;
     /// Block & extrinsics weights: base values and limits.
     type BlockWeights = SubspaceBlockWeights;
     /// The maximum length of a block (in bytes).
@@ -273,6 +326,8 @@ parameter_types! {
     pub const PotEntropyInjectionLookbackDepth: u8 = POT_ENTROPY_INJECTION_LOOKBACK_DEPTH;
     pub const PotEntropyInjectionDelay: SlotNumber = POT_ENTROPY_INJECTION_DELAY;
     pub const EraDuration: BlockNumber = ERA_DURATION_IN_BLOCKS;
+    pub const EquivocationBlockListDuration: pallet_subspace::BlockListDuration<BlockNumber> =
+        pallet_subspace::BlockListDuration::Eras(EQUIVOCATION_BLOCK_LIST_ERAS);
     pub const SlotProbability: (u64, u64) = SLOT_PROBABILITY;
     pub const ShouldAdjustSolutionRange: bool = false;
     pub const ExpectedVotesPerBlock: u32 = 9;
@@ -309,6 +364,8 @@ impl pallet_subspace::Config for Runtime {
         OffencesSubspace,
         ConstU64<{ EQUIVOCATION_REPORT_LONGEVITY as u64 }>,
     >;
+    type OffenceBlockListDuration =
+        pallet_subspace::equivocation::OffenceBlockListDuration<EquivocationBlockListDuration>;
 
     type WeightInfo = ();
 }
@@ -327,6 +384,7 @@ impl pallet_timestamp::Config for Runtime {
 pub enum HoldIdentifier {
     Domains(DomainsHoldIdentifier),
     Messenger(MessengerHoldIdentifier),
+    XdmCall,
 }
 
 impl pallet_domains::HoldIdentifier<Runtime> for HoldIdentifier {
@@ -351,6 +409,12 @@ impl pallet_messenger::HoldIdentifier<Runtime> for HoldIdentifier {
     }
 }
 
+impl pallet_xdm_call::HoldIdentifier<Runtime> for HoldIdentifier {
+    fn xdm_call() -> Self {
+        Self::XdmCall
+    }
+}
+
 impl VariantCount for HoldIdentifier {
     // TODO: HACK this is not the actual variant count but it is required see
     // https://github.com/subspace/subspace/issues/2674 for more details. It
@@ -605,6 +669,10 @@ impl pallet_messenger::Config for Runtime {
     fn get_endpoint_handler(endpoint: &Endpoint) -> Option<Box<dyn EndpointHandlerT<MessageId>>> {
         if endpoint == &Endpoint::Id(TransporterEndpointId::get()) {
             Some(Box::new(EndpointHandler(PhantomData::<Runtime>)))
+        } else if endpoint == &Endpoint::Id(XdmCallEndpointId::get()) {
+            Some(Box::new(pallet_xdm_call::EndpointHandler(
+                PhantomData::<Runtime>,
+            )))
         } else {
             None
         }
@@ -618,6 +686,7 @@ impl pallet_messenger::Config for Runtime {
     type MmrProofVerifier = MmrProofVerifier;
     type StorageKeys = StorageKeys;
     type DomainOwner = Domains;
+    type DeactivatedDomains = Domains;
     type HoldIdentifier = HoldIdentifier;
     type ChannelReserveFee = ChannelReserveFee;
 }
@@ -644,6 +713,42 @@ impl pallet_transporter::Config for Runtime {
     type WeightInfo = pallet_transporter::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    pub const XdmCallEndpointId: EndpointId = 2;
+    pub MaxXdmCallWeight: Weight = Perbill::from_percent(10) * SubspaceBlockWeights::get().max_block;
+    pub const MaxXdmCallLength: u32 = 4 * 1024;
+    pub const XdmCallDeposit: Balance = SSC;
+}
+
+/// Calls that other chains are allowed to dispatch on this chain through `pallet-xdm-call`.
+pub struct XdmCallFilter;
+
+impl frame_support::traits::Contains<RuntimeCall> for XdmCallFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::System(frame_system::Call::remark_with_event { .. })
+        )
+    }
+}
+
+impl pallet_xdm_call::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type SelfChainId = SelfChainId;
+    type SelfEndpointId = XdmCallEndpointId;
+    type Sender = Messenger;
+    type AccountIdConverter = AccountIdConverter;
+    type CallFilter = XdmCallFilter;
+    type MaxCallWeight = MaxXdmCallWeight;
+    type MaxCallLength = MaxXdmCallLength;
+    type OnCallResponse = ();
+    type Currency = Balances;
+    type HoldIdentifier = HoldIdentifier;
+    type CallDeposit = XdmCallDeposit;
+    type WeightInfo = pallet_xdm_call::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_offences_subspace::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type OnOffenceHandler = Subspace;
@@ -748,6 +853,10 @@ impl pallet_domains::Config for Runtime {
 parameter_types! {
     pub const AvgBlockspaceUsageNumBlocks: BlockNumber = 100;
     pub const ProposerTaxOnVotes: (u32, u32) = (1, 10);
+    // Rewards are paid out immediately, vesting of rewards is disabled.
+    pub const VestedRewardShare: Perbill = Perbill::zero();
+    pub const RewardVestingPeriod: BlockNumber = ERA_DURATION_IN_BLOCKS;
+    pub const RewardVestingPeriodCount: u32 = REWARD_VESTING_ERAS;
 }
 
 impl pallet_rewards::Config for Runtime {
@@ -762,6 +871,18 @@ impl pallet_rewards::Config for Runtime {
     type FindVotingRewardAddresses = Subspace;
     type WeightInfo = ();
     type OnReward = ();
+    type VestedRewardShare = VestedRewardShare;
+    type RewardVesting = pallet_rewards::vesting::OrmlRewardVesting<
+        Runtime,
+        RewardVestingPeriod,
+        RewardVestingPeriodCount,
+    >;
+}
+
+impl pallet_runtime_configs::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PalletsWithInherents = PalletsWithInherents;
+    type WeightInfo = ();
 }
 
 parameter_types! {
@@ -775,7 +896,8 @@ impl orml_vesting::Config for Runtime {
     type MinVestedTransfer = MinVestedTransfer;
     type VestedTransferOrigin = EnsureNever<AccountId>;
     type WeightInfo = ();
-    type MaxVestingSchedules = ConstU32<2>;
+    // Reward vesting schedules are merged per period, see `OrmlRewardVesting`
+    type MaxVestingSchedules = ConstU32<{ GENESIS_VESTING_SCHEDULES + REWARD_VESTING_ERAS + 1 }>;
     type BlockNumberProvider = System;
 }
 
@@ -833,6 +955,8 @@ construct_runtime!(
 
         Vesting: orml_vesting = 7,
 
+        RuntimeConfigs: pallet_runtime_configs = 14,
+
         Mmr: pallet_mmr = 30,
         SubspaceMmr: pallet_subspace_mmr = 31,
 
@@ -840,6 +964,7 @@ construct_runtime!(
         // Note: Indexes should match with indexes on other chains and domains
         Messenger: pallet_messenger exclude_parts { Inherent } = 60,
         Transporter: pallet_transporter = 61,
+        XdmCall: pallet_xdm_call = 62,
 
         // Reserve some room for other pallets as we'll remove sudo pallet eventually.
         Sudo: pallet_sudo = 100,
@@ -862,6 +987,7 @@ pub type SignedExtra = (
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
     pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    CheckCallPermissions,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -1166,6 +1292,26 @@ impl_runtime_apis! {
         }
     }
 
+    impl sp_transaction_fees::TransactionFeesApi<Block, Balance> for Runtime {
+        fn estimate_fees(uxt: <Block as BlockT>::Extrinsic, segments: u32) -> FeeEstimate<Balance> {
+            let call_size = uxt.function.encoded_size() as u32;
+            let len = uxt.encoded_size() as u32;
+            let fee_details = TransactionPayment::query_fee_details(uxt, len);
+            let inclusion_fee = fee_details
+                .inclusion_fee
+                .map(|inclusion_fee| inclusion_fee.inclusion_fee())
+                .unwrap_or_default();
+
+            TransactionFees::estimate_fees(inclusion_fee, fee_details.tip, call_size, segments)
+        }
+    }
+
+    impl sp_rewards::RewardsApi<Block, AccountId, Balance> for Runtime {
+        fn vested_rewards(account: AccountId) -> VestedRewards<Balance> {
+            Rewards::vested_rewards(account)
+        }
+    }
+
     impl sp_objects::ObjectsApi<Block> for Runtime {
         fn extract_block_object_mapping(block: Block, successful_calls: Vec<Hash>) -> BlockObjectMapping {
             extract_block_object_mapping(block, successful_calls)
@@ -1221,7 +1367,7 @@ impl_runtime_apis! {
         fn is_in_block_list(farmer_public_key: &FarmerPublicKey) -> bool {
             // TODO: Either check tx pool too for pending equivocations or replace equivocation
             //  mechanism with an alternative one, so that blocking happens faster
-            Subspace::is_in_block_list(farmer_public_key)
+            Subspace::is_in_block_list_at(farmer_public_key, System::block_number() + 1)
         }
 
         fn history_size() -> HistorySize {
@@ -1402,6 +1548,16 @@ impl_runtime_apis! {
         }
     }
 
+    impl sp_domains::DomainStakingApi<Block, AccountId, Balance, DomainNumber> for Runtime {
+        fn nominator_positions(nominator_id: AccountId) -> Vec<NominatorPosition<Balance, DomainNumber>> {
+            Domains::nominator_positions(nominator_id)
+        }
+
+        fn operator_summaries(domain_id: DomainId) -> Vec<OperatorSummary<Balance>> {
+            Domains::operator_summaries(domain_id)
+        }
+    }
+
     impl sp_session::SessionKeys<Block> for Runtime {
         fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
             SessionKeys::generate(seed)
@@ -1485,6 +1641,14 @@ impl_runtime_apis! {
         fn should_relay_inbox_message_response(dst_chain_id: ChainId, msg_id: MessageId) -> bool {
             Messenger::should_relay_inbox_message_response(dst_chain_id, msg_id)
         }
+
+        fn outbox_message_status(dst_chain_id: ChainId, msg_id: MessageId) -> OutboxMessageStatus {
+            Messenger::outbox_message_status(dst_chain_id, msg_id)
+        }
+
+        fn inbox_message_status(src_chain_id: ChainId, msg_id: MessageId) -> InboxMessageStatus {
+            Messenger::inbox_message_status(src_chain_id, msg_id)
+        }
     }
 
     impl sp_domains_fraud_proof::FraudProofApi<Block, DomainHeader> for Runtime {
@@ -1498,6 +1662,12 @@ impl_runtime_apis! {
         ) -> Vec<FraudProof<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>> {
             extract_fraud_proofs(domain_id, extrinsics)
         }
+
+        fn validate_fraud_proof(
+            fraud_proof: FraudProof<NumberFor<Block>, <Block as BlockT>::Hash, DomainHeader>,
+        ) -> Result<(), FraudProofValidationError> {
+            Domains::check_fraud_proof(&fraud_proof)
+        }
     }
 
     impl mmr::MmrApi<Block, mmr::Hash, BlockNumber> for Runtime {
@@ -0,0 +1,54 @@
+use crate::{Runtime, SudoCalls};
+use codec::{Decode, Encode};
+use frame_support::traits::Contains;
+use pallet_runtime_configs::CallPermissionsFilter;
+use scale_info::TypeInfo;
+use sp_runtime::traits::{DispatchInfoOf, SignedExtension};
+use sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+};
+
+/// Rejects denied calls according to the call permissions in `pallet-runtime-configs` early, when
+/// entering the transaction pool.
+///
+/// Only the outer call is checked here, the actual enforcement (including the calls nested in
+/// other calls) is done by the base call filter of the runtime.
+#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Default, TypeInfo)]
+pub struct CheckCallPermissions;
+
+impl SignedExtension for CheckCallPermissions {
+    const IDENTIFIER: &'static str = "CheckCallPermissions";
+    type AccountId = <Runtime as frame_system::Config>::AccountId;
+    type Call = <Runtime as frame_system::Config>::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        _who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        if !CallPermissionsFilter::<Runtime, SudoCalls>::contains(call) {
+            InvalidTransaction::Call.into()
+        } else {
+            Ok(ValidTransaction::default())
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        self.validate(who, call, info, len)?;
+        Ok(())
+    }
+}
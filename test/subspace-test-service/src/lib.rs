@@ -93,7 +93,8 @@ use subspace_service::{FullSelectChain, RuntimeExecutor};
 use subspace_test_client::{chain_spec, Backend, Client};
 use subspace_test_primitives::OnchainStateApi;
 use subspace_test_runtime::{
-    Runtime, RuntimeApi, RuntimeCall, SignedExtra, UncheckedExtrinsic, SLOT_DURATION,
+    CheckCallPermissions, Runtime, RuntimeApi, RuntimeCall, SignedExtra, UncheckedExtrinsic,
+    SLOT_DURATION,
 };
 use substrate_frame_rpc_system::AccountNonceApi;
 use substrate_test_client::{RpcHandlersExt, RpcTransactionError, RpcTransactionOutput};
@@ -1298,6 +1299,7 @@ where
         frame_system::CheckNonce::<Runtime>::from(nonce),
         frame_system::CheckWeight::<Runtime>::new(),
         pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+        CheckCallPermissions,
     );
     (
         generic::SignedPayload::<
@@ -1306,7 +1308,7 @@ where
         >::from_raw(
             function,
             extra.clone(),
-            ((), 100, 1, genesis_block, current_block_hash, (), (), ()),
+            ((), 100, 1, genesis_block, current_block_hash, (), (), (), ()),
         ),
         extra,
     )